@@ -0,0 +1,152 @@
+//! Benchmark of the local searches on a random country, showing how much time the don't look bits save when a
+//! local search is applied again to a route that has only changed around a few cities, as it is in a memetic algorithm,
+//! and how long 2-opt takes on large countries with the route kept in a tree rather than a vector, at sizes either
+//! side of [`TREE_TOUR_CITIES`], the size from which local searches use the tree.
+//!
+//! Run with `cargo bench --bench local-search`
+
+use std::time::{Duration, Instant};
+
+use rand::{seq::SliceRandom, thread_rng, Rng};
+use tsp_coursework::{
+    cancellation::CancellationToken,
+    chromosome::Chromosome,
+    country,
+    interface::{LocalSearch, Summation},
+    tour::{ArrayTour, Tour, TreeTour, TREE_TOUR_CITIES},
+};
+
+/// The number of cities in the random country
+const CITIES: usize = 500;
+/// The number of times each route is changed and searched again
+const REPEATS: usize = 200;
+/// The number of cities in each large random country, either side of the size the tree is used from
+const LARGE_CITIES: [usize; 6] = [2_000, 5_000, 10_000, TREE_TOUR_CITIES, 15_000, 20_000];
+
+/// Build a graph of cities placed at random in a square, with the straight line distance between each pair
+fn random_graph(cities: usize) -> country::Graph {
+    let points: Vec<(f64, f64)> = (0..cities)
+        .map(|_| (thread_rng().gen_range(0.0..1000.0), thread_rng().gen_range(0.0..1000.0)))
+        .collect();
+    let vertex: Vec<country::Vertex> = points
+        .iter()
+        .map(|from| country::Vertex {
+            edges: points
+                .iter()
+                .enumerate()
+                .map(|(to, point)| country::Edge {
+                    cost: ((from.0 - point.0).powi(2) + (from.1 - point.1).powi(2)).sqrt(),
+                    destination_city: to as u32,
+                })
+                .collect(),
+        })
+        .collect();
+    country::Graph::new(vertex)
+}
+
+/// Build a graph of cities placed at random in a square with only the distance matrix, as the edges of every
+/// vertex take twice as much memory again on large countries and local searches only use the matrix
+fn random_matrix_graph(cities: usize) -> country::Graph {
+    let points: Vec<(f64, f64)> = (0..cities)
+        .map(|_| (thread_rng().gen_range(0.0..1000.0), thread_rng().gen_range(0.0..1000.0)))
+        .collect();
+    let costs: Vec<f64> = points
+        .iter()
+        .flat_map(|from| points.iter().map(move |to| ((from.0 - to.0).powi(2) + (from.1 - to.1).powi(2)).sqrt()))
+        .collect();
+    let forbidden_cost: f64 = 1500.0 * cities as f64;
+    country::Graph {
+        vertex: Vec::new(),
+        distances: country::DistanceMatrix { size: cities, costs, forbidden_cost },
+        candidates: Vec::new(),
+        summation: Summation::Plain,
+        symmetric: true,
+    }
+}
+
+/// Swap two random cities of an improved route and search it again, either from every city or only from the
+/// swapped cities and their neighbours, returning the total time spent searching
+fn repeated_search(route: &Chromosome, local_search: LocalSearch, around: bool, graph: &country::Graph) -> Duration {
+    let length: usize = route.route.len();
+    let mut elapsed: Duration = Duration::ZERO;
+    for _ in 0..REPEATS {
+        let mut chromosome: Chromosome = route.clone();
+        let first: usize = thread_rng().gen_range(0..length);
+        let second: usize = thread_rng().gen_range(0..length);
+        chromosome.route.swap(first, second);
+
+        // The cities whose neighbours changed
+        let cities: Vec<u32> = [first, second]
+            .iter()
+            .flat_map(|position| [position + length - 1, *position, position + 1])
+            .map(|position| chromosome.route[position % length])
+            .collect();
+
+        let start: Instant = Instant::now();
+        if around {
+            chromosome.local_search_around(local_search, &cities, graph, &CancellationToken::new(), u64::MAX).unwrap();
+        } else {
+            chromosome.local_search(local_search, graph).unwrap();
+        }
+        elapsed += start.elapsed();
+    }
+    elapsed
+}
+
+fn main() {
+    let mut graph: country::Graph = random_graph(CITIES);
+    for candidates in [None, Some(8)] {
+        if let Some(size) = candidates {
+            graph.build_candidate_lists(size);
+        }
+        for local_search in [LocalSearch::TwoOpt, LocalSearch::OrOpt] {
+            // Improve a random route once, as the first application in a memetic algorithm would
+            let mut route: Chromosome = Chromosome::generation(&graph).unwrap();
+            let start: Instant = Instant::now();
+            route.local_search(local_search, &graph).unwrap();
+            let first: Duration = start.elapsed();
+
+            let every_city: Duration = repeated_search(&route, local_search, false, &graph);
+            let changed_cities: Duration = repeated_search(&route, local_search, true, &graph);
+
+            println!(
+                "{:?}, {} cities, candidates {:?}: first search {:?}, {} searches again from every city {:?}, \
+                from changed cities {:?} ({:.1}x faster)",
+                local_search,
+                CITIES,
+                candidates,
+                first,
+                REPEATS,
+                every_city,
+                changed_cities,
+                every_city.as_secs_f64() / changed_cities.as_secs_f64(),
+            );
+        }
+    }
+
+    // 2-opt from the same random route of each large country, with the route kept in a vector and in a tree
+    for cities in LARGE_CITIES {
+        let mut graph: country::Graph = random_matrix_graph(cities);
+        graph.build_candidate_lists(8);
+        let mut route: Chromosome = Chromosome::new((0..cities as u32).collect(), 0.0);
+        route.route.shuffle(&mut thread_rng());
+
+        let start: Instant = Instant::now();
+        let mut array: ArrayTour = ArrayTour::new(&route.route);
+        Chromosome::two_opt_tour(&mut array, route.route.clone(), &graph, &CancellationToken::new(), u64::MAX).unwrap();
+        let array_time: Duration = start.elapsed();
+
+        let start: Instant = Instant::now();
+        let mut tree: TreeTour = TreeTour::new(&route.route);
+        Chromosome::two_opt_tour(&mut tree, route.route.clone(), &graph, &CancellationToken::new(), u64::MAX).unwrap();
+        let tree_time: Duration = start.elapsed();
+
+        println!(
+            "TwoOpt, {} cities, candidates Some(8): array tour {:?}, tree tour {:?} (tree takes {:.2}x as long)",
+            tree.len(),
+            array_time,
+            tree_time,
+            tree_time.as_secs_f64() / array_time.as_secs_f64(),
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Build script that records where the binary came from, so every result can be traced to the code that produced it.
+//! The git commit, build profile and enabled features are passed to the compiler as environment variables, which
+//! the `build_info` module reads.
+
+use std::{env, process::Command};
+
+fn main() {
+    // The commit being built, or unknown when building outside of a git checkout such as from a release zip
+    let git_hash: String = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+        .unwrap_or_else(|| String::from("unknown"));
+
+    // Whether the commit has changes that aren't committed yet
+    let dirty: bool = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| !output.stdout.is_empty());
+
+    // Cargo names each enabled feature in an environment variable
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|feature| feature.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+
+    let profile: String = env::var("PROFILE").unwrap_or_else(|_| String::from("unknown"));
+
+    println!("cargo:rustc-env=TSP_GIT_HASH={}{}", git_hash, if dirty { "-dirty" } else { "" });
+    println!("cargo:rustc-env=TSP_BUILD_PROFILE={}", profile);
+    println!("cargo:rustc-env=TSP_FEATURES={}", features.join(","));
+
+    // Build again whenever the commit changes
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+    println!("cargo:rerun-if-changed=build.rs");
+}
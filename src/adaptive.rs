@@ -0,0 +1,142 @@
+//! This module defines [`AdaptivePursuit`], which picks between several operators, favouring those whose recent
+//! uses made children cheaper, and [`AdaptiveOperators`], which picks the crossover and mutation operator of every
+//! child this way. The probabilities follow Thierens' adaptive pursuit: the operator with the best estimated reward
+//! is pursued towards the highest probability allowed, while every other operator keeps a small chance of being tried.
+
+use clap::ValueEnum;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+use super::{
+    interface::{CrossoverOperator, MutationOperator},
+    random::rng,
+};
+
+/// How quickly the estimated reward of an operator moves towards the reward of its latest use. Most uses don't make
+/// a cheaper child, so this is low enough that a single success or failure doesn't decide which operator is favoured
+pub const ADAPTATION_RATE: f64 = 0.1;
+
+/// How quickly the probabilities move towards favouring the operator with the best estimated reward
+pub const PURSUIT_RATE: f64 = 0.1;
+
+/// How often each operator is used and how many of those uses made a cheaper child
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorStatistics {
+    /// The name of the operator
+    pub operator: String,
+    /// The number of times the operator was used
+    pub uses: u64,
+    /// The number of uses that made a child cheaper than what it was made from
+    pub successes: u64,
+    /// The probability of the operator being picked next
+    pub probability: f64,
+}
+
+/// Picks between several operators by adaptive pursuit
+#[derive(Debug, Clone)]
+pub struct AdaptivePursuit<T> {
+    /// The operators that can be picked
+    pub operators: Vec<T>,
+    /// The probability of each operator being picked, these add up to 1
+    pub probabilities: Vec<f64>,
+    /// The estimated reward of using each operator
+    pub rewards: Vec<f64>,
+    /// The number of times each operator was used
+    pub uses: Vec<u64>,
+    /// The number of uses of each operator that made a cheaper child
+    pub successes: Vec<u64>,
+    /// The lowest probability any operator can fall to, so every operator is still tried now and then
+    pub minimum_probability: f64,
+}
+
+/// Implement Methods on the [`AdaptivePursuit`] type
+impl<T: Copy + Debug> AdaptivePursuit<T> {
+    /// This function starts picking between the given operators, each equally likely to be picked
+    pub fn new(operators: Vec<T>) -> Self {
+        let count: usize = operators.len();
+        Self {
+            probabilities: vec![1.0 / count as f64; count],
+            rewards: vec![1.0; count],
+            uses: vec![0; count],
+            successes: vec![0; count],
+            // Half of an equal share, so operators that aren't favoured are still tried now and then
+            minimum_probability: 1.0 / (2 * count) as f64,
+            operators,
+        }
+    }
+
+    /// This function picks an operator with the current probabilities, returning its position and the operator
+    pub fn choose(&self) -> (usize, T) {
+        let position: usize = WeightedIndex::new(&self.probabilities)
+            .map(|weights| weights.sample(&mut rng()))
+            .unwrap_or(0);
+        (position, self.operators[position])
+    }
+
+    /// This function records a use of the operator at the given position, and whether it made a cheaper child,
+    /// then moves the probabilities towards the operator with the best estimated reward
+    pub fn reward(&mut self, position: usize, success: bool) {
+        self.uses[position] += 1;
+        let reward: f64 = if success {
+            self.successes[position] += 1;
+            1.0
+        } else {
+            0.0
+        };
+        self.rewards[position] += ADAPTATION_RATE * (reward - self.rewards[position]);
+
+        // Ties go to the earliest operator so the same one is always pursued
+        let best: usize = self.rewards
+            .iter()
+            .enumerate()
+            .max_by(|(x_position, x), (y_position, y)| x.total_cmp(y).then(y_position.cmp(x_position)))
+            .map_or(0, |(best, _)| best);
+        let maximum_probability: f64 = 1.0 - (self.operators.len() - 1) as f64 * self.minimum_probability;
+        for (index, probability) in self.probabilities.iter_mut().enumerate() {
+            let target: f64 = if index == best { maximum_probability } else { self.minimum_probability };
+            *probability += PURSUIT_RATE * (target - *probability);
+        }
+    }
+
+    /// This function reports the uses, successes and current probability of every operator
+    pub fn statistics(&self) -> Vec<OperatorStatistics> {
+        self.operators
+            .iter()
+            .enumerate()
+            .map(|(index, operator)| OperatorStatistics {
+                operator: format!("{:?}", operator),
+                uses: self.uses[index],
+                successes: self.successes[index],
+                probability: self.probabilities[index],
+            })
+            .collect()
+    }
+}
+
+/// Picks the crossover and mutation operator of every child by adaptive pursuit
+#[derive(Debug, Clone)]
+pub struct AdaptiveOperators {
+    /// Picks between every crossover operator
+    pub crossover: AdaptivePursuit<CrossoverOperator>,
+    /// Picks between every mutation operator
+    pub mutation: AdaptivePursuit<MutationOperator>,
+}
+
+/// Implement Methods on the [`AdaptiveOperators`] type
+impl AdaptiveOperators {
+    /// This function starts picking between every crossover and mutation operator, each equally likely to be picked
+    pub fn new() -> Self {
+        Self {
+            crossover: AdaptivePursuit::new(CrossoverOperator::value_variants().to_vec()),
+            mutation: AdaptivePursuit::new(MutationOperator::value_variants().to_vec()),
+        }
+    }
+}
+
+/// Implement Default for AdaptiveOperators, picking between every operator
+impl Default for AdaptiveOperators {
+    fn default() -> Self {
+        Self::new()
+    }
+}
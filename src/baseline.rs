@@ -0,0 +1,46 @@
+//! This module defines [`RandomSearch`], a baseline that samples random tours with the same number
+//! of fitness evaluations as a [`Simulation`], showing how much better the genetic algorithm does than blind sampling.
+//!
+//! [`Simulation`]: crate::simulation::Simulation
+
+use color_eyre::Result;
+
+use super::{
+    chromosome::Chromosome,
+    country::Graph,
+    population::Population,
+};
+
+/// The `RandomSearch` type, which keeps the best of every random tour sampled so far
+#[derive(Debug, Clone)]
+pub struct RandomSearch {
+    /// The best tour sampled so far
+    pub best: Chromosome,
+    /// The number of tours sampled so far, each using one fitness evaluation
+    pub evaluations: u64,
+}
+
+/// Implement Methods on the [`RandomSearch`] type
+impl RandomSearch {
+    /// This function starts a random search from a new [`Population`]. Every Chromosome of a new population
+    /// is a random tour, so the population counts as the first tours sampled
+    pub fn new(population: &Population) -> Self {
+        Self {
+            best: population.best_chromosome.clone(),
+            evaluations: population.evaluations,
+        }
+    }
+
+    /// This function samples random tours until the search has used the given number of evaluations,
+    /// keeping the best one found
+    pub fn sample_until(&mut self, evaluations: u64, graph: &Graph) -> Result<()> {
+        while self.evaluations < evaluations {
+            let tour = Chromosome::generation(graph)?;
+            if tour.cost < self.best.cost {
+                self.best = tour;
+            }
+            self.evaluations += 1;
+        }
+        Ok(())
+    }
+}
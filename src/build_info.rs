@@ -0,0 +1,68 @@
+//! This module defines [`BuildInfo`], the version, commit, profile and features of the build that is running.
+//! It is written into every export and printed when the program starts, so results can always be traced to the
+//! exact code that produced them. The values are recorded by the build script when the program is compiled.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The version of the crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The short hash of the git commit the program was built from, ending in `-dirty` if it had uncommitted
+/// changes, or `unknown` if it wasn't built from a git checkout
+pub const GIT_HASH: &str = env!("TSP_GIT_HASH");
+/// The cargo profile the program was built with, `debug` or `release`
+pub const PROFILE: &str = env!("TSP_BUILD_PROFILE");
+/// The cargo features the program was built with, separated by commas
+pub const FEATURES: &str = env!("TSP_FEATURES");
+/// The version printed by `--version`, along with the commit and profile
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"), " (commit ", env!("TSP_GIT_HASH"), ", ", env!("TSP_BUILD_PROFILE"), " build)"
+);
+
+/// The build of the program that produced a set of results
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// The version of the crate
+    pub version: String,
+    /// The short hash of the git commit the program was built from
+    pub git_hash: String,
+    /// The cargo profile the program was built with
+    pub profile: String,
+    /// The cargo features the program was built with
+    pub features: Vec<String>,
+}
+
+/// Implement methods on `BuildInfo`
+impl BuildInfo {
+    /// Function to describe the build that is running
+    pub fn current() -> Self {
+        Self {
+            version: VERSION.to_string(),
+            git_hash: GIT_HASH.to_string(),
+            profile: PROFILE.to_string(),
+            features: FEATURES
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Function to give the build as a single word, e.g. `0.1.0+5ce281f.release`, for places such as a CSV
+    /// column or a plot caption where the full description is too long
+    pub fn id(&self) -> String {
+        format!("{}+{}.{}", self.version, self.git_hash, self.profile)
+    }
+}
+
+/// Implement Display for BuildInfo, describing the build in full for log headers
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tsp-coursework {} (commit {}, {} build", self.version, self.git_hash, self.profile)?;
+        if !self.features.is_empty() {
+            write!(f, ", features: {}", self.features.join(", "))?;
+        }
+        write!(f, ")")
+    }
+}
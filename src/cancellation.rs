@@ -0,0 +1,34 @@
+//! This module defines [`CancellationToken`], which asks running simulations to stop. A simulation checks its
+//! token once a generation, and local searches check it before each city they look at, so a run stops soon after
+//! the token is cancelled and keeps the stats of every generation up to that point.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag shared between everything that should stop together. Cloning a token gives another handle to the same
+/// flag, so cancelling any of them cancels them all. A token that is never cancelled never stops anything
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Whether the token has been cancelled
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Implement methods on `CancellationToken`
+impl CancellationToken {
+    /// Function to create a token that hasn't been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Function to cancel the token, returning whether it had already been cancelled
+    pub fn cancel(&self) -> bool {
+        self.cancelled.swap(true, Ordering::SeqCst)
+    }
+
+    /// Function to check if the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
@@ -3,22 +3,40 @@
 //! [`Population`]: crate::population::Population
 
 use super::{
+    cancellation::CancellationToken,
     country::Graph, 
     interface::{
         MutationOperator, 
-        CrossoverOperator
-    }
+        CrossoverOperator,
+        LocalSearch,
+        Summation,
+    },
+    random::rng,
+    tour::{ArrayTour, Tour, TreeTour, TREE_TOUR_CITIES},
 };
 
-use rand::{thread_rng, Rng, seq::{SliceRandom, index}};
-use std::cmp::Ordering;
-use color_eyre::{eyre::ContextCompat, Result};
+use rand::{Rng, distributions::{Distribution, WeightedIndex}, seq::{SliceRandom, index}};
+use std::{cmp::Ordering, collections::{BTreeSet, HashSet, VecDeque}, hash::{DefaultHasher, Hash, Hasher}};
+use color_eyre::{eyre::{bail, ContextCompat}, Result};
+use serde::{Deserialize, Serialize};
+
+/// The relative difference below which two costs count as equal on datasets whose costs aren't all whole numbers,
+/// so costs that only differ by rounding in how they were added up are treated as the same
+pub const DEFAULT_COST_EPSILON: f64 = 1e-9;
+
+/// The fewest cities a route can have for every genetic operator to be able to pick genes to change
+pub const MINIMUM_CITIES: usize = 3;
 
 /// This defines a chromosome in the population, it has a vector "route" which contains the city numbers in the order they're visited
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Chromosome {
     pub route: Vec<u32>,
     pub cost: f64,
+    /// Identifier given by the [`Population`] when the Chromosome joins it, so a lower id is an older Chromosome.
+    /// Chromosomes that have not joined a population have an id of 0
+    /// 
+    /// [`Population`]: crate::population::Population
+    pub id: u64,
 }
 
 /// Implements [`PartialEq`] for Chromosome so two chromosomes can be tested for equality or lack thereof
@@ -46,7 +64,75 @@ impl Chromosome {
     /// 
     /// [`generation`]: Chromosome::generation
     pub fn new(route: Vec<u32>, cost: f64) -> Self {
-        Self { route, cost }
+        Self { route, cost, id: 0 }
+    }
+
+    /// Function to compare two costs, treating them as equal if they differ by no more than epsilon times the 
+    /// larger of the two. An epsilon of 0 compares them exactly, as does an infinite cost
+    pub fn compare_costs(x: f64, y: f64, epsilon: f64) -> Ordering {
+        let tolerance: f64 = epsilon * x.abs().max(y.abs());
+        if tolerance.is_finite() && (x - y).abs() <= tolerance {
+            Ordering::Equal
+        } else {
+            x.total_cmp(&y)
+        }
+    }
+
+    /// Function to list the edges of a route, including the edge from the last city back to the first.
+    /// Each edge is given with the smaller city first so edges can be compared regardless of direction
+    pub fn edges(route: &[u32]) -> Vec<(u32, u32)> {
+        route
+            .iter()
+            .zip(route.iter().cycle().skip(1))
+            .map(|(from, to)| (*from.min(to), *from.max(to)))
+            .collect()
+    }
+
+    /// Function to count how many edges two Chromosomes share, ignoring the direction each edge is travelled in.
+    /// Identical routes share every edge, so a lower count means the routes are more different
+    pub fn shared_edges(&self, other: &Chromosome) -> usize {
+        let other_edges: HashSet<(u32, u32)> = Chromosome::edges(&other.route).into_iter().collect();
+        Chromosome::edges(&self.route)
+            .iter()
+            .filter(|edge| other_edges.contains(edge))
+            .count()
+    }
+
+    /// Function to count how many edges of the route, including the edge back to the start, travel between cities
+    /// the graph has no edge between. A route that uses none is a feasible tour
+    pub fn forbidden_edges(&self, graph: &Graph) -> usize {
+        self.route
+            .iter()
+            .zip(self.route.iter().cycle().skip(1))
+            .filter(|(from, to)| graph.distances.is_forbidden(**from, **to))
+            .count()
+    }
+
+    /// Function to count how many edges of this Chromosome the other doesn't have, ignoring the direction each edge
+    /// is travelled in. Identical routes are 0 apart, and routes sharing no edges are as far apart as they are long
+    pub fn distance(&self, other: &Chromosome) -> usize {
+        self.route.len().saturating_sub(self.shared_edges(other))
+    }
+
+    /// Function to give the route in a normal form, starting from the lowest numbered city and heading towards 
+    /// the lower numbered of its two neighbours. Every route describing the same tour, whichever city it starts 
+    /// from and whichever direction it goes in, has the same normal form
+    pub fn normalised_route(&self) -> Vec<u32> {
+        let mut route: Vec<u32> = self.route.clone();
+        if let Some(start) = route.iter().enumerate().min_by_key(|(_, city)| **city).map(|(position, _)| position) {
+            route.rotate_left(start);
+        }
+        if route.len() > 2 && route[route.len() - 1] < route[1] {
+            route[1..].reverse();
+        }
+        route
+    }
+
+    /// Function to hash the normal form of the route, so Chromosomes with the same tour have the same hash
+    pub fn tour_hash(&self) -> u64 {
+        let mut hasher: DefaultHasher = DefaultHasher::new();
+        self.normalised_route().hash(&mut hasher);
+        hasher.finish()
     }
 
     /// Function to randomly generate a [`Chromosome`]
@@ -60,17 +146,46 @@ impl Chromosome {
         // Create a vector the length of the number of the cities, initialised as a range from 0 to num_cities -1, i.e 0,1,2,3.....
         let mut vec: Vec<u32> = (0..num_cities as u32).collect();
         // Randomly shuffle the sequence of this vector
-        // thread_rng() is a handle to a thread-local CSPRNG with periodic seeding from an interface to the operating system’s random number source
-        vec.shuffle(&mut thread_rng());
+        // rng() is a handle to the generator of this thread, which is seeded if the run is
+        vec.shuffle(&mut rng());
 
         let fitness: f64 = Chromosome::fitness(&vec, graph)?;
         // Return this vector as the route in the Chromosome
         Ok(Self {
             route: vec,
             cost: fitness,
+            id: 0,
         })
     }
 
+    /// Function to build a [`Chromosome`] by nearest neighbour construction, starting from the given city and always
+    /// travelling to the nearest city not yet visited, with the lowest numbered city taken when several are as near.
+    /// The tour is usually far cheaper than a random one, but takes time proportional to the square of the number of cities
+    pub fn nearest_neighbour(start: u32, graph: &Graph) -> Result<Self> {
+        let num_cities: usize = graph.distances.size;
+        let mut visited: Vec<bool> = vec![false; num_cities];
+        let mut route: Vec<u32> = Vec::with_capacity(num_cities);
+
+        let mut current: u32 = start;
+        for _ in 0..num_cities {
+            *visited.get_mut(current as usize).wrap_err("Error: Could not obtain Chromosome data")? = true;
+            route.push(current);
+
+            // Find the nearest city not yet visited, stopping once every city has been
+            let row: &[f64] = &graph.distances.costs[current as usize * num_cities..(current as usize + 1) * num_cities];
+            match (0..num_cities)
+                .filter(|city| !visited[*city])
+                .min_by(|x, y| row[*x].total_cmp(&row[*y]).then(x.cmp(y)))
+            {
+                Some(next) => current = next as u32,
+                None => break,
+            }
+        }
+
+        let cost: f64 = Chromosome::fitness(&route, graph)?;
+        Ok(Self { route, cost, id: 0 })
+    }
+
     /// Function to use inversion mutation on a [`Chromosome`]
     /// Like rust .. format first index is inclusive and second_index is exclusive
     /// Therefore it must be ensured that they are not the same
@@ -99,77 +214,643 @@ impl Chromosome {
         let _ = std::mem::replace(&mut self.route, new_route);
     }
 
-    /// Function to mutate a [`Chromosome`]s genes using multiple different methods
+    /// Function to find how much [`Chromosome::inversion`] changes the cost of the route from the edges it removes and
+    /// adds, without changing the route. The cities outside the slice are reversed as one, so when costs are the
+    /// same in both directions only the edges where the start, slice and end of the route meet change, along with
+    /// the edge the reversed cities are split at and the edge joining the end of the route to its start
+    pub fn inversion_delta(&self, first_index: usize, second_index: usize, graph: &Graph) -> Result<f64> {
+        let (start, rest) = self.route.split_at(first_index);
+        let (centre, end) = rest.split_at(second_index - first_index);
+        let outside: usize = start.len() + end.len();
+
+        // The cities outside the slice after reversing them, the end of the route reversed then the start reversed
+        let reversed = |index: usize| match index < end.len() {
+            true => end[end.len() - 1 - index],
+            false => start[start.len() - 1 - (index - end.len())],
+        };
+
+        // The edges where each part of the route meets the next, wrapping round from the last part to the first
+        let joins = |parts: &[Option<(u32, u32)>]| -> Vec<(u32, u32)> {
+            let parts: Vec<(u32, u32)> = parts.iter().flatten().copied().collect();
+            (0..parts.len()).map(|part| (parts[part].1, parts[(part + 1) % parts.len()].0)).collect()
+        };
+        let ends = |part: &[u32]| part.first().zip(part.last()).map(|(first, last)| (*first, *last));
+
+        // Before, the start, centre and end of the route meet. After, the reversed cities are split around the centre
+        let centre: Option<(u32, u32)> = ends(centre);
+        let mut removed: Vec<(u32, u32)> = joins(&[ends(start), centre, ends(end)]);
+        let mut added: Vec<(u32, u32)> = joins(&[
+            (first_index > 0).then(|| (reversed(0), reversed(first_index - 1))),
+            centre,
+            (first_index < outside).then(|| (reversed(first_index), reversed(outside - 1))),
+        ]);
+
+        // Reversing the cities outside the slice joins the first city of the end to the last city of the start,
+        // and splitting them around the centre removes the edge they are split at
+        if let (Some(first_of_end), Some(last_of_start)) = (end.first(), start.last()) {
+            added.push((*first_of_end, *last_of_start));
+        }
+        if first_index > 0 && first_index < outside {
+            removed.push((reversed(first_index - 1), reversed(first_index)));
+        }
+
+        let mut delta: f64 = 0.0;
+        for (edges, sign) in [(added, 1.0), (removed, -1.0)] {
+            for (from, to) in edges {
+                delta += sign * graph.distances.get(from, to).wrap_err("Error: Could not obtain Chromosome data")?;
+            }
+        }
+        Ok(delta)
+    }
+
+    /// Function to list the index in the route of the first city of every edge that touches one of the given positions.
+    /// The edge starting at index i goes from route[i] to route[i + 1], wrapping round to the first city
+    pub fn edges_touching(positions: impl IntoIterator<Item = usize>, route_length: usize) -> BTreeSet<usize> {
+        let mut starts: BTreeSet<usize> = BTreeSet::new();
+        for position in positions {
+            // The edge coming into this position and the edge leaving it
+            starts.insert((position + route_length - 1) % route_length);
+            starts.insert(position);
+        }
+        starts
+    }
+
+    /// Function to total the cost of the edges starting at the given indices of the route
+    pub fn edge_costs(route: &[u32], starts: &BTreeSet<usize>, graph: &Graph) -> Result<f64> {
+        let mut cost: f64 = 0.0;
+        for start in starts {
+            cost += graph.distances
+                .get(route[*start], route[(*start + 1) % route.len()])
+                .wrap_err("Error: Could not obtain Chromosome data")?;
+        }
+        Ok(cost)
+    }
+
+    /// Function to apply a change to the route that only moves the cities at the given positions, updating
+    /// the cost by subtracting the edges that are removed and adding the ones that replace them rather
+    /// than recalculating the cost of the whole route
+    pub fn change_with_delta(
+        &mut self, 
+        positions: impl IntoIterator<Item = usize>, 
+        graph: &Graph, 
+        change: impl FnOnce(&mut Self)
+    ) -> Result<()> {
+        // Find every edge that could be changed
+        let starts: BTreeSet<usize> = Chromosome::edges_touching(positions, self.route.len());
+
+        // Cost of those edges before and after the change
+        let removed: f64 = Chromosome::edge_costs(&self.route, &starts, graph)?;
+        change(self);
+        let added: f64 = Chromosome::edge_costs(&self.route, &starts, graph)?;
+
+        // Update the cost of the Chromosome
+        self.cost += added - removed;
+        Ok(())
+    }
+
+    /// Function to mutate a [`Chromosome`]s genes using multiple different methods. 
+    /// The cost is updated using only the edges the mutation changes
     pub fn mutation(&mut self, mutation_operator: MutationOperator, graph: &Graph) -> Result<()> {
+        // Routes of fewer than 3 cities are too short for every operator to pick genes to change
+        if self.route.len() < MINIMUM_CITIES {
+            bail!("Mutation needs routes of at least {} cities, not {}", MINIMUM_CITIES, self.route.len());
+        }
+
         // Pattern match off Enum MutationOperator
         match mutation_operator {
             // Inversion
             MutationOperator::Inversion => {
                 // Select which  to swap randomly
-                let first_index: usize = thread_rng().gen_range(1..=self.route.len());
-                let mut second_index: usize = thread_rng().gen_range(1..=self.route.len());
+                let first_index: usize = rng().gen_range(1..=self.route.len());
+                let mut second_index: usize = rng().gen_range(1..=self.route.len());
                 
                 // If the second index is the same as the first, regenerate it
                 while second_index == first_index {
-                    second_index = thread_rng().gen_range(0..self.route.len());
-                }
-
-                match first_index.cmp(&second_index) {
-                    // If the first index is lower, use that to create the first slice
-                    Ordering::Less => {
-                        // Run inversion on chromosome
-                        Chromosome::inversion(self, first_index, second_index);
-                    
-                        // Update the cost of the Chromosome
-                        let _ = std::mem::replace(&mut self.cost, Chromosome::fitness(&self.route, graph)?);
-                        Ok(())
-                    },
-                    // If the second index is lower, use that to create the first slice
-                    Ordering::Greater => {
-                        // Run inversion on chromosome
-                        Chromosome::inversion(self, second_index, first_index);
-
-                        // Update the cost of the Chromosome
-                        let _ = std::mem::replace(&mut self.cost, Chromosome::fitness(&self.route, graph)?);
-                        Ok(())
-                    },
-                    // Unreachable due to while loop above
-                    Ordering::Equal => unreachable!()
+                    second_index = rng().gen_range(0..self.route.len());
                 }
+                self.mutate_at(mutation_operator, &[first_index, second_index], graph)
             },
             // Single Swap
             MutationOperator::Single => {
                 // Select which genes to swap randomly
-                let first_gene: usize = thread_rng().gen_range(0..self.route.len());
-                let mut second_gene: usize = thread_rng().gen_range(0..self.route.len());
+                let first_gene: usize = rng().gen_range(0..self.route.len());
+                let mut second_gene: usize = rng().gen_range(0..self.route.len());
 
                 // If the second gene is the same as the first, regenerate it
                 while second_gene == first_gene {
-                    second_gene = thread_rng().gen_range(0..self.route.len());
+                    second_gene = rng().gen_range(0..self.route.len());
+                }
+                self.mutate_at(mutation_operator, &[first_gene, second_gene], graph)
+            },
+            // Multiple Swap
+            MutationOperator::Multiple => {
+                // Routes of fewer than 4 cities don't have two separate pairs of genes to swap, so only swap one pair
+                if self.route.len() < 4 {
+                    return self.mutation(MutationOperator::Single, graph);
+                }
+
+                // Randomly sample 4 distinct indices from 0..self.route.len(), and return them in random order (fully shuffled).
+                let results = index::sample(&mut rng(), self.route.len(), 4).into_vec();
+                self.mutate_at(mutation_operator, &results, graph)
+            },
+            // Scramble
+            MutationOperator::Scramble => {
+                // Randomly pick the start and end of a slice containing at least two genes,
+                // like rust .. format start is inclusive and end is exclusive
+                let start: usize = rng().gen_range(0..self.route.len() - 1);
+                let end: usize = rng().gen_range(start + 2..=self.route.len());
+                self.mutate_at(mutation_operator, &[start, end], graph)
+            },
+            // Displacement
+            MutationOperator::Displacement => {
+                // Randomly pick the length of the segment, leaving at least one gene outside of it
+                let segment_length: usize = rng().gen_range(1..self.route.len());
+                self.displace_segment(segment_length, graph)
+            },
+            // Or-opt
+            MutationOperator::OrOpt => {
+                // Randomly pick a chain of 1 to 3 genes, leaving at least one gene outside of it
+                let segment_length: usize = rng().gen_range(1..=3.min(self.route.len() - 1));
+                self.displace_segment(segment_length, graph)
+            },
+            // Insertion
+            MutationOperator::Insertion => {
+                let length: usize = self.route.len();
+
+                // Randomly pick a gene to move and where it goes in the genes left once it has been removed
+                let remove: usize = rng().gen_range(0..length);
+                let mut insert: usize = rng().gen_range(0..length);
+
+                // If the gene would go back where it came from, regenerate the position
+                while insert == remove {
+                    insert = rng().gen_range(0..length);
+                }
+                self.mutate_at(mutation_operator, &[remove, insert], graph)
+            },
+            // Expensive edge
+            MutationOperator::ExpensiveEdge => self.expensive_edge_inversion(graph),
+        }
+    }
+
+    /// Function to apply a mutation at the given indices rather than random ones, so its outcome is known in
+    /// advance. The cost is updated using only the edges the mutation changes. Each operator takes:
+    ///
+    /// - Inversion: two different indices from 0 to the length of the route, the cities outside of the slice
+    ///   between them are reversed
+    /// - Single: the two genes to swap
+    /// - Multiple: four genes, the first is swapped with the second and the third with the fourth
+    /// - Scramble: the start and end of the slice to shuffle, the only operator whose outcome is still random
+    /// - Displacement and Or-opt: the start and end of the segment to cut out, then where it goes in the genes
+    ///   left once it has been cut out
+    /// - Insertion: the gene to move, then where it goes in the genes left once it has been removed
+    /// - Expensive edge: the start of the two edges to remove, the cities between them are reversed
+    pub fn mutate_at(&mut self, mutation_operator: MutationOperator, indices: &[usize], graph: &Graph) -> Result<()> {
+        let expected: usize = match mutation_operator {
+            MutationOperator::Multiple => 4,
+            MutationOperator::Displacement | MutationOperator::OrOpt => 3,
+            _ => 2,
+        };
+        if indices.len() != expected {
+            bail!("{:?} mutation takes {} indices but was given {}", mutation_operator, expected, indices.len());
+        }
+        let length: usize = self.route.len();
+
+        // Check the indices before anything is changed, so invalid ones are an error rather than a panic. Operators
+        // that take the bounds of a slice can be given the length of the route, the rest need the index of a gene
+        let limit: usize = match mutation_operator {
+            MutationOperator::Inversion
+            | MutationOperator::Scramble
+            | MutationOperator::Displacement
+            | MutationOperator::OrOpt => length + 1,
+            _ => length,
+        };
+        if let Some(index) = indices.iter().find(|index| **index >= limit) {
+            bail!("{:?} mutation was given index {} but the route has {} genes", mutation_operator, index, length);
+        }
+        match mutation_operator {
+            MutationOperator::Scramble | MutationOperator::Displacement | MutationOperator::OrOpt
+                if indices[0] > indices[1] =>
+            {
+                bail!("{:?} mutation was given a start {} after its end {}", mutation_operator, indices[0], indices[1]);
+            },
+            MutationOperator::Displacement | MutationOperator::OrOpt => {
+                // The segment can go anywhere in the genes left once it has been cut out, including after the last
+                let left: usize = length - (indices[1] - indices[0]);
+                if indices[2] > left {
+                    bail!(
+                        "{:?} mutation was given insert position {} but only {} genes are left without the segment",
+                        mutation_operator, indices[2], left
+                    );
                 }
+            },
+            _ => {},
+        }
 
-                // Swap the first gene with the second gene
-                self.route.swap(first_gene, second_gene);
+        match mutation_operator {
+            // Inversion
+            MutationOperator::Inversion => {
+                // Inversion only moves the cities outside of the centre slice
+                let (lower, upper) = match indices[0].cmp(&indices[1]) {
+                    // If the first index is lower, use that to create the first slice
+                    Ordering::Less => (indices[0], indices[1]),
+                    // If the second index is lower, use that to create the first slice
+                    Ordering::Greater => (indices[1], indices[0]),
+                    Ordering::Equal => bail!("Inversion mutation needs two different indices"),
+                };
 
-                // Update the cost of the Chromosome
-                let _ = std::mem::replace(&mut self.cost, Chromosome::fitness(&self.route, graph)?);
-                Ok(())
+                // Run inversion on chromosome, updating the cost of the Chromosome from the few edges that change.
+                // When costs differ by direction every reversed edge changes, so they are all costed again
+                if graph.symmetric {
+                    let delta: f64 = self.inversion_delta(lower, upper, graph)?;
+                    Chromosome::inversion(self, lower, upper);
+                    self.cost += delta;
+                    Ok(())
+                } else {
+                    self.change_with_delta((0..lower).chain(upper..length), graph, |chromosome| {
+                        Chromosome::inversion(chromosome, lower, upper)
+                    })
+                }
+            },
+            // Single Swap
+            MutationOperator::Single => {
+                // Swap the first gene with the second gene, updating the cost of the Chromosome
+                let (first_gene, second_gene) = (indices[0], indices[1]);
+                self.change_with_delta([first_gene, second_gene], graph, |chromosome| {
+                    chromosome.route.swap(first_gene, second_gene)
+                })
             },
             // Multiple Swap
             MutationOperator::Multiple => {
-                // Randomly sample 4 distinct indices from 0..self.route.len(), and return them in random order (fully shuffled).
-                let results = index::sample(&mut thread_rng(), self.route.len(), 4).into_vec();
+                // Swap the first gene with the second gene and the third gene with the fourth gene,
+                // updating the cost of the Chromosome
+                self.change_with_delta(indices.iter().copied(), graph, |chromosome| {
+                    chromosome.route.swap(indices[0], indices[1]);
+                    chromosome.route.swap(indices[2], indices[3]);
+                })
+            },
+            // Scramble
+            MutationOperator::Scramble => {
+                // Shuffle the genes in the slice, updating the cost of the Chromosome
+                let (start, end) = (indices[0], indices[1]);
+                self.change_with_delta(start..end, graph, |chromosome| {
+                    chromosome.route[start..end].shuffle(&mut rng())
+                })
+            },
+            // Displacement and Or-opt
+            MutationOperator::Displacement | MutationOperator::OrOpt => {
+                let (start, end, insert) = (indices[0], indices[1], indices[2]);
+
+                // Only genes between the old and new positions of the segment move
+                let first_moved: usize = start.min(insert);
+                let last_moved: usize = start.max(insert) + end - start;
+
+                // Cut the segment out and reinsert it, updating the cost of the Chromosome
+                self.change_with_delta(first_moved..last_moved, graph, |chromosome| {
+                    let segment: Vec<u32> = chromosome.route.drain(start..end).collect();
+                    chromosome.route.splice(insert..insert, segment);
+                })
+            },
+            // Insertion
+            MutationOperator::Insertion => {
+                let (remove, insert) = (indices[0], indices[1]);
 
-                // Swap the first gene with the second gene
-                self.route.swap(results[0], results[1]);
-                // Swap the third gene with the fourth gene
-                self.route.swap(results[2], results[3]);
+                // Only genes between the old and new positions of the gene move
+                let first_moved: usize = remove.min(insert);
+                let last_moved: usize = remove.max(insert);
 
-                // Update the cost of the Chromosome
-                let _ = std::mem::replace(&mut self.cost, Chromosome::fitness(&self.route, graph)?);
-                Ok(())
+                // Remove the gene and reinsert it, updating the cost of the Chromosome
+                self.change_with_delta(first_moved..=last_moved, graph, |chromosome| {
+                    let gene: u32 = chromosome.route.remove(remove);
+                    chromosome.route.insert(insert, gene);
+                })
             },
+            // Expensive edge
+            MutationOperator::ExpensiveEdge => {
+                // Reverse the cities after the first edge up to the start of the second, which replaces both edges
+                let (lower, upper) = (indices[0].min(indices[1]), indices[0].max(indices[1]));
+                self.change_with_delta(lower + 1..=upper, graph, |chromosome| {
+                    chromosome.route[lower + 1..=upper].reverse()
+                })
+            },
+        }
+    }
+
+    /// Function to reverse the section of the route between two edges, each picked by roulette over the cost of the
+    /// edges of the route so the most expensive edges are the most likely to be removed. The edges on either side of
+    /// the first are left out of the second roulette so the reversal always changes the tour. The cost is updated
+    /// using only the edges that change. Routes of 3 or fewer cities only have one tour, so are left as they are
+    pub fn expensive_edge_inversion(&mut self, graph: &Graph) -> Result<()> {
+        let length: usize = self.route.len();
+        if length < 4 {
+            return Ok(());
+        }
+
+        // The edge starting at index i goes from route[i] to route[i + 1], wrapping round to the first city
+        let mut weights: Vec<f64> = (0..length)
+            .map(|start| graph.distances.get(self.route[start], self.route[(start + 1) % length]).unwrap_or(0.0))
+            .collect();
+
+        // Every edge is equally likely if they cost nothing, as a roulette needs some weight
+        if weights.iter().all(|weight| *weight <= 0.0) {
+            weights = vec![1.0; length];
+        }
+        let first: usize = WeightedIndex::new(&weights)?.sample(&mut rng());
+
+        // Reversing the section between neighbouring edges would only reverse a single city
+        let excluded: [usize; 3] = [(first + length - 1) % length, first, (first + 1) % length];
+        for start in excluded {
+            weights[start] = 0.0;
+        }
+
+        // If only the edges left out cost anything, every other edge is equally likely
+        if weights.iter().all(|weight| *weight <= 0.0) {
+            weights = (0..length)
+                .map(|start| if excluded.contains(&start) { 0.0 } else { 1.0 })
+                .collect();
+        }
+        let second: usize = WeightedIndex::new(&weights)?.sample(&mut rng());
+        self.mutate_at(MutationOperator::ExpensiveEdge, &[first, second], graph)
+    }
+
+    /// Function to cut a randomly placed segment of the given length out of the route and reinsert it at a 
+    /// different random position, updating the cost using only the edges that change
+    pub fn displace_segment(&mut self, segment_length: usize, graph: &Graph) -> Result<()> {
+        let length: usize = self.route.len();
+
+        // Randomly pick where the segment starts
+        let start: usize = rng().gen_range(0..=length - segment_length);
+
+        // Randomly pick where the segment goes in the genes left once it has been cut out
+        let mut insert: usize = rng().gen_range(0..=length - segment_length);
+
+        // If the segment would go back where it came from, regenerate the position
+        while insert == start {
+            insert = rng().gen_range(0..=length - segment_length);
+        }
+        self.mutate_at(MutationOperator::Displacement, &[start, start + segment_length, insert], graph)
+    }
+
+    /// Function to improve a [`Chromosome`] using a local search, then recalculate its cost
+    pub fn local_search(&mut self, local_search: LocalSearch, graph: &Graph) -> Result<()> {
+        // Pattern match off Enum LocalSearch
+        match local_search {
+            LocalSearch::TwoOpt => self.two_opt(graph)?,
+            LocalSearch::OrOpt => self.or_opt(graph)?,
+        };
+
+        // Recalculate the cost of the improved route
+        self.cost = Chromosome::fitness(&self.route, graph)?;
+        Ok(())
+    }
+
+    /// Function to improve a [`Chromosome`] using a local search that starts from only the given cities, then 
+    /// recalculate its cost. When a route that is already a local optimum has only changed around a few cities, 
+    /// searching from those cities finds the same improvements as searching the whole route for far fewer moves.
+    /// If the cancellation token is cancelled, or the given number of moves have been evaluated, the search stops
+    /// early, leaving the route improved as far as it got. Returns how many moves were evaluated, see
+    /// [`LocalSearch::evaluations`]
+    pub fn local_search_around(
+        &mut self,
+        local_search: LocalSearch,
+        cities: &[u32],
+        graph: &Graph,
+        cancellation: &CancellationToken,
+        max_moves: u64,
+    ) -> Result<u64> {
+        // Pattern match off Enum LocalSearch
+        let (_, moves) = match local_search {
+            LocalSearch::TwoOpt => self.two_opt_from(cities.iter().copied(), graph, cancellation, max_moves)?,
+            LocalSearch::OrOpt => self.or_opt_from(cities.iter().copied(), graph, cancellation, max_moves)?,
+        };
+
+        // Recalculate the cost of the improved route
+        self.cost = Chromosome::fitness(&self.route, graph)?;
+        Ok(moves)
+    }
+
+    /// Function to repeatedly reverse the section of the route between two edges whenever swapping those edges
+    /// for the ones joining their ends makes the route cheaper, until no reversal improves it.
+    /// Each move is compared using only the two edges it removes and the two it adds, so the cost
+    /// is left for the caller to update. Returns whether the route was changed
+    pub fn two_opt(&mut self, graph: &Graph) -> Result<bool> {
+        let cities: Vec<u32> = self.route.clone();
+        Ok(self.two_opt_from(cities, graph, &CancellationToken::new(), u64::MAX)?.0)
+    }
+
+    /// Function to do the same as [`Chromosome::two_opt`], starting from only the given cities. Each city has a 
+    /// don't look bit, a city is only looked at while its bit is off, and once no move from a city improves the 
+    /// route its bit is turned on. A move turns the bits of the four cities at its ends off again, so only 
+    /// cities whose neighbours have changed are looked at twice.
+    /// 
+    /// For a city a next to b, a-b and another edge c-d are swapped for a-c and b-d, trying the edges on both 
+    /// sides of a. If the graph has candidate lists c is only one of the candidates of a, nearest first, so once 
+    /// a candidate is no closer to a than b is no later one can be, as a move needs one of its new edges to be 
+    /// shorter than the edge it replaces. This makes 2-opt fast on countries with thousands of cities.
+    /// 
+    /// Routes with at least [`TREE_TOUR_CITIES`] cities are searched as a [`TreeTour`], so each reversal only 
+    /// moves a few cities of the tree rather than up to half of the route. The search stops early once the
+    /// cancellation token is cancelled, or once the given number of moves have been evaluated. A move reverses the section of the route between its edges, which changes
+    /// the cost of every edge in it when costs differ by direction, so routes of asymmetric graphs are left as
+    /// they are. Returns whether the route was changed and how many moves were evaluated
+    pub fn two_opt_from(
+        &mut self,
+        cities: impl IntoIterator<Item = u32>,
+        graph: &Graph,
+        cancellation: &CancellationToken,
+        max_moves: u64,
+    ) -> Result<(bool, u64)> {
+        if !graph.symmetric {
+            return Ok((false, 0));
+        }
+        let searched: (bool, u64) = if self.route.len() >= TREE_TOUR_CITIES {
+            let mut tour: TreeTour = TreeTour::new(&self.route);
+            let searched: (bool, u64) = Chromosome::two_opt_tour(&mut tour, cities, graph, cancellation, max_moves)?;
+            self.route = tour.route();
+            searched
+        } else {
+            let mut tour: ArrayTour = ArrayTour::new(&self.route);
+            let searched: (bool, u64) = Chromosome::two_opt_tour(&mut tour, cities, graph, cancellation, max_moves)?;
+            self.route = tour.route();
+            searched
+        };
+        Ok(searched)
+    }
+
+    /// Function to run the 2-opt of [`Chromosome::two_opt_from`] on any [`Tour`], starting from the given cities.
+    /// Returns whether the tour was changed and how many moves were evaluated
+    pub fn two_opt_tour(
+        tour: &mut impl Tour,
+        cities: impl IntoIterator<Item = u32>,
+        graph: &Graph,
+        cancellation: &CancellationToken,
+        max_moves: u64,
+    ) -> Result<(bool, u64)> {
+        let length: usize = tour.len();
+        let cost = |from: u32, to: u32| graph.distances.get(from, to).wrap_err("Error: Could not obtain Chromosome data");
+        let all_cities: Vec<u32> = (0..length as u32).collect();
+
+        let mut bits: DontLookBits = DontLookBits::new(cities, length);
+        let mut changed: bool = false;
+        let mut moves: u64 = 0;
+        while let Some(a) = bits.pop(cancellation) {
+            // Try the edge after a then the edge before it
+            'sides: for after in [true, false] {
+                let b: u32 = if after { tour.next(a) } else { tour.prev(a) };
+
+                // With candidate lists only try the candidates of a, otherwise try every city
+                let partners: &[u32] = if graph.candidates.is_empty() {
+                    &all_cities
+                } else {
+                    graph.candidates.get(a as usize).map_or(&[], |candidates| candidates.as_slice())
+                };
+                for c in partners.iter().copied() {
+                    // Nearer candidates have been tried, so none of the rest can make a cheaper route
+                    if !graph.candidates.is_empty() && cost(a, c)? >= cost(a, b)? {
+                        break;
+                    }
+
+                    // d is on the same side of c as b is of a
+                    if c == a || c == b {
+                        continue;
+                    }
+                    let d: u32 = if after { tour.next(c) } else { tour.prev(c) };
+                    if d == a {
+                        continue;
+                    }
+
+                    // The search may evaluate no more moves
+                    if moves == max_moves {
+                        return Ok((changed, moves));
+                    }
+
+                    // Swap edges a-b and c-d for a-c and b-d if it is cheaper, ignoring differences from rounding
+                    let delta: f64 = cost(a, c)? + cost(b, d)? - cost(a, b)? - cost(c, d)?;
+                    moves += 1;
+                    if delta < -1e-9 {
+                        // Going forwards the route runs a, b, ..., c, d, or d, c, ..., b, a if the edge is before a
+                        if after {
+                            tour.reverse(b, c);
+                        } else {
+                            tour.reverse(a, d);
+                        }
+                        for city in [a, b, c, d] {
+                            bits.wake(city);
+                        }
+                        changed = true;
+                        break 'sides;
+                    }
+                }
+            }
         }
+        Ok((changed, moves))
+    }
+
+    /// Function to repeatedly move chains of 1 to 3 consecutive cities, keeping their order, to between two
+    /// other neighbouring cities whenever that makes the route cheaper, until no move improves it.
+    /// If the graph has candidate lists, a chain is only moved next to one of the candidates of its first or last city.
+    /// Each move is compared using only the three edges it removes and the three it adds, so the cost
+    /// is left for the caller to update. Returns whether the route was changed
+    pub fn or_opt(&mut self, graph: &Graph) -> Result<bool> {
+        // A move can join two cities that a chain whose bit is on would be cheaper between, so the whole route is
+        // searched again until a search moves nothing
+        let mut changed: bool = false;
+        while self.or_opt_from(self.route.clone(), graph, &CancellationToken::new(), u64::MAX)?.0 {
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Function to do the same as [`Chromosome::or_opt`], starting from only the given cities. Like
+    /// [`Chromosome::two_opt_from`] each city has a don't look bit, the chains starting at a city are only
+    /// tried while its bit is off. A move changes three edges, and turns off the bits of every city whose chains
+    /// start or end at one of them, which is the city after each edge, the city before it and the two cities before
+    /// that. Chains may wrap around the end of the route. The search stops early once the cancellation token is
+    /// cancelled, or once the given number of moves have been evaluated. Returns whether the route was changed and
+    /// how many moves were evaluated
+    pub fn or_opt_from(
+        &mut self,
+        cities: impl IntoIterator<Item = u32>,
+        graph: &Graph,
+        cancellation: &CancellationToken,
+        max_moves: u64,
+    ) -> Result<(bool, u64)> {
+        let length: usize = self.route.len();
+        let cost = |from: u32, to: u32| graph.distances.get(from, to).wrap_err("Error: Could not obtain Chromosome data");
+
+        // The position of each city in the route, found again whenever a chain moves
+        let find_positions = |route: &[u32]| -> Vec<usize> {
+            let mut positions: Vec<usize> = vec![0; route.len()];
+            for (position, city) in route.iter().enumerate() {
+                positions[*city as usize] = position;
+            }
+            positions
+        };
+        let mut positions: Vec<usize> = find_positions(&self.route);
+
+        let mut bits: DontLookBits = DontLookBits::new(cities, length);
+        let mut changed: bool = false;
+        let mut moves: u64 = 0;
+        while let Some(city) = bits.pop(cancellation) {
+            // A chain needs at least two edges outside of it that it doesn't touch to move between
+            'chains: for chain_length in 1..=3.min(length.saturating_sub(3)) {
+                let start: usize = positions[city as usize];
+                let end: usize = (start + chain_length - 1) % length;
+
+                // The cities either side of the chain, and the ends of the chain
+                let before: u32 = self.route[(start + length - 1) % length];
+                let after: u32 = self.route[(end + 1) % length];
+                let (first, last) = (self.route[start], self.route[end]);
+
+                // Saving from cutting the chain out and joining the cities either side of it
+                let removed: f64 = cost(before, first)? + cost(last, after)? - cost(before, after)?;
+
+                // Try every edge that doesn't touch the chain, the edge starting at i goes from route[i] to route[i + 1].
+                // With candidate lists only try the edges from a candidate of the first city, and to a candidate of the last
+                let edges: Vec<usize> = if graph.candidates.is_empty() {
+                    (0..length).collect()
+                } else {
+                    let near_first = graph.candidates[first as usize].iter().map(|city| positions[*city as usize]);
+                    let near_last = graph.candidates[last as usize].iter().map(|city| (positions[*city as usize] + length - 1) % length);
+                    near_first.chain(near_last).collect()
+                };
+                for i in edges {
+                    // Skip the edge into the chain, the edges inside it and the edge out of it
+                    if (i + 1 + length - start) % length <= chain_length {
+                        continue;
+                    }
+                    let (a, b) = (self.route[i], self.route[(i + 1) % length]);
+
+                    // The search may evaluate no more moves
+                    if moves == max_moves {
+                        return Ok((changed, moves));
+                    }
+
+                    // Move the chain between a and b if it is cheaper, ignoring differences from rounding
+                    let added: f64 = cost(a, first)? + cost(last, b)? - cost(a, b)?;
+                    moves += 1;
+                    if added - removed < -1e-9 {
+                        // Turn the route so the chain starts it, as a chain can wrap around the end of the route
+                        self.route.rotate_left(start);
+                        let chain: Vec<u32> = self.route.drain(..chain_length).collect();
+                        let insert: usize = self.route
+                            .iter()
+                            .position(|city| *city == a)
+                            .wrap_err("Error: Could not find city in route")? + 1;
+                        self.route.splice(insert..insert, chain);
+                        positions = find_positions(&self.route);
+                        for (from, to) in [(before, after), (a, first), (last, b)] {
+                            bits.wake(to);
+                            for behind in 0..3 {
+                                bits.wake(self.route[(positions[from as usize] + length - behind) % length]);
+                            }
+                        }
+                        changed = true;
+                        break 'chains;
+                    }
+                }
+            }
+        }
+        Ok((changed, moves))
     }
 
     /// Function to fix a crossover, taking the child and slices from both parents
@@ -215,22 +896,26 @@ impl Chromosome {
         }
     }
 
-    /// Function to return the ordered crossover of two parents given the indices to take the crossover slices 
+    /// Function to return the ordered crossover of two parents given the indices to take the crossover slices
     /// 
     /// An ordered crossover is taking two slices from the parent and keeping those genes the same in the child,
-    /// but then reordering the genes outside those slices into the order they appear in the second parent
+    /// but then reordering the genes outside those slices into the order they appear in the second parent.
+    /// Giving only two indices takes a single slice, for routes too short to have two
     pub fn ordered_crossover(
         first_parent: &&[u32], 
         second_parent: &&[u32], 
         crossover_points: &[usize]
     ) -> Result<Vec<u32>> {
-        // Define first and second slice using the crossover points
+        // Define first and second slice using the crossover points, there is no second slice if only two are given
         let first_slice: &[u32] = first_parent
             .get(crossover_points[0]..=crossover_points[1])
             .wrap_err("Error, could not obtain Chromosome data")?;
-        let second_slice: &[u32] = first_parent
-            .get(crossover_points[2]..=crossover_points[3])
-            .wrap_err("Error, could not obtain Chromosome data")?;
+        let second_slice: &[u32] = match crossover_points.get(2..4) {
+            Some([start, end]) => first_parent
+                .get(*start..=*end)
+                .wrap_err("Error, could not obtain Chromosome data")?,
+            _ => &[],
+        };
 
         // Set each value to maximum of u32 for pattern matching
         let mut child: Vec<u32> = vec![u32::MAX; first_parent.len()];
@@ -262,8 +947,7 @@ impl Chromosome {
                     .iter()
                     .copied()
                     .enumerate()
-                    .filter(|(_, x)| x.eq(&value))
-                    .last()
+                    .rfind(|(_, x)| x.eq(&value))
                     .wrap_err("Error: Could not obtain Chromosome data")?
             );
         }
@@ -290,131 +974,391 @@ impl Chromosome {
         Ok(child)
     }
 
-    /// Function to perform crossover on two [`Chromosome`]s and return the children
+    /// Function to return the cycle crossover of two parents
+    /// 
+    /// A cycle crossover splits the positions into cycles: starting from a position, take the gene at that 
+    /// position in the second parent, find where that gene is in the first parent, and repeat until the
+    /// starting position is reached again. The child takes the genes of the first cycle from the first parent,
+    /// the next cycle from the second parent and so on, so every gene keeps the position it had in a parent
+    pub fn cycle_crossover(first_parent: &&[u32], second_parent: &&[u32]) -> Result<Vec<u32>> {
+        // Create a lookup of the position of each gene in the first parent
+        let mut position: Vec<usize> = vec![usize::MAX; first_parent.len()];
+        for (index, gene) in first_parent.iter().enumerate() {
+            *position
+                .get_mut(*gene as usize)
+                .wrap_err("Error: Could not obtain Chromosome data")? = index;
+        }
+
+        // Set each value to maximum of u32 for pattern matching
+        let mut child: Vec<u32> = vec![u32::MAX; first_parent.len()];
+        let mut from_first_parent: bool = true;
+
+        // Start a new cycle from every position that hasn't been assigned yet
+        for start in 0..child.len() {
+            if child[start] != u32::MAX {
+                continue;
+            }
+
+            // Follow the cycle, copying genes from the current parent, until it returns to the start
+            let mut index: usize = start;
+            loop {
+                child[index] = if from_first_parent { first_parent[index] } else { second_parent[index] };
+                index = *position
+                    .get(second_parent[index] as usize)
+                    .wrap_err("Error: Could not obtain Chromosome data")?;
+                if index == start || index == usize::MAX {
+                    break;
+                }
+            }
+
+            // Swap parent for the next cycle
+            from_first_parent = !from_first_parent;
+        }
+        Ok(child)
+    }
+
+    /// Function to return the nearest neighbour guided ordered crossover of two parents given the indices to take
+    /// the crossover slices
+    /// 
+    /// Like an ordered crossover, two slices, or one if only two indices are given, are taken from the first parent
+    /// and kept the same in the child.
+    /// Each remaining position is then filled, from left to right, with whichever remaining gene is the 
+    /// cheapest to travel to from the gene before it. Ties go to the gene that appears first in the second parent
+    pub fn nearest_neighbour_crossover(
+        first_parent: &&[u32], 
+        second_parent: &&[u32], 
+        crossover_points: &[usize],
+        graph: &Graph
+    ) -> Result<Vec<u32>> {
+        // Define first and second slice using the crossover points, there is no second slice if only two are given
+        let first_slice: &[u32] = first_parent
+            .get(crossover_points[0]..=crossover_points[1])
+            .wrap_err("Error, could not obtain Chromosome data")?;
+        let second_slice: &[u32] = match crossover_points.get(2..4) {
+            Some([start, end]) => first_parent
+                .get(*start..=*end)
+                .wrap_err("Error, could not obtain Chromosome data")?,
+            _ => &[],
+        };
+
+        // Set each value to maximum of u32 for pattern matching
+        let mut child: Vec<u32> = vec![u32::MAX; first_parent.len()];
+
+        // Add the values of both slices to the child at the correct index
+        for (index, value) in first_slice.iter().enumerate() {
+            child[index + crossover_points[0]] = *value
+        }
+        for (index, value) in second_slice.iter().enumerate() {
+            child[index + crossover_points[2]] = *value
+        }
+
+        // Create a vector of the genes not in either slice, in the order they appear in the second parent
+        let mut remainder: Vec<u32> = second_parent
+            .iter()
+            .filter(|x| !first_slice.contains(x) && !second_slice.contains(x))
+            .copied()
+            .collect();
+
+        // Loop over each position in the child
+        for index in 0..child.len() {
+            // Skip positions that came from the slices
+            if child[index] != u32::MAX {
+                continue;
+            }
+
+            // The gene before this one, wrapping round to the last gene for the first position
+            let previous: u32 = child[(index + child.len() - 1) % child.len()];
+
+            // Pick the remaining gene closest to the previous gene, or the first in the second parent
+            // if the previous gene hasn't been assigned yet
+            let chosen: usize = if previous == u32::MAX {
+                0
+            } else {
+                let mut chosen: usize = 0;
+                let mut cheapest: f64 = f64::INFINITY;
+                for (position, gene) in remainder.iter().enumerate() {
+                    let cost: f64 = graph.distances
+                        .get(previous, *gene)
+                        .wrap_err("Error: Could not obtain Chromosome data")?;
+                    // Strictly less than, so ties keep the gene earlier in the second parent
+                    if cost < cheapest {
+                        chosen = position;
+                        cheapest = cost;
+                    }
+                }
+                chosen
+            };
+
+            // Move the chosen gene from the remainder into the child
+            child[index] = remainder.remove(chosen);
+        }
+        Ok(child)
+    }
+
+    /// Function to return the sequential constructive crossover of two parents
+    /// 
+    /// The child starts at the first gene of the first parent. Then, for each parent, the first gene after the current
+    /// gene that isn't in the child yet is found, or if there is none the lowest unused gene is taken instead. 
+    /// Whichever of these two genes is cheaper to travel to from the current gene is added to the child next
+    pub fn sequential_constructive_crossover(
+        first_parent: &&[u32], 
+        second_parent: &&[u32], 
+        graph: &Graph
+    ) -> Result<Vec<u32>> {
+        let length: usize = first_parent.len();
+
+        // Create a lookup of the position of each gene in both parents
+        let mut positions: [Vec<usize>; 2] = [vec![usize::MAX; length], vec![usize::MAX; length]];
+        for (parent, position) in [first_parent, second_parent].iter().zip(positions.iter_mut()) {
+            for (index, gene) in parent.iter().enumerate() {
+                *position
+                    .get_mut(*gene as usize)
+                    .wrap_err("Error: Could not obtain Chromosome data")? = index;
+            }
+        }
+
+        // Record which genes have been added to the child
+        let mut visited: Vec<bool> = vec![false; length];
+        let mut child: Vec<u32> = Vec::with_capacity(length);
+
+        // Start from the first gene of the first parent
+        let mut current: u32 = *first_parent.first().wrap_err("Error: Could not obtain Chromosome data")?;
+        visited[current as usize] = true;
+        child.push(current);
+
+        while child.len() < length {
+            // Find the candidate gene from each parent
+            let mut candidates: [u32; 2] = [u32::MAX; 2];
+            for ((parent, position), candidate) in [first_parent, second_parent]
+                .iter()
+                .zip(positions.iter())
+                .zip(candidates.iter_mut())
+            {
+                // The first unvisited gene after the current gene in this parent, otherwise the lowest unvisited gene
+                *candidate = parent[position[current as usize] + 1..]
+                    .iter()
+                    .copied()
+                    .find(|gene| !visited[*gene as usize])
+                    .or_else(|| (0..length as u32).find(|gene| !visited[*gene as usize]))
+                    .wrap_err("Error: Could not obtain Chromosome data")?;
+            }
+
+            // Visit whichever candidate is cheaper to travel to, preferring the first parent on a tie
+            let first_cost: f64 = graph.distances
+                .get(current, candidates[0])
+                .wrap_err("Error: Could not obtain Chromosome data")?;
+            let second_cost: f64 = graph.distances
+                .get(current, candidates[1])
+                .wrap_err("Error: Could not obtain Chromosome data")?;
+            current = if second_cost < first_cost { candidates[1] } else { candidates[0] };
+
+            visited[current as usize] = true;
+            child.push(current);
+        }
+        Ok(child)
+    }
+
+    /// Function to make the child of two parents with the given crossover points rather than random ones, so its
+    /// outcome is known in advance. The first parent is the one the child takes the most from. Each operator takes:
+    ///
+    /// - Fix: the crossover point, the child takes the genes before it from the first parent and the rest from
+    ///   the second, then any repeated genes are fixed
+    /// - Ordered and Nearest: four sorted indices, the first two and last two bounding the slices kept, or two
+    ///   sorted indices bounding a single slice kept
+    /// - Cycle and Scx: no points, as their children don't depend on any
+    pub fn crossover_child(
+        first_parent: &[u32],
+        second_parent: &[u32],
+        crossover_operator: CrossoverOperator,
+        crossover_points: &[usize],
+        graph: &Graph
+    ) -> Result<Vec<u32>> {
+        let expected: &[usize] = match crossover_operator {
+            CrossoverOperator::Fix => &[1],
+            CrossoverOperator::Ordered | CrossoverOperator::Nearest => &[2, 4],
+            CrossoverOperator::Cycle | CrossoverOperator::Scx => &[0],
+        };
+        if !expected.contains(&crossover_points.len()) {
+            let expected: Vec<String> = expected.iter().map(|count| count.to_string()).collect();
+            bail!(
+                "{:?} crossover takes {} crossover points but was given {}",
+                crossover_operator, expected.join(" or "), crossover_points.len()
+            );
+        }
+
+        match crossover_operator {
+            // Crossover with Fix
+            CrossoverOperator::Fix => {
+                // Here we split the parents into the genes before the crossover point and the genes after it (inclusive)
+                let crossover_point: usize = crossover_points[0];
+                let first_parent_prefix: &[u32] = first_parent
+                    .get(..crossover_point)
+                    .wrap_err("Error, could not obtain Chromosome data")?;
+                let second_parent_suffix: &[u32] = second_parent
+                    .get(crossover_point..)
+                    .wrap_err("Error, could not obtain Chromosome data")?;
+
+                // Use .concat() method to flatten slice, the prefix is on the left side to preserve order
+                let mut child: Vec<u32> = [first_parent_prefix, second_parent_suffix].concat();
+
+                // Use previously defined fix_crossover function to fix the crossover should any genes be repeated in the child
+                Chromosome::fix_crossover(&mut child, crossover_point);
+                Ok(child)
+            },
+            CrossoverOperator::Ordered => Chromosome::ordered_crossover(&first_parent, &second_parent, crossover_points),
+            CrossoverOperator::Cycle => Chromosome::cycle_crossover(&first_parent, &second_parent),
+            CrossoverOperator::Nearest => {
+                Chromosome::nearest_neighbour_crossover(&first_parent, &second_parent, crossover_points, graph)
+            },
+            CrossoverOperator::Scx => Chromosome::sequential_constructive_crossover(&first_parent, &second_parent, graph),
+        }
+    }
+
+    /// Function to perform crossover on two [`Chromosome`]s and return the children, or None if the children
+    /// would be copies of the parents so no new genetic material was made
     /// 
     /// A crossover_operator of 0 results in a Crossover with fix
     /// A crossover_operator of 1 results in a Ordered Crossover
+    /// A crossover_operator of 2 results in a Cycle Crossover
+    /// A crossover_operator of 3 results in a Nearest Neighbour Guided Ordered Crossover
+    /// A crossover_operator of 4 results in a Sequential Constructive Crossover
     /// NOTE: If the Chromosome is of length u32::MAX (4294967295) then this operation will have undefined behaviour
-    pub fn crossover(
+    pub fn try_crossover(
         &self, 
         other: &Chromosome, 
         crossover_operator: CrossoverOperator, 
         graph: &Graph
-    ) -> Result<(Chromosome, Chromosome)> {
+    ) -> Result<Option<(Chromosome, Chromosome)>> {
 
-        // Pattern match on specified crossover type
-        match crossover_operator {
-            // Crossover with Fix
-            CrossoverOperator::Fix => {
-                // Define the fist parent as Chromosome this function is cast on and the second parent as Chromosome passed into function
-                let first_parent: &&[u32] = &self.route.as_slice();
-                let second_parent: &&[u32] = &other.route.as_slice();
-
-                // Select crossover point, if 1 all but first gene is swapped, if self.route.len() - 1 last gene is swapped
-                let crossover_point: usize = thread_rng().gen_range(1..self.route.len());
+        // Routes of fewer than 3 cities are too short for every operator to pick crossover points
+        if self.route.len() < MINIMUM_CITIES {
+            bail!("Crossover needs routes of at least {} cities, not {}", MINIMUM_CITIES, self.route.len());
+        }
 
-                // Here we split the parent vector into two slices and assign whats left of the midpoint to _parent_prefix and whats right (inclusive) to _crossover
-                let (first_parent_prefix, first_parent_suffix) = first_parent.split_at(crossover_point);
-                let (second_parent_prefix, second_parent_suffix) = second_parent.split_at(crossover_point);
-                
-                // Use .concat() method to flatten slice. _parent is on the left side and _crossover is on the right side to preserve order
-                let mut first_child: Vec<u32> = [first_parent_prefix, second_parent_suffix].concat();
-                let mut second_child: Vec<u32> = [second_parent_prefix, first_parent_suffix].concat();
+        // Identical parents would only give copies of themselves, so don't spend any evaluations on them
+        if self.route == other.route && crossover_operator.clones_identical_parents() {
+            return Ok(None);
+        }
 
-                // Use previously defined fix_crossover function to fix the crossover should any genes be repeated in the child
-                Chromosome::fix_crossover(&mut first_child, crossover_point);
-                Chromosome::fix_crossover(&mut second_child, crossover_point);
-
-                // Calculate fitness of the children
-                let first_child_fitness: f64 = Chromosome::fitness(&first_child, graph)?;
-                let second_child_fitness: f64 = Chromosome::fitness(&second_child, graph)?;
-
-                // Return both Chromosomes in a tuple
-                Ok((
-                    Chromosome {
-                        route: first_child, 
-                        cost: first_child_fitness,
-                    },   
-                    Chromosome {
-                        route: second_child, 
-                        cost: second_child_fitness,
-                    }
-                ))
-            },
-            // Ordered Crossover
-            CrossoverOperator::Ordered => {
-                // define the fist parent as Chromosome this function is cast on and the second parent as Chromosome passed into function
-                let first_parent: &&[u32] = &self.route.as_slice();
-                let second_parent: &&[u32] = &other.route.as_slice();
-
-                // Select 4 crossover points so that two slices can be taken from the parent, sort them so slices don't overlap
-                let mut crossover_points: Vec<usize> = index::sample(&mut thread_rng(), self.route.len(), 4).into_vec();
+        // Pattern match on specified crossover type to pick the crossover points
+        let crossover_points: Vec<usize> = match crossover_operator {
+            // Select crossover point, if 1 all but first gene is swapped, if self.route.len() - 1 last gene is swapped
+            CrossoverOperator::Fix => vec![rng().gen_range(1..self.route.len())],
+            // Select 4 crossover points so that two slices can be taken from the parent, sort them so slices don't
+            // overlap. Routes of fewer than 4 cities only have room for one slice, so only take 2
+            CrossoverOperator::Ordered | CrossoverOperator::Nearest => {
+                let count: usize = if self.route.len() < 4 { 2 } else { 4 };
+                let mut crossover_points: Vec<usize> = index::sample(&mut rng(), self.route.len(), count).into_vec();
                 crossover_points.sort();
-
-                let first_child: Vec<u32> = Chromosome::ordered_crossover(first_parent, second_parent, &crossover_points)?;
-                let second_child: Vec<u32> = Chromosome::ordered_crossover(second_parent, first_parent, &crossover_points)?;
-
-                // Calculate fitness of the children
-                let first_child_fitness: f64 = Chromosome::fitness(&first_child, graph)?;
-                let second_child_fitness: f64 = Chromosome::fitness(&second_child, graph)?;
-
-                // Return both Chromosomes in a tuple
-                Ok((
-                    Chromosome {
-                        route: first_child, 
-                        cost: first_child_fitness,
-                    },   
-                    Chromosome {
-                        route: second_child, 
-                        cost: second_child_fitness,
-                    }
-                ))
+                crossover_points
             },
+            CrossoverOperator::Cycle | CrossoverOperator::Scx => Vec::new(),
+        };
+
+        // The first child takes the most from the Chromosome this function is cast on, the second from the other
+        let first_child: Vec<u32> = Chromosome::crossover_child(
+            &self.route, &other.route, crossover_operator, &crossover_points, graph
+        )?;
+        let second_child: Vec<u32> = Chromosome::crossover_child(
+            &other.route, &self.route, crossover_operator, &crossover_points, graph
+        )?;
+
+        // Calculate fitness of the children
+        let first_child_fitness: f64 = Chromosome::fitness(&first_child, graph)?;
+        let second_child_fitness: f64 = Chromosome::fitness(&second_child, graph)?;
+
+        // Return both Chromosomes in a tuple
+        Ok(Some((
+            Chromosome::new(first_child, first_child_fitness),
+            Chromosome::new(second_child, second_child_fitness),
+        )))
+    }
+
+    /// Function to perform crossover on two [`Chromosome`]s and return the children, using copies
+    /// of the parents as the children when [`Chromosome::try_crossover`] doesn't make any new ones
+    pub fn crossover(
+        &self, 
+        other: &Chromosome, 
+        crossover_operator: CrossoverOperator, 
+        graph: &Graph
+    ) -> Result<(Chromosome, Chromosome)> {
+        match self.try_crossover(other, crossover_operator, graph)? {
+            Some(children) => Ok(children),
+            None => Ok((
+                Chromosome::new(self.route.clone(), self.cost),
+                Chromosome::new(other.route.clone(), other.cost),
+            )),
         }
     }
 
     /// Function to calculate the cost of a [`Chromosome`]
     pub fn fitness(route: &[u32], graph: &Graph) -> Result<f64> {
         let mut cost: f64 = 0.0;
+        // The low order part of the total lost to rounding, only kept with compensated summation
+        let mut compensation: f64 = 0.0;
 
-        // Loop over all elements in chromosome
-        for (i, x) in route.iter().enumerate() {
+        // Loop over every edge in the route, including the edge from the last city back to the first
+        for (from, to) in route.iter().zip(route.iter().cycle().skip(1)) {
+            // Look up the cost of this edge in the distance matrix and add it to the cost variable
+            let edge: f64 = graph.distances
+                .get(*from, *to)
+                .wrap_err("Error: Could not obtain Chromosome data")?;
+            match graph.summation {
+                Summation::Plain => cost += edge,
+                // Neumaier's variant of Kahan summation, which also keeps the error when the edge is bigger than the total
+                Summation::Compensated => {
+                    let total: f64 = cost + edge;
+                    compensation += if cost.abs() >= edge.abs() {
+                        (cost - total) + edge
+                    } else {
+                        (edge - total) + cost
+                    };
+                    cost = total;
+                },
+            }
+        }
 
-            // Cost function include travel from the last city back to the first (or in this representation first to last)
-            // This accounts for that
-            if i == 0 {
-                // Find last city
-                let prev: &u32 = route.iter()
-                    .last()
-                    .wrap_err("Error: Could not obtain Chromosome data")?;
+        // Return cost
+        Ok(cost + compensation)
+    }
+}
 
-                // Loop through each city in country
-                for (index, vert) in graph.vertex.iter().enumerate() {
-                    // Loop over each edge between all other cities and this one
-                    for edge in vert {
-                        // If the city is the last city and the edge is the connection between the last and the first
-                        if index == *prev as usize && edge.destination_city == *x {
-                            // Add this cost to the cost variable
-                            cost += edge.cost
-                        }
-                    }
-                }
-            } else {
+/// The don't look bits of a local search, a queue of the cities that still need to be looked at. A city's bit is
+/// off while it is in the queue, so cities are never queued twice
+struct DontLookBits {
+    /// The cities to look at, in the order their bits were turned off
+    queue: VecDeque<u32>,
+    /// Whether each city is in the queue
+    queued: Vec<bool>,
+}
 
-                // Loop through each city in the country
-                for (index, vert) in graph.vertex.iter().enumerate() {
-                    // Loop through each edge between all other cities and this one
-                    for edge in vert {
-                        // If the city is the previous city in the route and edge is the connection to the current city in the route
-                        if index == route[i - 1] as usize && edge.destination_city == *x {
-                            // Add this cost to the cost variable
-                            cost += edge.cost
-                        }
-                    }
-                }
-            }
+/// Implement Methods on the [`DontLookBits`] type
+impl DontLookBits {
+    /// This function turns off the bits of the given cities, out of the given number of cities
+    fn new(cities: impl IntoIterator<Item = u32>, length: usize) -> Self {
+        let mut bits = Self { queue: VecDeque::new(), queued: vec![false; length] };
+        for city in cities {
+            bits.wake(city);
         }
-        // Return cost
-        Ok(cost)
+        bits
+    }
+
+    /// This function turns off the bit of a city so it is looked at again
+    fn wake(&mut self, city: u32) {
+        if !self.queued[city as usize] {
+            self.queued[city as usize] = true;
+            self.queue.push_back(city);
+        }
+    }
+
+    /// This function turns on the bit of the next city to look at and returns it, or None once the search has been
+    /// cancelled
+    fn pop(&mut self, cancellation: &CancellationToken) -> Option<u32> {
+        if cancellation.is_cancelled() {
+            return None;
+        }
+        let city: u32 = self.queue.pop_front()?;
+        self.queued[city as usize] = false;
+        Some(city)
     }
 }
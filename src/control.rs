@@ -0,0 +1,145 @@
+//! This module defines [`ControlFile`], a file of settings that is checked between generations so some
+//! parameters of a running [`Simulation`] can be changed while it runs, and [`ParameterChange`], which
+//! records each change made so it is kept with the results of the run.
+//! 
+//! [`Simulation`]: crate::simulation::Simulation
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::ValueEnum;
+use color_eyre::{eyre::eyre, Result};
+use serde::{Deserialize, Serialize};
+
+use super::interface::{CrossoverOperator, MutationOperator, RunParameters, SelectionOperator, TiePolicy};
+
+/// How often the control file is checked for changes, so runs don't spend their time reading it
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A change made to the parameters of a running simulation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterChange {
+    /// The generation the change was made before
+    pub generation: u32,
+    /// Name of the setting changed, as written in the control file
+    pub setting: String,
+    /// The new value of the setting
+    pub value: String,
+}
+
+/// A file of `setting = value` lines that is read again whenever it changes
+#[derive(Clone, Debug)]
+pub struct ControlFile {
+    /// Where the file is
+    pub path: PathBuf,
+    /// When the file was last changed as of the last time it was read
+    modified: Option<SystemTime>,
+    /// When the file was last checked for changes
+    checked: Option<Instant>,
+}
+
+/// Implement methods on `ControlFile`
+impl ControlFile {
+    /// Function to watch a control file, which doesn't have to exist yet
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, modified: None, checked: None }
+    }
+
+    /// Function to return every setting in the file if it has changed since it was last read. Returns None
+    /// if the file hasn't changed, doesn't exist or was checked too recently. Blank lines and lines starting
+    /// with `#` are ignored
+    pub fn read_if_changed(&mut self) -> Result<Option<Vec<(String, String)>>> {
+        // Only check the file every so often
+        if self.checked.is_some_and(|checked| checked.elapsed() < CHECK_INTERVAL) {
+            return Ok(None);
+        }
+        self.checked = Some(Instant::now());
+
+        // Only read the file if it has changed
+        let Ok(modified) = fs::metadata(&self.path).and_then(|metadata| metadata.modified()) else {
+            return Ok(None);
+        };
+        if self.modified == Some(modified) {
+            return Ok(None);
+        }
+        self.modified = Some(modified);
+
+        // Split every line into its setting and value
+        let src: String = fs::read_to_string(&self.path)?;
+        let mut settings: Vec<(String, String)> = Vec::new();
+        for (number, line) in src.lines().enumerate() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (setting, value) = line
+                .split_once('=')
+                .ok_or_else(|| eyre!("Line {} of {} isn't of the form setting = value", number + 1, self.path.display()))?;
+            settings.push((setting.trim().to_string(), value.trim().to_string()));
+        }
+        Ok(Some(settings))
+    }
+}
+
+/// Function to change one of the parameters that can be changed while a simulation runs, given the size the population
+/// currently is. Returns whether the parameter changed, or why the value can't be used
+pub fn apply_setting(
+    parameters: &mut RunParameters, 
+    setting: &str, 
+    value: &str, 
+    population_size: u64
+) -> Result<bool, String> {
+    match setting {
+        "tournament-size" => {
+            let tournament_size: u32 = value
+                .parse()
+                .map_err(|_| format!("tournament-size must be a whole number, not {}", value))?;
+            if tournament_size < 2 || tournament_size as u64 > population_size {
+                return Err(format!("tournament-size must be between 2 and the population size ({})", population_size));
+            }
+            Ok(std::mem::replace(&mut parameters.tournament_size, tournament_size) != tournament_size)
+        },
+        "mutation-operator" => {
+            let mutation_operator = MutationOperator::from_str(value, true)?;
+            Ok(std::mem::replace(&mut parameters.mutation_operator, mutation_operator) != mutation_operator)
+        },
+        "crossover-operator" => {
+            let crossover_operator = CrossoverOperator::from_str(value, true)?;
+            Ok(std::mem::replace(&mut parameters.crossover_operator, crossover_operator) != crossover_operator)
+        },
+        "crossover-rate" => {
+            let crossover_rate: f64 = value
+                .parse()
+                .ok()
+                .filter(|rate| (0.0..=1.0).contains(rate))
+                .ok_or_else(|| format!("crossover-rate must be between 0 and 1, not {}", value))?;
+            Ok(std::mem::replace(&mut parameters.crossover_rate, crossover_rate) != crossover_rate)
+        },
+        "mutation-rate" => {
+            let mutation_rate: f64 = value
+                .parse()
+                .ok()
+                .filter(|rate| (0.0..=1.0).contains(rate))
+                .ok_or_else(|| format!("mutation-rate must be between 0 and 1, not {}", value))?;
+            Ok(std::mem::replace(&mut parameters.mutation_rate, mutation_rate) != mutation_rate)
+        },
+        "selection-operator" => {
+            let selection_operator = SelectionOperator::from_str(value, true)?;
+            Ok(std::mem::replace(&mut parameters.selection_operator, selection_operator) != selection_operator)
+        },
+        "tie-policy" => {
+            let tie_policy = TiePolicy::from_str(value, true)?;
+            Ok(std::mem::replace(&mut parameters.tie_policy, tie_policy) != tie_policy)
+        },
+        "distinct-parents" => {
+            let distinct_parents: bool = value
+                .parse()
+                .map_err(|_| format!("distinct-parents must be true or false, not {}", value))?;
+            Ok(std::mem::replace(&mut parameters.distinct_parents, distinct_parents) != distinct_parents)
+        },
+        _ => Err(format!("{} can't be changed while running", setting)),
+    }
+}
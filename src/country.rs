@@ -1,11 +1,315 @@
 //! This module creates the structure [`Country`] and methods to import data from
-//! an XML file and deserialize into a [`Country`] so that it can be used.
+//! an XML file and deserialize into a [`Country`] so that it can be used. XML files are read a tag at a time
+//! by [`Country::from_xml`], as deserializing the whole file is slow for instances with thousands of cities.
+//! [`InstanceFormat`] detects which format each file in the data directory is in, and TSPLIB `.tsp` files
+//! are read with [`Country::from_tsplib`], which takes the cost of every edge from the matrix in the file or builds
+//! it from the coordinates of the cities with the distance function of the file, such as [`euclidean_2d`].
+//! Files ending in `.gz`, such as `pla85900.tsp.gz`, are decompressed as they are read.
 
-use std::{fs,slice};
+use std::{collections::HashMap, fmt::Write, fs, io::Read, path::{Path, PathBuf}, slice};
 
+use flate2::read::GzDecoder;
+
+use quick_xml::{escape::escape, events::{BytesStart, Event}, Reader};
 use serde::Deserialize;
-use serde_xml_rs;
-use color_eyre::{eyre::WrapErr, Result};
+use super::interface::{RunParameters, Summation};
+use color_eyre::{eyre::{bail, eyre, WrapErr}, Result};
+
+/// The path of each file in a directory, along with whether it was loaded
+pub type FileFormats = Vec<(PathBuf, FileStatus)>;
+
+/// The most problems [`Graph::validate`] lists before saying how many more there are
+pub const VALIDATION_PROBLEMS: usize = 5;
+
+/// Enumerate that represents what happened to a file in the data directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file was loaded as this format
+    Loaded(InstanceFormat),
+    /// The file was skipped, for this reason
+    Skipped(String),
+}
+
+/// Enumerate that represents the file formats a [`Country`] can be loaded from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InstanceFormat {
+    /// A `travellingSalesmanProblemInstance` XML file
+    Xml,
+    /// A TSPLIB `.tsp` file
+    Tsplib,
+}
+
+/// Implement methods on `InstanceFormat`
+impl InstanceFormat {
+    /// Function to detect the format of a file from its extension, falling back on its contents
+    /// when the extension is missing or unknown. Returns None if the format is not recognised
+    pub fn detect(path: &Path, src: &str) -> Option<Self> {
+        // Trust the extension if it is one we know
+        let extension: Option<String> = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("xml") => return Some(InstanceFormat::Xml),
+            Some("tsp") => return Some(InstanceFormat::Tsplib),
+            _ => {},
+        }
+
+        // Otherwise look for the root element of an instance
+        let contents: &str = src.trim_start();
+        if contents.starts_with('<') && contents.contains("<travellingSalesmanProblemInstance") {
+            return Some(InstanceFormat::Xml);
+        }
+
+        // Or the TYPE of a TSPLIB problem, tour files and the like are other TYPEs
+        if TsplibFile::parse(src).field("TYPE") == Some("TSP") {
+            return Some(InstanceFormat::Tsplib);
+        }
+
+        None
+    }
+
+    /// Function to read a [`Country`] from the contents of a file in this format, checking its graph is valid
+    pub fn parse(&self, src: &str) -> Result<Country> {
+        let country: Country = match self {
+            InstanceFormat::Xml => Country::from_xml(src).wrap_err("Failed to deserialize XML data")?,
+            InstanceFormat::Tsplib => Country::from_tsplib(src)?,
+        };
+        country.graph.validate().wrap_err_with(|| format!("Invalid graph in {}", country.name))?;
+        Ok(country)
+    }
+}
+
+/// Enumerate that represents what an element of a `travellingSalesmanProblemInstance` XML file is, going by
+/// the elements it is inside, so [`Country::from_xml`] knows what its text is
+enum XmlElement {
+    /// The `travellingSalesmanProblemInstance` element everything else is inside
+    Root,
+    /// An element directly inside the root other than the graph, such as `name`
+    Field(String),
+    /// The `graph` element holding every city
+    Graph,
+    /// A `vertex` element, holding the edges of one city
+    Vertex,
+    /// An `edge` element, whose text is its destination city
+    Edge,
+    /// Any element that isn't part of the format
+    Other,
+}
+
+/// The specification of a TSPLIB file, split into its `KEYWORD : value` fields and the values of each of its
+/// data sections, such as `NODE_COORD_SECTION`
+struct TsplibFile<'a> {
+    /// The value of each field, keyed by its keyword
+    fields: HashMap<&'a str, &'a str>,
+    /// The whitespace separated values of each data section, keyed by its keyword
+    sections: HashMap<&'a str, Vec<&'a str>>,
+}
+
+/// Implement methods on `TsplibFile`
+impl<'a> TsplibFile<'a> {
+    /// Function to split the contents of a TSPLIB file into its fields and data sections. Lines that are neither
+    /// are ignored, so this never fails, it is up to the caller to check the fields it needs are there
+    fn parse(src: &'a str) -> Self {
+        let mut fields: HashMap<&str, &str> = HashMap::new();
+        let mut sections: HashMap<&str, Vec<&str>> = HashMap::new();
+        // The data section the current line belongs to, if any
+        let mut section: Option<&str> = None;
+
+        for line in src.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if line == "EOF" {
+                break;
+            }
+
+            // A keyword on its own starts a data section, whose values run until the next keyword
+            let keyword: &str = line.trim_end_matches(':').trim_end();
+            if TsplibFile::is_keyword(keyword) && keyword.ends_with("_SECTION") {
+                sections.entry(keyword).or_default();
+                section = Some(keyword);
+                continue;
+            }
+
+            // A keyword followed by a colon is a field, the value may have colons of its own
+            if let Some((keyword, value)) = line.split_once(':') {
+                if TsplibFile::is_keyword(keyword.trim()) {
+                    fields.insert(keyword.trim(), value.trim());
+                    section = None;
+                    continue;
+                }
+            }
+
+            if let Some(values) = section.and_then(|section| sections.get_mut(section)) {
+                values.extend(line.split_whitespace());
+            }
+        }
+
+        Self { fields, sections }
+    }
+
+    /// Function to check if a word is a TSPLIB keyword, which are upper case with underscores
+    fn is_keyword(word: &str) -> bool {
+        !word.is_empty() && word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    }
+
+    /// Function to return the value of a field, or None if the file doesn't have it
+    fn field(&self, keyword: &str) -> Option<&'a str> {
+        self.fields.get(keyword).copied()
+    }
+
+    /// Function to return the values of a data section, erroring if the file doesn't have it
+    fn section(&self, keyword: &str) -> Result<&[&'a str]> {
+        self.sections
+            .get(keyword)
+            .map(Vec::as_slice)
+            .ok_or_else(|| eyre!("Missing {}", keyword))
+    }
+
+    /// Function to read the cost of travel between every pair of cities from `EDGE_WEIGHT_SECTION`, laid out as
+    /// `EDGE_WEIGHT_FORMAT` says. `FULL_MATRIX` lists every row of the matrix, `UPPER_ROW` lists each row from just
+    /// after the diagonal and `LOWER_DIAG_ROW` lists each row up to and including the diagonal. The costs are
+    /// returned indexed by `from * dimension + to`, with the missing half of a triangle filled in from the other
+    fn explicit_weights(&self, dimension: usize) -> Result<Vec<f64>> {
+        // The row and column of each value of the section, in the order they are listed
+        let columns: fn(usize, usize) -> std::ops::Range<usize> = match self.field("EDGE_WEIGHT_FORMAT") {
+            Some("FULL_MATRIX") => |_, dimension| 0..dimension,
+            Some("UPPER_ROW") => |row, dimension| row + 1..dimension,
+            Some("LOWER_DIAG_ROW") => |row, _| 0..row + 1,
+            Some(other) => return Err(eyre!("Unsupported EDGE_WEIGHT_FORMAT {}", other)),
+            None => return Err(eyre!("Missing EDGE_WEIGHT_FORMAT")),
+        };
+        let layout: Vec<(usize, usize)> = (0..dimension)
+            .flat_map(|row| columns(row, dimension).map(move |column| (row, column)))
+            .collect();
+
+        let values: &[&str] = self.section("EDGE_WEIGHT_SECTION")?;
+        if values.len() != layout.len() {
+            return Err(eyre!(
+                "EDGE_WEIGHT_SECTION has {} values, expected {} for {} cities", values.len(), layout.len(), dimension
+            ));
+        }
+
+        let mut costs: Vec<f64> = vec![0.0; dimension * dimension];
+        let triangle: bool = self.field("EDGE_WEIGHT_FORMAT") != Some("FULL_MATRIX");
+        for ((row, column), value) in layout.into_iter().zip(values) {
+            let cost: f64 = value
+                .parse()
+                .wrap_err_with(|| format!("Invalid weight {} from city {} to city {}", value, row + 1, column + 1))?;
+            costs[row * dimension + column] = cost;
+            if triangle {
+                costs[column * dimension + row] = cost;
+            }
+        }
+        Ok(costs)
+    }
+
+    /// Function to read the coordinates of every city from `NODE_COORD_SECTION`, where each city is listed as its
+    /// number, counting from 1, followed by its coordinates. Returns the coordinates in city order
+    fn coordinates(&self, dimension: usize) -> Result<Vec<(f64, f64)>> {
+        let values: &[&str] = self.section("NODE_COORD_SECTION")?;
+        if values.len() != dimension * 3 {
+            return Err(eyre!(
+                "NODE_COORD_SECTION has {} values, expected 3 for each of the {} cities", values.len(), dimension
+            ));
+        }
+
+        let mut coordinates: Vec<Option<(f64, f64)>> = vec![None; dimension];
+        for city in values.chunks(3) {
+            let number: usize = city[0].parse().wrap_err_with(|| format!("Invalid city number {}", city[0]))?;
+            let x: f64 = city[1].parse().wrap_err_with(|| format!("Invalid coordinate {} of city {}", city[1], number))?;
+            let y: f64 = city[2].parse().wrap_err_with(|| format!("Invalid coordinate {} of city {}", city[2], number))?;
+            match coordinates.get_mut(number.wrapping_sub(1)) {
+                Some(slot @ None) => *slot = Some((x, y)),
+                Some(Some(_)) => return Err(eyre!("City {} is listed more than once", number)),
+                None => return Err(eyre!("City {} is outside 1 to {}", number, dimension)),
+            }
+        }
+        // Every city is listed once, as there are as many entries as cities and none were repeated
+        Ok(coordinates.into_iter().flatten().collect())
+    }
+}
+
+/// Function to find the TSPLIB `EUC_2D` distance between two cities, the straight line distance between their
+/// coordinates rounded to the nearest whole number, so the published optimal costs are reproduced
+pub fn euclidean_2d(from: (f64, f64), to: (f64, f64)) -> f64 {
+    (from.0 - to.0).hypot(from.1 - to.1).round()
+}
+
+/// The value of pi used by TSPLIB for `GEO` distances, which is rounded so the published optimal costs are reproduced
+#[allow(clippy::approx_constant)]
+pub const TSPLIB_PI: f64 = 3.141592;
+
+/// The radius of the earth in kilometres used by TSPLIB for `GEO` distances
+pub const EARTH_RADIUS: f64 = 6378.388;
+
+/// Function to convert a TSPLIB `GEO` coordinate, given as `DDD.MM` degrees and minutes, to radians
+fn geographic_radians(coordinate: f64) -> f64 {
+    let degrees: f64 = coordinate.trunc();
+    let minutes: f64 = coordinate - degrees;
+    TSPLIB_PI * (degrees + 5.0 * minutes / 3.0) / 180.0
+}
+
+/// Function to find the TSPLIB `GEO` distance between two cities, given as (latitude, longitude), which is the
+/// great circle distance between them in kilometres rounded down to a whole number, plus one
+pub fn geographic(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let (from_latitude, from_longitude) = (geographic_radians(from.0), geographic_radians(from.1));
+    let (to_latitude, to_longitude) = (geographic_radians(to.0), geographic_radians(to.1));
+    let q1: f64 = (from_longitude - to_longitude).cos();
+    let q2: f64 = (from_latitude - to_latitude).cos();
+    let q3: f64 = (from_latitude + to_latitude).cos();
+    (EARTH_RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
+
+/// Function to find the TSPLIB `ATT` pseudo-Euclidean distance between two cities, used by att48 and att532. The
+/// straight line distance is scaled down by the square root of 10, then rounded up unless rounding to the nearest
+/// whole number already rounds it up
+pub fn pseudo_euclidean(from: (f64, f64), to: (f64, f64)) -> f64 {
+    let distance: f64 = (((from.0 - to.0).powi(2) + (from.1 - to.1).powi(2)) / 10.0).sqrt();
+    let rounded: f64 = distance.round();
+    if rounded < distance { rounded + 1.0 } else { rounded }
+}
+
+/// Function to decompress the contents of a gzipped file
+pub fn decompress_gzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decompressed: Vec<u8> = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Function to check if a name matches a glob pattern, where `*` matches any run of characters, including none,
+/// and `?` matches any single character. Every other character only matches itself, with upper and lower case
+/// being different
+pub fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    let (mut position, mut next) = (0, 0);
+
+    // Where the last `*` was in the pattern, and where in the name it started matching from
+    let mut star: Option<(usize, usize)> = None;
+    while position < name.len() {
+        match pattern.get(next) {
+            Some('*') => {
+                star = Some((next, position));
+                next += 1;
+            },
+            Some(character) if *character == '?' || *character == name[position] => {
+                position += 1;
+                next += 1;
+            },
+            // Otherwise let the last `*` match one more character and try again from there
+            _ => match star {
+                Some((star_next, star_position)) => {
+                    star = Some((star_next, star_position + 1));
+                    position = star_position + 1;
+                    next = star_next + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+
+    // Whatever is left of the pattern can only match the end of the name if it is all `*`
+    pattern[next..].iter().all(|character| *character == '*')
+}
 
 /// This Struct defines the datatype of an Edge, which is the cost to get to a city as a float
 #[derive(Clone, Debug, Deserialize)]
@@ -32,10 +336,241 @@ impl<'a> IntoIterator for &'a Vertex {
     }
 }
 
-/// This Struct defines the graph, which is a Vector of all the Vertexs
+/// This Struct defines the graph, which is a Vector of all the Vertexs along with
+/// a [`DistanceMatrix`] built from them when the graph is created
 #[derive(Clone, Debug, Deserialize)]
+#[serde(from = "GraphData")]
 pub struct Graph {
     pub vertex: Vec<Vertex>,
+    pub distances: DistanceMatrix,
+    /// The nearest cities to each city, nearest first, which local searches limit their moves to. 
+    /// Empty unless candidate lists have been built, in which case local searches try every move
+    pub candidates: Vec<Vec<u32>>,
+    /// How the cost of the edges of a route is added up when a route is evaluated
+    pub summation: Summation,
+    /// Whether travel between every pair of cities costs the same in both directions. Routes of asymmetric graphs
+    /// are costed in the direction they are travelled, so moves that reverse part of a route cost the reversed edges
+    pub symmetric: bool,
+}
+
+/// This Struct defines the graph as it appears in the XML file, before the distance matrix is built
+#[derive(Deserialize)]
+struct GraphData {
+    vertex: Vec<Vertex>,
+}
+
+/// Implements Trait From so serde builds the distance matrix whenever a Graph is deserialized
+impl From<GraphData> for Graph {
+    fn from(data: GraphData) -> Self {
+        Graph::new(data.vertex)
+    }
+}
+
+/// Implement methods on `Graph`
+impl Graph {
+    /// Function to create a Graph from its vertices, precomputing the cost of travel between every pair of cities
+    pub fn new(vertex: Vec<Vertex>) -> Self {
+        let distances = DistanceMatrix::new(&vertex);
+        let symmetric: bool = distances.is_symmetric();
+        Self { vertex, distances, candidates: Vec::new(), summation: Summation::Plain, symmetric }
+    }
+
+    /// Function to check the graph is a valid instance, so the cost of every route is worked out correctly.
+    /// Every city needs at most one edge to each other city, with a cost that is finite and not negative. Cities
+    /// don't need an edge to every other city, travel between cities without one is forbidden, and an edge can
+    /// cost something different, or be missing, in the other direction, which makes the graph asymmetric. Lists
+    /// the first [`VALIDATION_PROBLEMS`] problems found, naming the cities and edges at fault
+    pub fn validate(&self) -> Result<()> {
+        let cities: usize = self.vertex.len();
+        let mut problems: Vec<String> = Vec::new();
+
+        // Check each edge on its own, noting which edges there are
+        let mut present: Vec<bool> = vec![false; cities * cities];
+        for (from, vertex) in self.vertex.iter().enumerate() {
+            for edge in vertex {
+                let to: usize = edge.destination_city as usize;
+                if to >= cities {
+                    problems.push(format!(
+                        "city {} has an edge to city {}, but there are only {} cities",
+                        from, to, cities
+                    ));
+                    continue;
+                }
+                if to == from {
+                    problems.push(format!("city {} has an edge to itself", from));
+                    continue;
+                }
+                if present[from * cities + to] {
+                    problems.push(format!("city {} has more than one edge to city {}", from, to));
+                }
+                present[from * cities + to] = true;
+                if !edge.cost.is_finite() || edge.cost < 0.0 {
+                    problems.push(format!("the edge from city {} to city {} has a cost of {}", from, to, edge.cost));
+                }
+            }
+        }
+
+        match problems.len() {
+            0 => Ok(()),
+            count if count <= VALIDATION_PROBLEMS => Err(eyre!("{}", problems.join("; "))),
+            count => Err(eyre!(
+                "{} and {} more problems",
+                problems[..VALIDATION_PROBLEMS].join("; "),
+                count - VALIDATION_PROBLEMS
+            )),
+        }
+    }
+
+    /// Function to count the pairs of different cities without an edge between them in at least one direction,
+    /// which tours can't travel between that way
+    pub fn forbidden_edges(&self) -> usize {
+        let cities: u32 = self.distances.size as u32;
+        (0..cities)
+            .map(|from| {
+                (from + 1..cities)
+                    .filter(|to| self.distances.is_forbidden(from, *to) || self.distances.is_forbidden(*to, from))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Function to check if the cost of travel between every pair of cities is a whole number
+    pub fn has_integral_costs(&self) -> bool {
+        self.distances.costs.iter().all(|cost| cost.fract() == 0.0)
+    }
+
+    /// Function to find a lower bound on the cost of any tour. A tour joins each city to two others, so costs at least
+    /// half the total, over every city, of its two cheapest connections. Each connection is the cheaper direction of
+    /// travel, so the bound also holds for costs that differ by direction
+    pub fn lower_bound(&self) -> f64 {
+        let cities: usize = self.distances.size;
+        if cities < 2 {
+            return 0.0;
+        }
+
+        let mut total: f64 = 0.0;
+        for from in 0..cities {
+            let mut connections: Vec<f64> = (0..cities)
+                .filter(|to| *to != from)
+                .map(|to| self.distances.costs[from * cities + to].min(self.distances.costs[to * cities + from]))
+                .collect();
+            connections.sort_by(f64::total_cmp);
+            total += connections.iter().take(2).sum::<f64>();
+        }
+        total / 2.0
+    }
+
+    /// Function to set the graph up for the given run parameters, building the candidate lists local searches
+    /// are limited to, or clearing them if there are none, and picking how routes are added up
+    pub fn configure(&mut self, parameters: &RunParameters) {
+        match parameters.candidate_list_size {
+            Some(size) => self.build_candidate_lists(size as usize),
+            None => self.candidates.clear(),
+        }
+        self.summation = parameters.summation;
+    }
+
+    /// Function to check the graph is already set up for the given run parameters by [`Graph::configure`], so a
+    /// graph shared between runs only needs copying when they want it set up differently
+    pub fn is_configured(&self, parameters: &RunParameters) -> bool {
+        let candidates: bool = match parameters.candidate_list_size {
+            Some(size) => {
+                let expected: usize = (size as usize).min(self.distances.size.saturating_sub(1));
+                self.candidates.len() == self.distances.size && self.candidates.iter().all(|list| list.len() == expected)
+            },
+            None => self.candidates.is_empty(),
+        };
+        candidates && self.summation == parameters.summation
+    }
+
+    /// Function to build a list of the given number of nearest cities to each city, nearest first,
+    /// so local searches only try moves that add an edge to a nearby city
+    pub fn build_candidate_lists(&mut self, size: usize) {
+        let cities: usize = self.distances.size;
+        self.candidates = (0..cities)
+            .map(|from| {
+                // Every other city, sorted by the cost to reach it
+                let mut nearest: Vec<(f64, u32)> = (0..cities)
+                    .filter(|to| *to != from)
+                    .map(|to| (self.distances.costs[from * cities + to], to as u32))
+                    .collect();
+                nearest.sort_by(|x, y| x.0.total_cmp(&y.0).then(x.1.cmp(&y.1)));
+                nearest.into_iter().take(size).map(|(_, to)| to).collect()
+            })
+            .collect();
+    }
+}
+
+/// This Struct stores the cost of travel between every pair of cities so it can be looked up
+/// without searching the edges. Costs are stored in a flat vector indexed by `from * size + to`
+#[derive(Clone, Debug, Default)]
+pub struct DistanceMatrix {
+    /// Number of cities
+    pub size: usize,
+    /// Cost of travel from each city to each other city, cities without an edge between them cost the forbidden cost
+    pub costs: Vec<f64>,
+    /// The cost of travel between cities without an edge between them. Forbidden edges are infinitely expensive, but
+    /// are given a finite cost more than any tour along the edges of the graph costs, so tours cost more the more
+    /// forbidden edges they use and every operator and local search can still work out the cost of their moves
+    pub forbidden_cost: f64,
+}
+
+/// Implement methods on `DistanceMatrix`
+impl DistanceMatrix {
+    /// Function to build the matrix from the edges of each vertex
+    pub fn new(vertex: &[Vertex]) -> Self {
+        let size: usize = vertex.len();
+
+        // A tour along the edges of the graph costs no more than the number of cities times its most expensive edge
+        let most_expensive: f64 = vertex
+            .iter()
+            .flat_map(|vert| vert.edges.iter())
+            .map(|edge| edge.cost)
+            .filter(|cost| cost.is_finite())
+            .fold(0.0, f64::max);
+        let forbidden_cost: f64 = most_expensive * size as f64 + 1.0;
+
+        // Every city is free to stay where it is, and can't travel to a city it has no edge to
+        let mut costs: Vec<f64> = vec![forbidden_cost; size * size];
+        for city in 0..size {
+            costs[city * size + city] = 0.0;
+        }
+
+        // Loop through each city and each of its edges, ignoring edges to cities that don't exist
+        for (from, vert) in vertex.iter().enumerate() {
+            for edge in vert {
+                let to: usize = edge.destination_city as usize;
+                if to < size {
+                    costs[from * size + to] = edge.cost;
+                }
+            }
+        }
+
+        Self { size, costs, forbidden_cost }
+    }
+
+    /// Function to check if travel between every pair of cities costs the same in both directions
+    pub fn is_symmetric(&self) -> bool {
+        (0..self.size).all(|from| (from + 1..self.size).all(|to| {
+            self.costs[from * self.size + to] == self.costs[to * self.size + from]
+        }))
+    }
+
+    /// Function to check if there is no edge from one different city to another, so travel between them is forbidden.
+    /// Cities that don't exist have no forbidden edges
+    pub fn is_forbidden(&self, from: u32, to: u32) -> bool {
+        from != to && self.get(from, to) == Some(self.forbidden_cost)
+    }
+
+    /// Function to return the cost of travel from one city to another, or None if either city doesn't exist
+    pub fn get(&self, from: u32, to: u32) -> Option<f64> {
+        let (from, to) = (from as usize, to as usize);
+        if from < self.size && to < self.size {
+            Some(self.costs[from * self.size + to])
+        } else {
+            None
+        }
+    }
 }
 
 /// This Struct defines the root data structure containing all the information from the XML file
@@ -50,28 +585,439 @@ pub struct Country {
     pub double_precision: f64,
     pub ignored_digits: i32,
     pub graph: Graph,
+    /// Optional unit the edge costs are measured in, e.g. "km"
+    #[serde(default)]
+    pub cost_unit: Option<String>,
+    /// Optional number of decimal places costs should be reported to
+    #[serde(default)]
+    pub cost_decimals: Option<usize>,
 }
 
 /// Implement methods on `Country`
 impl Country {
-    /// Function to create the root structure for each countries XML file
-    /// that is found in the data directory
-    pub fn new() -> Result<Vec<Self>> {
-        // Create iterator over all files in data/ directory
-        let directory = fs::read_dir("data/")?;
+    /// Function to create the root structure for each countries file
+    /// that is found in the given data directory, printing the format each file was loaded as.
+    /// If strict, any file that can't be loaded is an error rather than being skipped
+    pub fn new(directory: &Path, strict: bool) -> Result<Vec<Self>> {
+        let (output, formats) = Country::from_directory(directory, strict)?;
+
+        // Output what happened to every file so skipped files aren't missed silently
+        for (path, status) in formats {
+            match status {
+                FileStatus::Loaded(format) => println!("Loaded {} as {:?}", path.display(), format),
+                FileStatus::Skipped(reason) => println!("Skipped {}: {}", path.display(), reason),
+            }
+        }
+
+        // Return data as the type Country
+        Ok(output)
+    }
+
+    /// Function to read a Country from the contents of a `travellingSalesmanProblemInstance` XML file, giving the
+    /// same Country as deserializing it with serde. The file is streamed through quick-xml a tag at a time, so the
+    /// edges of each city are read straight into the graph. Elements that aren't part of the format are ignored
+    pub fn from_xml(src: &str) -> Result<Self> {
+        let mut reader = Reader::from_str(src);
+        reader.config_mut().trim_text(true);
+
+        // The elements currently open, outermost first
+        let mut open: Vec<XmlElement> = Vec::new();
+        // The text of each element directly inside the root, keyed by its name
+        let mut fields: HashMap<String, String> = HashMap::new();
+        let mut vertex: Vec<Vertex> = Vec::new();
+        let mut found_root: bool = false;
+        let mut found_graph: bool = false;
+        // The cost and destination city of the edge being read
+        let mut cost: f64 = 0.0;
+        let mut destination: String = String::new();
+
+        loop {
+            let event = reader
+                .read_event()
+                .wrap_err_with(|| format!("Invalid XML at byte {}", reader.error_position()))?;
+            match &event {
+                Event::Start(element) | Event::Empty(element) => {
+                    // Work out what the element is from the element it is in, only allocating for the fields
+                    let name = element.local_name();
+                    let element_kind: XmlElement = match (open.last(), name.as_ref()) {
+                        (None, b"travellingSalesmanProblemInstance") => {
+                            found_root = true;
+                            XmlElement::Root
+                        },
+                        (None, other) => bail!(
+                            "Expected a travellingSalesmanProblemInstance element, found {}",
+                            String::from_utf8_lossy(other)
+                        ),
+                        (Some(XmlElement::Root), b"graph") => {
+                            found_graph = true;
+                            XmlElement::Graph
+                        },
+                        (Some(XmlElement::Root), field) => {
+                            let field: String = String::from_utf8_lossy(field).into_owned();
+                            fields.entry(field.clone()).or_default();
+                            XmlElement::Field(field)
+                        },
+                        (Some(XmlElement::Graph), b"vertex") => {
+                            vertex.push(Vertex { edges: Vec::new() });
+                            XmlElement::Vertex
+                        },
+                        (Some(XmlElement::Vertex), b"edge") => {
+                            cost = Country::edge_cost(element, vertex.len() - 1)?;
+                            destination.clear();
+                            XmlElement::Edge
+                        },
+                        _ => XmlElement::Other,
+                    };
+
+                    // An empty element is closed straight away, so an empty edge has no destination city
+                    match (matches!(event, Event::Empty(_)), element_kind) {
+                        (true, XmlElement::Edge) => bail!("An edge of city {} has no destination city", vertex.len() - 1),
+                        (true, _) => {},
+                        (false, element_kind) => open.push(element_kind),
+                    }
+                },
+                Event::Text(text) => match open.last() {
+                    Some(XmlElement::Field(field)) => {
+                        let text = text.unescape().wrap_err_with(|| format!("Invalid text in {}", field))?;
+                        fields.entry(field.clone()).or_default().push_str(&text);
+                    },
+                    Some(XmlElement::Edge) => {
+                        destination.push_str(&text.unescape().wrap_err("Invalid destination city")?);
+                    },
+                    _ => {},
+                },
+                Event::CData(text) => {
+                    if let Some(XmlElement::Field(field)) = open.last() {
+                        fields.entry(field.clone()).or_default().push_str(&String::from_utf8_lossy(text));
+                    }
+                },
+                // Each edge is added to its city once its destination city has been read
+                Event::End(_) => {
+                    if let Some(XmlElement::Edge) = open.pop() {
+                        let city: usize = vertex.len() - 1;
+                        let destination_city: u32 = destination
+                            .trim()
+                            .parse()
+                            .wrap_err_with(|| format!("Invalid destination city {:?} of city {}", destination, city))?;
+                        vertex[city].edges.push(Edge { cost, destination_city });
+                    }
+                },
+                Event::Eof => break,
+                // Declarations, comments and processing instructions aren't part of the instance
+                _ => {},
+            }
+        }
+        if !found_root {
+            bail!("Missing travellingSalesmanProblemInstance");
+        }
+        if !found_graph {
+            bail!("Missing graph");
+        }
+
+        // Read each field of the instance from its text
+        let field = |name: &str| fields.get(name).map(|value| value.trim()).ok_or_else(|| eyre!("Missing {}", name));
+        let optional = |name: &str| fields.get(name).map(|value| value.trim());
+        Ok(Self {
+            name: field("name")?.to_string(),
+            source: field("source")?.to_string(),
+            description: field("description")?.to_string(),
+            double_precision: field("doublePrecision")?.parse().wrap_err("Invalid doublePrecision")?,
+            ignored_digits: field("ignoredDigits")?.parse().wrap_err("Invalid ignoredDigits")?,
+            graph: Graph::new(vertex),
+            cost_unit: optional("costUnit").map(str::to_string),
+            cost_decimals: optional("costDecimals").map(str::parse).transpose().wrap_err("Invalid costDecimals")?,
+        })
+    }
+
+    /// Function to write the Country in the `travellingSalesmanProblemInstance` XML format it is read from, laid
+    /// out like the coursework files so solvers that only read that format can use it. Costs are written in
+    /// scientific notation like the coursework files, so reading the file back gives the same costs
+    pub fn to_xml(&self) -> String {
+        let mut xml: String = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+        xml.push_str("<travellingSalesmanProblemInstance>\n\n");
+        let _ = writeln!(xml, "  <name>{}</name>\n", escape(&self.name));
+        let _ = writeln!(xml, "  <source>{}</source>\n", escape(&self.source));
+        let _ = writeln!(xml, "  <description>{}</description>\n", escape(&self.description));
+        let _ = writeln!(xml, "  <doublePrecision>{}</doublePrecision>\n", self.double_precision);
+        let _ = writeln!(xml, "  <ignoredDigits>{}</ignoredDigits>\n", self.ignored_digits);
+        if let Some(unit) = &self.cost_unit {
+            let _ = writeln!(xml, "  <costUnit>{}</costUnit>\n", escape(unit));
+        }
+        if let Some(decimals) = self.cost_decimals {
+            let _ = writeln!(xml, "  <costDecimals>{}</costDecimals>\n", decimals);
+        }
+
+        // Every city lists an edge to each city it is connected to
+        xml.push_str("  <graph>\n");
+        for vertex in self.graph.vertex.iter() {
+            xml.push_str("    <vertex>\n");
+            for edge in vertex {
+                let _ = writeln!(
+                    xml,
+                    "      <edge cost=\"{}\">{}</edge>",
+                    Country::scientific_cost(edge.cost),
+                    edge.destination_city
+                );
+            }
+            xml.push_str("    </vertex>\n");
+        }
+        xml.push_str("  </graph>\n\n</travellingSalesmanProblemInstance>\n");
+        xml
+    }
+
+    /// Function to write the Country to an XML file at the given path, see [`Country::to_xml`]
+    pub fn save_xml(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_xml()).wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Function to format a cost like the coursework files, e.g. `1.530000000000000e+02`, with a 16th decimal
+    /// place for the few costs that need it to be read back exactly. Rust leaves out the sign and leading zero of
+    /// the exponent, so they are added back
+    pub fn scientific_cost(cost: f64) -> String {
+        let mut formatted: String = format!("{:.15e}", cost);
+        if formatted.parse::<f64>().is_ok_and(|parsed| parsed != cost) {
+            formatted = format!("{:.16e}", cost);
+        }
+        match formatted.split_once('e').map(|(mantissa, exponent)| (mantissa, exponent.parse::<i32>())) {
+            Some((mantissa, Ok(exponent))) => {
+                let sign: char = if exponent < 0 { '-' } else { '+' };
+                format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+            },
+            // Infinite and NaN costs have no exponent
+            _ => formatted,
+        }
+    }
+
+    /// Function to read the cost attribute of an edge of the given city
+    fn edge_cost(element: &BytesStart, city: usize) -> Result<f64> {
+        let attribute = element
+            .try_get_attribute("cost")
+            .wrap_err_with(|| format!("Invalid attributes on an edge of city {}", city))?
+            .ok_or_else(|| eyre!("An edge of city {} has no cost", city))?;
+        let value = attribute.unescape_value().wrap_err_with(|| format!("Invalid cost on an edge of city {}", city))?;
+        value.trim().parse().wrap_err_with(|| format!("Invalid cost {:?} on an edge of city {}", value, city))
+    }
+
+    /// Function to read a Country from the contents of a TSPLIB `.tsp` file. Only symmetric problems (`TYPE : TSP`)
+    /// are supported, either with their costs listed with `EDGE_WEIGHT_TYPE : EXPLICIT`, or with their cities given
+    /// by their coordinates with an `EDGE_WEIGHT_TYPE` of `EUC_2D`, `GEO` or `ATT`, each with the rounding TSPLIB
+    /// uses so the published optimal costs are reproduced. The cities are
+    /// numbered from 0 in the order of their numbers in the file, like the cities of the XML format
+    pub fn from_tsplib(src: &str) -> Result<Self> {
+        let file = TsplibFile::parse(src);
+
+        match file.field("TYPE") {
+            Some("TSP") => {},
+            Some(other) => return Err(eyre!("Unsupported TYPE {}, only TSP is supported", other)),
+            None => return Err(eyre!("Missing TYPE")),
+        }
+        let dimension: usize = file.field("DIMENSION")
+            .ok_or_else(|| eyre!("Missing DIMENSION"))?
+            .parse()
+            .wrap_err("Invalid DIMENSION")?;
+
+        // Work out the cost of travel between every pair of cities, either given in the file or from the
+        // coordinates of the cities
+        let distance: fn((f64, f64), (f64, f64)) -> f64 = match file.field("EDGE_WEIGHT_TYPE") {
+            Some("EXPLICIT") => {
+                return Country::from_tsplib_costs(&file, dimension, file.explicit_weights(dimension)?);
+            },
+            Some("EUC_2D") => euclidean_2d,
+            Some("GEO") => geographic,
+            Some("ATT") => pseudo_euclidean,
+            Some(other) => return Err(eyre!("Unsupported EDGE_WEIGHT_TYPE {}", other)),
+            None => return Err(eyre!("Missing EDGE_WEIGHT_TYPE")),
+        };
+        let coordinates: Vec<(f64, f64)> = file.coordinates(dimension)?;
+        let costs: Vec<f64> = coordinates
+            .iter()
+            .flat_map(|from| coordinates.iter().map(move |to| distance(*from, *to)))
+            .collect();
+        Country::from_tsplib_costs(&file, dimension, costs)
+    }
+
+    /// Function to build a Country from a TSPLIB file and the cost of travel between every pair of its cities,
+    /// indexed by `from * dimension + to` like a [`DistanceMatrix`]
+    fn from_tsplib_costs(file: &TsplibFile, dimension: usize, costs: Vec<f64>) -> Result<Self> {
+        // Each city has an edge to every other city, as in the XML format
+        let vertex: Vec<Vertex> = costs
+            .chunks(dimension.max(1))
+            .enumerate()
+            .map(|(from, row)| Vertex {
+                edges: row
+                    .iter()
+                    .enumerate()
+                    .filter(|(to, _)| *to != from)
+                    .map(|(to, cost)| Edge { cost: *cost, destination_city: to as u32 })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Self {
+            name: file.field("NAME").ok_or_else(|| eyre!("Missing NAME"))?.to_string(),
+            source: String::from("TSPLIB"),
+            description: file.field("COMMENT").unwrap_or_default().to_string(),
+            double_precision: 15.0,
+            ignored_digits: 0,
+            graph: Graph::new(vertex),
+            cost_unit: None,
+            cost_decimals: None,
+        })
+    }
+
+    /// Function to load every file in a directory whose format can be detected, whatever mix of formats
+    /// they are in. Files that aren't text, aren't in a recognised format or fail to parse are skipped,
+    /// or are an error if strict. Hidden files, such as `.DS_Store`, are always skipped. Also returns the
+    /// path of every file, in name order, along with whether it was loaded
+    pub fn from_directory(directory: &Path, strict: bool) -> Result<(Vec<Self>, FileFormats)> {
+        // Create a sorted list of all files in the directory so they always load in the same order
+        let mut paths: Vec<PathBuf> = fs::read_dir(directory)
+            .wrap_err_with(|| format!("Failed to read data directory {}", directory.display()))?
+            .map(|file| file.map(|file| file.path()))
+            .collect::<std::io::Result<Vec<PathBuf>>>()?;
+        paths.retain(|path| path.is_file());
+        paths.sort();
+
         // Create a vector of Countries
         let mut output: Vec<Self> = Vec::new();
+        let mut formats: FileFormats = Vec::with_capacity(paths.len());
 
         // Loop over all files in directory
-        for file in  directory {
-            // Imports the XML file as a String
-            let src: String = fs::read_to_string(file?.path()).wrap_err("Failed to read XML file")?;
-            // Convert String to &str and use serde_xml_rs to deserialize into the Struct Country
-            let data: Self = serde_xml_rs::from_str(src.as_str()).wrap_err("Failed to deserialize XML data")?;
-            // Push Country to the output vector
-            output.push(data);
+        for path in paths {
+            let status: FileStatus = match Country::load_file(&path)? {
+                Ok((country, format)) => {
+                    output.push(country);
+                    FileStatus::Loaded(format)
+                },
+                // Hidden files are never datasets, so are skipped even if strict
+                Err(reason) if strict && !Country::is_hidden(&path) => {
+                    return Err(eyre!("Failed to load {}: {}", path.display(), reason));
+                },
+                Err(reason) => FileStatus::Skipped(reason),
+            };
+            formats.push((path, status));
+        }
+
+        Ok((output, formats))
+    }
+
+    /// Function to create the root structure for each of the given files only, printing the format each file was
+    /// loaded as. Each file is looked for where it is given, then in the data directory, so a dataset in the data
+    /// directory can be given by its file name alone. Unlike loading a whole directory, a file that can't be found
+    /// or loaded is always an error, as is the same file given twice
+    pub fn from_files(files: &[PathBuf], directory: &Path) -> Result<Vec<Self>> {
+        let mut output: Vec<Self> = Vec::with_capacity(files.len());
+        let mut loaded: Vec<PathBuf> = Vec::with_capacity(files.len());
+        for file in files {
+            let path: PathBuf = Country::resolve_file(file, directory)?;
+            if loaded.contains(&path) {
+                return Err(eyre!("{} was given more than once", path.display()));
+            }
+            let (country, format) = Country::load_file(&path)?
+                .map_err(|reason| eyre!("Failed to load {}: {}", path.display(), reason))?;
+            println!("Loaded {} as {:?}", path.display(), format);
+            output.push(country);
+            loaded.push(path);
         }
-        // Return data as the type Country
         Ok(output)
     }
+
+    /// Function to keep only the countries whose name matches any of the given patterns, see [`matches_pattern`].
+    /// Every country is kept if there are no patterns, and it is an error if there are but none match
+    pub fn filter_by_name(countries: Vec<Self>, patterns: &[String]) -> Result<Vec<Self>> {
+        if patterns.is_empty() {
+            return Ok(countries);
+        }
+        let loaded: usize = countries.len();
+        let matching: Vec<Self> = countries
+            .into_iter()
+            .filter(|country| patterns.iter().any(|pattern| matches_pattern(&country.name, pattern)))
+            .collect();
+        if matching.is_empty() {
+            return Err(eyre!("None of the {} datasets loaded match {}", loaded, patterns.join(" or ")));
+        }
+        println!("Running the {} of {} datasets matching {}", matching.len(), loaded, patterns.join(" or "));
+        Ok(matching)
+    }
+
+    /// Function to find a file given on its own, either where it is given or in the data directory
+    pub fn resolve_file(file: &Path, directory: &Path) -> Result<PathBuf> {
+        [file.to_path_buf(), directory.join(file)]
+            .into_iter()
+            .find(|path| path.is_file())
+            .ok_or_else(|| eyre!("{} isn't a file, or a file in {}", file.display(), directory.display()))
+    }
+
+    /// Function to load a single file of the data directory, returning why it can't be loaded if it can't.
+    /// Only failing to read the file at all is an error
+    fn load_file(path: &Path) -> Result<Result<(Self, InstanceFormat), String>> {
+        if Country::is_hidden(path) {
+            return Ok(Err(String::from("hidden file")));
+        }
+
+        // Imports the file as a String, skipping binary files
+        let mut bytes: Vec<u8> = fs::read(path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+
+        // Gzipped files are decompressed first, then the extension under `.gz` gives their format
+        let mut format_path: &Path = path;
+        let stem: PathBuf;
+        if Country::is_gzipped(path) {
+            bytes = match decompress_gzip(&bytes) {
+                Ok(decompressed) => decompressed,
+                Err(error) => return Ok(Err(format!("could not be decompressed: {}", error))),
+            };
+            stem = path.with_extension("");
+            format_path = &stem;
+        }
+        let Ok(src) = String::from_utf8(bytes) else {
+            return Ok(Err(String::from("not a text file")));
+        };
+
+        // Work out the format of the file, then parse it as that format
+        let Some(format) = InstanceFormat::detect(format_path, &src) else {
+            return Ok(Err(String::from("format not recognised")));
+        };
+        Ok(format
+            .parse(&src)
+            .map(|country| (country, format))
+            .map_err(|report| format!("could not be read as {:?}: {:#}", format, report)))
+    }
+
+    /// Function to check if a file is gzipped, going by its `.gz` extension
+    fn is_gzipped(path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"))
+    }
+
+    /// Function to check if a file is hidden, which on Unix means its name starts with a `.`
+    fn is_hidden(path: &Path) -> bool {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'))
+    }
+
+    /// Function to create the axis label for costs, including the unit if the instance has one
+    pub fn cost_label(&self) -> String {
+        match &self.cost_unit {
+            Some(unit) => format!("cost ({})", unit),
+            None => String::from("cost"),
+        }
+    }
+
+    /// Function to format a cost for output, rounded to the instances decimal places
+    /// and followed by its unit if the instance has them
+    pub fn format_cost(&self, cost: f64) -> String {
+        // Round to the given number of decimal places, or print as is
+        let value: String = match self.cost_decimals {
+            Some(decimals) => format!("{:.*}", decimals, cost),
+            None => format!("{}", cost),
+        };
+
+        // Append the unit if there is one
+        match &self.cost_unit {
+            Some(unit) => format!("{} {}", value, unit),
+            None => value,
+        }
+    }
 }
@@ -0,0 +1,259 @@
+//! This module downloads TSPLIB instances by name, such as `berlin52`, from a mirror into the data directory with
+//! [`fetch`]. Each download has to match a known SHA-256 checksum, either one given by the user, one in the
+//! [`CHECKSUM_FILE`] of the data directory or one of the [`PINNED_CHECKSUMS`] shipped with the program, and is then
+//! converted to the XML format so it can be loaded like the coursework datasets.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::{bail, eyre, WrapErr}, Result};
+use sha2::{Digest, Sha256};
+
+use super::country::{decompress_gzip, Country};
+
+/// The mirror instances are downloaded from unless another is given, the home of TSPLIB
+pub const DEFAULT_MIRROR: &str = "https://comopt.ifi.uni-heidelberg.de/software/TSPLIB95/tsp/";
+
+/// The file in the data directory that holds the checksum of every instance downloaded into it, in the format
+/// of `sha256sum`. It is hidden so it is never loaded as a dataset
+pub const CHECKSUM_FILE: &str = ".checksums.sha256";
+
+/// The checksums of the gzipped TSPLIB instances shipped with the program, in the format of `sha256sum`, so
+/// instances can be checked the first time they are downloaded
+pub const PINNED_CHECKSUMS: &str = include_str!("tsplib.sha256");
+
+/// The largest download accepted, which is well above the largest gzipped TSPLIB instance
+pub const MAX_DOWNLOAD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// The SHA-256 checksum of every file downloaded into a data directory, by file name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Checksums {
+    /// The lowercase hex checksum of each file, in name order so the file is always written the same way
+    pub entries: BTreeMap<String, String>,
+}
+
+/// Implement methods on `Checksums`
+impl Checksums {
+    /// Function to read the checksums in a data directory, which there are none of if it has no checksum file
+    pub fn load(directory: &Path) -> Result<Self> {
+        let path: PathBuf = directory.join(CHECKSUM_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let src: String = fs::read_to_string(&path).wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        Checksums::parse(&src).wrap_err_with(|| format!("Invalid checksum file {}", path.display()))
+    }
+
+    /// Function to read the checksums shipped with the program, see [`PINNED_CHECKSUMS`]
+    pub fn pinned() -> Result<Self> {
+        Checksums::parse(PINNED_CHECKSUMS).wrap_err("Invalid pinned checksums")
+    }
+
+    /// Function to read checksums in the format of `sha256sum`, a checksum then the file name on each line.
+    /// Blank lines and comments starting with `#` are ignored
+    pub fn parse(src: &str) -> Result<Self> {
+        let mut entries: BTreeMap<String, String> = BTreeMap::new();
+        for (number, line) in src
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        {
+            // sha256sum marks files read in binary mode with a `*` before their name
+            let (checksum, name) = line
+                .split_once(char::is_whitespace)
+                .map(|(checksum, name)| (checksum, name.trim().trim_start_matches('*')))
+                .ok_or_else(|| eyre!("Line {} has no file name", number + 1))?;
+            if checksum.len() != 64 || !checksum.chars().all(|character| character.is_ascii_hexdigit()) {
+                bail!("Line {} has an invalid checksum {:?}", number + 1, checksum);
+            }
+            entries.insert(name.to_string(), checksum.to_ascii_lowercase());
+        }
+        Ok(Self { entries })
+    }
+
+    /// Function to write the checksums in the format of `sha256sum`
+    pub fn to_sha256sum(&self) -> String {
+        let mut src: String = String::new();
+        for (name, checksum) in self.entries.iter() {
+            let _ = writeln!(src, "{}  {}", checksum, name);
+        }
+        src
+    }
+
+    /// Function to write the checksums to the checksum file of a data directory
+    pub fn save(&self, directory: &Path) -> Result<()> {
+        let path: PathBuf = directory.join(CHECKSUM_FILE);
+        fs::write(&path, self.to_sha256sum()).wrap_err_with(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Function to check the contents of a file against its checksum, erroring if they don't match or the file
+    /// has no checksum, so nothing is trusted without one
+    pub fn verify(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let checksum: String = sha256_hex(bytes);
+        match self.entries.get(name) {
+            Some(expected) if *expected == checksum => Ok(()),
+            Some(expected) => Err(eyre!(
+                "Checksum mismatch for {}: expected {} but downloaded {}",
+                name, expected, checksum
+            )),
+            None => Err(eyre!(
+                "No known checksum for {}, give one from a trusted source with --checksum",
+                name
+            )),
+        }
+    }
+
+    /// Function to add the checksums of another set, keeping these ones for files in both
+    pub fn or(mut self, other: &Checksums) -> Self {
+        for (name, checksum) in other.entries.iter() {
+            self.entries.entry(name.clone()).or_insert_with(|| checksum.clone());
+        }
+        self
+    }
+}
+
+/// Function to find the lowercase hex SHA-256 checksum of some bytes, as printed by `sha256sum`
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hex: String = String::with_capacity(64);
+    for byte in Sha256::digest(bytes) {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+    hex
+}
+
+/// Function to check that an instance name is safe to put in a URL and a file name, so only letters, digits,
+/// `-`, `_` and `.` are allowed, and it can't start with a `.`
+pub fn check_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        bail!("An instance name can't be empty");
+    }
+    if name.starts_with('.') {
+        bail!("Invalid instance name {:?}, it can't start with a '.'", name);
+    }
+    if let Some(character) = name
+        .chars()
+        .find(|character| !(character.is_ascii_alphanumeric() || matches!(character, '-' | '_' | '.')))
+    {
+        bail!("Invalid instance name {:?}, it can't contain {:?}", name, character);
+    }
+    Ok(())
+}
+
+/// Function to find the location of the gzipped TSPLIB file of an instance on a mirror, which is either a URL
+/// or a local directory laid out like one
+pub fn instance_url(mirror: &str, name: &str) -> String {
+    format!("{}/{}.tsp.gz", mirror.trim_end_matches('/'), name)
+}
+
+/// Function to check if a mirror is on the web rather than a local directory
+fn is_remote(mirror: &str) -> bool {
+    mirror.starts_with("http://") || mirror.starts_with("https://")
+}
+
+/// Function to download a file from a URL, or read it if the mirror is a local directory
+pub fn download(url: &str) -> Result<Vec<u8>> {
+    if !is_remote(url) {
+        return fs::read(url).wrap_err_with(|| format!("Failed to read {}", url));
+    }
+
+    let response = ureq::get(url).call().wrap_err_with(|| format!("Failed to download {}", url))?;
+    let mut bytes: Vec<u8> = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .wrap_err_with(|| format!("Failed to download {}", url))?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        bail!("{} is larger than {} bytes", url, MAX_DOWNLOAD_BYTES);
+    }
+    Ok(bytes)
+}
+
+/// Function to convert a downloaded TSPLIB file into a Country, decompressing it first if it is gzipped, as some
+/// mirrors decompress files as they send them
+pub fn convert(bytes: &[u8]) -> Result<Country> {
+    let decompressed: Vec<u8>;
+    let bytes: &[u8] = if bytes.starts_with(&[0x1f, 0x8b]) {
+        decompressed = decompress_gzip(bytes).wrap_err("Failed to decompress the download")?;
+        &decompressed
+    } else {
+        bytes
+    };
+    let src: &str = std::str::from_utf8(bytes).wrap_err("The download isn't text")?;
+    let country: Country = Country::from_tsplib(src)?;
+    country.graph.validate()?;
+    Ok(country)
+}
+
+/// Function to read a checksum given for an instance as `<name>=<sha256>`, keyed by the file name of the instance
+/// on a mirror
+pub fn parse_checksum(src: &str) -> Result<(String, String)> {
+    let (name, checksum) = src
+        .split_once('=')
+        .ok_or_else(|| eyre!("{} isn't a checksum, give it as <name>=<sha256>", src))?;
+    check_name(name)?;
+    let parsed: Checksums = Checksums::parse(&format!("{}  {}.tsp.gz", checksum, name))?;
+    parsed.entries.into_iter().next().ok_or_else(|| eyre!("{} isn't a checksum", src))
+}
+
+/// Function to download each named instance from a mirror, check its checksum and write it to the data directory
+/// as `<name>.xml`, returning the paths written. Each download is checked against the checksum given for it in
+/// `trusted`, then the one in the checksum file of the data directory, then the pinned one, and an instance with
+/// none of these is an error. A dataset already in the data directory is only overwritten if `force` is set.
+/// Every name is checked before anything is downloaded, and the checksums of the instances fetched before a
+/// failure are still recorded in the data directory
+pub fn fetch(
+    names: &[String],
+    mirror: &str,
+    directory: &Path,
+    trusted: &Checksums,
+    force: bool,
+) -> Result<Vec<PathBuf>> {
+    for name in names {
+        check_name(name)?;
+        let path: PathBuf = directory.join(format!("{}.xml", name));
+        if path.exists() && !force {
+            bail!("{} already exists, pass --force to overwrite it", path.display());
+        }
+    }
+    fs::create_dir_all(directory)
+        .wrap_err_with(|| format!("Failed to create data directory {}", directory.display()))?;
+
+    let mut checksums: Checksums = Checksums::load(directory)?;
+    let known: Checksums = trusted.clone().or(&checksums).or(&Checksums::pinned()?);
+    let mut written: Vec<PathBuf> = Vec::with_capacity(names.len());
+    let mut result: Result<()> = Ok(());
+    for name in names {
+        let url: String = instance_url(mirror, name);
+        let file_name: String = format!("{}.tsp.gz", name);
+        let path: PathBuf = directory.join(format!("{}.xml", name));
+        let fetched: Result<()> = download(&url).and_then(|bytes| {
+            known.verify(&file_name, &bytes)?;
+            let mut country: Country = convert(&bytes).wrap_err_with(|| format!("Invalid instance {}", name))?;
+            country.name = name.clone();
+            country.save_xml(&path)?;
+
+            // Record the checksum the download matched, so it is checked the same way next time
+            checksums.entries.insert(file_name.clone(), sha256_hex(&bytes));
+            Ok(())
+        });
+        match fetched {
+            Ok(()) => {
+                println!("Fetched {} to {} (checksum verified)", name, path.display());
+                written.push(path);
+            },
+            Err(report) => {
+                result = Err(report);
+                break;
+            },
+        }
+    }
+
+    checksums.save(directory)?;
+    result.map(|()| written)
+}
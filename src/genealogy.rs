@@ -0,0 +1,176 @@
+//! This module defines [`Genealogy`], which records the parents of every [`Chromosome`] that joined a
+//! [`Population`], so the lineage of the best tour of a run can be traced back to the initial population.
+//! It is written to a JSON file and a Graphviz DOT file once the run finishes, along with the build that ran it and
+//! the tags and notes of the experiment.
+//!
+//! [`Population`]: crate::population::Population
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
+use chrono::prelude::*;
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{build_info::BuildInfo, chromosome::Chromosome, manifest::ExperimentNotes};
+
+/// Where a single Chromosome that joined the population came from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ancestry {
+    /// The id the Chromosome was given by the population
+    pub id: u64,
+    /// The ids of the Chromosomes it was bred from, empty for the initial population and immigrants. Children
+    /// passed through without crossover have only the parent they are a copy of
+    pub parents: Vec<u64>,
+    /// The cost of the Chromosome when it joined the population
+    pub cost: f64,
+    /// The generation the Chromosome joined the population at
+    pub generation: u32,
+}
+
+/// Every Chromosome that joined the population of a run, along with its parents
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Genealogy {
+    /// Name of the country the population was run on
+    pub instance: String,
+    /// Which run of the country the population is from, starting from 1. 0 until the run is known
+    pub run: u32,
+    /// The generation being bred, given to the Chromosomes that join the population
+    pub generation: u32,
+    /// The id of the best Chromosome in the population
+    pub best: u64,
+    /// Every Chromosome that joined the population, in the order they joined
+    pub chromosomes: Vec<Ancestry>,
+    /// The build that recorded the genealogy, None for genealogies saved before builds were recorded
+    #[serde(default)]
+    pub build: Option<BuildInfo>,
+    /// Freeform tags attached to every run in the experiment, empty until the run is known
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Why the experiment was run and who ran it, empty until the run is known
+    #[serde(default)]
+    pub notes: ExperimentNotes,
+    /// Children that have been bred but haven't been through replacement yet, only those that survive it are kept
+    #[serde(skip)]
+    pub pending: Vec<Ancestry>,
+}
+
+/// Implement methods on `Genealogy`
+impl Genealogy {
+    /// Function to start the genealogy of a population from its initial Chromosomes, which have no parents
+    pub fn new(population_data: &[Chromosome]) -> Self {
+        let chromosomes: Vec<Ancestry> = population_data
+            .iter()
+            .map(|chromosome| Ancestry { id: chromosome.id, parents: Vec::new(), cost: chromosome.cost, generation: 0 })
+            .collect();
+        let best: u64 = population_data
+            .iter()
+            .min_by(|x, y| x.cost.total_cmp(&y.cost))
+            .map_or(0, |chromosome| chromosome.id);
+        Self { best, chromosomes, build: Some(BuildInfo::current()), ..Default::default() }
+    }
+
+    /// Function to remember the parents of a Chromosome that may join the population. Parents are only listed once,
+    /// even if the same Chromosome was picked as both
+    pub fn propose(&mut self, chromosome: &Chromosome, parents: &[u64]) {
+        let mut unique: Vec<u64> = Vec::with_capacity(parents.len());
+        for parent in parents {
+            if !unique.contains(parent) {
+                unique.push(*parent);
+            }
+        }
+        self.pending.push(Ancestry {
+            id: chromosome.id,
+            parents: unique,
+            cost: chromosome.cost,
+            generation: self.generation,
+        });
+    }
+
+    /// Function to keep the proposed Chromosomes that are now in the population, forgetting those that weren't
+    /// accepted, and to note the best Chromosome of the population. Costs are taken from the population, as a
+    /// child can be changed after it is proposed, such as when it is perturbed for being a duplicate
+    pub fn settle(&mut self, population_data: &[Chromosome], best: u64) {
+        let costs: HashMap<u64, f64> = population_data
+            .iter()
+            .map(|chromosome| (chromosome.id, chromosome.cost))
+            .collect();
+        for mut ancestry in self.pending.drain(..) {
+            if let Some(cost) = costs.get(&ancestry.id) {
+                ancestry.cost = *cost;
+                self.chromosomes.push(ancestry);
+            }
+        }
+        self.best = best;
+    }
+
+    /// Function to find the ids of every ancestor of a Chromosome, along with the Chromosome itself, in id order
+    pub fn lineage(&self, id: u64) -> BTreeSet<u64> {
+        let parents: HashMap<u64, &[u64]> = self.chromosomes
+            .iter()
+            .map(|ancestry| (ancestry.id, ancestry.parents.as_slice()))
+            .collect();
+
+        // Walk back through the parents, visiting each ancestor once
+        let mut lineage: BTreeSet<u64> = BTreeSet::new();
+        let mut unvisited: Vec<u64> = vec![id];
+        while let Some(current) = unvisited.pop() {
+            if lineage.insert(current) {
+                unvisited.extend(parents.get(&current).copied().unwrap_or_default());
+            }
+        }
+        lineage
+    }
+
+    /// Function to write the genealogy as a Graphviz DOT graph, with an arrow from each parent to its children.
+    /// The lineage of the best Chromosome is highlighted, and the graph is labelled with the build, tags and notes
+    pub fn to_dot(&self) -> String {
+        let lineage: BTreeSet<u64> = self.lineage(self.best);
+        let mut label: String = format!("{} run {}", self.instance, self.run);
+        if let Some(build) = &self.build {
+            label.push_str(&format!(", Build: {}", build.id()));
+        }
+        label.push_str(&self.notes.tagged_caption(&self.tags));
+
+        let mut dot: String = String::from("digraph genealogy {\n");
+        let _ = writeln!(dot, "    label=\"{}\";", label.replace('\\', "\\\\").replace('"', "\\\""));
+        dot.push_str("    node [shape=box];\n");
+        for ancestry in self.chromosomes.iter() {
+            let style: &str = if lineage.contains(&ancestry.id) { ", color=red, penwidth=2" } else { "" };
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\\ngeneration {}\\ncost {}\"{}];",
+                ancestry.id, ancestry.id, ancestry.generation, ancestry.cost, style
+            );
+            for parent in ancestry.parents.iter() {
+                let _ = writeln!(dot, "    {} -> {};", parent, ancestry.id);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Function to write the genealogy to a new JSON file and DOT file in the results directory, returning their paths
+    pub fn save(&self) -> Result<(PathBuf, PathBuf)> {
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+
+        // Generate unique paths for the genealogy using the run, date and time
+        let time: DateTime<Utc> = Utc::now();
+        let name: String = format!(
+            "results/genealogy-{}-run{}-{}",
+            self.instance,
+            self.run,
+            time.format("%Y-%m-%d-%H-%M-%S"),
+        );
+        let json = PathBuf::from(format!("{}.json", name));
+        let dot = PathBuf::from(format!("{}.dot", name));
+        fs::write(&json, serde_json::to_string(self)?)?;
+        fs::write(&dot, self.to_dot())?;
+        Ok((json, dot))
+    }
+}
@@ -0,0 +1,253 @@
+//! This module is a test harness for the genetic operators. It has small fixed graphs whose tour costs were worked
+//! out by hand, along with the children each crossover operator makes from fixed crossover points and the routes
+//! each mutation operator makes at fixed indices, worked out by hand on those graphs. The check functions run an
+//! operator on a case and say what went wrong if it doesn't match, so a new operator can be validated by adding
+//! its cases to [`crossover_cases`] or [`mutation_cases`], which the tests already run every case of.
+
+use color_eyre::{eyre::{bail, WrapErr}, Result};
+
+use super::{
+    chromosome::Chromosome,
+    country::{Edge, Graph, Vertex},
+    interface::{CrossoverOperator, MutationOperator},
+};
+
+/// A small graph along with some of its tours and their costs, worked out by hand
+#[derive(Debug, Clone)]
+pub struct GraphFixture {
+    /// The name the graph is reported under
+    pub name: &'static str,
+    /// The graph itself
+    pub graph: Graph,
+    /// Routes on the graph and the cost of the tour each one makes
+    pub tours: Vec<(Vec<u32>, f64)>,
+}
+
+/// The child a crossover operator should make from two parents and fixed crossover points
+#[derive(Debug, Clone)]
+pub struct CrossoverCase {
+    /// The operator the case checks
+    pub operator: CrossoverOperator,
+    /// The parent the child takes the most from
+    pub first_parent: Vec<u32>,
+    /// The other parent
+    pub second_parent: Vec<u32>,
+    /// The crossover points, as taken by [`Chromosome::crossover_child`]
+    pub crossover_points: Vec<usize>,
+    /// The child the operator should make
+    pub child: Vec<u32>,
+}
+
+/// The route a mutation operator should make from a route and fixed indices
+#[derive(Debug, Clone)]
+pub struct MutationCase {
+    /// The operator the case checks
+    pub operator: MutationOperator,
+    /// The route before the mutation
+    pub route: Vec<u32>,
+    /// The indices, as taken by [`Chromosome::mutate_at`]
+    pub indices: Vec<usize>,
+    /// The route the operator should make
+    pub mutated: Vec<u32>,
+}
+
+/// Function to build a graph from the full matrix of costs between every pair of cities, where the cost from
+/// city i to city j is `costs[i][j]`. The diagonal is ignored
+pub fn graph_from_costs(costs: &[Vec<f64>]) -> Graph {
+    let vertex: Vec<Vertex> = costs
+        .iter()
+        .enumerate()
+        .map(|(from, row)| Vertex {
+            edges: row
+                .iter()
+                .enumerate()
+                .filter(|(to, _)| *to != from)
+                .map(|(to, cost)| Edge { cost: *cost, destination_city: to as u32 })
+                .collect(),
+        })
+        .collect();
+    Graph::new(vertex)
+}
+
+/// The first four cities of burma14, which only have three different tours
+pub fn burma4() -> GraphFixture {
+    GraphFixture {
+        name: "burma4",
+        graph: graph_from_costs(&[
+            vec![0.0, 153.0, 510.0, 706.0],
+            vec![153.0, 0.0, 422.0, 664.0],
+            vec![510.0, 422.0, 0.0, 289.0],
+            vec![706.0, 664.0, 289.0, 0.0],
+        ]),
+        tours: vec![
+            // 153 + 422 + 289 + 706
+            (vec![0, 1, 2, 3], 1570.0),
+            // 153 + 664 + 289 + 510
+            (vec![0, 1, 3, 2], 1616.0),
+            // 510 + 422 + 664 + 706
+            (vec![0, 2, 1, 3], 2302.0),
+            // The first tour started elsewhere and travelled backwards
+            (vec![2, 1, 0, 3], 1570.0),
+        ],
+    }
+}
+
+/// Six cities evenly spaced along a line, so the cost between two cities is the difference between their numbers.
+/// Every operator case is worked out on this graph
+pub fn line6() -> GraphFixture {
+    let costs: Vec<Vec<f64>> = (0..6)
+        .map(|from: i32| (0..6).map(|to: i32| (from - to).abs() as f64).collect())
+        .collect();
+    GraphFixture {
+        name: "line6",
+        graph: graph_from_costs(&costs),
+        tours: vec![
+            // Along the line and back, 1 + 1 + 1 + 1 + 1 + 5
+            (vec![0, 1, 2, 3, 4, 5], 10.0),
+            (vec![5, 4, 3, 2, 1, 0], 10.0),
+            // 2 + 2 + 3 + 2 + 2 + 5
+            (vec![0, 2, 4, 1, 3, 5], 16.0),
+            // 5 + 4 + 3 + 2 + 1 + 3
+            (vec![0, 5, 1, 4, 2, 3], 18.0),
+        ],
+    }
+}
+
+/// Function to list every fixed graph
+pub fn graphs() -> Vec<GraphFixture> {
+    vec![burma4(), line6()]
+}
+
+/// Function to list the crossover cases, all worked out on [`line6`]
+pub fn crossover_cases() -> Vec<CrossoverCase> {
+    let first: Vec<u32> = vec![0, 1, 2, 3, 4, 5];
+    let second: Vec<u32> = vec![3, 5, 1, 0, 4, 2];
+    let case = |operator: CrossoverOperator, swapped: bool, crossover_points: Vec<usize>, child: Vec<u32>| {
+        let (first_parent, second_parent) = if swapped {
+            (second.clone(), first.clone())
+        } else {
+            (first.clone(), second.clone())
+        };
+        CrossoverCase { operator, first_parent, second_parent, crossover_points, child }
+    };
+
+    vec![
+        // [0, 1, 2] + [0, 4, 2] repeats 0 and 2, so their first copies are replaced with the missing 3 and 5
+        case(CrossoverOperator::Fix, false, vec![3], vec![3, 1, 5, 0, 4, 2]),
+        // [3, 5, 1] + [3, 4, 5] repeats 3 and 5, so their first copies are replaced with the missing 0 and 2
+        case(CrossoverOperator::Fix, true, vec![3], vec![0, 2, 1, 3, 4, 5]),
+        // [0, 1] and [3, 4] are kept, then 5 and 2 fill the gaps in the order they are in the second parent
+        case(CrossoverOperator::Ordered, false, vec![0, 1, 3, 4], vec![0, 1, 5, 3, 4, 2]),
+        // [5, 1] and [4, 2] are kept, then 0 and 3 fill the gaps in the order they are in the second parent
+        case(CrossoverOperator::Ordered, true, vec![1, 2, 4, 5], vec![0, 5, 1, 3, 4, 2]),
+        // With two points only [1, 2, 3] is kept, then 5, 0 and 4 fill the gaps in the order they are in the second parent
+        case(CrossoverOperator::Ordered, false, vec![1, 3], vec![5, 1, 2, 3, 0, 4]),
+        // Cycles are positions {0, 3}, {1, 5, 2} and {4}, taken from the first, second and first parent
+        case(CrossoverOperator::Cycle, false, vec![], vec![0, 5, 1, 3, 4, 2]),
+        // [0, 1] and [3, 4] are kept, then 2 is nearer to 1 than 5 is, even though 5 is first in the second parent
+        case(CrossoverOperator::Nearest, false, vec![0, 1, 3, 4], vec![0, 1, 2, 3, 4, 5]),
+        // [1, 2] and [4, 5] are kept, the first gap comes after 5 at the end so takes 3, which leaves 0
+        case(CrossoverOperator::Nearest, false, vec![1, 2, 4, 5], vec![3, 1, 2, 0, 4, 5]),
+        // Following the first parent is never beaten by the second, so the child is the first parent
+        case(CrossoverOperator::Scx, false, vec![], vec![0, 1, 2, 3, 4, 5]),
+        // From 3, 4 (1) beats 5 (2). From 4, 5 (1) beats 2 (2). From 5, 1 (4) beats the lowest unvisited 0 (5).
+        // From 1, 0 and 2 tie so the first parent's 0 is taken, which leaves 2
+        case(CrossoverOperator::Scx, true, vec![], vec![3, 4, 5, 1, 0, 2]),
+    ]
+}
+
+/// Function to list the mutation cases, all worked out on [`line6`]. The outcome of scramble is random even with
+/// fixed indices, so its case shuffles a slice of one gene, which can only stay where it is
+pub fn mutation_cases() -> Vec<MutationCase> {
+    let route: Vec<u32> = vec![0, 1, 2, 3, 4, 5];
+    let case = |operator: MutationOperator, indices: Vec<usize>, mutated: Vec<u32>| {
+        MutationCase { operator, route: route.clone(), indices, mutated }
+    };
+
+    vec![
+        // The cities outside of [2, 3] are reversed, [0, 1, 4, 5] becoming [5, 4, 1, 0]
+        case(MutationOperator::Inversion, vec![2, 4], vec![5, 4, 2, 3, 1, 0]),
+        // The indices can be given in either order, [0, 4, 5] becoming [5, 4, 0]
+        case(MutationOperator::Inversion, vec![4, 1], vec![5, 1, 2, 3, 4, 0]),
+        case(MutationOperator::Single, vec![1, 4], vec![0, 4, 2, 3, 1, 5]),
+        // 0 and 5 are swapped, then 2 and 3
+        case(MutationOperator::Multiple, vec![0, 5, 2, 3], vec![5, 1, 3, 2, 4, 0]),
+        // The slice [2] has only one order
+        case(MutationOperator::Scramble, vec![2, 3], vec![0, 1, 2, 3, 4, 5]),
+        // [1, 2] is cut out leaving [0, 3, 4, 5], then goes in at index 2
+        case(MutationOperator::Displacement, vec![1, 3, 2], vec![0, 3, 1, 2, 4, 5]),
+        // [4, 5] is cut out leaving [0, 1, 2, 3], then goes in at the start
+        case(MutationOperator::OrOpt, vec![4, 6, 0], vec![4, 5, 0, 1, 2, 3]),
+        // 0 is removed leaving [1, 2, 3, 4, 5], then goes in at index 3
+        case(MutationOperator::Insertion, vec![0, 3], vec![1, 2, 3, 0, 4, 5]),
+        // 4 is removed leaving [0, 1, 2, 3, 5], then goes in at index 1
+        case(MutationOperator::Insertion, vec![4, 1], vec![0, 4, 1, 2, 3, 5]),
+        // The edges 1 -> 2 and 4 -> 5 are replaced by reversing [2, 3, 4]
+        case(MutationOperator::ExpensiveEdge, vec![1, 4], vec![0, 1, 4, 3, 2, 5]),
+    ]
+}
+
+/// Function to check a route visits every city of a graph exactly once
+pub fn check_route(route: &[u32], graph: &Graph) -> Result<()> {
+    let mut sorted: Vec<u32> = route.to_vec();
+    sorted.sort();
+    if sorted != (0..graph.vertex.len() as u32).collect::<Vec<u32>>() {
+        bail!("{:?} doesn't visit each of the {} cities exactly once", route, graph.vertex.len());
+    }
+    Ok(())
+}
+
+/// Function to check the cost of every tour of a fixed graph is the one worked out by hand
+pub fn check_fitness(fixture: &GraphFixture) -> Result<()> {
+    for (route, cost) in &fixture.tours {
+        let fitness: f64 = Chromosome::fitness(route, &fixture.graph)?;
+        if fitness != *cost {
+            bail!("{} route {:?} costs {} but should cost {}", fixture.name, route, fitness, cost);
+        }
+    }
+    Ok(())
+}
+
+/// Function to check a crossover operator makes the expected child from a case, and that the child is a valid route
+pub fn check_crossover(case: &CrossoverCase, graph: &Graph) -> Result<()> {
+    let child: Vec<u32> = Chromosome::crossover_child(
+        &case.first_parent,
+        &case.second_parent,
+        case.operator,
+        &case.crossover_points,
+        graph,
+    )
+    .wrap_err_with(|| format!("{:?} crossover of {:?} failed", case.operator, case.first_parent))?;
+    check_route(&child, graph)?;
+    if child != case.child {
+        bail!(
+            "{:?} crossover of {:?} and {:?} at {:?} made {:?} but should make {:?}",
+            case.operator, case.first_parent, case.second_parent, case.crossover_points, child, case.child
+        );
+    }
+    Ok(())
+}
+
+/// Function to check a mutation operator makes the expected route from a case, and that the cost it updates
+/// using only the changed edges matches the cost of the whole route
+pub fn check_mutation(case: &MutationCase, graph: &Graph) -> Result<()> {
+    let mut chromosome = Chromosome::new(case.route.clone(), Chromosome::fitness(&case.route, graph)?);
+    chromosome
+        .mutate_at(case.operator, &case.indices, graph)
+        .wrap_err_with(|| format!("{:?} mutation of {:?} failed", case.operator, case.route))?;
+    check_route(&chromosome.route, graph)?;
+    if chromosome.route != case.mutated {
+        bail!(
+            "{:?} mutation of {:?} at {:?} made {:?} but should make {:?}",
+            case.operator, case.route, case.indices, chromosome.route, case.mutated
+        );
+    }
+    let fitness: f64 = Chromosome::fitness(&chromosome.route, graph)?;
+    if chromosome.cost != fitness {
+        bail!(
+            "{:?} mutation of {:?} at {:?} updated the cost to {} but the route costs {}",
+            case.operator, case.route, case.indices, chromosome.cost, fitness
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! This module defines [`HillClimber`], a first-improvement 2-opt hill climber that restarts from a random
+//! tour whenever it reaches a local optimum. It is a baseline for the genetic algorithm, run as a [`Solver`]
+//! with the same number of fitness evaluations.
+
+use color_eyre::{eyre::ContextCompat, Result};
+
+use super::{
+    chromosome::Chromosome,
+    country::Graph,
+    interface::RunParameters,
+    population::Population,
+    solver::Solver,
+};
+
+/// The `HillClimber` type, which holds the tour being climbed and the best tour found by any climb
+#[derive(Debug, Clone)]
+pub struct HillClimber {
+    /// The tour being improved
+    pub current: Chromosome,
+    /// The cheapest tour found by any climb
+    pub best: Chromosome,
+    /// The number of fitness evaluations used so far, each 2-opt move tried and each restart uses one
+    pub evaluations: u64,
+    /// The number of times a local optimum was reached and the climb restarted
+    pub restarts: u32,
+    /// The pair of edges to try swapping next
+    next_move: (usize, usize),
+    /// The number of moves tried since the last improvement, once every move has been tried the tour is a local optimum
+    moves_since_improvement: usize,
+}
+
+/// Implement Methods on the [`HillClimber`] type
+impl HillClimber {
+    /// This function starts a hill climber from the best Chromosome of a new [`Population`]. Every Chromosome of a
+    /// new population is a random tour, so the climber is counted as having used the evaluations of the population
+    pub fn new(population: &Population) -> Self {
+        Self {
+            current: population.best_chromosome.clone(),
+            best: population.best_chromosome.clone(),
+            evaluations: population.evaluations,
+            restarts: 0,
+            next_move: (0, 2),
+            moves_since_improvement: 0,
+        }
+    }
+
+    /// This function returns the number of different 2-opt moves on a route of the given length,
+    /// every pair of edges that don't share a city
+    pub fn neighbourhood_size(length: usize) -> usize {
+        length * length.saturating_sub(3) / 2
+    }
+
+    /// This function tries 2-opt moves on the current tour, taking the first one that makes it cheaper,
+    /// until the given number of evaluations have been used. Once every move has been tried without an
+    /// improvement the climb starts again from a random tour
+    pub fn climb(&mut self, evaluations: u64, graph: &Graph) -> Result<()> {
+        let length: usize = self.current.route.len();
+        let neighbourhood: usize = HillClimber::neighbourhood_size(length);
+        let cost = |from: u32, to: u32| graph.distances.get(from, to).wrap_err("Error: Could not obtain Chromosome data");
+
+        while self.evaluations < evaluations {
+            // A local optimum, or a route too short to have any moves, so start again
+            if self.moves_since_improvement >= neighbourhood {
+                self.current = Chromosome::generation(graph)?;
+                self.evaluations += 1;
+                self.restarts += 1;
+                self.next_move = (0, 2);
+                self.moves_since_improvement = 0;
+                self.keep_if_best();
+                continue;
+            }
+
+            // The edge starting at i goes from route[i] to route[i + 1]
+            let (i, j) = self.next_move;
+            let (a, b) = (self.current.route[i], self.current.route[i + 1]);
+            let (c, d) = (self.current.route[j], self.current.route[(j + 1) % length]);
+
+            // Swap edges a-b and c-d for a-c and b-d if it is cheaper, ignoring differences from rounding. When
+            // costs differ by direction the edges between b and c are travelled the other way once reversed too
+            let mut delta: f64 = cost(a, c)? + cost(b, d)? - cost(a, b)? - cost(c, d)?;
+            if !graph.symmetric {
+                for k in i + 1..j {
+                    let (from, to) = (self.current.route[k], self.current.route[k + 1]);
+                    delta += cost(to, from)? - cost(from, to)?;
+                }
+            }
+            self.evaluations += 1;
+            if delta < -1e-9 {
+                self.current.route[i + 1..=j].reverse();
+                self.current.cost += delta;
+                self.moves_since_improvement = 0;
+                self.keep_if_best();
+            } else {
+                self.moves_since_improvement += 1;
+            }
+
+            self.next_move = HillClimber::following_move(self.next_move, length);
+        }
+        Ok(())
+    }
+
+    /// This function returns the pair of edges to try after the given pair, going back to the first pair after the last
+    fn following_move((i, j): (usize, usize), length: usize) -> (usize, usize) {
+        // The first and last edges share the first city, so the last pair for the first edge is one earlier
+        let last_j: usize = if i == 0 { length - 2 } else { length - 1 };
+        if j < last_j {
+            (i, j + 1)
+        } else if i + 3 < length {
+            (i + 1, i + 3)
+        } else {
+            (0, 2)
+        }
+    }
+
+    /// This function keeps a copy of the current tour if it is the cheapest found so far
+    fn keep_if_best(&mut self) {
+        if self.current.cost < self.best.cost {
+            self.best = self.current.clone();
+        }
+    }
+}
+
+/// A generation of the hill climber uses as many evaluations as the most a generation of the genetic algorithm can,
+/// stopping at the evaluation budget
+impl Solver for HillClimber {
+    fn step(&mut self, parameters: &RunParameters, graph: &Graph) -> Result<()> {
+        let generation: u64 = self.evaluations + parameters.evaluations_per_generation();
+        self.climb(generation.min(parameters.max_evaluations.unwrap_or(u64::MAX)), graph)
+    }
+
+    fn best(&self) -> &Chromosome {
+        &self.best
+    }
+
+    fn worst(&self) -> &Chromosome {
+        &self.current
+    }
+
+    fn average_cost(&self) -> f64 {
+        self.current.cost
+    }
+
+    fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
+}
@@ -1,39 +1,829 @@
 //! This module defines [`Cli`], [`MutationOperator`], 
-//! [`CrossoverOperator`] and [`PlotOperator`] for clap to use
+//! [`CrossoverOperator`] and [`PlotOperator`] for clap to use, along with
+//! [`RunParameters`] which carries the parsed options into each [`Simulation`]
+//! 
+//! [`Simulation`]: crate::simulation::Simulation
 
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf};
+
+use super::build_info::LONG_VERSION;
 
 /// A Rust program to solve the Travelling Salesman Problem. It uses a steady state evolutionary algorithm
 /// and assumes its given XML files detailing the costs associated with travel between each city.
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, long_version = LONG_VERSION, about, long_about = None)]
 pub struct Cli {
+    /// Which algorithm to run:
+    #[arg(value_enum, default_value_t = Algorithm::Genetic, long)]
+    pub algorithm: Algorithm,
     /// Which crossover type to use:
     #[arg(value_enum, default_value_t = CrossoverOperator::Fix, short, long)]
     pub crossover_operator: CrossoverOperator,
     /// Which mutation type to use:
     #[arg(value_enum, default_value_t = MutationOperator::Single, short, long)]
     pub mutation_operator: MutationOperator,
+    /// Number of the cheapest Chromosomes always kept in the population: Must be less than the population size
+    #[arg(default_value_t = 0, long, visible_alias = "protected-elites")]
+    pub elitism: u64,
+    /// Which parent selection type to use:
+    #[arg(value_enum, default_value_t = SelectionOperator::Tournament, long)]
+    pub selection_operator: SelectionOperator,
+    /// Which Chromosomes children replace:
+    #[arg(value_enum, default_value_t = ReplacementStrategy::Weakest, long)]
+    pub replacement_strategy: ReplacementStrategy,
+    /// Number of randomly picked Chromosomes a child is compared against by restricted tournament replacement: Minimum 1. Cannot exceed population size
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), default_value_t = 10, long)]
+    pub window_size: u32,
+    /// What to do with children whose tour is already in the population:
+    #[arg(value_enum, default_value_t = DuplicatePolicy::Allow, long = "duplicates")]
+    pub duplicate_policy: DuplicatePolicy,
+    /// Breed this many offspring each generation and keep the cheapest of the population and offspring, a (μ+λ) evolution strategy: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u64).range(1..), long)]
+    pub offspring: Option<u64>,
+    /// Number of children bred each generation, two by each round of selection, crossover, mutation and replacement: Minimum 2. Must be even. Cannot be used with --offspring
+    #[arg(value_parser = clap::value_parser!(u64).range(2..), default_value_t = 2, long, conflicts_with = "offspring")]
+    pub children_per_generation: u64,
+    /// Relative difference below which two costs count as equal when deciding if a child replaces a Chromosome or a run has improved: Minimum 0. Defaults to exact on datasets with whole number costs and 1e-9 otherwise
+    #[arg(long)]
+    pub cost_epsilon: Option<f64>,
+    /// Fraction of the initial population built by nearest neighbour construction from random start cities, rather than randomly: Between 0 and 1.
+    #[arg(value_parser = parse_probability, default_value_t = 0.0, long)]
+    pub nearest_neighbour_fraction: f64,
     /// Population size: Minimum 10.
     #[arg(value_parser = clap::value_parser!(u64).range(10..), default_value_t = 50, short, long)]
     pub population_size: u64,
     /// Tournament size: Minimum 2. Cannot exceed population size
     #[arg(value_parser = clap::value_parser!(u32).range(2..), default_value_t = 5, short, long)]
     pub tournament_size: u32,
+    /// Probability the cheapest Chromosome of a tournament wins, otherwise the win passes to the next cheapest: Between 0 and 1.
+    #[arg(value_parser = parse_probability, default_value_t = 1.0, long)]
+    pub tournament_p: f64,
+    /// Tournament size reached by the last generation, growing or shrinking linearly from the tournament size: Minimum 2.
+    /// Cannot exceed population size
+    #[arg(value_parser = clap::value_parser!(u32).range(2..), long)]
+    pub final_tournament_size: Option<u32>,
+    /// Grow the tournament size as the costs of the population get closer together, up to this size: Minimum 2.
+    /// Cannot exceed population size
+    #[arg(value_parser = clap::value_parser!(u32).range(2..), long, conflicts_with = "final_tournament_size")]
+    pub max_tournament_size: Option<u32>,
+    /// Number of generations each simulation runs for: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), default_value_t = 10_000, short, long)]
+    pub generations: u32,
     /// Number of Runs: Minimum 1.
     #[arg(value_parser = clap::value_parser!(u32).range(1..), default_value_t = 1, short, long)]
     pub number_runs: u32,
+    /// Directory the datasets are loaded from
+    #[arg(default_value = "data", long)]
+    pub data_dir: PathBuf,
+    /// Only run this dataset, given as a path or the name of a file in the data directory. Can be given more than once
+    #[arg(long = "instance", value_name = "FILE")]
+    pub instances: Vec<PathBuf>,
+    /// Only run the datasets whose name matches this pattern, where * matches anything and ? any single character.
+    /// Can be given more than once to run the datasets matching any of them
+    #[arg(long = "filter", value_name = "PATTERN")]
+    pub filters: Vec<String>,
+    /// Stop with an error if any file in the data directory can't be loaded, rather than skipping it
+    #[arg(long)]
+    pub strict_data: bool,
+    /// Limit local search moves to joining each city to this many of its nearest cities: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), long)]
+    pub candidate_list_size: Option<u32>,
+    /// How the cost of the edges of a route is added up:
+    #[arg(value_enum, default_value_t = Summation::Plain, long)]
+    pub summation: Summation,
     /// Which plot type to use:
     #[arg(value_enum, default_value_t = PlotOperator::Average, short = 'o', long = "output-type")]
     pub plot_operator: PlotOperator,
     /// Which statistic from the simulation to plot:
     #[arg(value_enum, default_value_t = PlotStatistic::Average, short, long)]
     pub statistic_plotted: PlotStatistic,
+    /// Also write each chart as a Vega-Lite spec with its data embedded
+    #[arg(long)]
+    pub vega_lite: bool,
+    /// Which colour theme to draw plots with:
+    #[arg(value_enum, default_value_t = PlotTheme::Light, long)]
+    pub plot_theme: PlotTheme,
+    /// Directory of TSPLIB `<name>.opt.tour` files to compare the best tours against
+    #[arg(long)]
+    pub optimal_tours: Option<PathBuf>,
+    /// Plot the best tour against the optimal tour. Requires --optimal-tours
+    #[arg(long, requires = "optimal_tours")]
+    pub plot_tour_comparison: bool,
+    /// Write every edge of the best tour of each country to a CSV file, ranking and printing this many of the most
+    /// expensive edges
+    #[arg(long)]
+    pub edge_breakdown: Option<u32>,
+    /// Record the fraction of the population holding the edge between two cities each generation, given as
+    /// `<from>-<to>` with cities numbered from 0. Can be given more than once
+    #[arg(long = "track-edge", value_parser = parse_edge)]
+    pub tracked_edges: Vec<(u32, u32)>,
+    /// Record the fraction of the population holding each edge of the optimal tour of every country that has one.
+    /// Requires --optimal-tours
+    #[arg(long, requires = "optimal_tours")]
+    pub track_optimal_edges: bool,
+    /// How the population size changes over the run:
+    #[arg(value_enum, default_value_t = PopulationSchedule::Constant, long)]
+    pub population_schedule: PopulationSchedule,
+    /// Number of generations in one saw-tooth cycle: Minimum 2.
+    #[arg(value_parser = clap::value_parser!(u32).range(2..), default_value_t = 500, long)]
+    pub schedule_period: u32,
+    /// Smallest population size a schedule can shrink to: Minimum 2. Cannot exceed population size
+    #[arg(value_parser = clap::value_parser!(u64).range(2..), default_value_t = 10, long)]
+    pub minimum_population: u64,
+    /// Fraction of the population, the most expensive first, replaced with random Chromosomes every --immigrant-period generations: Between 0 and 1.
+    #[arg(value_parser = parse_probability, default_value_t = 0.0, long)]
+    pub immigrant_rate: f64,
+    /// Number of generations between each wave of random immigrants: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), default_value_t = 100, long)]
+    pub immigrant_period: u32,
+    /// Record the stats of every Nth generation: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), default_value_t = 1, long = "record-every")]
+    pub record_stride: u32,
+    /// Also record this many generations either side of each new best cost at full resolution
+    #[arg(default_value_t = 0, long)]
+    pub record_window: u32,
+    /// Update the progress bar of a run every Nth generation, rather than as often as it is redrawn: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), long)]
+    pub progress_every: Option<u32>,
+    /// Slow each run down to at most this many generations per second, so it can be watched: Greater than 0.
+    #[arg(value_parser = parse_rate, long)]
+    pub throttle: Option<f64>,
+    /// Stop a Chromosome being selected as a parent more than once per generation
+    #[arg(long)]
+    pub distinct_parents: bool,
+    /// Rerun the second selection while the parents differ by fewer than this many edges, 0 allows any parents
+    #[arg(default_value_t = 0, long)]
+    pub min_parent_distance: u32,
+    /// Probability the parents are crossed over, otherwise they are copied: Between 0 and 1.
+    #[arg(value_parser = parse_probability, default_value_t = 1.0, long)]
+    pub crossover_rate: f64,
+    /// Number of children crossover makes from each pair of parents, only the cheapest two of which are kept: Minimum 2.
+    #[arg(value_parser = clap::value_parser!(u64).range(2..), default_value_t = 2, long)]
+    pub brood_size: u64,
+    /// Probability each child is mutated: Between 0 and 1.
+    #[arg(value_parser = parse_probability, default_value_t = 1.0, long)]
+    pub mutation_rate: f64,
+    /// Raise the mutation rate each time the best cost hasn't improved for this many generations, lowering it again after improvements: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), long = "adaptive-mutation")]
+    pub stagnation_limit: Option<u32>,
+    /// Pick the crossover and mutation operator of each child from every operator, favouring those that have recently made cheaper children
+    #[arg(long)]
+    pub adaptive_operators: bool,
+    /// How to pick the winner when tournament Chromosomes tie on cost:
+    #[arg(value_enum, default_value_t = TiePolicy::First, long)]
+    pub tie_policy: TiePolicy,
+    /// Seed the random number generator of every run, so the experiment can be repeated exactly
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Stop a run once its fitness evaluations reach this budget, even if generations remain: Minimum 1.
+    #[arg(value_parser = clap::value_parser!(u64).range(1..), long)]
+    pub max_evaluations: Option<u64>,
+    /// Also sample random tours with the same number of fitness evaluations, drawn on cost plots as a baseline
+    #[arg(long)]
+    pub random_baseline: bool,
+    /// Freeform tag recorded with the results, can be given more than once. Cannot contain ',' or ';'
+    #[arg(value_parser = parse_tag, long = "tag")]
+    pub tags: Vec<String>,
+    /// What the experiment is being run for, recorded with the results and on every plot
+    #[arg(value_parser = parse_note, long)]
+    pub description: Option<String>,
+    /// Who is running the experiment, recorded with the results and on every plot
+    #[arg(value_parser = parse_note, long)]
+    pub author: Option<String>,
+    /// Which statistics to record each generation, beyond the best, worst and average cost:
+    #[arg(value_enum, default_value_t = MetricsLevel::Standard, long)]
+    pub metrics: MetricsLevel,
+    /// Experiment manifest of an interrupted experiment, runs only its unfinished jobs with its parameters
+    #[arg(long)]
+    pub resume_experiment: Option<PathBuf>,
+    /// Local search used to improve every child before it joins the population:
+    #[arg(value_enum, long)]
+    pub local_search: Option<LocalSearch>,
+    /// Run every simulation on a fixed pool of worker threads with a single progress bar, suited to many small countries
+    #[arg(long)]
+    pub batch: bool,
+    /// Number of worker threads in batch mode: Minimum 1. Defaults to the number of CPUs. Requires --batch
+    #[arg(value_parser = clap::value_parser!(u32).range(1..), long, requires = "batch")]
+    pub workers: Option<u32>,
+    /// Pin each thread to a CPU core, keeping runs of the same country on the same socket
+    #[arg(long)]
+    pub pin_threads: bool,
+    /// File of `setting = value` lines checked between generations, so some parameters can be changed while running
+    #[arg(long)]
+    pub control_file: Option<PathBuf>,
+    /// Directory each run writes a log of its events to, such as new best costs, control file changes and warnings
+    #[arg(long)]
+    pub log_dir: Option<PathBuf>,
+    /// Save a snapshot of every population at this generation, can be given more than once
+    #[arg(long = "snapshot-at")]
+    pub snapshot_generations: Vec<u32>,
+    /// Record the parents of every child that joins the population, writing the genealogy of each run to a JSON
+    /// and a DOT file
+    #[arg(long)]
+    pub genealogy: bool,
+    /// Run a tool instead of an experiment
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Enumerate that represents the tools that can be run instead of an experiment
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Compare two population snapshots saved with --snapshot-at
+    DiffPopulations {
+        /// The earlier snapshot
+        first: PathBuf,
+        /// The later snapshot
+        second: PathBuf,
+    },
+    /// Write a dataset in any format that can be loaded out as a travellingSalesmanProblemInstance XML file
+    ConvertInstance {
+        /// The dataset to convert, looked for in the data directory if it isn't found where it is given
+        input: PathBuf,
+        /// The XML file to write
+        output: PathBuf,
+    },
+    /// Download TSPLIB instances by name, such as berlin52, into the data directory as XML files
+    Fetch {
+        /// The names of the instances to download
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// The URL to download the instances from, or a local directory laid out like it
+        #[arg(long, default_value = crate::fetch::DEFAULT_MIRROR)]
+        mirror: String,
+        /// The SHA-256 checksum of the gzipped file of an instance from a trusted source, given as <name>=<sha256>,
+        /// needed for instances without a pinned checksum. Can be given more than once
+        #[arg(long = "checksum", value_parser = parse_checksum)]
+        checksums: Vec<(String, String)>,
+        /// Overwrite datasets of the same name already in the data directory
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Implements methods on `Cli`
+impl Cli {
+    /// Function to check every combination of options that clap can't check on its own, returning the
+    /// [`RunParameters`] if they are all valid or every problem found at once if not. Options that only
+    /// matter up to the number of generations are clamped to it
+    pub fn into_config(self) -> Result<RunParameters, ConfigError> {
+        let mut parameters = RunParameters::from(&self);
+        let mut violations: Vec<String> = parameters.violations();
+
+        // The statistic being plotted must be recorded
+        if self.statistic_plotted.required_metrics() > parameters.metrics {
+            violations.push(format!(
+                "plotting {:?} requires --metrics {} or above",
+                self.statistic_plotted, 
+                format!("{:?}", self.statistic_plotted.required_metrics()).to_lowercase()
+            ));
+        }
+
+        // Snapshots can only be taken of generations that are run
+        for generation in &self.snapshot_generations {
+            if *generation >= parameters.generations {
+                violations.push(format!(
+                    "snapshot generation ({}) is after the last generation ({})",
+                    generation, parameters.generations - 1
+                ));
+            }
+        }
+
+        // Recording every Nth generation or a window of generations means nothing past the length of the run
+        parameters.record_stride = parameters.record_stride.min(parameters.generations);
+        parameters.record_window = parameters.record_window.min(parameters.generations);
+
+        if violations.is_empty() {
+            Ok(parameters)
+        } else {
+            Err(ConfigError { violations })
+        }
+    }
+}
+
+/// The error returned when the options given on the command line don't work together
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// A description of every problem found
+    pub violations: Vec<String>,
+}
+
+/// Implements [`Display`] for ConfigError, listing every problem on its own line
+/// 
+/// [`Display`]: std::fmt::Display
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid combination of options:")?;
+        for violation in &self.violations {
+            write!(f, "\n  - {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Implements [`Error`] for ConfigError so it can be propagated with `?`
+/// 
+/// [`Error`]: std::error::Error
+impl std::error::Error for ConfigError {}
+
+/// Enumerate that represents how the program finished, each with a stable exit code
+/// so scripts can tell what happened without reading the error output
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// Exit code 0, every run finished and the results were written
+    Success,
+
+    /// Exit code 2, the options given don't work together. This matches the code clap uses for invalid arguments
+    InvalidConfig,
+
+    /// Exit code 3, the countries or the experiment manifest couldn't be loaded
+    DataLoad,
+
+    /// Exit code 4, something went wrong while running, plotting or writing results
+    Runtime,
+
+    /// Exit code 5, the program was interrupted, the runs that finished are kept in the experiment manifest
+    Interrupted,
+}
+
+/// Implement methods on `RunOutcome`
+impl RunOutcome {
+    /// Function to return the exit code of the outcome
+    pub fn code(&self) -> u8 {
+        match self {
+            RunOutcome::Success => 0,
+            RunOutcome::InvalidConfig => 2,
+            RunOutcome::DataLoad => 3,
+            RunOutcome::Runtime => 4,
+            RunOutcome::Interrupted => 5,
+        }
+    }
+}
+
+/// Implements [`From`] so a RunOutcome can be returned from main
+impl From<RunOutcome> for std::process::ExitCode {
+    fn from(outcome: RunOutcome) -> Self {
+        std::process::ExitCode::from(outcome.code())
+    }
+}
+
+/// Function used by clap to check a description or author fits on one line of the summary CSV file
+fn parse_note(note: &str) -> Result<String, String> {
+    if note.trim().is_empty() {
+        Err(String::from("cannot be empty"))
+    } else if note.contains(['\n', '\r']) {
+        Err(String::from("cannot contain line breaks"))
+    } else {
+        Ok(note.to_string())
+    }
+}
+
+/// Function used by clap to check a tag can be written to the summary CSV file
+fn parse_tag(tag: &str) -> Result<String, String> {
+    if tag.is_empty() {
+        Err(String::from("tags cannot be empty"))
+    } else if tag.contains([',', ';']) {
+        Err(String::from("tags cannot contain ',' or ';'"))
+    } else {
+        Ok(tag.to_string())
+    }
+}
+
+/// Function used by clap to read an edge given as two city numbers joined by a dash
+fn parse_edge(edge: &str) -> Result<(u32, u32), String> {
+    let (from, to) = edge
+        .split_once('-')
+        .ok_or_else(|| format!("{} isn't an edge, give it as <from>-<to>", edge))?;
+    let from: u32 = from.trim().parse().map_err(|_| format!("{} isn't a city number", from))?;
+    let to: u32 = to.trim().parse().map_err(|_| format!("{} isn't a city number", to))?;
+    if from == to {
+        Err(String::from("an edge must join two different cities"))
+    } else {
+        Ok((from, to))
+    }
+}
+
+/// Function used by clap to read the checksum of an instance given as its name and checksum joined by `=`
+fn parse_checksum(checksum: &str) -> Result<(String, String), String> {
+    crate::fetch::parse_checksum(checksum).map_err(|report| format!("{:#}", report))
+}
+
+/// Function used by clap to check a probability is between 0 and 1
+fn parse_probability(probability: &str) -> Result<f64, String> {
+    let probability: f64 = probability
+        .parse()
+        .map_err(|_| format!("{} isn't a number", probability))?;
+    if (0.0..=1.0).contains(&probability) {
+        Ok(probability)
+    } else {
+        Err(String::from("must be between 0 and 1"))
+    }
+}
+
+/// Function used by clap to check a rate is a finite number greater than 0
+fn parse_rate(rate: &str) -> Result<f64, String> {
+    let rate: f64 = rate
+        .parse()
+        .map_err(|_| format!("{} isn't a number", rate))?;
+    if rate.is_finite() && rate > 0.0 {
+        Ok(rate)
+    } else {
+        Err(String::from("must be greater than 0"))
+    }
+}
+
+/// The parameters used to build and run a single [`Simulation`]
+/// 
+/// [`Simulation`]: crate::simulation::Simulation
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunParameters {
+    /// Which algorithm to run
+    pub algorithm: Algorithm,
+    /// Which crossover type to use
+    pub crossover_operator: CrossoverOperator,
+    /// Which mutation type to use
+    pub mutation_operator: MutationOperator,
+    /// Population size the simulation starts with
+    pub population_size: u64,
+    /// Tournament size used to select parents
+    pub tournament_size: u32,
+    /// Probability the cheapest Chromosome of a tournament wins, each next cheapest winning with it if none before did
+    pub tournament_p: f64,
+    /// Tournament size reached by the last generation, changing linearly from the tournament size, None if it never changes
+    pub final_tournament_size: Option<u32>,
+    /// Largest size the tournament grows to as the costs of the population get closer together, None if it doesn't adapt
+    pub max_tournament_size: Option<u32>,
+    /// Number of the cheapest Chromosomes always kept in the population
+    pub elitism: u64,
+    /// How parents are selected
+    pub selection_operator: SelectionOperator,
+    /// Which Chromosomes children replace
+    pub replacement_strategy: ReplacementStrategy,
+    /// Number of randomly picked Chromosomes a child is compared against by restricted tournament replacement
+    pub window_size: u32,
+    /// What is done with children whose tour is already in the population
+    pub duplicate_policy: DuplicatePolicy,
+    /// Number of offspring bred each generation of a (μ+λ) evolution strategy, None to replace as children are bred
+    pub offspring: Option<u64>,
+    /// Number of children bred each generation when they replace as they are bred, two by each round of breeding
+    pub children_per_generation: u64,
+    /// Relative difference below which two costs count as equal, None to pick one from the costs of the dataset
+    pub cost_epsilon: Option<f64>,
+    /// Fraction of the initial population built by nearest neighbour construction
+    pub nearest_neighbour_fraction: f64,
+    /// Number of generations the simulation runs for
+    pub generations: u32,
+    /// How the population size changes over the run
+    pub population_schedule: PopulationSchedule,
+    /// Number of generations in one saw-tooth cycle
+    pub schedule_period: u32,
+    /// Smallest population size a schedule can shrink to
+    pub minimum_population: u64,
+    /// Fraction of the population replaced with random Chromosomes in each wave of immigrants
+    pub immigrant_rate: f64,
+    /// Number of generations between each wave of random immigrants
+    pub immigrant_period: u32,
+    /// The stats of every Nth generation are recorded
+    pub record_stride: u32,
+    /// Number of generations either side of each new best cost that are always recorded
+    pub record_window: u32,
+    /// The progress bar is updated every Nth generation, None to update it as often as it is redrawn
+    pub progress_every: Option<u32>,
+    /// The most generations run each second, None to run them as fast as possible
+    pub throttle: Option<f64>,
+    /// Whether a Chromosome can only be selected as a parent once per generation
+    pub distinct_parents: bool,
+    /// The fewest edges the parents must differ by before the second selection stops being rerun
+    pub min_parent_distance: u32,
+    /// Probability the parents are crossed over rather than copied
+    pub crossover_rate: f64,
+    /// Number of children crossover makes from each pair of parents, the cheapest two are kept
+    pub brood_size: u64,
+    /// Probability each child is mutated. With adaptive mutation this is the lowest the rate can fall to
+    pub mutation_rate: f64,
+    /// Number of generations without the best cost improving before the mutation rate is raised, None if it never changes
+    pub stagnation_limit: Option<u32>,
+    /// Seed the seed of every run is derived from, None if runs are not seeded
+    pub seed: Option<u64>,
+    /// Whether the crossover and mutation operators are picked for each child by adaptive pursuit
+    pub adaptive_operators: bool,
+    /// How to pick the winner when tournament Chromosomes tie on cost
+    pub tie_policy: TiePolicy,
+    /// Number of fitness evaluations after which the run stops, if any
+    pub max_evaluations: Option<u64>,
+    /// Whether a random search with the same number of fitness evaluations is run alongside
+    pub random_baseline: bool,
+    /// Which statistics are recorded each generation
+    pub metrics: MetricsLevel,
+    /// Local search used to improve every child, None if children aren't improved
+    pub local_search: Option<LocalSearch>,
+    /// Number of nearest cities each city can be joined to by a local search move, None to try every move
+    pub candidate_list_size: Option<u32>,
+    /// How the cost of the edges of a route is added up
+    pub summation: Summation,
+}
+
+/// Implements [`Default`] for RunParameters using the same defaults as [`Cli`]
+impl Default for RunParameters {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Genetic,
+            crossover_operator: CrossoverOperator::Fix,
+            mutation_operator: MutationOperator::Single,
+            population_size: 50,
+            tournament_size: 5,
+            tournament_p: 1.0,
+            final_tournament_size: None,
+            max_tournament_size: None,
+            elitism: 0,
+            selection_operator: SelectionOperator::Tournament,
+            replacement_strategy: ReplacementStrategy::Weakest,
+            window_size: 10,
+            duplicate_policy: DuplicatePolicy::Allow,
+            offspring: None,
+            children_per_generation: 2,
+            cost_epsilon: None,
+            nearest_neighbour_fraction: 0.0,
+            generations: 10_000,
+            population_schedule: PopulationSchedule::Constant,
+            schedule_period: 500,
+            minimum_population: 10,
+            immigrant_rate: 0.0,
+            immigrant_period: 100,
+            record_stride: 1,
+            record_window: 0,
+            progress_every: None,
+            throttle: None,
+            distinct_parents: false,
+            min_parent_distance: 0,
+            crossover_rate: 1.0,
+            brood_size: 2,
+            mutation_rate: 1.0,
+            stagnation_limit: None,
+            seed: None,
+            adaptive_operators: false,
+            tie_policy: TiePolicy::First,
+            max_evaluations: None,
+            random_baseline: false,
+            metrics: MetricsLevel::Standard,
+            local_search: None,
+            candidate_list_size: None,
+            summation: Summation::Plain,
+        }
+    }
+}
+
+/// Implements methods on `RunParameters`
+impl RunParameters {
+    /// Function to find every problem with the parameters on their own, such as a tournament bigger than the
+    /// population. The ranges clap checks on the command line are checked too, as library users can build the
+    /// parameters directly. Returns an empty list if they are all valid
+    pub fn violations(&self) -> Vec<String> {
+        let mut violations: Vec<String> = Vec::new();
+
+        // Library users don't go through clap, so check the smallest value of each option it checks as well
+        let minimums: [(&str, Option<u64>, u64); 17] = [
+            ("population size", Some(self.population_size), 10),
+            ("tournament size", Some(self.tournament_size as u64), 2),
+            ("final tournament size", self.final_tournament_size.map(u64::from), 2),
+            ("max tournament size", self.max_tournament_size.map(u64::from), 2),
+            ("window size", Some(self.window_size as u64), 1),
+            ("offspring", self.offspring, 1),
+            ("children per generation", Some(self.children_per_generation), 2),
+            ("generations", Some(self.generations as u64), 1),
+            ("schedule period", Some(self.schedule_period as u64), 2),
+            ("minimum population", Some(self.minimum_population), 2),
+            ("immigrant period", Some(self.immigrant_period as u64), 1),
+            ("record stride", Some(self.record_stride as u64), 1),
+            ("progress every", self.progress_every.map(u64::from), 1),
+            ("brood size", Some(self.brood_size), 2),
+            ("stagnation limit", self.stagnation_limit.map(u64::from), 1),
+            ("max evaluations", self.max_evaluations, 1),
+            ("candidate list size", self.candidate_list_size.map(u64::from), 1),
+        ];
+        for (name, value, minimum) in minimums {
+            if let Some(value) = value.filter(|value| *value < minimum) {
+                violations.push(format!("{} ({}) must be at least {}", name, value, minimum));
+            }
+        }
+
+        // Probabilities and fractions have to be between 0 and 1
+        let probabilities: [(&str, f64); 5] = [
+            ("tournament p", self.tournament_p),
+            ("nearest neighbour fraction", self.nearest_neighbour_fraction),
+            ("immigrant rate", self.immigrant_rate),
+            ("crossover rate", self.crossover_rate),
+            ("mutation rate", self.mutation_rate),
+        ];
+        for (name, probability) in probabilities {
+            if !(0.0..=1.0).contains(&probability) {
+                violations.push(format!("{} ({}) must be between 0 and 1", name, probability));
+            }
+        }
+        if let Some(throttle) = self.throttle.filter(|throttle| !(throttle.is_finite() && *throttle > 0.0)) {
+            violations.push(format!("throttle ({}) must be greater than 0", throttle));
+        }
+
+        // Tournaments pick from the population so can't be bigger than it
+        if (self.tournament_size as u64) > self.population_size {
+            violations.push(format!(
+                "tournament size ({}) is greater than the population size ({})",
+                self.tournament_size, self.population_size
+            ));
+        }
+
+        // Neither can the tournaments at the end of a tournament size schedule
+        if let Some(final_size) = self.final_tournament_size {
+            if final_size as u64 > self.population_size {
+                violations.push(format!(
+                    "final tournament size ({}) is greater than the population size ({})",
+                    final_size, self.population_size
+                ));
+            }
+        }
+
+        // Nor can the tournaments of an adaptive tournament size, which only grow from the tournament size
+        if let Some(maximum) = self.max_tournament_size {
+            if maximum as u64 > self.population_size {
+                violations.push(format!(
+                    "max tournament size ({}) is greater than the population size ({})",
+                    maximum, self.population_size
+                ));
+            }
+            if maximum < self.tournament_size {
+                violations.push(format!(
+                    "max tournament size ({}) is less than the tournament size ({})",
+                    maximum, self.tournament_size
+                ));
+            }
+        }
+
+        // The window of restricted tournament replacement picks from the population so can't be bigger than it
+        if self.replacement_strategy == ReplacementStrategy::Restricted
+            && (self.window_size as u64) > self.population_size {
+            violations.push(format!(
+                "window size ({}) is greater than the population size ({})",
+                self.window_size, self.population_size
+            ));
+        }
+
+        // Costs can only be compared with a tolerance that is a real, non-negative number
+        if let Some(epsilon) = self.cost_epsilon {
+            if !(epsilon.is_finite() && epsilon >= 0.0) {
+                violations.push(format!("cost epsilon ({}) must be a non-negative number", epsilon));
+            }
+        }
+
+        // Children can only join the population if some Chromosomes aren't kept by elitism
+        if self.elitism >= self.population_size {
+            violations.push(format!(
+                "elitism ({}) must be less than the population size ({})",
+                self.elitism, self.population_size
+            ));
+        }
+
+        // A population schedule cannot shrink the population below the tournament size
+        // or grow it past the population size
+        if self.population_schedule != PopulationSchedule::Constant {
+            if self.minimum_population > self.population_size {
+                violations.push(format!(
+                    "minimum population ({}) is greater than the population size ({})",
+                    self.minimum_population, self.population_size
+                ));
+            }
+            if (self.tournament_size as u64) > self.minimum_population {
+                violations.push(format!(
+                    "tournament size ({}) is greater than the minimum population ({})",
+                    self.tournament_size, self.minimum_population
+                ));
+            }
+            if let Some(final_size) = self.final_tournament_size {
+                if final_size as u64 > self.minimum_population {
+                    violations.push(format!(
+                        "final tournament size ({}) is greater than the minimum population ({})",
+                        final_size, self.minimum_population
+                    ));
+                }
+            }
+            if let Some(maximum) = self.max_tournament_size {
+                if maximum as u64 > self.minimum_population {
+                    violations.push(format!(
+                        "max tournament size ({}) is greater than the minimum population ({})",
+                        maximum, self.minimum_population
+                    ));
+                }
+            }
+            if self.elitism >= self.minimum_population {
+                violations.push(format!(
+                    "elitism ({}) must be less than the minimum population ({})",
+                    self.elitism, self.minimum_population
+                ));
+            }
+        }
+
+        // Children are bred in pairs
+        if !self.children_per_generation.is_multiple_of(2) {
+            violations.push(format!(
+                "children per generation ({}) must be even, as children are bred in pairs",
+                self.children_per_generation
+            ));
+        }
+
+        // Creating the population uses one evaluation per Chromosome, so a smaller budget would end before any generations
+        if let Some(max_evaluations) = self.max_evaluations {
+            if max_evaluations <= self.population_size {
+                violations.push(format!(
+                    "max evaluations ({}) must be greater than the population size ({})",
+                    max_evaluations, self.population_size
+                ));
+            }
+        }
+
+        // Adaptive mutation raises the mutation rate, so needs room to raise it
+        if self.stagnation_limit.is_some() && self.mutation_rate >= 1.0 {
+            violations.push(String::from("adaptive mutation requires a --mutation-rate below 1"));
+        }
+
+        violations
+    }
+
+    /// Function to check the parameters on their own, returning every problem found at once if any are invalid
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let violations: Vec<String> = self.violations();
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError { violations }),
+        }
+    }
+
+    /// Returns the most fitness evaluations a generation of the genetic algorithm can use, when every
+    /// child is crossed over, mutated and improved by the local search. How many moves a local search evaluates
+    /// depends on the route, so only the recalculation of each improved route is counted here
+    pub fn evaluations_per_generation(&self) -> u64 {
+        let per_child: u64 = self.mutation_operator.evaluations()
+            + self.local_search.map_or(0, |local_search| local_search.evaluations(0, 1));
+        // A generation breeds a pair of children for every two children, or every two offspring of a (μ+λ) generation
+        let pairs: u64 = self.offspring.map_or(self.children_per_generation.div_ceil(2), |offspring| offspring.div_ceil(2));
+        // Each pair is crossed over once for every two children of its brood
+        pairs * (self.brood_size.div_ceil(2) * self.crossover_operator.evaluations() + 2 * per_child)
+    }
+}
+
+/// Builds the RunParameters from the options given on the command line
+impl From<&Cli> for RunParameters {
+    fn from(cli: &Cli) -> Self {
+        Self {
+            algorithm: cli.algorithm,
+            crossover_operator: cli.crossover_operator,
+            mutation_operator: cli.mutation_operator,
+            population_size: cli.population_size,
+            tournament_size: cli.tournament_size,
+            tournament_p: cli.tournament_p,
+            final_tournament_size: cli.final_tournament_size,
+            max_tournament_size: cli.max_tournament_size,
+            elitism: cli.elitism,
+            selection_operator: cli.selection_operator,
+            replacement_strategy: cli.replacement_strategy,
+            window_size: cli.window_size,
+            duplicate_policy: cli.duplicate_policy,
+            offspring: cli.offspring,
+            children_per_generation: cli.children_per_generation,
+            cost_epsilon: cli.cost_epsilon,
+            nearest_neighbour_fraction: cli.nearest_neighbour_fraction,
+            generations: cli.generations,
+            population_schedule: cli.population_schedule,
+            schedule_period: cli.schedule_period,
+            minimum_population: cli.minimum_population,
+            immigrant_rate: cli.immigrant_rate,
+            immigrant_period: cli.immigrant_period,
+            record_stride: cli.record_stride,
+            record_window: cli.record_window,
+            progress_every: cli.progress_every,
+            throttle: cli.throttle,
+            distinct_parents: cli.distinct_parents,
+            min_parent_distance: cli.min_parent_distance,
+            crossover_rate: cli.crossover_rate,
+            brood_size: cli.brood_size,
+            mutation_rate: cli.mutation_rate,
+            stagnation_limit: cli.stagnation_limit,
+            seed: cli.seed,
+            adaptive_operators: cli.adaptive_operators,
+            tie_policy: cli.tie_policy,
+            max_evaluations: cli.max_evaluations,
+            random_baseline: cli.random_baseline,
+            metrics: cli.metrics,
+            local_search: cli.local_search,
+            candidate_list_size: cli.candidate_list_size,
+            summation: cli.summation,
+        }
+    }
 }
 
 /// Enumerate that represents the possible state of the mutation type
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
 pub enum MutationOperator {
 
     /// Alias: I, Runs inversion mutation on the chromosomes
@@ -47,10 +837,48 @@ pub enum MutationOperator {
     /// Alias: M, Runs multiple swap mutation on the chromosomes
     #[value(alias("M"))]
     Multiple,
+
+    /// Alias: C, Runs scramble mutation on the chromosomes
+    #[value(alias("C"))]
+    Scramble,
+
+    /// Alias: D, Runs displacement mutation on the chromosomes
+    #[value(alias("D"))]
+    Displacement,
+
+    /// Alias: N, Runs insertion mutation on the chromosomes
+    #[value(alias("N"))]
+    Insertion,
+
+    /// Alias: O, Runs Or-opt mutation on the chromosomes, moving a chain of 1 to 3 cities
+    #[value(alias("O"))]
+    OrOpt,
+
+    /// Alias: E, Runs inversion mutation on the chromosomes between two edges picked by roulette over their costs
+    #[value(alias("E"))]
+    ExpensiveEdge,
+}
+
+/// Implements methods on `MutationOperator`
+impl MutationOperator {
+    /// Returns how many fitness evaluations one mutation of a Chromosome consumes
+    pub fn evaluations(&self) -> u64 {
+        match self {
+            // Each mutation recalculates the cost of the mutated route once
+            MutationOperator::Inversion 
+            | MutationOperator::Single 
+            | MutationOperator::Multiple 
+            | MutationOperator::Scramble 
+            | MutationOperator::Displacement 
+            | MutationOperator::Insertion 
+            | MutationOperator::OrOpt
+            | MutationOperator::ExpensiveEdge => 1,
+        }
+    }
 }
 
 /// Enumerate that represents the possible state of the crossover type
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
 pub enum CrossoverOperator {
 
     /// Alias: F, Runs crossover with fix on the chromosomes
@@ -60,6 +888,109 @@ pub enum CrossoverOperator {
     /// Alias: O, Runs ordered crossover on the chromosomes
     #[value(alias("O"))]
     Ordered,
+
+    /// Alias: C, Runs cycle crossover on the chromosomes
+    #[value(alias("C"))]
+    Cycle,
+
+    /// Alias: N, Runs ordered crossover on the chromosomes, filling each gap with the nearest remaining city
+    #[value(alias("N"))]
+    Nearest,
+
+    /// Alias: S, Runs sequential constructive crossover on the chromosomes
+    #[value(alias("S"))]
+    Scx,
+}
+
+/// Implements methods on `CrossoverOperator`
+impl CrossoverOperator {
+    /// Returns whether crossing a Chromosome with an identical copy of itself always gives copies of it back.
+    /// The nearest neighbour crossover can reorder the cities it fills in, so can make new routes from identical parents
+    pub fn clones_identical_parents(&self) -> bool {
+        match self {
+            CrossoverOperator::Fix 
+            | CrossoverOperator::Ordered 
+            | CrossoverOperator::Cycle 
+            | CrossoverOperator::Scx => true,
+            CrossoverOperator::Nearest => false,
+        }
+    }
+
+    /// Returns how many fitness evaluations one crossover of two parents consumes
+    pub fn evaluations(&self) -> u64 {
+        match self {
+            // Both crossovers create two children and calculate the cost of each
+            CrossoverOperator::Fix 
+            | CrossoverOperator::Ordered 
+            | CrossoverOperator::Cycle 
+            | CrossoverOperator::Nearest 
+            | CrossoverOperator::Scx => 2,
+        }
+    }
+}
+
+/// Enumerate that represents the possible algorithms to run
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum Algorithm {
+    /// Alias: G, the steady state genetic algorithm
+    #[value(alias("G"))]
+    Genetic,
+
+    /// Alias: H, a 2-opt hill climber that restarts from a random tour at each local optimum, as a baseline
+    #[value(alias("H"))]
+    Hillclimb,
+}
+
+/// Enumerate that represents the possible ways of selecting parents
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum SelectionOperator {
+    /// Alias: T, Picks the cheapest of a random tournament of Chromosomes
+    #[value(alias("T"))]
+    Tournament,
+
+    /// Alias: R, Picks a Chromosome with a probability proportional to the inverse of its cost
+    #[value(alias("R"))]
+    Roulette,
+
+    /// Alias: S, Picks both parents from one roulette spin with evenly spaced pointers (stochastic universal sampling)
+    #[value(alias("S"))]
+    Sus,
+}
+
+/// Enumerate that represents the possible ways children join the population
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum ReplacementStrategy {
+    /// Alias: W, each child replaces the most expensive Chromosome in the population if it is no more expensive
+    #[value(alias("W"))]
+    Weakest,
+
+    /// Alias: P, each child replaces the more expensive of its parents if it is no more expensive
+    #[value(alias("P"))]
+    WorstParent,
+
+    /// Alias: C, each child replaces the parent sharing the most edges with it if it is cheaper (deterministic crowding)
+    #[value(alias("C"))]
+    Crowding,
+
+    /// Alias: R, each child replaces the Chromosome sharing the most edges with it out of a random window of the population if it is cheaper
+    #[value(alias("R"))]
+    Restricted,
+}
+
+/// Enumerate that represents the possible ways of dealing with children whose tour is already in the population
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    /// Alias: A, children join the population even if their tour is already in it
+    #[value(alias("A"))]
+    Allow,
+
+    /// Alias: R, children whose tour is already in the population are thrown away
+    #[value(alias("R"))]
+    Reject,
+
+    /// Alias: P, children whose tour is already in the population are mutated until their tour is new
+    #[value(alias("P"))]
+    Perturb,
 }
 
 /// Enumerate that represents the possible types of the plot output
@@ -101,4 +1032,158 @@ pub enum PlotStatistic {
     /// Alias: W, will plot the worst cost from each generation
     #[value(alias("W"))]
     Worst,
+
+    /// Alias: D, will plot the standard deviation of the costs of each generation
+    #[value(alias("D"))]
+    Deviation,
+
+    /// Alias: E, will plot the entropy of the edges used by each generation
+    #[value(alias("E"))]
+    Entropy,
+
+    /// Alias: V, will plot the average fraction of edges two tours of each generation don't share
+    #[value(alias("V"))]
+    Diversity,
+
+    /// Alias: I, will plot how much the best cost fell over the last 100 generations at each generation
+    #[value(alias("I"))]
+    Improvement,
+}
+
+/// Implements methods on `PlotStatistic`
+impl PlotStatistic {
+    /// Returns the lowest [`MetricsLevel`] that records this statistic
+    pub fn required_metrics(&self) -> MetricsLevel {
+        match self {
+            PlotStatistic::Average | PlotStatistic::Best | PlotStatistic::Worst | PlotStatistic::Improvement => {
+                MetricsLevel::Basic
+            },
+            PlotStatistic::Deviation => MetricsLevel::Standard,
+            PlotStatistic::Entropy | PlotStatistic::Diversity => MetricsLevel::Full,
+        }
+    }
+}
+
+/// Enumerate that represents how many statistics are recorded each generation, 
+/// each level records everything the levels before it do
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum MetricsLevel {
+    /// Alias: B, only records the best, worst and average cost
+    #[value(alias("B"))]
+    Basic,
+
+    /// Alias: S, also records the standard deviation of the costs
+    #[value(alias("S"))]
+    Standard,
+
+    /// Alias: F, also records the entropy of the edges used and the diversity of the tours, which are expensive on
+    /// large countries
+    #[value(alias("F"))]
+    Full,
+}
+
+/// Enumerate that represents the possible local searches used to improve children
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum LocalSearch {
+    /// Alias: T, reverses sections of the route while doing so makes it cheaper
+    #[value(alias("T"))]
+    TwoOpt,
+
+    /// Alias: O, moves chains of 1 to 3 cities to between two other cities while doing so makes the route cheaper
+    #[value(alias("O"))]
+    OrOpt,
+}
+
+/// Implements methods on `LocalSearch`
+impl LocalSearch {
+    /// Returns how many edges of the route each move of the local search changes
+    pub fn edges_changed(&self) -> u64 {
+        match self {
+            // A reversal swaps two edges for two others
+            LocalSearch::TwoOpt => 2,
+            // Moving a chain swaps the three edges either side of the chain and where it goes
+            LocalSearch::OrOpt => 3,
+        }
+    }
+
+    /// Returns how many fitness evaluations improving one Chromosome consumes, given how many moves the search
+    /// evaluated on a route of the given number of cities. Each move is compared using only the edges it changes, so
+    /// counts as that fraction of the route, rounded up over the whole search, then the cost of the improved route is
+    /// recalculated once
+    pub fn evaluations(&self, moves: u64, cities: usize) -> u64 {
+        (moves * self.edges_changed()).div_ceil(cities.max(1) as u64) + 1
+    }
+
+    /// Returns the most moves the search can evaluate on a route of the given number of cities so improving one
+    /// Chromosome consumes no more than the given fitness evaluations, see [`LocalSearch::evaluations`]
+    pub fn max_moves(&self, evaluations: u64, cities: usize) -> u64 {
+        evaluations.saturating_sub(1).saturating_mul(cities.max(1) as u64) / self.edges_changed()
+    }
+}
+
+/// Enumerate that represents the possible colour themes for plots
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum PlotTheme {
+    /// Alias: L, dark lines on a white background
+    #[value(alias("L"))]
+    Light,
+
+    /// Alias: D, bright lines on a dark background, suited to slides
+    #[value(alias("D"))]
+    Dark,
+
+    /// Alias: R, thick colour-blind friendly lines and serif fonts on a white background, suited to printed reports
+    #[value(alias("R"))]
+    Report,
+}
+
+/// Enumerate that represents the possible ways the population size can change over a run
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum PopulationSchedule {
+    /// Alias: C, the population size never changes
+    #[value(alias("C"))]
+    Constant,
+
+    /// Alias: T, the population shrinks to the minimum size over each period,
+    /// then is refilled with random chromosomes
+    #[value(alias("T"))]
+    SawTooth,
+
+    /// Alias: S, the population shrinks steadily to the minimum size over the whole run
+    #[value(alias("S"))]
+    Shrinking,
+}
+
+/// Enumerate that represents the possible ways of choosing between Chromosomes that tie on cost in a tournament
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum TiePolicy {
+    /// Alias: F, picks the tied Chromosome that was randomly picked for the tournament first
+    #[value(alias("F"))]
+    First,
+
+    /// Alias: R, picks any of the tied Chromosomes at random
+    #[value(alias("R"))]
+    Random,
+
+    /// Alias: O, picks the tied Chromosome that has been in the population the longest
+    #[value(alias("O"))]
+    Older,
+
+    /// Alias: D, picks the tied Chromosome that shares the fewest edges with the best Chromosome
+    #[value(alias("D"))]
+    Diverse,
+}
+
+/// Enumerate that represents the possible ways of adding up the cost of the edges of a route
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize, Deserialize)]
+pub enum Summation {
+    /// Alias: P, adds each edge to the total in turn
+    #[default]
+    #[value(alias("P"))]
+    Plain,
+
+    /// Alias: C, keeps track of the rounding error of each addition and adds it back at the end,
+    /// so the total stays accurate on routes with tens of thousands of edges
+    #[value(alias("C"))]
+    Compensated,
 }
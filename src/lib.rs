@@ -1,8 +1,27 @@
 pub mod chromosome;
 pub mod country;
 pub mod population;
+pub mod results;
 pub mod simulation;
 pub mod interface;
-
-/// This is hardcoded for the course requirement
-pub const NUMBER_OF_GENERATIONS: usize = 10_000;
+pub mod manifest;
+pub mod optimal;
+pub mod repair;
+pub mod placement;
+pub mod control;
+pub mod snapshot;
+pub mod baseline;
+pub mod solver;
+pub mod hillclimb;
+pub mod tour;
+pub mod build_info;
+pub mod warnings;
+pub mod cancellation;
+pub mod adaptive;
+pub mod orchestrator;
+pub mod runner;
+pub mod random;
+pub mod runlog;
+pub mod harness;
+pub mod genealogy;
+pub mod fetch;
@@ -1,17 +1,33 @@
 // Importing some of my programs modules
 use tsp_coursework::{
+        build_info::BuildInfo,
+        cancellation::CancellationToken,
         country::Country, 
+        fetch::{fetch, Checksums},
         interface::*, 
+        chromosome::Chromosome,
+        manifest::{ExperimentManifest, ExperimentNotes},
+        optimal::OptimalTour,
+        orchestrator::{group_by_instance, queue_runs, spawn_runs, FinishedRun, InstanceGroups, ResultStream, RunSettings, Scheduling},
+        placement::{available_cores, plan_placements, CorePlacement},
+        results::{ExperimentResults, RunSummary},
         simulation::Simulation, 
-        NUMBER_OF_GENERATIONS
+        snapshot::{PopulationDiff, PopulationSnapshot},
+        warnings::WarningKind,
     };
 
 // Importing some modules from the standard library
 use std::{
     collections::HashMap,
     fmt::Write,
-    sync::mpsc,
+    path::Path,
+    process::ExitCode,
+    sync::{
+        mpsc,
+        Arc,
+    },
     thread, 
+    time::Duration,
 };
 
 // Here I am importing my external dependencies:
@@ -28,28 +44,204 @@ use indicatif::{
 use color_eyre::Result;
 
 
-/// Main function for this program
-fn main() -> Result<()> {
+/// Main function for this program, exiting with the code of its [`RunOutcome`]
+fn main() -> ExitCode {
     // Setup color_eyre so errors output nicely
-    color_eyre::install()?;
+    if let Err(report) = color_eyre::install() {
+        eprintln!("Error: {:?}", report);
+        return RunOutcome::Runtime.into();
+    }
 
     // Create variable of type CLI and parse in info from command line
     let cli = Cli::parse();
 
-    // Compare given tournament size and population size
-    match cli.tournament_size.cmp(&(cli.population_size as u32)) {
-        // Do nothing if the user selects a tournament size lower than the population size
-        std::cmp::Ordering::Less => (),
-        // If the user selects a tournament size equal to the population size, warn them
-        std::cmp::Ordering::Equal => {
-            println!("Warning: Selected Tournament Size is equal to the population size");
+    // Run the tool asked for instead of an experiment
+    if let Some(Command::DiffPopulations { first, second }) = &cli.command {
+        return diff_populations(first, second).into();
+    }
+    if let Some(Command::ConvertInstance { input, output }) = &cli.command {
+        return convert_instance(input, output, &cli.data_dir).into();
+    }
+    if let Some(Command::Fetch { names, mirror, checksums, force }) = &cli.command {
+        let trusted = Checksums { entries: checksums.iter().cloned().collect() };
+        return fetch_instances(names, mirror, &cli.data_dir, &trusted, *force).into();
+    }
+
+    // Say which build is running, so the output can be traced to the code that produced it
+    println!("{}", BuildInfo::current());
+
+    // Check every option works together before any simulations start
+    let parameters: RunParameters = match cli.clone().into_config() {
+        Ok(parameters) => parameters,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            return RunOutcome::InvalidConfig.into();
+        }
+    };
+
+    // Get Countries data from the files asked for, otherwise from every file in the data directory, then keep
+    // those whose name matches a filter if any were given
+    let loaded: Result<Vec<Country>> = if cli.instances.is_empty() {
+        Country::new(&cli.data_dir, cli.strict_data)
+    } else {
+        Country::from_files(&cli.instances, &cli.data_dir)
+    };
+    let input_data: Vec<Country> = match loaded.and_then(|loaded| Country::filter_by_name(loaded, &cli.filters)) {
+        Ok(input_data) => input_data,
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            return RunOutcome::DataLoad.into();
+        }
+    };
+
+    // Carry on with the jobs of an interrupted experiment, failing to read its manifest is a data load failure
+    let manifest: Option<ExperimentManifest> = match &cli.resume_experiment {
+        Some(path) => match ExperimentManifest::load(path) {
+            Ok(manifest) => Some(manifest),
+            Err(report) => {
+                eprintln!("Error: {:?}", report);
+                return RunOutcome::DataLoad.into();
+            }
         },
-        // If the user selects a tournament size greater than the population size,
-        // exit the program with an error message
-        std::cmp::Ordering::Greater => {
-            panic!("ERROR: Selected Tournament Size is greater than the population size")
+        None => None,
+    };
+
+    // Run the experiment, any error from here on is a runtime failure
+    match run_experiment(&cli, parameters, input_data, manifest) {
+        Ok(outcome) => outcome.into(),
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            RunOutcome::Runtime.into()
+        }
+    }
+}
+
+/// Function to print a comparison of two population snapshots
+fn diff_populations(first: &Path, second: &Path) -> RunOutcome {
+    // Failing to read either snapshot is a data load failure
+    let snapshots = PopulationSnapshot::load(first).and_then(|first| Ok((first, PopulationSnapshot::load(second)?)));
+    let (first, second) = match snapshots {
+        Ok(snapshots) => snapshots,
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            return RunOutcome::DataLoad;
+        }
+    };
+
+    match PopulationDiff::new(&first, &second) {
+        Ok(diff) => {
+            println!("{}", diff);
+            RunOutcome::Success
         },
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            RunOutcome::Runtime
+        }
     }
+}
+
+/// Function to load a single dataset and write it back out in the coursework XML format
+fn convert_instance(input: &Path, output: &Path, data_dir: &Path) -> RunOutcome {
+    // Failing to read the dataset is a data load failure
+    let country: Country = match Country::from_files(&[input.to_path_buf()], data_dir) {
+        Ok(mut countries) => countries.remove(0),
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            return RunOutcome::DataLoad;
+        }
+    };
+
+    match country.save_xml(output) {
+        Ok(()) => {
+            println!("Wrote {} to {}", country.name, output.display());
+            RunOutcome::Success
+        },
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            RunOutcome::Runtime
+        }
+    }
+}
+
+/// Function to download TSPLIB instances into the data directory, see [`fetch`]
+fn fetch_instances(names: &[String], mirror: &str, data_dir: &Path, trusted: &Checksums, force: bool) -> RunOutcome {
+    // Failing to download, verify or convert an instance is a data load failure
+    match fetch(names, mirror, data_dir, trusted, force) {
+        Ok(paths) => {
+            println!("Fetched {} instances into {}", paths.len(), data_dir.display());
+            RunOutcome::Success
+        },
+        Err(report) => {
+            eprintln!("Error: {:?}", report);
+            RunOutcome::DataLoad
+        }
+    }
+}
+
+/// Function to plot and export the results of countries whose runs have all finished
+fn write_instance_outputs(cli: &Cli, results: &mut ExperimentResults) -> Result<()> {
+    results.plot(cli.plot_operator, cli.statistic_plotted, cli.plot_theme)?;
+    if cli.vega_lite {
+        for path in results.export_vega_lite(cli.plot_operator, cli.statistic_plotted)? {
+            println!("Vega-Lite chart written to {}", path.display());
+        }
+    }
+    if let Some(directory) = &cli.optimal_tours {
+        results.compare_with_optimal(directory, cli.plot_tour_comparison, cli.plot_theme)?;
+    }
+    if let Some(expensive) = cli.edge_breakdown {
+        for path in results.export_edge_breakdown(expensive as usize)? {
+            println!("Edge breakdown written to {}", path.display());
+        }
+    }
+    for path in results.plot_edge_frequencies(cli.plot_theme)? {
+        println!("Edge frequency plot written to {}", path.display());
+    }
+    results.check_convergence(cli.optimal_tours.as_deref())?;
+    for line in results.operator_report() {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Function to find the edges to track for each country: those given with --track-edge, along with the edges
+/// of the optimal tour of the country if asked for. Countries with no edges to track are left out
+fn tracked_edges(cli: &Cli, input_data: &[Arc<Country>]) -> Result<HashMap<String, Vec<(u32, u32)>>> {
+    let mut tracked: HashMap<String, Vec<(u32, u32)>> = HashMap::new();
+    for country in input_data {
+        let mut edges: Vec<(u32, u32)> = cli.tracked_edges.clone();
+        if let (true, Some(directory)) = (cli.track_optimal_edges, &cli.optimal_tours) {
+            if let Some(optimal) = OptimalTour::find(country, directory)? {
+                edges.extend(Chromosome::edges(&optimal.route));
+            }
+        }
+
+        // Check the edges fit the country now rather than once its runs have started
+        let edges: Vec<(u32, u32)> = Simulation::normalise_edges(&edges, country)?;
+        if !edges.is_empty() {
+            tracked.insert(country.name.clone(), edges);
+        }
+    }
+    Ok(tracked)
+}
+
+/// Function to run every job of the experiment, plotting each country as soon as all of its runs finish,
+/// then summarise the results, returning early with [`RunOutcome::Interrupted`] if Ctrl-C is pressed
+fn run_experiment(
+    cli: &Cli,
+    parameters: RunParameters,
+    input_data: Vec<Country>,
+    manifest: Option<ExperimentManifest>,
+) -> Result<RunOutcome> {
+    // Cancel every run when Ctrl-C is pressed so the finished runs can be kept before exiting,
+    // pressing it a second time exits straight away
+    let cancellation = CancellationToken::new();
+    let handler_token = cancellation.clone();
+    ctrlc::set_handler(move || {
+        if handler_token.cancel() {
+            std::process::exit(RunOutcome::Interrupted.code() as i32);
+        }
+    })?;
 
     // Create object to manage multiple progress bars
     let multi_bar = MultiProgress::new();
@@ -65,92 +257,218 @@ fn main() -> Result<()> {
     // Set characters to be used for Progress bar
     .progress_chars("#>-");
 
-    // Get Countries data from the data directory
-    let input_data: Vec<Country> = Country::new()?;
+    // Plan a job for every run of every country, unless carrying on with the jobs of an interrupted experiment
+    let mut manifest = match manifest {
+        Some(manifest) => manifest,
+        None => ExperimentManifest::new(
+            &input_data,
+            parameters,
+            cli.number_runs,
+            cli.tags.clone(),
+            ExperimentNotes { description: cli.description.clone(), author: cli.author.clone() },
+        )?,
+    };
+    println!("Experiment manifest written to {}", manifest.path.display());
 
-    // Create vector for Simulations 
-    let mut output_data: Vec<Simulation> = Vec::with_capacity(input_data.capacity() * cli.number_runs as usize);
+    // Collect the options needed by each Simulation and the jobs still to run
+    let parameters = manifest.parameters;
+    let jobs: Vec<usize> = manifest.missing_jobs();
 
-    // Create Multi-producer, single-consumer channel
-    let (tx, rx) = mpsc::channel();
+    // Collect the Simulations of each country, paired with their run number, until every run of the country
+    // still to finish has, so it can be plotted as soon as the last one does
+    let mut groups: InstanceGroups<(u32, Simulation)> =
+        InstanceGroups::new(jobs.iter().map(|index| manifest.jobs[*index].instance.as_str()));
 
-    // Create a vector to hold the thread handlers
-    let mut threads = Vec::with_capacity(input_data.len() * cli.number_runs as usize);
+    // Runs that finished before resuming are merged into the results of their country, and countries with
+    // none left to run are only summarised
+    let mut previous_runs: HashMap<String, Vec<RunSummary>> = group_by_instance(manifest.completed_summaries(&jobs));
+    let mut results = ExperimentResults::from_runs(
+        Vec::new(), 
+        previous_runs
+            .extract_if(|name, _| !groups.contains(name))
+            .flat_map(|(name, summaries)| summaries.into_iter().map(move |summary| (name.clone(), summary)))
+            .collect(), 
+        manifest.number_runs
+    )?.with_tags(manifest.tags.clone()).with_notes(manifest.notes.clone());
 
-    // Loop for number of runs specified
-    for _ in 0..cli.number_runs {
+    // Warn the user if every tournament will contain the whole population
+    if cli.tournament_size as u64 == cli.population_size {
+        results.warnings.push(
+            WarningKind::Configuration,
+            "",
+            "Selected Tournament Size is equal to the population size",
+        );
+    }
 
-        // Loop over each separate file in the directory
-        for country in &input_data {
+    // Set the graph of each country up for the runs once, then share it read-only between the threads. Only
+    // threads pinned to a core copy it, so their copy is in memory close to that core
+    let input_data: Vec<Arc<Country>> = input_data
+        .into_iter()
+        .map(|mut country| {
+            country.graph.configure(&parameters);
+            Arc::new(country)
+        })
+        .collect();
 
-            // Clone transmitter so the thread will have a unique one
-            let thread_tx = tx.clone();
+    // Collect everything each run needs besides its country, sharing the edges tracked for each country
+    // between the threads
+    let settings = RunSettings {
+        parameters,
+        control_file: cli.control_file.clone(),
+        log_dir: cli.log_dir.clone(),
+        snapshot_generations: cli.snapshot_generations.clone(),
+        genealogy: cli.genealogy,
+        tags: manifest.tags.clone(),
+        tracked_edges: Arc::new(tracked_edges(cli, &input_data)?),
+        cancellation: cancellation.clone(),
+    };
 
-            // Clone the country data because only one thread can have access to a value at a time
-            let country_data = (*country).clone();
+    // Find the cores to pin threads to, if asked to
+    let cores: Vec<CorePlacement> = if cli.pin_threads { available_cores() } else { Vec::new() };
+    if cli.pin_threads && cores.is_empty() {
+        results.warnings.push(
+            WarningKind::Configuration,
+            "",
+            "Could not find the CPU cores to pin threads to, threads will not be pinned",
+        );
+    }
 
-            // Create a new progress bar for this operation and add styling
-            let progress_bar = multi_bar.add(ProgressBar::new(NUMBER_OF_GENERATIONS as u64));
-            progress_bar.set_style(bar_style.clone());
+    // Pick a core for every job that hasn't finished, keeping runs of the same country on the same socket,
+    // then pair every job with the data for its country and the core planned for it
+    let instances: Vec<&str> = jobs.iter().map(|index| manifest.jobs[*index].instance.as_str()).collect();
+    let placements: Vec<Option<CorePlacement>> = plan_placements(&instances, &cores);
+    let queue = queue_runs(&manifest.jobs, &jobs, &input_data, placements)?;
 
-            // Generate a Thread to build and run the simulation
-            let thread = thread::spawn(move || -> Result<()> {
+    // In batch mode a single progress bar counts the finished runs instead of one bar per run
+    let mut batch_bar: Option<ProgressBar> = None;
+    let scheduling: Scheduling = if cli.batch {
+        // Use one worker per CPU unless told otherwise, never more than there are jobs
+        let workers: usize = cli.workers
+            .map(|workers| workers as usize)
+            .unwrap_or_else(|| thread::available_parallelism().map(|cpus| cpus.get()).unwrap_or(1))
+            .min(jobs.len());
 
-                // Create a Simulation type
-                let mut simulation = Simulation::new(
-                    country_data,
-                    cli.crossover_operator,
-                    cli.mutation_operator,
-                    cli.population_size,
-                    cli.tournament_size,
-                )?;
+        // Create the progress bar for the whole batch and add styling
+        let progress_bar = multi_bar.add(ProgressBar::new(jobs.len() as u64));
+        progress_bar.set_style(bar_style.clone());
+        progress_bar.set_message(format!("0 of {} runs on {} workers", jobs.len(), workers));
+        batch_bar = Some(progress_bar);
 
-                // Run the Simulation
-                simulation.run(progress_bar)?;
+        Scheduling::Batch { workers, cores }
+    } else {
+        Scheduling::PerRun
+    };
 
-                // Transmit the simulation back to main
-                thread_tx.send(simulation)?;
+    // Start the runs, each run in its own thread gets a new progress bar with styling
+    let mut stream: ResultStream = spawn_runs(queue, &settings, scheduling, || {
+        let progress_bar = multi_bar.add(ProgressBar::new(parameters.generations as u64));
+        progress_bar.set_style(bar_style.clone());
+        progress_bar
+    });
 
-                // Exit thread
-                Ok(())
-            });
+    // Runs stopped part way through by Ctrl-C, along with their run number, which are summarised up to the
+    // generation they stopped at
+    let mut cancelled_runs: Vec<(u32, Simulation)> = Vec::new();
 
-            // Push the Thread Handler to the threads vector
-            threads.push(thread)
+    // Loop until every job has run and group the result of each one by country, marking each job as
+    // finished in the manifest as soon as it arrives
+    while !stream.is_complete() {
+        match stream.recv_timeout(Duration::from_millis(100)) {
+            // A cancelled run is left unfinished in the manifest, so resuming runs it again from the start
+            Ok(FinishedRun { index, simulation, .. }) if simulation.cancelled => {
+                cancelled_runs.push((manifest.jobs[index].run, simulation));
+            }
+            Ok(FinishedRun { index, mut simulation, placement }) => {
+                let run: u32 = manifest.jobs[index].run;
+                manifest.jobs[index].placement = placement;
+                manifest.complete(index, RunSummary::new(run, &simulation)?)?;
+
+                // Save any snapshots of the population now the run is known
+                for snapshot in simulation.snapshots.iter_mut() {
+                    snapshot.run = run;
+                    let path = snapshot.save()?;
+                    multi_bar.println(format!("Population snapshot written to {}", path.display()))?;
+                }
+
+                // Save the genealogy of the population now the run is known
+                if let Some(genealogy) = simulation.population.genealogy.as_mut() {
+                    genealogy.run = run;
+                    genealogy.tags = manifest.tags.clone();
+                    genealogy.notes = manifest.notes.clone();
+                    let (json, dot) = genealogy.save()?;
+                    multi_bar.println(format!("Genealogy written to {} and {}", json.display(), dot.display()))?;
+                }
+
+                // Count the finished run on the batch progress bar
+                if let Some(progress_bar) = &batch_bar {
+                    progress_bar.inc(1);
+                    progress_bar.set_message(format!("{} of {} runs", stream.received(), stream.expected()));
+                }
+
+                // Once every run of the country has finished, plot and export it straight away so
+                // long experiments have usable results before the other countries finish
+                let instance: String = manifest.jobs[index].instance.clone();
+                if let Some(runs) = groups.push(&instance, (run, simulation))? {
+                    let previous: Vec<(String, RunSummary)> = previous_runs
+                        .remove(&instance)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|summary| (instance.clone(), summary))
+                        .collect();
+                    let mut instance_results = ExperimentResults::from_runs(runs, previous, manifest.number_runs)?
+                        .with_tags(manifest.tags.clone())
+                        .with_notes(manifest.notes.clone());
+                    multi_bar.suspend(|| write_instance_outputs(cli, &mut instance_results))?;
+                    results.append(instance_results);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
+            // Every thread has stopped, so the error of the one that failed is found when they are closed below
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
         }
     }
 
-    // The number of threads spawned is the number of files multiplied by the number of runs specified
-    // Loop for this value and push the result of each one to the output_data vector
-    for _ in 0..cli.number_runs * input_data.len() as u32 {
-        output_data.push(rx.recv()?);
+    // If Ctrl-C was pressed before every run finished, the finished runs are already saved in the manifest. Summarise
+    // them along with the cancelled runs, which are marked as cancelled, so the experiment has results so far
+    if cancellation.is_cancelled() && !manifest.missing_jobs().is_empty() {
+        multi_bar.clear()?;
+        let mut runs: Vec<(u32, Simulation)> = groups.take_arrived();
+        runs.extend(cancelled_runs);
+        let previous: Vec<(String, RunSummary)> = previous_runs
+            .drain()
+            .flat_map(|(name, summaries)| summaries.into_iter().map(move |summary| (name.clone(), summary)))
+            .collect();
+        results.append(ExperimentResults::from_runs(runs, previous, manifest.number_runs)?);
+        let summary_path = results.export_summary()?;
+        eprintln!("Partial run summary written to {}", summary_path.display());
+        eprintln!(
+            "Interrupted with {} of {} runs finished, the rest start again with --resume-experiment {}",
+            manifest.jobs.len() - manifest.missing_jobs().len(),
+            manifest.jobs.len(),
+            manifest.path.display()
+        );
+        if !results.warnings.is_empty() {
+            eprintln!("{}", results.warnings);
+        }
+        return Ok(RunOutcome::Interrupted);
     }
 
-    // Loop through the vector of thread handlers and close each thread
-    for thread in threads {
-        thread.join().expect("Threads panicked")?;
+    // Close every thread, erroring if any failed or fewer runs finished than were queued
+    let finished_runs: usize = stream.received();
+    stream.finish()?;
+    if let Some(progress_bar) = &batch_bar {
+        progress_bar.finish_with_message(format!("{} runs Done", finished_runs));
     }
 
-    // Create a HashMap to store all the simulations by their names
-    let mut ordered_data: HashMap<String, Vec<Simulation>> = HashMap::with_capacity(output_data.capacity());
+    // Every country has been plotted as it finished, so write the summary of every run
+    let summary_path = results.export_summary()?;
+    println!("Run summary written to {}", summary_path.display());
 
-    // Loop over each Simulation in output_data
-    for sim in output_data {
-        ordered_data
-            // Get the entry of the key, where the key is the name out the country used
-            .entry(sim.country_data.name.clone())
-            // If that key doesn't exist yet, create it and set its entry to be an empty vector
-            .or_default()
-            // Push the Simulation into the entry
-            .push(sim);
+    // Print the problems found along the way together, now the progress bars are finished
+    if !results.warnings.is_empty() {
+        println!("{}", results.warnings);
     }
 
-    // For each Simulation in ordered_data create a plot for it
-    ordered_data.retain(|key: &String, data: &mut Vec<Simulation>| {
-        Simulation::plot(data, cli.plot_operator, cli.statistic_plotted, cli.number_runs, key.clone()).expect("Plotting of Simulation failed");
-        true
-    });
-
     // End program
-    Ok(())
+    Ok(RunOutcome::Success)
 }
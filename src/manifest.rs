@@ -0,0 +1,186 @@
+//! This module defines [`ExperimentManifest`], a JSON file listing every job planned for an experiment
+//! along with whether it has finished, so an interrupted experiment can be resumed without rerunning
+//! the jobs that already finished.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::prelude::*;
+use color_eyre::{eyre::{eyre, WrapErr}, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    build_info::BuildInfo,
+    country::Country,
+    interface::RunParameters,
+    placement::CorePlacement,
+    random::derive_seed,
+    results::RunSummary,
+};
+
+/// A single run of a country planned for an experiment
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Job {
+    /// Name of the country to run
+    pub instance: String,
+    /// Which run of the country this is, starting from 1
+    pub run: u32,
+    /// Seed for the random number generator of the run, None if the run is not seeded
+    pub seed: Option<u64>,
+    /// Whether the run has finished
+    pub completed: bool,
+    /// Summary of the run once it has finished
+    pub summary: Option<RunSummary>,
+    /// The core the run was pinned to, None if it wasn't pinned
+    #[serde(default)]
+    pub placement: Option<CorePlacement>,
+}
+
+/// Why an experiment was run and who ran it, recorded with its results so archived results keep their context
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentNotes {
+    /// What the experiment was run for
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Who ran the experiment
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// Implement methods on `ExperimentNotes`
+impl ExperimentNotes {
+    /// Function to write the notes for the end of a plot caption, empty if there are none
+    pub fn caption(&self) -> String {
+        let mut caption: String = String::new();
+        if let Some(description) = &self.description {
+            caption.push_str(&format!(", Description: {}", description));
+        }
+        if let Some(author) = &self.author {
+            caption.push_str(&format!(", Author: {}", author));
+        }
+        caption
+    }
+
+    /// Function to write the tags of the experiment and then the notes for the end of a plot caption, empty if
+    /// there are neither
+    pub fn tagged_caption(&self, tags: &[String]) -> String {
+        match tags.is_empty() {
+            true => self.caption(),
+            false => format!(", Tags: {}{}", tags.join(";"), self.caption()),
+        }
+    }
+}
+
+/// Every job planned for an experiment, along with the parameters they are all run with
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExperimentManifest {
+    /// The operators and sizes every job is run with
+    pub parameters: RunParameters,
+    /// Number of times each country is run
+    pub number_runs: u32,
+    /// Freeform tags attached to every run
+    pub tags: Vec<String>,
+    /// Why the experiment was run and who ran it
+    #[serde(default, flatten)]
+    pub notes: ExperimentNotes,
+    /// Every planned job, ordered by country then run
+    pub jobs: Vec<Job>,
+    /// The build that planned the experiment, None for manifests written before builds were recorded
+    #[serde(default)]
+    pub build: Option<BuildInfo>,
+    /// Where the manifest is saved
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// Implement methods on `ExperimentManifest`
+impl ExperimentManifest {
+    /// Function to plan a job for each run of each country, saved to a new manifest in the results directory
+    pub fn new(
+        countries: &[Country],
+        parameters: RunParameters,
+        number_runs: u32,
+        tags: Vec<String>,
+        notes: ExperimentNotes,
+    ) -> Result<Self> {
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+
+        // Generate unique path for the manifest using date and time
+        let time: DateTime<Utc> = Utc::now();
+        let path = PathBuf::from(format!(
+            "results/manifest-{}.json",
+            time.format("%Y-%m-%d-%H-%M-%S"),
+        ));
+
+        // Plan every run of every country, giving each its own seed derived from the seed of the experiment
+        let mut names: Vec<&str> = countries.iter().map(|country| country.name.as_str()).collect();
+        names.sort();
+        let jobs: Vec<Job> = names
+            .iter()
+            .flat_map(|name| (1..=number_runs).map(move |run| (name, run)))
+            .enumerate()
+            .map(|(position, (name, run))| Job {
+                instance: name.to_string(),
+                run,
+                seed: parameters.seed.map(|seed| derive_seed(seed, position as u64)),
+                completed: false,
+                summary: None,
+                placement: None,
+            })
+            .collect();
+
+        let manifest = Self { parameters, number_runs, tags, notes, jobs, build: Some(BuildInfo::current()), path };
+        manifest.save()?;
+        Ok(manifest)
+    }
+
+    /// Function to read a manifest written by an earlier invocation
+    pub fn load(path: &Path) -> Result<Self> {
+        let src: String = fs::read_to_string(path).wrap_err("Failed to read experiment manifest")?;
+        let mut manifest: Self = serde_json::from_str(&src).wrap_err("Failed to deserialize experiment manifest")?;
+        manifest.path = path.to_path_buf();
+        Ok(manifest)
+    }
+
+    /// Function to write the manifest to its path. It is written to a temporary file first
+    /// so an interruption part way through writing can't corrupt the existing manifest
+    pub fn save(&self) -> Result<()> {
+        let temporary: PathBuf = self.path.with_extension("json.tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    /// Function to list the indices of every job that hasn't finished
+    pub fn missing_jobs(&self) -> Vec<usize> {
+        self.jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| !job.completed)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Function to mark a job as finished, saving the manifest straight away
+    pub fn complete(&mut self, index: usize, summary: RunSummary) -> Result<()> {
+        let job = self.jobs
+            .get_mut(index)
+            .ok_or_else(|| eyre!("Experiment manifest has no job {}", index))?;
+        job.completed = true;
+        job.summary = Some(summary);
+        self.save()
+    }
+
+    /// Function to return the name and summary of every job that has finished, leaving out the given jobs
+    pub fn completed_summaries(&self, excluded: &[usize]) -> Vec<(String, RunSummary)> {
+        self.jobs
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !excluded.contains(index))
+            .filter_map(|(_, job)| job.summary.clone().map(|summary| (job.instance.clone(), summary)))
+            .collect()
+    }
+}
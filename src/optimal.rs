@@ -0,0 +1,248 @@
+//! This module defines [`OptimalTour`], a known optimal route for a country loaded from a TSPLIB
+//! `.opt.tour` file, and [`EdgeComparison`] which measures how many edges a found route shares with it.
+
+use std::{
+    collections::HashSet,
+    f64::consts::PI,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::prelude::*;
+use color_eyre::{eyre::{bail, eyre, WrapErr}, Result};
+use plotters::prelude::*;
+
+use super::{
+    build_info::BuildInfo,
+    chromosome::Chromosome,
+    country::Country,
+    interface::PlotTheme,
+    manifest::ExperimentNotes,
+    simulation::ThemeStyle,
+};
+
+/// A known optimal route through a country
+#[derive(Clone, Debug)]
+pub struct OptimalTour {
+    /// Name of the country the tour belongs to
+    pub name: String,
+    /// The order the cities are visited in, numbered from 0 like [`Chromosome`] routes
+    pub route: Vec<u32>,
+}
+
+/// Implement methods on `OptimalTour`
+impl OptimalTour {
+    /// Function to read an optimal tour from a TSPLIB tour file.
+    /// Cities in the file are numbered from 1 and the list ends with -1
+    pub fn from_file(name: &str, path: &Path) -> Result<Self> {
+        let src: String = fs::read_to_string(path).wrap_err("Failed to read tour file")?;
+        Self::from_tsplib(name, &src)
+    }
+
+    /// Function to parse an optimal tour from the contents of a TSPLIB tour file
+    pub fn from_tsplib(name: &str, src: &str) -> Result<Self> {
+        let mut route: Vec<u32> = Vec::new();
+        let mut in_tour_section: bool = false;
+
+        // Loop over every whitespace separated value in the file
+        for token in src.split_whitespace() {
+            // Skip the header until the tour section starts
+            if !in_tour_section {
+                in_tour_section = token == "TOUR_SECTION";
+                continue;
+            }
+
+            // The tour section ends with -1 or EOF
+            if token == "-1" || token == "EOF" {
+                break;
+            }
+
+            // Convert city from numbered from 1 to numbered from 0
+            let city: u32 = token.parse().wrap_err("Failed to parse city in tour file")?;
+            if city == 0 {
+                return Err(eyre!("Tour file cities must be numbered from 1"));
+            }
+            route.push(city - 1);
+        }
+
+        if route.is_empty() {
+            return Err(eyre!("Tour file does not contain a TOUR_SECTION"));
+        }
+
+        Ok(Self { name: name.to_string(), route })
+    }
+
+    /// Function to check the tour visits every city of a country with the given number of cities exactly once,
+    /// naming the first city at fault, numbered from 1 as in the tour file
+    pub fn check_cities(&self, cities: usize) -> Result<()> {
+        if self.route.len() != cities {
+            bail!("The optimal tour of {} visits {} cities, but it has {}", self.name, self.route.len(), cities);
+        }
+        let mut visited: Vec<bool> = vec![false; cities];
+        for city in self.route.iter() {
+            match visited.get_mut(*city as usize) {
+                None => bail!(
+                    "The optimal tour of {} visits city {}, but it only has {} cities",
+                    self.name, city + 1, cities
+                ),
+                Some(true) => bail!("The optimal tour of {} visits city {} more than once", self.name, city + 1),
+                Some(seen) => *seen = true,
+            }
+        }
+        Ok(())
+    }
+
+    /// Function to find the optimal tour for a country in a directory, if one exists, checking it visits every
+    /// city of the country once. The file must be named after the country, e.g. `burma14.opt.tour`
+    pub fn find(country: &Country, directory: &Path) -> Result<Option<Self>> {
+        let path: PathBuf = directory.join(format!("{}.opt.tour", country.name));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let optimal: Self = Self::from_file(&country.name, &path)?;
+        optimal
+            .check_cities(country.graph.vertex.len())
+            .wrap_err_with(|| format!("Optimal tour {} doesn't match its country", path.display()))?;
+        Ok(Some(optimal))
+    }
+}
+
+/// A comparison of the edges in a found route against those in an optimal route
+#[derive(Clone, Debug)]
+pub struct EdgeComparison {
+    /// Number of edges the found route shares with the optimal route
+    pub shared_edges: usize,
+    /// Number of edges in a route
+    pub total_edges: usize,
+    /// Edges in the found route that are not in the optimal route
+    pub extra_edges: Vec<(u32, u32)>,
+    /// Edges in the optimal route that are not in the found route
+    pub missing_edges: Vec<(u32, u32)>,
+}
+
+/// Implement methods on `EdgeComparison`
+impl EdgeComparison {
+    /// Function to compare the edges of a found route against the edges of an optimal route,
+    /// ignoring the direction each edge is travelled in
+    pub fn new(found: &[u32], optimal: &[u32]) -> Self {
+        let found_edges: Vec<(u32, u32)> = Chromosome::edges(found);
+        let optimal_edges: Vec<(u32, u32)> = Chromosome::edges(optimal);
+
+        // Use sets so membership checks are fast on large routes
+        let found_set: HashSet<&(u32, u32)> = found_edges.iter().collect();
+        let optimal_set: HashSet<&(u32, u32)> = optimal_edges.iter().collect();
+
+        let extra_edges: Vec<(u32, u32)> = found_edges
+            .iter()
+            .filter(|edge| !optimal_set.contains(edge))
+            .copied()
+            .collect();
+        let missing_edges: Vec<(u32, u32)> = optimal_edges
+            .iter()
+            .filter(|edge| !found_set.contains(edge))
+            .copied()
+            .collect();
+
+        Self {
+            shared_edges: found_edges.len() - extra_edges.len(),
+            total_edges: found_edges.len(),
+            extra_edges,
+            missing_edges,
+        }
+    }
+
+    /// Function to return the fraction of edges shared with the optimal route
+    pub fn shared_fraction(&self) -> f64 {
+        if self.total_edges == 0 {
+            return 0.0;
+        }
+        self.shared_edges as f64 / self.total_edges as f64
+    }
+
+    /// Function to plot the found route against the optimal route. As the countries only give costs,
+    /// the cities are placed on a circle in the order of the optimal route, so the optimal route is the
+    /// circle itself. Shared edges are drawn in the themes best colour, extra edges in its worst colour
+    /// and missing optimal edges in its grid colour. The caption ends with the tags and notes of the experiment.
+    /// Returns the path the plot was saved to
+    pub fn plot(
+        &self,
+        optimal: &OptimalTour,
+        plot_theme: PlotTheme,
+        tags: &[String],
+        notes: &ExperimentNotes,
+    ) -> Result<PathBuf> {
+        let style = ThemeStyle::new(plot_theme);
+
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+
+        // Generate unique path for plot to be saved to using date, time and name
+        let time: DateTime<Utc> = Utc::now();
+        let name: String = format!(
+            "results/tour-comparison-{}-({}).png",
+            time.format("%Y-%m-%d-%H-%M-%S"),
+            optimal.name
+        );
+
+        let root = BitMapBackend::new(name.as_str(), (1080, 1080)).into_drawing_area();
+        root.fill(&style.background)?;
+
+        let caption: String = format!(
+            "{}: {} of {} edges shared with the optimal tour, Build: {}{}",
+            optimal.name, self.shared_edges, self.total_edges, BuildInfo::current().id(), notes.tagged_caption(tags)
+        );
+
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .caption(caption, (style.font, style.caption_size).into_font().color(&style.foreground))
+            .build_cartesian_2d(-1.2f64..1.2f64, -1.2f64..1.2f64)?;
+
+        // Place each city on a circle in the order it appears in the optimal route
+        let mut positions: Vec<(f64, f64)> = vec![(0.0, 0.0); optimal.route.len()];
+        for (index, city) in optimal.route.iter().enumerate() {
+            let angle: f64 = 2.0 * PI * index as f64 / optimal.route.len() as f64;
+            if let Some(position) = positions.get_mut(*city as usize) {
+                *position = (angle.cos(), angle.sin());
+            }
+        }
+        let position = |city: u32| positions.get(city as usize).copied().unwrap_or((0.0, 0.0));
+
+        // Draw the optimal edges that were not found
+        for (from, to) in &self.missing_edges {
+            chart.draw_series(LineSeries::new(
+                vec![position(*from), position(*to)],
+                style.grid.stroke_width(style.line_width),
+            ))?;
+        }
+
+        // Draw the found edges, coloured by whether they are in the optimal route
+        let extra: HashSet<&(u32, u32)> = self.extra_edges.iter().collect();
+        for edge in Chromosome::edges(&optimal.route)
+            .iter()
+            .filter(|edge| !self.missing_edges.contains(edge))
+            .chain(self.extra_edges.iter())
+        {
+            let colour = if extra.contains(edge) { style.primary } else { style.tertiary };
+            chart.draw_series(LineSeries::new(
+                vec![position(edge.0), position(edge.1)],
+                colour.stroke_width(style.line_width),
+            ))?;
+        }
+
+        // Draw and label every city
+        chart.draw_series(optimal.route.iter().map(|city| {
+            let (x, y) = position(*city);
+            EmptyElement::at((x, y))
+                + Circle::new((0, 0), 6, style.foreground.filled())
+                + Text::new(
+                    format!("{}", city),
+                    (8, -8),
+                    (style.font, style.label_size).into_font().color(&style.foreground),
+                )
+        }))?;
+
+        root.present()?;
+
+        Ok(PathBuf::from(&name))
+    }
+}
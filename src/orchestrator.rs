@@ -0,0 +1,387 @@
+//! This module defines how the runs of an experiment are shared out between threads: [`queue_runs`] pairs every job
+//! with the data for its country, [`spawn_runs`] starts the threads and returns a [`ResultStream`] of the runs as they
+//! finish, and [`InstanceGroups`] collects the finished runs of each country until every one of them has arrived.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use color_eyre::{eyre::eyre, Result};
+use indicatif::ProgressBar;
+
+use super::{
+    cancellation::CancellationToken,
+    country::Country,
+    interface::RunParameters,
+    manifest::Job,
+    placement::{pin_current_thread, CorePlacement},
+    random::seed_current_thread,
+    runlog::RunLog,
+    simulation::Simulation,
+};
+
+/// A run waiting for a thread to start it
+#[derive(Debug, Clone)]
+pub struct QueuedRun {
+    /// Position of the job of the run in the experiment manifest
+    pub index: usize,
+    /// Which run of the country this is, starting from 1
+    pub run: u32,
+    /// The data for the country of the run, shared between the runs of that country
+    pub country: Arc<Country>,
+    /// The core planned for the run, if threads are pinned
+    pub placement: Option<CorePlacement>,
+    /// Seed for the random number generator of the run, None if the run is not seeded
+    pub seed: Option<u64>,
+}
+
+/// A run that has finished, along with where it ran
+pub struct FinishedRun {
+    /// Position of the job of the run in the experiment manifest
+    pub index: usize,
+    /// The Simulation once it has run
+    pub simulation: Simulation,
+    /// The core the run was pinned to, if it was
+    pub placement: Option<CorePlacement>,
+}
+
+/// Everything needed to build the Simulation of a run besides the data for its country
+#[derive(Debug, Clone, Default)]
+pub struct RunSettings {
+    /// The parameters of every run
+    pub parameters: RunParameters,
+    /// File checked between generations for changes to the parameters, if any
+    pub control_file: Option<PathBuf>,
+    /// Directory each run writes its log to, if any
+    pub log_dir: Option<PathBuf>,
+    /// The generations to take a snapshot of the population at
+    pub snapshot_generations: Vec<u32>,
+    /// Record the parents of every child that joins the population of each run
+    pub genealogy: bool,
+    /// Freeform tags attached to every run, written at the top of each log
+    pub tags: Vec<String>,
+    /// The edges tracked for each country, countries with none are left out
+    pub tracked_edges: Arc<HashMap<String, Vec<(u32, u32)>>>,
+    /// Stops every run when cancelled
+    pub cancellation: CancellationToken,
+}
+
+/// Implement methods on `RunSettings`
+impl RunSettings {
+    /// Function to build the Simulation of the given run of a country, opening its log if runs are logged
+    fn simulation(&self, country_data: Arc<Country>, run: u32) -> Result<Simulation> {
+        let edges: &[(u32, u32)] = self.tracked_edges.get(&country_data.name).map_or(&[], Vec::as_slice);
+        let log: Option<RunLog> = self.log_dir
+            .as_deref()
+            .map(|directory| RunLog::open(directory, &country_data.name, run, &self.tags))
+            .transpose()?;
+        Simulation::new(country_data, self.parameters)?
+            .with_control_file(self.control_file.clone())
+            .with_log(log)
+            .with_snapshots(&self.snapshot_generations)
+            .with_genealogy(self.genealogy)
+            .with_cancellation(self.cancellation.clone())
+            .with_tracked_edges(edges)
+    }
+}
+
+/// Enumerate that represents how runs are shared out between threads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scheduling {
+    /// Every run gets a thread and a progress bar of its own, pinned to the core planned for it
+    PerRun,
+    /// A fixed number of workers take the next run from a shared queue whenever they are free, pinned to the
+    /// given cores in order
+    Batch {
+        /// The number of worker threads, never more than there are runs
+        workers: usize,
+        /// The cores to pin the workers to, empty if they aren't pinned
+        cores: Vec<CorePlacement>,
+    },
+}
+
+/// Function to pair each job of the manifest given by position with the data for its country and the core planned
+/// for it, erroring if a job is for a country there is no data for
+pub fn queue_runs(
+    jobs: &[Job],
+    indices: &[usize],
+    input_data: &[Arc<Country>],
+    placements: Vec<Option<CorePlacement>>,
+) -> Result<VecDeque<QueuedRun>> {
+    let mut queue: VecDeque<QueuedRun> = VecDeque::with_capacity(indices.len());
+    for (index, placement) in indices.iter().zip(placements.into_iter().chain(std::iter::repeat(None))) {
+        let index: usize = *index;
+        let job: &Job = jobs.get(index).ok_or_else(|| eyre!("No job at position {} of the manifest", index))?;
+
+        // Find the data for the country of this job
+        let country = input_data
+            .iter()
+            .find(|country| country.name == job.instance)
+            .ok_or_else(|| eyre!("No data found for {}", job.instance))?;
+
+        queue.push_back(QueuedRun { index, run: job.run, country: Arc::clone(country), placement, seed: job.seed });
+    }
+    Ok(queue)
+}
+
+/// Function to give a run the data for its country. Runs on a pinned thread get a copy of their own, allocated in
+/// memory close to the core they run on, while the rest share the data read-only
+fn local_copy(country: Arc<Country>, placement: Option<CorePlacement>) -> Arc<Country> {
+    match placement {
+        Some(_) => Arc::new((*country).clone()),
+        None => country,
+    }
+}
+
+/// Function to start a thread for every queued run, or a pool of workers taking runs from the queue in batch mode,
+/// returning the stream the finished runs arrive on. Each run started in its own thread reports its progress
+/// on a bar from `new_bar`, while runs taken by workers report none
+pub fn spawn_runs(
+    queue: VecDeque<QueuedRun>,
+    settings: &RunSettings,
+    scheduling: Scheduling,
+    mut new_bar: impl FnMut() -> ProgressBar,
+) -> ResultStream {
+    // Create Multi-producer, single-consumer channel
+    let (tx, rx) = mpsc::channel();
+    let expected: usize = queue.len();
+    let mut threads: Vec<thread::JoinHandle<Result<()>>> = Vec::with_capacity(expected);
+
+    match scheduling {
+        Scheduling::Batch { workers, cores } => {
+            // Share the queue of jobs so each worker takes the next one as soon as it is free
+            let queue = Arc::new(Mutex::new(queue));
+
+            for worker in 0..workers.min(expected) {
+                // Clone transmitter, queue handle and settings so the thread will have a unique one
+                let thread_tx = tx.clone();
+                let thread_queue = Arc::clone(&queue);
+                let thread_settings: RunSettings = settings.clone();
+
+                // Workers take jobs of any country, so are pinned to the cores in order, filling each socket in turn
+                let placement: Option<CorePlacement> = cores.get(worker % cores.len().max(1)).copied();
+
+                // Generate a Thread to run simulations until the queue is empty
+                let thread = thread::spawn(move || -> Result<()> {
+                    // Pin the worker to its core before it copies any country data
+                    let placement: Option<CorePlacement> = pin_current_thread(placement);
+
+                    // Stop taking jobs once the experiment is cancelled
+                    while !thread_settings.cancellation.is_cancelled() {
+                        // Take the next job, releasing the queue straight away so other workers aren't held up
+                        let next: Option<QueuedRun> = thread_queue
+                            .lock()
+                            .map_err(|_| eyre!("Job queue was poisoned by a panicked worker"))?
+                            .pop_front();
+                        let Some(QueuedRun { index, run, country, seed, .. }) = next else {
+                            break;
+                        };
+
+                        // Create and run the Simulation without a progress bar of its own, seeding the worker
+                        // first so the whole run, including its first population, can be repeated
+                        seed_current_thread(seed);
+                        let country_data: Arc<Country> = local_copy(country, placement);
+                        let mut simulation: Simulation = thread_settings.simulation(country_data, run)?;
+                        simulation.run(ProgressBar::hidden())?;
+
+                        // Transmit the simulation back along with its job and where it ran
+                        thread_tx.send(FinishedRun { index, simulation, placement })?;
+                    }
+
+                    // Exit thread
+                    Ok(())
+                });
+
+                // Push the Thread Handler to the threads vector
+                threads.push(thread)
+            }
+        },
+        Scheduling::PerRun => {
+            // Loop over every queued run
+            for QueuedRun { index, run, country, placement, seed } in queue {
+                // Clone transmitter and settings so the thread will have a unique one
+                let thread_tx = tx.clone();
+                let thread_settings: RunSettings = settings.clone();
+                let progress_bar: ProgressBar = new_bar();
+
+                // Generate a Thread to build and run the simulation
+                let thread = thread::spawn(move || -> Result<()> {
+                    // Pin the thread to its core, then copy the country data if it was pinned so the copy is
+                    // allocated in memory close to that core
+                    let placement: Option<CorePlacement> = pin_current_thread(placement);
+                    let country_data: Arc<Country> = local_copy(country, placement);
+
+                    // Seed the thread, then create and run the Simulation
+                    seed_current_thread(seed);
+                    let mut simulation: Simulation = thread_settings.simulation(country_data, run)?;
+                    simulation.run(progress_bar)?;
+
+                    // Transmit the simulation back along with its job and where it ran
+                    thread_tx.send(FinishedRun { index, simulation, placement })?;
+
+                    // Exit thread
+                    Ok(())
+                });
+
+                // Push the Thread Handler to the threads vector
+                threads.push(thread)
+            }
+        },
+    }
+
+    // The original transmitter is dropped here, so the channel closes once every thread stops
+    ResultStream { receiver: rx, threads, expected, received: 0, cancelled: 0 }
+}
+
+/// The runs of an experiment as they finish, along with the threads running them
+pub struct ResultStream {
+    /// Receives each run as its thread finishes it
+    receiver: mpsc::Receiver<FinishedRun>,
+    /// The handle of every thread started
+    threads: Vec<thread::JoinHandle<Result<()>>>,
+    /// The number of runs queued
+    expected: usize,
+    /// The number of runs that have finished so far
+    received: usize,
+    /// The number of runs stopped part way through by their cancellation token so far
+    cancelled: usize,
+}
+
+/// Implement methods on `ResultStream`
+impl ResultStream {
+    /// Function to count the runs queued
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+
+    /// Function to count the runs that have finished so far
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// Function to count the runs stopped part way through by their cancellation token so far
+    pub fn cancelled(&self) -> usize {
+        self.cancelled
+    }
+
+    /// Function to check if every queued run has arrived, whether it finished or was cancelled
+    pub fn is_complete(&self) -> bool {
+        self.received + self.cancelled >= self.expected
+    }
+
+    /// Function to wait up to the given time for the next run to arrive. A run stopped part way through by its
+    /// cancellation token still arrives, with [`Simulation::cancelled`] set and the stats of every generation up
+    /// to the stop, but isn't counted as finished. Errors with [`mpsc::RecvTimeoutError::Disconnected`] once every
+    /// thread has stopped
+    pub fn recv_timeout(&mut self, timeout: Duration) -> std::result::Result<FinishedRun, mpsc::RecvTimeoutError> {
+        let finished: FinishedRun = self.receiver.recv_timeout(timeout)?;
+        if finished.simulation.cancelled {
+            self.cancelled += 1;
+        } else {
+            self.received += 1;
+        }
+        Ok(finished)
+    }
+
+    /// Function to wait for every thread to stop, returning the error of any that failed, or an error if fewer
+    /// runs finished than were queued
+    pub fn finish(self) -> Result<()> {
+        // Loop through the vector of thread handlers and close each thread
+        for thread in self.threads {
+            thread.join().map_err(|_| eyre!("A thread running simulations panicked"))??;
+        }
+        if self.received < self.expected {
+            return Err(eyre!("Only {} of {} runs finished", self.received, self.expected));
+        }
+        Ok(())
+    }
+
+    /// Function to wait for every queued run to finish, returning them in the order they finished. Runs that were
+    /// cancelled are left out, so are reported as missing
+    pub fn collect(mut self) -> Result<Vec<FinishedRun>> {
+        let mut finished: Vec<FinishedRun> = Vec::with_capacity(self.expected);
+        while !self.is_complete() {
+            match self.recv_timeout(Duration::from_millis(100)) {
+                Ok(run) if run.simulation.cancelled => continue,
+                Ok(run) => finished.push(run),
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                // Every thread has stopped, so the error of the one that failed is found when they are closed
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+        self.finish()?;
+        Ok(finished)
+    }
+}
+
+/// The finished runs of each country, held until every run of the country has arrived
+#[derive(Debug, Clone)]
+pub struct InstanceGroups<T> {
+    /// The number of runs of each country still to arrive
+    remaining: HashMap<String, usize>,
+    /// The runs of each country that have arrived so far
+    runs: HashMap<String, Vec<T>>,
+}
+
+/// Implement methods on `InstanceGroups`
+impl<T> InstanceGroups<T> {
+    /// Function to expect a run for each country given, countries given more than once expect that many runs
+    pub fn new<'a>(instances: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut remaining: HashMap<String, usize> = HashMap::new();
+        for instance in instances {
+            *remaining.entry(instance.to_string()).or_default() += 1;
+        }
+        Self { remaining, runs: HashMap::new() }
+    }
+
+    /// Function to check if any runs of the country are expected, including those that have already arrived
+    pub fn contains(&self, instance: &str) -> bool {
+        self.remaining.contains_key(instance)
+    }
+
+    /// Function to count the runs of the country still to arrive
+    pub fn remaining(&self, instance: &str) -> usize {
+        self.remaining.get(instance).copied().unwrap_or(0)
+    }
+
+    /// Function to add a run of the country, returning every run of it in the order they arrived once the last
+    /// one has, erroring if the run wasn't expected
+    pub fn push(&mut self, instance: &str, run: T) -> Result<Option<Vec<T>>> {
+        let remaining = self.remaining
+            .get_mut(instance)
+            .filter(|remaining| **remaining > 0)
+            .ok_or_else(|| eyre!("More runs of {} finished than were queued", instance))?;
+        *remaining -= 1;
+        let runs: &mut Vec<T> = self.runs.entry(instance.to_string()).or_default();
+        runs.push(run);
+
+        if *remaining == 0 {
+            return Ok(self.runs.remove(instance));
+        }
+        Ok(None)
+    }
+
+    /// Function to take every run that has arrived for countries still waiting on others, in country name order
+    /// then the order they arrived, such as when the experiment is stopped before they can all arrive
+    pub fn take_arrived(&mut self) -> Vec<T> {
+        let mut instances: Vec<String> = self.runs.keys().cloned().collect();
+        instances.sort();
+        instances
+            .into_iter()
+            .flat_map(|instance| self.runs.remove(&instance).unwrap_or_default())
+            .collect()
+    }
+}
+
+/// Function to group results by their country, keeping the results of each country in the order given
+pub fn group_by_instance<T>(results: impl IntoIterator<Item = (String, T)>) -> HashMap<String, Vec<T>> {
+    let mut grouped: HashMap<String, Vec<T>> = HashMap::new();
+    for (instance, result) in results {
+        grouped.entry(instance).or_default().push(result);
+    }
+    grouped
+}
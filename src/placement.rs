@@ -0,0 +1,82 @@
+//! This module defines [`CorePlacement`], which records the CPU core and socket a run was pinned to, and
+//! [`plan_placements`], which keeps every run of the same country on the same socket so the copies
+//! of its distance matrix stay in memory close to the cores using them.
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+/// The CPU core a run was pinned to, along with the socket (physical package) the core is on
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CorePlacement {
+    /// Socket the core is on, 0 if it couldn't be found
+    pub socket: usize,
+    /// Id of the core, as used by the operating system
+    pub core: usize,
+}
+
+/// Function to list every core this program can run on along with its socket, ordered by socket then core.
+/// Returns an empty list if the cores can't be found on this platform
+pub fn available_cores() -> Vec<CorePlacement> {
+    let mut cores: Vec<CorePlacement> = core_affinity::get_core_ids()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|core_id| CorePlacement { socket: socket_of(core_id.id), core: core_id.id })
+        .collect();
+    cores.sort();
+    cores
+}
+
+/// Function to find the socket of a core. Only Linux reports this, so every core is on socket 0 elsewhere
+fn socket_of(core: usize) -> usize {
+    fs::read_to_string(format!("/sys/devices/system/cpu/cpu{}/topology/physical_package_id", core))
+        .ok()
+        .and_then(|id| id.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Function to pick a core for each run, given the country of each run in order. Countries are shared
+/// out between the sockets in turn and each run of a country goes on the next core of its socket,
+/// so runs of the same country share a socket. Returns None for every run if there are no cores
+pub fn plan_placements(instances: &[&str], cores: &[CorePlacement]) -> Vec<Option<CorePlacement>> {
+    // Group the cores by socket, they are already ordered by socket
+    let mut sockets: Vec<Vec<CorePlacement>> = Vec::new();
+    for core in cores {
+        match sockets.last_mut() {
+            Some(socket) if socket[0].socket == core.socket => socket.push(*core),
+            _ => sockets.push(vec![*core]),
+        }
+    }
+    if sockets.is_empty() {
+        return vec![None; instances.len()];
+    }
+
+    // Countries in the order they are first seen, and how many runs each socket has been given
+    let mut countries: Vec<&str> = Vec::new();
+    let mut used: Vec<usize> = vec![0; sockets.len()];
+
+    instances
+        .iter()
+        .map(|instance| {
+            // Give each new country the next socket in turn
+            let country: usize = match countries.iter().position(|country| country == instance) {
+                Some(country) => country,
+                None => {
+                    countries.push(instance);
+                    countries.len() - 1
+                }
+            };
+            let socket: usize = country % sockets.len();
+
+            // Use the next core of the socket, going back round once every core has a run
+            let core: CorePlacement = sockets[socket][used[socket] % sockets[socket].len()];
+            used[socket] += 1;
+            Some(core)
+        })
+        .collect()
+}
+
+/// Function to pin the current thread to a core, returning the placement if it worked
+pub fn pin_current_thread(placement: Option<CorePlacement>) -> Option<CorePlacement> {
+    placement.filter(|placement| core_affinity::set_for_current(core_affinity::CoreId { id: placement.core }))
+}
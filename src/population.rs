@@ -2,16 +2,26 @@
 
 
 use super::{
+        adaptive::AdaptiveOperators,
+        cancellation::CancellationToken,
         chromosome::Chromosome, 
         country::Graph, 
-        interface::{
-            MutationOperator, 
-            CrossoverOperator
-        }
+        genealogy::Genealogy,
+        interface::{CrossoverOperator, DuplicatePolicy, ReplacementStrategy, RunParameters, SelectionOperator, TiePolicy},
+        random::rng,
+        repair::{NoRepair, Repair},
+        solver::Solver,
     };
     
-use rand::{thread_rng, seq::SliceRandom};
-use color_eyre::{eyre::ContextCompat, Result};
+use rand::{Rng, distributions::{Distribution, WeightedIndex}, seq::{index, SliceRandom}};
+use std::{cmp::Ordering, collections::{HashMap, HashSet}, sync::Arc};
+use color_eyre::{eyre::{eyre, ContextCompat}, Result};
+
+/// The number of times a duplicate child is mutated looking for a new tour before it is rejected
+pub const PERTURB_ATTEMPTS: usize = 10;
+
+/// The number of times the second parent is selected again when the parents are closer than the minimum parent distance
+pub const PARENT_RESELECTIONS: usize = 10;
 
 /// The Struct defines the population
 #[derive(Clone)]
@@ -26,6 +36,35 @@ pub struct Population {
     pub best_chromosome: Chromosome,
     /// The worst Chromosome in this population
     pub worst_chromosome: Chromosome,
+    /// The id that will be given to the next Chromosome to join the population
+    pub next_id: u64,
+    /// The number of fitness evaluations used to create every Chromosome that has been in the population
+    pub evaluations: u64,
+    /// The most fitness evaluations the population may use, no more children are made once they have been used
+    pub max_evaluations: Option<u64>,
+    /// The repair applied to every child before it can join the population
+    pub repair: Arc<dyn Repair>,
+    /// The number of the cheapest Chromosomes that are always kept, whatever children are made
+    pub elitism: usize,
+    /// The ids of the Chromosomes kept by elitism, which replacement can't overwrite
+    pub elite: Vec<u64>,
+    /// The relative difference below which a child and the Chromosome it could replace count as costing the same
+    pub cost_epsilon: f64,
+    /// Stops the local search of children early once cancelled
+    pub cancellation: CancellationToken,
+    /// The number of pairs of parents bred so far
+    pub bred_pairs: u64,
+    /// The number of pairs of parents that were the same Chromosome twice
+    pub duplicate_parents: u64,
+    /// The indices of the Chromosomes picked as parents this generation, which with distinct parents can't be picked
+    /// again until the next generation. A child that replaces a parent takes its index, so it waits too
+    pub generation_parents: Vec<usize>,
+    /// The number of children thrown away because their tour was already in the population
+    pub rejected_children: u64,
+    /// Picks the crossover and mutation operator of each child by how well each has done, if operators are adaptive
+    pub adaptive_operators: Option<AdaptiveOperators>,
+    /// The parents of every Chromosome that has joined the population, if the genealogy is recorded
+    pub genealogy: Option<Genealogy>,
 }
 
 /// Implements methods on `Population`
@@ -41,18 +80,20 @@ impl Population {
         // Loop whilst counter is less than population size
         while i < population_size {
 
-            // Add a new chromosome to vector "population"
-            population_data.push(Chromosome::generation(country_data)?);
+            // Add a new chromosome to vector "population", with ids starting from 1
+            let mut chromosome: Chromosome = Chromosome::generation(country_data)?;
+            chromosome.id = i + 1;
+            population_data.push(chromosome);
 
             // Increment counter
             i += 1;
         }
 
         // Find best Chromosome in population
-        let best_chromosome: Chromosome = Population::find_best_chromosome(&population_data)?;
+        let best_chromosome: Chromosome = Population::find_best_chromosome(&population_data, 0.0)?;
 
         // Find worst Chromosome in the population
-        let worst_chromosome: Chromosome = Population::find_worst_chromosome(&population_data)?;
+        let worst_chromosome: Chromosome = Population::find_worst_chromosome(&population_data, 0.0)?;
 
         // Find average cost of new Population
         let average_population_cost: f64 = Population::find_average_cost(&population_data);
@@ -64,9 +105,132 @@ impl Population {
             average_population_cost,
             best_chromosome,
             worst_chromosome,
+            next_id: population_size + 1,
+            // Generating each Chromosome calculates its cost once
+            evaluations: population_size,
+            max_evaluations: None,
+            repair: Arc::new(NoRepair),
+            elitism: 0,
+            elite: Vec::new(),
+            cost_epsilon: 0.0,
+            cancellation: CancellationToken::new(),
+            bred_pairs: 0,
+            duplicate_parents: 0,
+            generation_parents: Vec::new(),
+            rejected_children: 0,
+            adaptive_operators: None,
+            genealogy: None,
         })
     }
 
+    /// A Function to always keep the given number of the cheapest Chromosomes in the population. At least one
+    /// Chromosome is always left out of the elite so children can still join the population
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self.update_elite();
+        self
+    }
+
+    /// A Function to pick the crossover and mutation operator of each child by adaptive pursuit, rather than always
+    /// using the operators of the parameters
+    pub fn with_adaptive_operators(mut self, adaptive: bool) -> Self {
+        self.adaptive_operators = adaptive.then(AdaptiveOperators::new);
+        self
+    }
+
+    /// A Function to set the repair applied to every child, repairing the Chromosomes already in the population too
+    /// so the algorithm starts from repaired routes. Each repaired Chromosome has its cost recalculated
+    pub fn with_repair(mut self, repair: Arc<dyn Repair>, country_data: &Graph) -> Result<Self> {
+        for chromosome in self.population_data.iter_mut() {
+            if repair.repair(chromosome, country_data) {
+                chromosome.cost = Chromosome::fitness(&chromosome.route, country_data)?;
+                self.evaluations += 1;
+            }
+        }
+        self.repair = repair;
+        self.update_statistics()?;
+        Ok(self)
+    }
+
+    /// A Function to record the parents of every child that joins the population from now on, starting from the
+    /// Chromosomes already in it
+    pub fn with_genealogy(mut self, genealogy: bool) -> Self {
+        self.genealogy = genealogy.then(|| Genealogy::new(&self.population_data));
+        self
+    }
+
+    /// A Function to replace the given fraction of the population with tours built by nearest neighbour construction,
+    /// each from a different randomly picked start city, so the algorithm starts from reasonable tours rather than
+    /// random ones. There is only one nearest neighbour tour from each city, so at most one tour per city is added
+    pub fn with_nearest_neighbour_tours(mut self, fraction: f64, country_data: &Graph) -> Result<Self> {
+        let cities: usize = country_data.distances.size;
+        let count: usize = ((self.population_data.len() as f64 * fraction) as usize).min(cities);
+        if count == 0 {
+            return Ok(self);
+        }
+
+        // Each seeded tour keeps the id of the random tour it replaces, and costs an evaluation to build
+        for (position, start) in index::sample(&mut rng(), cities, count).into_iter().enumerate() {
+            let mut tour: Chromosome = Chromosome::nearest_neighbour(start as u32, country_data)?;
+            tour.id = self.population_data[position].id;
+            self.population_data[position] = tour;
+            self.evaluations += 1;
+        }
+
+        self.update_statistics()?;
+        Ok(self)
+    }
+
+    /// A Function to treat costs within the given relative difference of each other as equal when deciding if a
+    /// child replaces a Chromosome and when finding the best and worst Chromosome, see [`Chromosome::compare_costs`]
+    pub fn with_cost_epsilon(mut self, cost_epsilon: f64) -> Self {
+        self.cost_epsilon = cost_epsilon;
+        self
+    }
+
+    /// A Function to stop making children once the given number of fitness evaluations have been used. Each step
+    /// of making a child is only taken if the evaluations left pay for it, so the budget is never overspent
+    pub fn with_max_evaluations(mut self, max_evaluations: Option<u64>) -> Self {
+        self.max_evaluations = max_evaluations;
+        self
+    }
+
+    /// A Function to find how many fitness evaluations are left of the budget, as many as can be counted without one
+    fn evaluations_left(&self) -> u64 {
+        self.max_evaluations.map_or(u64::MAX, |budget| budget.saturating_sub(self.evaluations))
+    }
+
+    /// A Function to compare the cost of a child with the cost of a Chromosome it could replace
+    fn compare_costs(&self, child: &Chromosome, current: &Chromosome) -> Ordering {
+        Chromosome::compare_costs(child.cost, current.cost, self.cost_epsilon)
+    }
+
+    /// A Function to order Chromosomes from cheapest to most expensive, with the oldest first when they cost the same
+    fn cheapest_first(x: &Chromosome, y: &Chromosome) -> Ordering {
+        x.cost.total_cmp(&y.cost).then(x.id.cmp(&y.id))
+    }
+
+    /// A Function to pick the Chromosomes kept by elitism, the cheapest in the population
+    pub fn update_elite(&mut self) {
+        let size: usize = self.elitism.min(self.population_data.len().saturating_sub(1));
+        if size == 0 {
+            self.elite.clear();
+            return;
+        }
+
+        // Only the cheapest few need to be in order, so partition around the last of them rather than sort them all
+        let mut ranked: Vec<&Chromosome> = self.population_data.iter().collect();
+        ranked.select_nth_unstable_by(size - 1, |x, y| Population::cheapest_first(x, y));
+        ranked[..size].sort_unstable_by(|x, y| Population::cheapest_first(x, y));
+        self.elite = ranked[..size].iter().map(|chromosome| chromosome.id).collect();
+    }
+
+    /// A Function to give a [`Chromosome`] the next id of the population as it joins it
+    pub fn assign_id(&mut self, chromosome: &mut Chromosome) {
+        chromosome.id = self.next_id;
+        self.next_id += 1;
+    }
+
     /// A Function to find and return the average cost of a population given a vector of that populations chromosomes
     pub fn find_average_cost(population_data: &[Chromosome]) -> f64 {
         // Create mutable variable
@@ -79,38 +243,41 @@ impl Population {
         average_cost
     }
 
-    /// A function to find the worst Chromosome in the population
-    pub fn find_worst_chromosome(population_data: &[Chromosome]) -> Result<Chromosome> {
+    /// A function to find the worst Chromosome in the population, treating costs within epsilon of each other as
+    /// equal, see [`Chromosome::compare_costs`]
+    pub fn find_worst_chromosome(population_data: &[Chromosome], cost_epsilon: f64) -> Result<Chromosome> {
         let worst = population_data
             .iter()
-            .max_by(|x, y| x.partial_cmp(y).unwrap())
+            .max_by(|x, y| Chromosome::compare_costs(x.cost, y.cost, cost_epsilon))
             .wrap_err("Can't find best Chromosome in")?;
         Ok(worst.to_owned())
     }
 
-    /// A function to find the best Chromosome in the population
-    pub fn find_best_chromosome(population_data: &[Chromosome]) -> Result<Chromosome> {
+    /// A function to find the best Chromosome in the population, treating costs within epsilon of each other as
+    /// equal, see [`Chromosome::compare_costs`]
+    pub fn find_best_chromosome(population_data: &[Chromosome], cost_epsilon: f64) -> Result<Chromosome> {
         let best = population_data
             .iter()
-            .min_by(|x, y| x.partial_cmp(y).unwrap())
+            .min_by(|x, y| Chromosome::compare_costs(x.cost, y.cost, cost_epsilon))
             .wrap_err("Can't find best Chromosome in")?;
         Ok(best.to_owned())
     }
 
-    /// A Function to implement the Replace Weakest algorithm
+    /// A Function to implement the Replace Weakest algorithm, never replacing a Chromosome kept by elitism
     pub fn replacement(&mut self, child: Chromosome) -> Option<()> {
-        // Iterate over the population_data and find the index of the most expensive chromosome
+        // Iterate over the population_data and find the index of the most expensive chromosome outside the elite
         let worst_chromosome: (usize, Chromosome) = self.population_data
             .iter()
             .enumerate()
+            .filter(|(_, x)| !self.elite.contains(&x.id))
             // find most expensive chromosome
-            .max_by(|(_,x), (_,y)| x.partial_cmp(y).unwrap())
+            .max_by(|(_,x), (_,y)| Chromosome::compare_costs(x.cost, y.cost, self.cost_epsilon))
             // strip chromosome from iter, leaving only index
             .map(|(i, x)| (i, x.to_owned()))?;
 
         
         // Check that the cost of the worse chromosome is actually greater than the cost of the child
-        if worst_chromosome.1.cost >= child.cost {
+        if self.compare_costs(&child, &worst_chromosome.1) != Ordering::Greater {
 
             // Replace the worst chromosome with the child
             let _ = std::mem::replace( &mut self.population_data[worst_chromosome.0], child);
@@ -118,63 +285,838 @@ impl Population {
         Some(())
     }
 
+    /// A Function to replace the parents at the given indices with their children. Both slots are chosen from the
+    /// parents before anything is written, so the cheapest child competes against the most expensive parent and the
+    /// other child against the other parent, and a child never takes the place of its sibling. A child only replaces
+    /// its parent if it is at least as cheap. Parents kept by elitism are never replaced
+    pub fn replace_parents(&mut self, mut children: Vec<Chromosome>, parents: [usize; 2]) {
+        // Find the parents outside the elite, most expensive first. A parent picked twice only has one slot
+        let mut slots: Vec<usize> = parents
+            .iter()
+            .copied()
+            .filter(|index| !self.elite.contains(&self.population_data[*index].id))
+            .collect();
+        slots.dedup();
+        slots.sort_by(|x, y| self.population_data[*y].cost.total_cmp(&self.population_data[*x].cost));
+
+        // Pair the cheapest child with the most expensive parent, a child left without a slot is thrown away
+        children.sort_by(|x, y| x.cost.total_cmp(&y.cost));
+        for (child, slot) in children.into_iter().zip(slots) {
+            // Replace the parent if the child is no more expensive
+            if self.compare_costs(&child, &self.population_data[slot]) != Ordering::Greater {
+                self.population_data[slot] = child;
+            }
+        }
+    }
+
+    /// A Function to implement deterministic crowding. Each child is paired with one of the parents at the given 
+    /// indices, so that the children and their parents share as many edges as possible, and replaces that parent 
+    /// only if the child is cheaper. Children only replace routes like their own, so different routes survive for longer.
+    /// Parents kept by elitism are never replaced
+    pub fn crowding_replacement(&mut self, children: Vec<Chromosome>, parents: [usize; 2]) {
+        let [first_parent, second_parent] = parents;
+        let similarity = |child: &Chromosome, parent: usize| child.shared_edges(&self.population_data[parent]);
+
+        let pairs: Vec<(Chromosome, usize)> = match <[Chromosome; 2]>::try_from(children) {
+            // Pair each child with a parent, keeping the pairing that shares the most edges in total
+            Ok([first_child, second_child]) => {
+                let kept: usize = similarity(&first_child, first_parent) + similarity(&second_child, second_parent);
+                let swapped: usize = similarity(&first_child, second_parent) + similarity(&second_child, first_parent);
+                if kept >= swapped {
+                    vec![(first_child, first_parent), (second_child, second_parent)]
+                } else {
+                    vec![(first_child, second_parent), (second_child, first_parent)]
+                }
+            },
+            // A lone child is paired with the parent it shares the most edges with
+            Err(children) => children
+                .into_iter()
+                .map(|child| {
+                    let parent: usize = if similarity(&child, first_parent) >= similarity(&child, second_parent) {
+                        first_parent
+                    } else {
+                        second_parent
+                    };
+                    (child, parent)
+                })
+                .collect(),
+        };
+
+        // Replace each parent with its child if the child is cheaper
+        for (child, parent) in pairs {
+            let current: &Chromosome = &self.population_data[parent];
+            if self.compare_costs(&child, current) == Ordering::Less && !self.elite.contains(&current.id) {
+                self.population_data[parent] = child;
+            }
+        }
+    }
+
+    /// A Function to implement restricted tournament replacement. The child is compared against a window of
+    /// randomly picked Chromosomes, and replaces the one sharing the most edges with it if the child is cheaper.
+    /// Children only replace routes like their own, so the population keeps niches of different routes.
+    /// Chromosomes kept by elitism are never replaced, and the window is never bigger than the population
+    pub fn restricted_replacement(&mut self, child: Chromosome, window_size: usize) -> Option<()> {
+        let window: Vec<usize> = index::sample(
+            &mut rng(),
+            self.population_data.len(), 
+            window_size.min(self.population_data.len())
+        ).into_vec();
+
+        // Find the Chromosome in the window most like the child, the first picked if several tie
+        let nearest: usize = window
+            .into_iter()
+            .rev()
+            .max_by_key(|index| child.shared_edges(&self.population_data[*index]))?;
+
+        // Replace it if the child is cheaper
+        let current: &Chromosome = &self.population_data[nearest];
+        if self.compare_costs(&child, current) == Ordering::Less && !self.elite.contains(&current.id) {
+            self.population_data[nearest] = child;
+        }
+        Some(())
+    }
+
+    /// A Function to deal with children whose tour is already in the population, or is the same as the other 
+    /// child's, following the duplicate policy. Tours are compared by the hash of their normal form. A rejected 
+    /// child is left out of the children returned, and a child that is perturbed is mutated with the mutation 
+    /// operator until its tour is new, up to [`PERTURB_ATTEMPTS`] times before it is rejected
+    pub fn unique_children(
+        &mut self,
+        children: [Chromosome; 2],
+        parameters: &RunParameters,
+        country_data: &Graph
+    ) -> Result<Vec<Chromosome>> {
+        if parameters.duplicate_policy == DuplicatePolicy::Allow {
+            return Ok(children.into());
+        }
+
+        let mut tours: HashSet<u64> = self.population_data.iter().map(Chromosome::tour_hash).collect();
+        let mut unique: Vec<Chromosome> = Vec::with_capacity(2);
+        for mut child in children {
+            if parameters.duplicate_policy == DuplicatePolicy::Perturb {
+                for _ in 0..PERTURB_ATTEMPTS {
+                    // A duplicate the budget can't pay to perturb is rejected
+                    let affordable: bool = self.evaluations_left() >= parameters.mutation_operator.evaluations();
+                    if !tours.contains(&child.tour_hash()) || !affordable {
+                        break;
+                    }
+                    child.mutation(parameters.mutation_operator, country_data)?;
+                    self.evaluations += parameters.mutation_operator.evaluations();
+                }
+            }
+
+            // Keep the child only if its tour is new
+            if tours.insert(child.tour_hash()) {
+                unique.push(child);
+            } else {
+                self.rejected_children += 1;
+            }
+        }
+        Ok(unique)
+    }
+
     /// This function takes a tournament size, randomly picks that many chromosomes from 
     /// the population and returns the best ones
     pub fn run_tournament(&self, tournament_size: u32) -> Chromosome {
-        // Create a Tournament population by randomly selecting "Tournament_size" number of chromosomes from the population
-        let mut tournament_population: Vec<Chromosome> = self.population_data
-            .choose_multiple(&mut thread_rng(), tournament_size as usize)
-            .cloned()
+        self.run_tournament_excluding(tournament_size, &[], TiePolicy::First, 1.0).1
+    }
+
+    /// This function runs a tournament only between the Chromosomes whose indices are not in excluded,
+    /// returning the index of the winner along with a copy of it. If every Chromosome is excluded
+    /// the tournament is run on the whole population. When several Chromosomes share the cheapest
+    /// cost, within the cost epsilon, the winner is chosen using the tie_policy. With a winner probability below 1 the tournament is
+    /// soft: the cheapest wins with that probability, otherwise the next cheapest wins with it, and so on down
+    /// to the most expensive, which wins whenever every other Chromosome didn't
+    pub fn run_tournament_excluding(
+        &self, 
+        tournament_size: u32, 
+        excluded: &[usize], 
+        tie_policy: TiePolicy,
+        winner_probability: f64
+    ) -> (usize, Chromosome) {
+        // Create a list of the indices of every Chromosome that can be selected
+        let mut candidates: Vec<usize> = (0..self.population_data.len())
+            .filter(|index| !excluded.contains(index))
+            .collect();
+        if candidates.is_empty() {
+            candidates = (0..self.population_data.len()).collect();
+        }
+
+        // Create a Tournament population by randomly selecting "Tournament_size" number of candidates
+        let tournament_population: Vec<usize> = candidates
+            .choose_multiple(&mut rng(), tournament_size as usize)
+            .copied()
+            .collect();
+
+        // Find the cheapest Chromosome in the tournament
+        let cheapest: usize = *tournament_population
+            .iter()
+            .min_by(|x, y| self.population_data[**x].cost.total_cmp(&self.population_data[**y].cost))
+            .unwrap_or(&candidates[0]);
+
+        // Collect every Chromosome in the tournament that ties with the cheapest within the cost epsilon, in the
+        // order they were picked
+        let tied: Vec<usize> = tournament_population
+            .iter()
+            .copied()
+            .filter(|index| {
+                self.compare_costs(&self.population_data[*index], &self.population_data[cheapest]) == Ordering::Equal
+            })
+            .collect();
+
+        let mut winner: usize = self.break_tie(&tied, tie_policy).unwrap_or(cheapest);
+
+        // In a soft tournament rank the rest of the tournament from cheapest to most expensive behind the winner
+        // of the tie, then pass the win down the ranking until a Chromosome takes it
+        if winner_probability < 1.0 && tournament_population.len() > 1 {
+            let mut ranked: Vec<usize> = tournament_population;
+            ranked.retain(|index| *index != winner);
+            ranked.sort_by(|x, y| self.population_data[*x].cost.total_cmp(&self.population_data[*y].cost));
+            ranked.insert(0, winner);
+
+            let mut generator = rng();
+            let position: usize = (0..ranked.len() - 1)
+                .find(|_| generator.gen_bool(winner_probability))
+                .unwrap_or(ranked.len() - 1);
+            winner = ranked[position];
+        }
+
+        (winner, self.population_data[winner].clone())
+    }
+
+    /// This function lays out a roulette wheel between the Chromosomes whose indices are not in excluded, returning
+    /// the index of each Chromosome on the wheel along with its weight. Each weight is the inverse of its cost so cheaper
+    /// routes are more likely to be picked. If every Chromosome is excluded the wheel has the whole population on it.
+    /// Chromosomes that cost nothing can't be weighted, so if there are any they are the only ones on the wheel
+    fn wheel(&self, excluded: &[usize]) -> (Vec<usize>, Vec<f64>) {
+        // Create a list of the indices of every Chromosome that can be selected
+        let mut candidates: Vec<usize> = (0..self.population_data.len())
+            .filter(|index| !excluded.contains(index))
+            .collect();
+        if candidates.is_empty() {
+            candidates = (0..self.population_data.len()).collect();
+        }
+
+        // Any free route is better than every other route, so they share the wheel equally
+        let free: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|index| self.population_data[*index].cost <= 0.0)
             .collect();
+        if !free.is_empty() {
+            let weights: Vec<f64> = vec![1.0; free.len()];
+            return (free, weights);
+        }
+
+        let weights: Vec<f64> = candidates
+            .iter()
+            .map(|index| 1.0 / self.population_data[*index].cost)
+            .collect();
+        (candidates, weights)
+    }
+
+    /// This function spins a roulette wheel between the Chromosomes whose indices are not in excluded, where each
+    /// Chromosome's chance of being picked is proportional to the inverse of its cost.
+    /// Returns the index of the winner along with a copy of it
+    pub fn run_roulette_excluding(&self, excluded: &[usize]) -> (usize, Chromosome) {
+        let (candidates, weights) = self.wheel(excluded);
+
+        // Pick uniformly if the weights can't be used, e.g. if a cost was infinite
+        let winner: usize = match WeightedIndex::new(&weights) {
+            Ok(weights) => candidates[weights.sample(&mut rng())],
+            Err(_) => *candidates.choose(&mut rng()).unwrap_or(&0),
+        };
+
+        (winner, self.population_data[winner].clone())
+    }
+
+    /// This function picks count Chromosomes with stochastic universal sampling. The roulette wheel is spun once
+    /// with count evenly spaced pointers, so each Chromosome is picked close to as often as its weight says it should be,
+    /// with less variance than spinning the wheel count times. Returns the index of each Chromosome picked along with
+    /// a copy of it, in a random order so no pointer always goes to the same parent
+    pub fn run_universal_sampling(&self, count: usize) -> Vec<(usize, Chromosome)> {
+        let (candidates, weights) = self.wheel(&[]);
+        let total: f64 = weights.iter().sum();
+        if count == 0 || !total.is_finite() || total <= 0.0 {
+            return (0..count).map(|_| self.run_roulette_excluding(&[])).collect();
+        }
 
-        // Sort our tournament_population (using the custom implementation of PartialOrd) by cost - this results in lowest cost first
-        tournament_population.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        // Spin once to place the first pointer, the others follow it at even spaces around the wheel
+        let spacing: f64 = total / count as f64;
+        let start: f64 = rng().gen_range(0.0..spacing);
 
-        // Remove and return the first index (and therefore cheapest chromosome) from the tournament population
-        tournament_population.remove(0)
+        // Walk around the wheel once, picking the Chromosome each pointer lands on
+        let mut picked: Vec<(usize, Chromosome)> = Vec::with_capacity(count);
+        let mut position: usize = 0;
+        let mut cumulative: f64 = weights[0];
+        for pointer in (0..count).map(|step| start + step as f64 * spacing) {
+            // Rounding can leave the last pointer just past the end of the wheel, so stop at the last Chromosome
+            while cumulative < pointer && position + 1 < candidates.len() {
+                position += 1;
+                cumulative += weights[position];
+            }
+            picked.push((candidates[position], self.population_data[candidates[position]].clone()));
+        }
+
+        picked.shuffle(&mut rng());
+        picked
+    }
+
+    /// This function selects a parent from the Chromosomes whose indices are not in excluded, using the selection operator.
+    /// Picking a single parent with stochastic universal sampling is the same as spinning the roulette wheel once
+    pub fn select_parent(&self, parameters: &RunParameters, excluded: &[usize]) -> (usize, Chromosome) {
+        match parameters.selection_operator {
+            SelectionOperator::Tournament => self.run_tournament_excluding(
+                parameters.tournament_size, 
+                excluded, 
+                parameters.tie_policy,
+                parameters.tournament_p
+            ),
+            SelectionOperator::Roulette | SelectionOperator::Sus => self.run_roulette_excluding(excluded),
+        }
+    }
+
+    /// This function selects both parents for crossover using the selection operator, returning the index of each
+    /// parent along with a copy of it. While the parents differ by fewer edges than the minimum parent distance,
+    /// the second selection is rerun up to [`PARENT_RESELECTIONS`] times, keeping the most different second parent found.
+    /// With distinct parents, no Chromosome that has already been a parent this generation is selected
+    pub fn select_parents(&self, parameters: &RunParameters) -> ((usize, Chromosome), (usize, Chromosome)) {
+        let used: Vec<usize> = if parameters.distinct_parents { self.generation_parents.clone() } else { vec![] };
+        let (first, mut second) = self.select_pair(parameters, &used);
+        if parameters.min_parent_distance == 0 {
+            return (first, second);
+        }
+
+        // Nearly identical parents make children like themselves, so look for a second parent less like the first
+        let mut excluded: Vec<usize> = used;
+        if parameters.distinct_parents {
+            excluded.push(first.0);
+        }
+        let mut distance: usize = first.1.distance(&second.1);
+        for _ in 0..PARENT_RESELECTIONS {
+            if distance >= parameters.min_parent_distance as usize {
+                break;
+            }
+            let candidate = self.select_parent(parameters, &excluded);
+            let candidate_distance: usize = first.1.distance(&candidate.1);
+            if candidate_distance > distance {
+                (second, distance) = (candidate, candidate_distance);
+            }
+        }
+        (first, second)
+    }
+
+    /// This function selects a pair of parents for crossover using the selection operator, without the Chromosomes
+    /// whose indices are in used. If each Chromosome can only be selected once, the first parent can't be selected
+    /// as the second
+    fn select_pair(&self, parameters: &RunParameters, used: &[usize]) -> ((usize, Chromosome), (usize, Chromosome)) {
+        let mut excluded: Vec<usize> = used.to_vec();
+
+        // Stochastic universal sampling picks both parents with the same spin
+        if parameters.selection_operator == SelectionOperator::Sus {
+            let mut picked = self.run_universal_sampling(2);
+            if let (Some(second), Some(first)) = (picked.pop(), picked.pop()) {
+                if !parameters.distinct_parents {
+                    return (first, second);
+                }
+
+                // A pointer that landed on a Chromosome that is already a parent spins again without it
+                let first = if excluded.contains(&first.0) { self.run_roulette_excluding(&excluded) } else { first };
+                excluded.push(first.0);
+                let second = if excluded.contains(&second.0) { self.run_roulette_excluding(&excluded) } else { second };
+                return (first, second);
+            }
+        }
+
+        // Select first parent
+        let first = self.select_parent(parameters, &excluded);
+
+        // If each Chromosome can only be selected once, remove the first parent from the second selection
+        if parameters.distinct_parents {
+            excluded.push(first.0);
+        }
+
+        // Select second parent
+        let second = self.select_parent(parameters, &excluded);
+
+        (first, second)
+    }
+
+    /// This function picks one of the indices of Chromosomes that tied in a tournament using the tie_policy
+    pub fn break_tie(&self, tied: &[usize], tie_policy: TiePolicy) -> Option<usize> {
+        match tie_policy {
+            // The first Chromosome picked for the tournament
+            TiePolicy::First => tied.first().copied(),
+            // Any of the tied Chromosomes
+            TiePolicy::Random => tied.choose(&mut rng()).copied(),
+            // The Chromosome that joined the population first
+            TiePolicy::Older => tied
+                .iter()
+                .min_by_key(|index| self.population_data[**index].id)
+                .copied(),
+            // The Chromosome sharing the fewest edges with the best Chromosome
+            TiePolicy::Diverse => tied
+                .iter()
+                .min_by_key(|index| self.population_data[**index].shared_edges(&self.best_chromosome))
+                .copied(),
+        }
     }
 
     /// This function runs a tournament twice to obtain two parents, then it creates two children from those
-    /// parents. It will take the first child and if it is better than the worst chromosome in the population
-    /// it will replace it. Then it will do the same with the second child.
+    /// parents. Both children are mutated and passed through the populations [`Repair`]. It will take the first 
+    /// child and if it is better than the worst chromosome in the population it will replace it. Then it will do 
+    /// the same with the second child. This is repeated until the number of children per generation have been
+    /// bred. If a number of offspring is given the whole generation is instead done by [`Population::plus_selection`]
     pub fn selection_and_replacement(
         &mut self, 
-        tournament_size: u32, 
-        crossover_operator: CrossoverOperator, 
-        mutation_operator: MutationOperator, 
+        parameters: &RunParameters,
         country_data: &Graph
     ) -> Result<()> {
+        // Every Chromosome can be a parent again in a new generation
+        self.generation_parents.clear();
+
+        // A (μ+λ) generation replaces the population all at once
+        if let Some(offspring) = parameters.offspring {
+            return self.plus_selection(offspring, parameters, country_data);
+        }
 
-        // Select first and second parents using tournaments
-        let first_parent: Chromosome = Population::run_tournament(self, tournament_size);
-        let second_parent: Chromosome = Population::run_tournament(self, tournament_size);
+        // Each pass breeds a pair of children, so later pairs can be bred from the children of earlier ones, until
+        // the evaluation budget has been used
+        for _ in 0..parameters.children_per_generation.div_ceil(2).max(1) {
+            if self.evaluations_left() == 0 {
+                break;
+            }
+            self.breed_and_replace(parameters, country_data)?;
+        }
+        Ok(())
+    }
 
-        // Use crossover to generate two children from the parents
-        let (mut first_child, mut second_child) = first_parent.crossover(&second_parent, crossover_operator, country_data)?;
+    /// This function breeds a single pair of children and replaces Chromosomes of the population with them
+    /// following the replacement strategy
+    fn breed_and_replace(&mut self, parameters: &RunParameters, country_data: &Graph) -> Result<()> {
+        let ([first_index, second_index], children) = self.breed(parameters, country_data)?;
 
-        // Apply mutation to the two children
-        first_child.mutation(mutation_operator, country_data)?;
-        second_child.mutation(mutation_operator, country_data)?;
+        // Leave out or perturb children whose tour is already in the population
+        let children: Vec<Chromosome> = self.unique_children(children, parameters, country_data)?;
 
-        // Run replacement function with first child first
-        self.replacement(first_child);
-        // Re-run replacement function with second child
-        self.replacement(second_child);
+        match parameters.replacement_strategy {
+            ReplacementStrategy::Weakest => {
+                // Run replacement function with each child in turn
+                for child in children {
+                    self.replacement(child);
+                }
+            },
+            ReplacementStrategy::WorstParent => {
+                // Each child competes against its own parent, the cheaper child against the worse parent
+                self.replace_parents(children, [first_index, second_index]);
+            },
+            ReplacementStrategy::Crowding => {
+                // Each child competes against the parent its route is most like
+                self.crowding_replacement(children, [first_index, second_index]);
+            },
+            ReplacementStrategy::Restricted => {
+                // Each child competes against the Chromosome its route is most like in a random window
+                for child in children {
+                    self.restricted_replacement(child, parameters.window_size as usize);
+                }
+            },
+        }
 
         // Update old population stats with new ones
+        self.update_statistics()
+    }
+
+    /// This function runs a (μ+λ) generation, breeding the given number of offspring λ from the population 
+    /// of μ Chromosomes, then keeping the cheapest μ of the population and the offspring. Children are 
+    /// bred in pairs, so if the number of offspring is odd the second child of the last pair is thrown away
+    pub fn plus_selection(&mut self, offspring: u64, parameters: &RunParameters, country_data: &Graph) -> Result<()> {
+        let mut children: Vec<Chromosome> = Vec::with_capacity(offspring as usize);
+        for _ in 0..offspring.div_ceil(2) {
+            if self.evaluations_left() == 0 {
+                break;
+            }
+            let (_, pair) = self.breed(parameters, country_data)?;
+            children.extend(self.unique_children(pair, parameters, country_data)?);
+        }
+        children.truncate(offspring as usize);
+
+        self.plus_replacement(children);
+        self.update_statistics()
+    }
+
+    /// A Function to add all of the children to the population at once, then remove the most expensive
+    /// Chromosomes until the population is back to its size. The elite are the cheapest, so always survive
+    pub fn plus_replacement(&mut self, children: Vec<Chromosome>) {
+        let size: usize = self.population_data.len();
+        self.population_data.extend(children);
+        self.population_data.sort_by(Population::cheapest_first);
+        self.population_data.truncate(size);
+    }
+
+    /// This function selects two parents and creates two children from them, crossing them over, mutating them, 
+    /// repairing them and improving them with the local search as the parameters say. Returns the indices of the
+    /// parents and the children, which have been given their ids. With adaptive operators, the operators are picked
+    /// for each pair and each child, and are rewarded if they made a child cheaper than what it was made from
+    pub fn breed(&mut self, parameters: &RunParameters, country_data: &Graph) -> Result<([usize; 2], [Chromosome; 2])> {
+        // Select both parents, counting how often they are the same Chromosome
+        let ((first_index, first_parent), (second_index, second_parent)) = self.select_parents(parameters);
+        self.bred_pairs += 1;
+        self.generation_parents.extend([first_index, second_index]);
+        if first_index == second_index {
+            self.duplicate_parents += 1;
+        }
+
+        // Cross the parents over as often as the crossover rate says, otherwise pass them through as they are. The
+        // brood is cut to the pairs of children the evaluations left pay for, and passed through if they pay for none
+        let mut crossed_over: Option<(Chromosome, Chromosome)> = None;
+        if rng().gen_bool(parameters.crossover_rate) {
+            let (position, crossover_operator) = match &self.adaptive_operators {
+                Some(adaptive) => adaptive.crossover.choose(),
+                None => (0, parameters.crossover_operator),
+            };
+            let pairs: u64 = self.evaluations_left() / crossover_operator.evaluations().max(1);
+            if pairs > 0 {
+                crossed_over = self.brood(
+                    &first_parent,
+                    &second_parent,
+                    crossover_operator,
+                    parameters.brood_size.min(pairs.saturating_mul(2)),
+                    country_data
+                )?;
+            }
+
+            // Crossover succeeds if either child is cheaper than the cheaper parent
+            let cheapest_parent: f64 = first_parent.cost.min(second_parent.cost);
+            let success: bool = crossed_over.as_ref().is_some_and(|(first_child, second_child)| {
+                [first_child, second_child].iter().any(|child| {
+                    Chromosome::compare_costs(child.cost, cheapest_parent, self.cost_epsilon) == Ordering::Less
+                })
+            });
+            if let Some(adaptive) = self.adaptive_operators.as_mut() {
+                adaptive.crossover.reward(position, success);
+            }
+        }
+
+        // Use the children from crossover, or copies of the parents if they were passed through
+        let crossed: bool = crossed_over.is_some();
+        let (mut first_child, mut second_child) = crossed_over.unwrap_or_else(|| (
+            Chromosome::new(first_parent.route.clone(), first_parent.cost),
+            Chromosome::new(second_parent.route.clone(), second_parent.cost),
+        ));
+
+        // Mutate each child as often as the mutation rate says, counting the evaluations used. A mutation the
+        // evaluations left don't pay for is left out
+        for child in [&mut first_child, &mut second_child] {
+            if rng().gen_bool(parameters.mutation_rate) {
+                let (position, mutation_operator) = match &self.adaptive_operators {
+                    Some(adaptive) => adaptive.mutation.choose(),
+                    None => (0, parameters.mutation_operator),
+                };
+                if self.evaluations_left() < mutation_operator.evaluations() {
+                    continue;
+                }
+                let cost: f64 = child.cost;
+                child.mutation(mutation_operator, country_data)?;
+                self.evaluations += mutation_operator.evaluations();
+
+                // Mutation succeeds if it made the child cheaper
+                if let Some(adaptive) = self.adaptive_operators.as_mut() {
+                    let success: bool = Chromosome::compare_costs(child.cost, cost, self.cost_epsilon) == Ordering::Less;
+                    adaptive.mutation.reward(position, success);
+                }
+            }
+        }
+
+        // Repair both children, recalculating the cost of any that were changed. Once the budget is used a child
+        // keeps its route, whose cost is already known
+        for child in [&mut first_child, &mut second_child] {
+            if self.evaluations_left() > 0 && self.repair.repair(child, country_data) {
+                child.cost = Chromosome::fitness(&child.route, country_data)?;
+                self.evaluations += 1;
+            }
+        }
+
+        // Improve both children with a local search if one was chosen, stopping it after the moves the evaluations
+        // left pay for
+        if let Some(local_search) = parameters.local_search {
+            for child in [&mut first_child, &mut second_child] {
+                if self.evaluations_left() == 0 {
+                    break;
+                }
+                let cities: Vec<u32> = child.route.clone();
+                let max_moves: u64 = local_search.max_moves(self.evaluations_left(), cities.len());
+                let moves: u64 = child.local_search_around(
+                    local_search,
+                    &cities,
+                    country_data,
+                    &self.cancellation,
+                    max_moves
+                )?;
+                self.evaluations += local_search.evaluations(moves, cities.len());
+            }
+        }
+
+        // Give the children their ids
+        self.assign_id(&mut first_child);
+        self.assign_id(&mut second_child);
+
+        // Remember where the children came from, a child passed through only comes from the parent it copies
+        if let Some(genealogy) = self.genealogy.as_mut() {
+            let parents: [u64; 2] = [first_parent.id, second_parent.id];
+            genealogy.propose(&first_child, if crossed { &parents } else { &parents[..1] });
+            genealogy.propose(&second_child, if crossed { &parents } else { &parents[1..] });
+        }
+
+        Ok(([first_index, second_index], [first_child, second_child]))
+    }
+
+    /// This function crosses the parents over enough times to make the given number of children, counting the
+    /// evaluations used, and returns the cheapest two. Crossover makes children in pairs, so an odd number of
+    /// children uses the evaluations of one more child than it picks from. Returns None if crossover makes no new
+    /// genetic material, as the children would be copies of the parents
+    pub fn brood(
+        &mut self,
+        first_parent: &Chromosome,
+        second_parent: &Chromosome,
+        crossover_operator: CrossoverOperator,
+        brood_size: u64,
+        country_data: &Graph
+    ) -> Result<Option<(Chromosome, Chromosome)>> {
+        let mut brood: Vec<Chromosome> = Vec::with_capacity(brood_size.max(2) as usize + 1);
+        for _ in 0..brood_size.div_ceil(2).max(1) {
+            // Crossover that made no new genetic material once won't make any the next time either
+            let children = first_parent.try_crossover(second_parent, crossover_operator, country_data)?;
+            let Some((first_child, second_child)) = children else {
+                return Ok(None);
+            };
+            self.evaluations += crossover_operator.evaluations();
+            brood.push(first_child);
+            brood.push(second_child);
+        }
+        brood.truncate(brood_size.max(2) as usize);
+
+        // Keep the cheapest two children, in the order they were made if they cost the same
+        brood.sort_by(|x, y| x.cost.total_cmp(&y.cost));
+        let mut cheapest = brood.into_iter();
+        Ok(cheapest.next().zip(cheapest.next()))
+    }
+
+    /// This function calculates how spread out the costs of the population are, as their standard deviation
+    /// relative to their average, so it can be compared between countries and generations. 0 if every cost is 0
+    pub fn cost_spread(&self) -> f64 {
+        if self.average_population_cost > 0.0 {
+            self.cost_deviation() / self.average_population_cost
+        } else {
+            0.0
+        }
+    }
+
+    /// This function calculates the standard deviation of the costs of the population
+    pub fn cost_deviation(&self) -> f64 {
+        // Average the squared distance of each cost from the average cost
+        let variance: f64 = self.population_data
+            .iter()
+            .map(|chromosome| (chromosome.cost - self.average_population_cost).powi(2))
+            .sum::<f64>() / self.population_data.len() as f64;
+        variance.sqrt()
+    }
+
+    /// This function calculates the Shannon entropy of how often each edge appears across every route in the 
+    /// population. It is lowest, the natural log of the number of cities, when every Chromosome has the same edges
+    /// and grows as the routes use more varied edges,
+    /// so it shows structural convergence even when different routes have similar costs
+    pub fn edge_entropy(&self) -> f64 {
+        // Count how many routes use each edge, ignoring direction
+        let mut frequency: HashMap<(u32, u32), u64> = HashMap::new();
+        let mut total: u64 = 0;
+        for chromosome in &self.population_data {
+            for edge in Chromosome::edges(&chromosome.route) {
+                *frequency.entry(edge).or_insert(0) += 1;
+                total += 1;
+            }
+        }
+
+        // Sum -p ln p over the proportion of all edges each edge makes up
+        frequency
+            .values()
+            .map(|count| {
+                let proportion: f64 = *count as f64 / total as f64;
+                -proportion * proportion.ln()
+            })
+            .sum()
+    }
+
+    /// This function calculates how different the routes of the population are, as the average fraction of edges
+    /// two different Chromosomes don't share. It is 0 when every Chromosome has the same edges, and is found by
+    /// counting how many routes use each edge, as every pair of routes using an edge shares it
+    pub fn diversity(&self) -> f64 {
+        let size: u64 = self.population_data.len() as u64;
+        let route_length: u64 = self.population_data.first().map_or(0, |chromosome| chromosome.route.len() as u64);
+        if size < 2 || route_length == 0 {
+            return 0.0;
+        }
+
+        // Count how many routes use each edge, ignoring direction
+        let mut frequency: HashMap<(u32, u32), u64> = HashMap::new();
+        for chromosome in &self.population_data {
+            for edge in Chromosome::edges(&chromosome.route) {
+                *frequency.entry(edge).or_insert(0) += 1;
+            }
+        }
+
+        // Every pair of routes using an edge shares it, out of route_length edges for each pair of routes
+        let shared: u64 = frequency.values().map(|count| count * (count - 1) / 2).sum();
+        let pairs: u64 = size * (size - 1) / 2;
+        1.0 - shared as f64 / (pairs * route_length) as f64
+    }
+
+    /// This function returns the fraction of the population whose route contains each of the given edges, ignoring
+    /// the direction each edge is travelled in
+    pub fn edge_frequencies(&self, edges: &[(u32, u32)]) -> Vec<f64> {
+        let mut counts: Vec<u64> = vec![0; edges.len()];
+        for chromosome in &self.population_data {
+            let route_edges: HashSet<(u32, u32)> = Chromosome::edges(&chromosome.route).into_iter().collect();
+            for (edge, count) in edges.iter().zip(counts.iter_mut()) {
+                if route_edges.contains(&(edge.0.min(edge.1), edge.0.max(edge.1))) {
+                    *count += 1;
+                }
+            }
+        }
+
+        let size: f64 = self.population_data.len().max(1) as f64;
+        counts.into_iter().map(|count| count as f64 / size).collect()
+    }
+
+    /// This function recalculates the average cost, best Chromosome and worst Chromosome
+    /// of the population so they match the current population_data
+    pub fn update_statistics(&mut self) -> Result<()> {
         let _ = std::mem::replace(
             &mut self.average_population_cost, 
             Population::find_average_cost(&self.population_data)
         );
         let _ = std::mem::replace(
             &mut self.best_chromosome, 
-            Population::find_best_chromosome(&self.population_data)?
+            Population::find_best_chromosome(&self.population_data, self.cost_epsilon)?
         );
         let _ = std::mem::replace(
             &mut self.worst_chromosome, 
-            Population::find_worst_chromosome(&self.population_data)?
+            Population::find_worst_chromosome(&self.population_data, self.cost_epsilon)?
         );
+        self.update_elite();
+
+        // Keep the genealogy of the children that made it into the population
+        if let Some(genealogy) = self.genealogy.as_mut() {
+            genealogy.settle(&self.population_data, self.best_chromosome.id);
+        }
 
         Ok(())
     }
+
+    /// This function changes the number of Chromosomes in the population. When shrinking, the most
+    /// expensive Chromosomes are removed so the best ones survive. When growing, the population is
+    /// refilled with newly generated random Chromosomes.
+    pub fn resize(&mut self, new_size: u64, country_data: &Graph) -> Result<()> {
+        // An empty population would have no best or worst Chromosome
+        if new_size == 0 {
+            return Err(eyre!("Population cannot be resized to 0 Chromosomes"));
+        }
+
+        match new_size.cmp(&(self.population_data.len() as u64)) {
+            // Shrink the population by keeping only the cheapest Chromosomes
+            Ordering::Less => {
+                // Sort population by cost so the cheapest Chromosomes are first, keeping the elite
+                self.population_data.sort_by(Population::cheapest_first);
+                // Remove every Chromosome after the new size
+                self.population_data.truncate(new_size as usize);
+            },
+            // Grow the population by adding new random Chromosomes, as many as the evaluation budget pays for
+            Ordering::Greater => {
+                while (self.population_data.len() as u64) < new_size && self.evaluations_left() > 0 {
+                    let mut chromosome: Chromosome = Chromosome::generation(country_data)?;
+                    self.assign_id(&mut chromosome);
+                    if let Some(genealogy) = self.genealogy.as_mut() {
+                        genealogy.propose(&chromosome, &[]);
+                    }
+                    self.population_data.push(chromosome);
+                    self.evaluations += 1;
+                }
+            },
+            // Population is already the correct size
+            Ordering::Equal => return Ok(()),
+        }
+
+        // Record the new size and update the stats to reflect the new population
+        self.population_size = self.population_data.len() as u64;
+        self.update_statistics()
+    }
+
+    /// This function replaces the given fraction of the population, the most expensive first, with newly generated
+    /// random Chromosomes so a population that has converged has new routes to breed from. Chromosomes kept by
+    /// elitism are never replaced. Returns the number of immigrants added
+    pub fn add_immigrants(&mut self, rate: f64, country_data: &Graph) -> Result<usize> {
+        let count: usize = (self.population_data.len() as f64 * rate) as usize;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        // Find the most expensive Chromosomes outside the elite
+        let mut replaceable: Vec<usize> = (0..self.population_data.len())
+            .filter(|index| !self.elite.contains(&self.population_data[*index].id))
+            .collect();
+        replaceable.sort_by(|x, y| Population::cheapest_first(&self.population_data[*y], &self.population_data[*x]));
+        replaceable.truncate(count.min(usize::try_from(self.evaluations_left()).unwrap_or(usize::MAX)));
+
+        // Replace each with a random Chromosome, which costs an evaluation to generate, as many as the budget pays for
+        for index in replaceable.iter() {
+            let mut immigrant: Chromosome = Chromosome::generation(country_data)?;
+            self.assign_id(&mut immigrant);
+            if let Some(genealogy) = self.genealogy.as_mut() {
+                genealogy.propose(&immigrant, &[]);
+            }
+            self.population_data[*index] = immigrant;
+            self.evaluations += 1;
+        }
+
+        self.update_statistics()?;
+        Ok(replaceable.len())
+    }
+}
+
+/// A generation of the genetic algorithm creates two children and replaces the weakest Chromosomes with them
+impl Solver for Population {
+    fn step(&mut self, parameters: &RunParameters, graph: &Graph) -> Result<()> {
+        self.selection_and_replacement(parameters, graph)
+    }
+
+    fn best(&self) -> &Chromosome {
+        &self.best_chromosome
+    }
+
+    fn worst(&self) -> &Chromosome {
+        &self.worst_chromosome
+    }
+
+    fn average_cost(&self) -> f64 {
+        self.average_population_cost
+    }
+
+    fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
+
+    fn cost_deviation(&self) -> Option<f64> {
+        Some(Population::cost_deviation(self))
+    }
+
+    fn edge_entropy(&self) -> Option<f64> {
+        Some(Population::edge_entropy(self))
+    }
+
+    fn diversity(&self) -> Option<f64> {
+        Some(Population::diversity(self))
+    }
+
+    fn edge_frequencies(&self, edges: &[(u32, u32)]) -> Option<Vec<f64>> {
+        Some(Population::edge_frequencies(self, edges))
+    }
 }
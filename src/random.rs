@@ -0,0 +1,61 @@
+//! This module defines the random number generator every operator draws from. Each thread has its own
+//! [`StdRng`], seeded from the operating system unless [`seed_current_thread`] gives it a seed. A run is built
+//! and run on a single thread, so seeding that thread before the run starts makes the whole run repeatable.
+//! [`derive_seed`] gives each run of an experiment its own seed from the seed of the experiment.
+
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+thread_local! {
+    /// The generator of the current thread
+    static GENERATOR: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// A handle to the random number generator of the current thread, used in place of `rand::thread_rng`
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RunRng;
+
+/// Implement RngCore for RunRng, drawing every number from the generator of the current thread
+impl RngCore for RunRng {
+    fn next_u32(&mut self) -> u32 {
+        GENERATOR.with(|generator| generator.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        GENERATOR.with(|generator| generator.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        GENERATOR.with(|generator| generator.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        GENERATOR.with(|generator| generator.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+/// Function to get a handle to the random number generator of the current thread
+pub fn rng() -> RunRng {
+    RunRng
+}
+
+/// Function to restart the random number generator of the current thread from the given seed, so every number it
+/// gives from now on is the same each time. Without a seed it is seeded from the operating system again, so a
+/// thread that ran a seeded run doesn't repeat it
+pub fn seed_current_thread(seed: Option<u64>) {
+    let generator: StdRng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    GENERATOR.with(|current| *current.borrow_mut() = generator);
+}
+
+/// Function to derive the seed of one of many streams, such as the runs of an experiment, from a single seed.
+/// Uses the SplitMix64 finaliser so neighbouring streams get unrelated seeds
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z: u64 = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
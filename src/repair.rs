@@ -0,0 +1,84 @@
+//! This module defines the [`Repair`] trait, a hook that is run on every child a [`Population`] creates
+//! so that variants of the problem with extra constraints can fix up routes without their own GA loop.
+//! [`ForbiddenEdgeRepair`] is used for graphs where some cities have no edge between them.
+//! 
+//! [`Population`]: crate::population::Population
+
+use super::{chromosome::Chromosome, country::Graph};
+
+/// A repair that is applied to every child after crossover and mutation, before it can join the population
+pub trait Repair: Send + Sync {
+    /// Function to make the route of a child valid. It should return true if the route was changed,
+    /// so the cost of the child can be recalculated. By default the child is left as it is
+    fn repair(&self, _child: &mut Chromosome, _graph: &Graph) -> bool {
+        false
+    }
+}
+
+/// The default [`Repair`], which never changes a child
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NoRepair;
+
+/// Uses the default repair, which does nothing
+impl Repair for NoRepair {}
+
+/// A [`Repair`] that removes forbidden edges from a route, the edges between cities the graph has no edge between,
+/// so children stay feasible tours where possible. Each forbidden edge is removed by reversing the part of the route
+/// between it and another edge, as a 2-opt move does, if the two edges that replace them use fewer forbidden edges.
+/// Moves are made until none removes a forbidden edge, which leaves routes that can't be made feasible this way
+/// as close to feasible as it can, with their cost penalised for the forbidden edges left
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ForbiddenEdgeRepair;
+
+/// Implement methods on `ForbiddenEdgeRepair`
+impl ForbiddenEdgeRepair {
+    /// Function to find a 2-opt move that removes the forbidden edge starting at the given position, returning the
+    /// position of the other edge it replaces. The edge starting at position `i` joins `route[i]` to the next city
+    fn find_move(route: &[u32], position: usize, graph: &Graph) -> Option<usize> {
+        let length: usize = route.len();
+        let forbidden = |from: u32, to: u32| graph.distances.is_forbidden(from, to) as usize;
+        let edge = |i: usize| (route[i], route[(i + 1) % length]);
+
+        (0..length)
+            .filter(|other| other.abs_diff(position) > 1 && other.abs_diff(position) < length - 1)
+            .find(|other| {
+                // Reversing the cities between the two edges joins the starts of both, and the ends of both
+                let (first, second) = (position.min(*other), position.max(*other));
+                let ((a, b), (c, d)) = (edge(first), edge(second));
+                let (mut added, mut removed) = (forbidden(a, c) + forbidden(b, d), forbidden(a, b) + forbidden(c, d));
+
+                // When costs differ by direction the edges between them are travelled the other way as well
+                if !graph.symmetric {
+                    for (from, to) in (first + 1..second).map(edge) {
+                        added += forbidden(to, from);
+                        removed += forbidden(from, to);
+                    }
+                }
+                added < removed
+            })
+    }
+}
+
+/// Removes as many forbidden edges from each child as 2-opt moves can
+impl Repair for ForbiddenEdgeRepair {
+    fn repair(&self, child: &mut Chromosome, graph: &Graph) -> bool {
+        let length: usize = child.route.len();
+        let mut changed: bool = false;
+
+        // Every move removes at least one forbidden edge, so this ends once no move can remove any more
+        'search: loop {
+            for position in 0..length {
+                if !graph.distances.is_forbidden(child.route[position], child.route[(position + 1) % length]) {
+                    continue;
+                }
+                if let Some(other) = ForbiddenEdgeRepair::find_move(&child.route, position, graph) {
+                    let (first, second) = (position.min(other), position.max(other));
+                    child.route[first + 1..=second].reverse();
+                    changed = true;
+                    continue 'search;
+                }
+            }
+            return changed;
+        }
+    }
+}
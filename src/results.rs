@@ -0,0 +1,675 @@
+//! This module defines [`ExperimentResults`], which groups every finished [`Simulation`] by the
+//! country it was run on and provides methods to plot and export them.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as FmtWrite,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::prelude::*;
+use color_eyre::{eyre::{eyre, ContextCompat}, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{
+    adaptive::OperatorStatistics,
+    build_info::BuildInfo,
+    chromosome::Chromosome,
+    control::ParameterChange,
+    country::Graph,
+    interface::{PlotOperator, PlotStatistic, PlotTheme, RunParameters},
+    manifest::ExperimentNotes,
+    optimal::{EdgeComparison, OptimalTour},
+    simulation::{PlotSeries, Simulation, BOUND_GAP, OPTIMAL_GAP},
+    warnings::{WarningKind, Warnings},
+};
+
+/// A summary of the final generation of a single [`Simulation`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Which run of the country this was, starting from 1
+    pub run: u32,
+    /// The cost of the cheapest Chromosome found by the run
+    pub best_cost: f64,
+    /// The cost of the worst Chromosome in the final generation
+    pub worst_cost: f64,
+    /// The average cost of the final generation
+    pub average_cost: f64,
+    /// The route of the cheapest Chromosome found by the run
+    pub best_route: Vec<u32>,
+    /// The number of fitness evaluations used by the whole run
+    pub evaluations: u64,
+    /// How many seconds the run took
+    #[serde(default)]
+    pub seconds: f64,
+    /// How many of those seconds were spent updating the progress bar of the run
+    #[serde(default)]
+    pub progress_seconds: f64,
+    /// Every change made to the parameters while the run was running
+    #[serde(default)]
+    pub parameter_changes: Vec<ParameterChange>,
+    /// The id of the build that ran it, see [`BuildInfo::id`]. Empty for runs summarised before builds were recorded
+    #[serde(default)]
+    pub build: String,
+    /// The parameters the run finished with, which are the ones it started with after every change in
+    /// `parameter_changes`. None for runs summarised before they were recorded
+    #[serde(default)]
+    pub parameters: Option<RunParameters>,
+    /// Whether the run was cancelled before its last generation, so only summarises the generations up to then
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// Implement methods on `RunSummary`
+impl RunSummary {
+    /// Function to summarise the final generation of a [`Simulation`]
+    pub fn new(run: u32, simulation: &Simulation) -> Result<Self> {
+        // The best tour, evaluations and time are reported the same way by every algorithm
+        let report = simulation.report()?;
+        let worst = simulation.worst_chromosome
+            .last()
+            .wrap_err("Cannot access Chromosome data in Simulation")?;
+        let average_cost = simulation.average_cost
+            .last()
+            .wrap_err("Cannot access Chromosome data in Simulation")?;
+
+        Ok(Self {
+            run,
+            best_cost: report.best_cost(),
+            worst_cost: worst.cost,
+            average_cost: *average_cost,
+            best_route: report.best_tour.route,
+            evaluations: report.evaluations,
+            seconds: report.elapsed.as_secs_f64(),
+            progress_seconds: simulation.progress_overhead.as_secs_f64(),
+            parameter_changes: simulation.parameter_changes.clone(),
+            build: BuildInfo::current().id(),
+            parameters: Some(simulation.parameters),
+            cancelled: simulation.cancelled,
+        })
+    }
+}
+
+/// A single edge of a route, along with the cost of the route up to and including it
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteEdge {
+    /// The city the edge starts at
+    pub from: u32,
+    /// The city the edge ends at
+    pub to: u32,
+    /// The cost of travelling the edge
+    pub cost: f64,
+    /// The cost of every edge of the route up to and including this one
+    pub cumulative: f64,
+}
+
+/// Function to break a route down into its edges in the order they are travelled, including the edge from the
+/// last city back to the first, erroring if a city of the route isn't in the graph
+pub fn route_breakdown(route: &[u32], graph: &Graph) -> Result<Vec<RouteEdge>> {
+    let mut cumulative: f64 = 0.0;
+    route
+        .iter()
+        .zip(route.iter().cycle().skip(1))
+        .map(|(from, to)| {
+            let cost: f64 = graph.distances
+                .get(*from, *to)
+                .ok_or_else(|| eyre!("No edge from city {} to city {}", from, to))?;
+            cumulative += cost;
+            Ok(RouteEdge { from: *from, to: *to, cost, cumulative })
+        })
+        .collect()
+}
+
+/// Function to find the positions in a breakdown of its most expensive edges, most expensive first. Edges that
+/// cost the same are in route order
+pub fn most_expensive_edges(breakdown: &[RouteEdge], count: usize) -> Vec<usize> {
+    let mut positions: Vec<usize> = (0..breakdown.len()).collect();
+    positions.sort_by(|x, y| breakdown[*y].cost.total_cmp(&breakdown[*x].cost).then(x.cmp(y)));
+    positions.truncate(count);
+    positions
+}
+
+/// All the results for a single country
+pub struct InstanceResults {
+    /// Name of the country the simulations were run on
+    pub name: String,
+    /// Every simulation run on this country
+    pub simulations: Vec<Simulation>,
+    /// A summary of each run in run order, including runs from an earlier invocation that have no simulation
+    pub summaries: Vec<RunSummary>,
+    /// Paths of every file written for this country
+    pub artifacts: Vec<PathBuf>,
+    /// Comparison of the best ever route against the known optimal route, if one was supplied
+    pub optimal_comparison: Option<EdgeComparison>,
+}
+
+/// Implement methods on `InstanceResults`
+impl InstanceResults {
+    /// Function to find the cheapest Chromosome found in any generation of any run
+    pub fn best_ever(&self) -> Option<&Chromosome> {
+        self.simulations
+            .iter()
+            .flat_map(|sim| sim.best_chromosome.iter())
+            .min_by(|x, y| x.cost.partial_cmp(&y.cost).unwrap())
+    }
+}
+
+/// The results of every simulation run in an experiment, grouped by country
+pub struct ExperimentResults {
+    /// Results for each country, sorted by name
+    pub instances: Vec<InstanceResults>,
+    /// Number of times each country was simulated
+    pub number_runs: u32,
+    /// Paths of every file written that covers the whole experiment
+    pub artifacts: Vec<PathBuf>,
+    /// Freeform tags attached to every run in the experiment
+    pub tags: Vec<String>,
+    /// Why the experiment was run and who ran it
+    pub notes: ExperimentNotes,
+    /// Problems found during the experiment that didn't stop it
+    pub warnings: Warnings,
+}
+
+/// Implement methods on `ExperimentResults`
+impl ExperimentResults {
+    /// Function to group finished simulations by the name of their country and summarise each one.
+    /// The runs of each country are numbered in the order they are given
+    pub fn new(simulations: Vec<Simulation>, number_runs: u32) -> Result<Self> {
+        // Count the runs of each country so far to number them
+        let mut run_counts: HashMap<String, u32> = HashMap::new();
+        let numbered: Vec<(u32, Simulation)> = simulations
+            .into_iter()
+            .map(|sim| {
+                let count = run_counts.entry(sim.country_data.name.clone()).or_default();
+                *count += 1;
+                (*count, sim)
+            })
+            .collect();
+
+        ExperimentResults::from_runs(numbered, Vec::new(), number_runs)
+    }
+
+    /// Function to group finished simulations, each given with its run number, by the name of their country
+    /// and summarise each one. Summaries of runs that finished earlier without a [`Simulation`], e.g. in an
+    /// interrupted experiment, are given with the name of their country and are merged in
+    pub fn from_runs(
+        simulations: Vec<(u32, Simulation)>, 
+        previous: Vec<(String, RunSummary)>, 
+        number_runs: u32
+    ) -> Result<Self> {
+        // Create a HashMap to store all the simulations by their names
+        let mut ordered_data: HashMap<String, Vec<(u32, Simulation)>> = HashMap::new();
+
+        // Loop over each Simulation
+        for (run, sim) in simulations {
+            ordered_data
+                // Get the entry of the key, where the key is the name out the country used
+                .entry(sim.country_data.name.clone())
+                // If that key doesn't exist yet, create it and set its entry to be an empty vector
+                .or_default()
+                // Push the Simulation into the entry
+                .push((run, sim));
+        }
+
+        // Convert each entry into InstanceResults, collecting the warnings of every run
+        let mut instances: Vec<InstanceResults> = Vec::with_capacity(ordered_data.len());
+        let mut warnings: Warnings = Warnings::default();
+        for (name, runs) in ordered_data {
+            for (run, sim) in runs.iter() {
+                for warning in sim.warnings.warnings.iter() {
+                    warnings.push(warning.kind, format!("{} run {}", warning.source, run), warning.message.clone());
+                }
+            }
+
+            let summaries = runs
+                .iter()
+                .map(|(run, sim)| RunSummary::new(*run, sim))
+                .collect::<Result<Vec<RunSummary>>>()?;
+
+            instances.push(InstanceResults {
+                name,
+                simulations: runs.into_iter().map(|(_, sim)| sim).collect(),
+                summaries,
+                artifacts: Vec::new(),
+                optimal_comparison: None,
+            });
+        }
+
+        // Add the summaries of earlier runs to their country, which may not have any simulations
+        for (name, summary) in previous {
+            match instances.iter_mut().find(|instance| instance.name == name) {
+                Some(instance) => instance.summaries.push(summary),
+                None => instances.push(InstanceResults {
+                    name,
+                    simulations: Vec::new(),
+                    summaries: vec![summary],
+                    artifacts: Vec::new(),
+                    optimal_comparison: None,
+                }),
+            }
+        }
+
+        // Sort by name so the output order doesn't depend on which thread finished first
+        instances.sort_by(|x, y| x.name.cmp(&y.name));
+        for instance in instances.iter_mut() {
+            instance.summaries.sort_by_key(|summary| summary.run);
+        }
+
+        Ok(Self { instances, number_runs, artifacts: Vec::new(), tags: Vec::new(), notes: ExperimentNotes::default(), warnings })
+    }
+
+    /// Function to move the countries of another set of results into these ones, so countries that were
+    /// plotted and exported as soon as their runs finished can be summarised together at the end
+    pub fn append(&mut self, other: ExperimentResults) {
+        for other_instance in other.instances {
+            match self.instances.iter_mut().find(|instance| instance.name == other_instance.name) {
+                Some(instance) => {
+                    instance.simulations.extend(other_instance.simulations);
+                    instance.summaries.extend(other_instance.summaries);
+                    instance.artifacts.extend(other_instance.artifacts);
+                    instance.summaries.sort_by_key(|summary| summary.run);
+                    if other_instance.optimal_comparison.is_some() {
+                        instance.optimal_comparison = other_instance.optimal_comparison;
+                    }
+                }
+                None => self.instances.push(other_instance),
+            }
+        }
+        self.artifacts.extend(other.artifacts);
+        self.warnings.extend(other.warnings);
+
+        // Keep the countries sorted by name so the output order doesn't depend on which finished first
+        self.instances.sort_by(|x, y| x.name.cmp(&y.name));
+    }
+
+    /// Function to attach freeform tags to every run in the experiment
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Function to record why the experiment was run and who ran it, written to the summary and every plot
+    pub fn with_notes(mut self, notes: ExperimentNotes) -> Self {
+        self.notes = notes;
+        self
+    }
+
+    /// Function to check the experiment has every one of the given tags
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+
+    /// Function to find the results for a country by its name
+    pub fn instance(&self, name: &str) -> Option<&InstanceResults> {
+        self.instances.iter().find(|instance| instance.name == name)
+    }
+
+    /// Function to create a plot for every country, recording the path of each plot
+    pub fn plot(
+        &mut self, 
+        plot_operator: PlotOperator, 
+        statistic_plotted: PlotStatistic, 
+        plot_theme: PlotTheme
+    ) -> Result<()> {
+        for instance in self.instances.iter_mut() {
+            // Countries whose runs all finished in an earlier invocation have nothing to plot
+            if instance.simulations.is_empty() {
+                continue;
+            }
+            let path = Simulation::plot(
+                &instance.simulations,
+                plot_operator,
+                statistic_plotted,
+                plot_theme,
+                self.number_runs,
+                instance.name.clone(),
+                &self.tags,
+                &self.notes,
+            )?;
+            instance.artifacts.push(path);
+        }
+        Ok(())
+    }
+
+    /// Function to plot how often the tracked edges appear in the population for every country that tracked any,
+    /// recording the path of each plot
+    pub fn plot_edge_frequencies(&mut self, plot_theme: PlotTheme) -> Result<Vec<PathBuf>> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for instance in self.instances.iter_mut() {
+            let tracked: bool = instance.simulations.first().is_some_and(|sim| !sim.tracked_edges.is_empty());
+            if !tracked {
+                continue;
+            }
+            let path = Simulation::plot_edge_frequencies(
+                &instance.simulations,
+                plot_theme,
+                self.number_runs,
+                instance.name.clone(),
+                &self.tags,
+                &self.notes,
+            )?;
+            instance.artifacts.push(path.clone());
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Function to describe how often each operator was used by the runs of every country with adaptive operators,
+    /// how often it made a cheaper child and how likely it was to be picked by the end of the runs, on average
+    pub fn operator_report(&self) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        for instance in self.instances.iter() {
+            // Only runs with adaptive operators have statistics, each listing the same operators in the same order
+            let crossover: Vec<&[OperatorStatistics]> = instance.simulations
+                .iter()
+                .map(|sim| sim.crossover_statistics.as_slice())
+                .filter(|statistics| !statistics.is_empty())
+                .collect();
+            let mutation: Vec<&[OperatorStatistics]> = instance.simulations
+                .iter()
+                .map(|sim| sim.mutation_statistics.as_slice())
+                .filter(|statistics| !statistics.is_empty())
+                .collect();
+
+            for (kind, runs) in [("crossover", crossover), ("mutation", mutation)] {
+                let Some(first) = runs.first() else {
+                    continue;
+                };
+                for (index, operator) in first.iter().enumerate() {
+                    let uses: u64 = runs.iter().map(|statistics| statistics[index].uses).sum();
+                    let successes: u64 = runs.iter().map(|statistics| statistics[index].successes).sum();
+                    let probability: f64 = runs.iter().map(|statistics| statistics[index].probability).sum::<f64>()
+                        / runs.len() as f64;
+                    lines.push(format!(
+                        "{} {} {}: {} of {} uses made a cheaper child, picked {:.1}% of the time by the end",
+                        instance.name, kind, operator.operator, successes, uses, probability * 100.0
+                    ));
+                }
+            }
+        }
+        lines
+    }
+
+    /// Function to add a warning for every country where runs lost the diversity of their population while their best
+    /// tour was still far from the optimal tour, if one is found in the given directory, or the lower bound of the
+    /// country otherwise, along with changes to the parameters that may help
+    pub fn check_convergence(&mut self, optimal_tours: Option<&Path>) -> Result<()> {
+        for instance in self.instances.iter() {
+            let Some(first) = instance.simulations.first() else {
+                continue;
+            };
+
+            // Compare against the optimal tour if there is one, the lower bound is much further below the best tours
+            let optimal = match optimal_tours {
+                Some(directory) => OptimalTour::find(&first.country_data, directory)?,
+                None => None,
+            };
+            let (reference, reference_cost, allowed_gap) = match optimal {
+                Some(optimal) => ("optimal tour", Chromosome::fitness(&optimal.route, &first.country_data.graph)?, OPTIMAL_GAP),
+                None => ("lower bound", first.country_data.graph.lower_bound(), BOUND_GAP),
+            };
+
+            let converged: Vec<(u32, f64)> = instance.simulations
+                .iter()
+                .filter_map(|sim| sim.premature_convergence(reference_cost, allowed_gap))
+                .collect();
+            if converged.is_empty() {
+                continue;
+            }
+
+            let generation: u32 = converged.iter().map(|(generation, _)| *generation).min().unwrap_or_default();
+            let gap: f64 = converged.iter().map(|(_, gap)| *gap).fold(f64::INFINITY, f64::min);
+            self.warnings.push(
+                WarningKind::Convergence,
+                &instance.name,
+                format!(
+                    "{} of {} runs lost their diversity (from generation {}) while their best tour was at least {:.1}% above the {}, {}",
+                    converged.len(),
+                    instance.simulations.len(),
+                    generation,
+                    gap * 100.0,
+                    reference,
+                    first.convergence_advice(),
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// Function to write a Vega-Lite spec of the same chart [`ExperimentResults::plot`] draws for each country, with its
+    /// data embedded, so the chart can be opened and changed in a web notebook without running the simulations again
+    pub fn export_vega_lite(
+        &mut self, 
+        plot_operator: PlotOperator, 
+        statistic_plotted: PlotStatistic
+    ) -> Result<Vec<PathBuf>> {
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+        let time: DateTime<Utc> = Utc::now();
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for instance in self.instances.iter_mut() {
+            // Countries whose runs all finished in an earlier invocation have nothing to plot
+            let Some(first) = instance.simulations.first() else {
+                continue;
+            };
+
+            // One row for each point of each line drawn
+            let lines = Simulation::statistic_lines(&instance.simulations, statistic_plotted);
+            let mut series: Vec<PlotSeries> = Simulation::plot_series(&lines, plot_operator)?;
+            series.extend(Simulation::random_search_series(&instance.simulations, statistic_plotted));
+            let values: Vec<serde_json::Value> = series
+                .into_iter()
+                .flat_map(|(series, line)| line
+                    .into_iter()
+                    .map(move |(generation, value)| json!({ "series": series, "generation": generation, "value": value })))
+                .collect();
+
+            let (_, y_desc) = Simulation::value_names(statistic_plotted, &first.country_data);
+            let spec = json!({
+                "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
+                "title": first.caption(&instance.name, self.number_runs, &self.tags, &self.notes),
+                "usermeta": {
+                    "build": BuildInfo::current(),
+                    "parameters": first.parameters,
+                    "tags": self.tags,
+                    "description": self.notes.description,
+                    "author": self.notes.author,
+                },
+                "width": 800,
+                "height": 450,
+                "data": { "values": values },
+                "mark": { "type": "line" },
+                "encoding": {
+                    "x": { "field": "generation", "type": "quantitative", "title": "Generations Passed" },
+                    "y": { "field": "value", "type": "quantitative", "title": y_desc },
+                    "color": { "field": "series", "type": "nominal", "title": null },
+                },
+            });
+
+            // Save the spec next to the chart of the country
+            let path = PathBuf::from(format!(
+                "results/chart-{}-({}).vl.json",
+                time.format("%Y-%m-%d-%H-%M-%S"),
+                instance.name,
+            ));
+            fs::write(&path, serde_json::to_string_pretty(&spec)?)?;
+            instance.artifacts.push(path.clone());
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Function to compare the best ever route of every country against its optimal tour, for
+    /// countries that have a `<name>.opt.tour` file in the given directory. The number of shared
+    /// edges and the edges that differ are printed, and a comparison plot can optionally be drawn
+    pub fn compare_with_optimal(
+        &mut self, 
+        directory: &Path, 
+        plot_comparison: bool, 
+        plot_theme: PlotTheme
+    ) -> Result<()> {
+        for instance in self.instances.iter_mut() {
+            // Skip countries whose runs all finished in an earlier invocation
+            let (Some(first), Some(best)) = (instance.simulations.first(), instance.best_ever()) else {
+                continue;
+            };
+
+            // Skip countries without a known optimal tour
+            let optimal = match OptimalTour::find(&first.country_data, directory)? {
+                Some(optimal) => optimal,
+                None => continue,
+            };
+            let comparison = EdgeComparison::new(&best.route, &optimal.route);
+
+            // Output how close the best route is to the optimal route
+            println!(
+                "Best tour of {} shares {} of {} edges with the optimal tour ({:.1}%)",
+                instance.name,
+                comparison.shared_edges,
+                comparison.total_edges,
+                comparison.shared_fraction() * 100.0,
+            );
+            if !comparison.extra_edges.is_empty() {
+                let edges = comparison.extra_edges
+                    .iter()
+                    .map(|(from, to)| format!("{}-{}", from, to))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                println!("Edges of {} not in the optimal tour: {}", instance.name, edges);
+            }
+
+            if plot_comparison {
+                instance.artifacts.push(comparison.plot(&optimal, plot_theme, &self.tags, &self.notes)?);
+            }
+
+            instance.optimal_comparison = Some(comparison);
+        }
+        Ok(())
+    }
+
+    /// Function to write the edges of the best ever route of every country to a CSV file, in the order they are
+    /// travelled along with the cost of the route so far, ranking the given number of most expensive edges. The
+    /// most expensive edges are also printed. Returns the path of each file written
+    pub fn export_edge_breakdown(&mut self, expensive: usize) -> Result<Vec<PathBuf>> {
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+        let time: DateTime<Utc> = Utc::now();
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for instance in self.instances.iter_mut() {
+            // Countries whose runs all finished in an earlier invocation have no graph to cost the route with
+            let (Some(first), Some(best)) = (instance.simulations.first(), instance.best_ever()) else {
+                continue;
+            };
+            let country = &first.country_data;
+            let breakdown: Vec<RouteEdge> = route_breakdown(&best.route, &country.graph)?;
+            let ranked: Vec<usize> = most_expensive_edges(&breakdown, expensive);
+
+            // Write header then one line per edge, leaving the rank empty for edges that aren't ranked
+            let mut output = String::from("position,from,to,cost,cumulative,expensive_rank\n");
+            for (position, edge) in breakdown.iter().enumerate() {
+                let rank: String = ranked
+                    .iter()
+                    .position(|ranked| *ranked == position)
+                    .map_or_else(String::new, |rank| (rank + 1).to_string());
+                writeln!(output, "{},{},{},{},{},{}", position, edge.from, edge.to, edge.cost, edge.cumulative, rank)?;
+            }
+
+            let path = PathBuf::from(format!(
+                "results/edge-breakdown-{}-({}).csv",
+                time.format("%Y-%m-%d-%H-%M-%S"),
+                instance.name,
+            ));
+            fs::write(&path, output)?;
+
+            // Output where the cost of the route is concentrated
+            if !ranked.is_empty() {
+                let edges = ranked
+                    .iter()
+                    .map(|position| {
+                        let edge: &RouteEdge = &breakdown[*position];
+                        format!(
+                            "{}-{} ({}, {:.1}%)",
+                            edge.from,
+                            edge.to,
+                            country.format_cost(edge.cost),
+                            edge.cost / best.cost * 100.0
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                println!("Most expensive edges of the best tour of {}: {}", instance.name, edges);
+            }
+            instance.artifacts.push(path.clone());
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Function to write the summary of every run to a CSV file in the results directory,
+    /// returning the path of the file
+    pub fn export_summary(&mut self) -> Result<PathBuf> {
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+
+        // Generate unique path for the summary using date and time
+        let time: DateTime<Utc> = Utc::now();
+        let path = PathBuf::from(format!(
+            "results/summary-{}.csv",
+            time.format("%Y-%m-%d-%H-%M-%S"),
+        ));
+
+        // Write header then one line per run. New columns go at the end, so scripts reading the columns by
+        // position keep working
+        let mut output = String::from(
+            "instance,run,best_cost,worst_cost,average_cost,best_route,evaluations,tags,seconds,build,description,author,\
+            progress_seconds,cancelled\n"
+        );
+        let tags: String = self.tags.join(";");
+        let description: String = csv_field(self.notes.description.as_deref().unwrap_or_default());
+        let author: String = csv_field(self.notes.author.as_deref().unwrap_or_default());
+        for instance in &self.instances {
+            for summary in &instance.summaries {
+                let route = summary.best_route
+                    .iter()
+                    .map(|city| city.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                writeln!(
+                    output,
+                    "{},{},{},{},{},{},{},{},{:.3},{},{},{},{:.6},{}",
+                    instance.name,
+                    summary.run,
+                    summary.best_cost,
+                    summary.worst_cost,
+                    summary.average_cost,
+                    route,
+                    summary.evaluations,
+                    tags,
+                    summary.seconds,
+                    summary.build,
+                    description,
+                    author,
+                    summary.progress_seconds,
+                    summary.cancelled,
+                )?;
+            }
+        }
+        fs::write(&path, output)?;
+
+        self.artifacts.push(path.clone());
+        Ok(path)
+    }
+}
+
+/// Function to write a value as a field of a CSV file, quoting it if it contains a comma or a quote
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,111 @@
+//! This module defines [`RunLog`], the log file a single run writes its events to when `--log-dir` is given,
+//! such as each new best cost, changes made through the control file and the warnings of the run. Each line is
+//! written as soon as it happens, so the log of a run that crashed still has everything up to the crash. Once a
+//! log grows past [`MAX_LOG_BYTES`] it is rotated, keeping the last [`LOG_BACKUPS`] full logs alongside it.
+//! Every log starts with a line describing the build that wrote it and the tags of the experiment, as does each
+//! session appended to it.
+
+use std::{
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::{LineWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::prelude::*;
+use color_eyre::{eyre::WrapErr, Result};
+
+use super::build_info::BuildInfo;
+
+/// The size a log can grow to before it is rotated
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// The number of rotated logs kept for each run, the oldest is deleted when another is rotated
+pub const LOG_BACKUPS: u32 = 3;
+
+/// The log file of a single run
+#[derive(Debug)]
+pub struct RunLog {
+    /// Where the current log is
+    pub path: PathBuf,
+    /// The open current log, written a line at a time
+    file: LineWriter<File>,
+    /// The size of the current log
+    written: u64,
+    /// Freeform tags attached to the run, written in the header of the log
+    tags: Vec<String>,
+}
+
+/// Implement methods on `RunLog`
+impl RunLog {
+    /// Function to open the log of a run of a country in a directory, creating the directory if needed. The log is
+    /// named after the country and run, e.g. `burma14-run1.log`, and is appended to if it already exists.
+    /// A line describing the build and the tags of the run is written as soon as it is opened
+    pub fn open(directory: &Path, instance: &str, run: u32, tags: &[String]) -> Result<Self> {
+        fs::create_dir_all(directory)
+            .wrap_err_with(|| format!("Failed to create log directory {}", directory.display()))?;
+        let path: PathBuf = directory.join(format!("{}-run{}.log", instance, run));
+        let file: File = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .wrap_err_with(|| format!("Failed to open log {}", path.display()))?;
+        let written: u64 = file.metadata()?.len();
+        let mut log: RunLog = Self { path, file: LineWriter::new(file), written, tags: tags.to_vec() };
+        log.write_header()?;
+        Ok(log)
+    }
+
+    /// Function to write the line describing the build that is running and the tags of the run, so every log says
+    /// what wrote it
+    fn write_header(&mut self) -> Result<()> {
+        match self.tags.is_empty() {
+            true => self.write(format!("Build: {}", BuildInfo::current())),
+            false => self.write(format!("Build: {}, Tags: {}", BuildInfo::current(), self.tags.join(";"))),
+        }
+    }
+
+    /// Function to write a line to the log, starting with the time it was written. The log is rotated first if
+    /// the line would take it past [`MAX_LOG_BYTES`]
+    pub fn write(&mut self, message: impl Display) -> Result<()> {
+        let line: String = format!("{} {}\n", Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"), message);
+        if self.written > 0 && self.written + line.len() as u64 > MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        self.file
+            .write_all(line.as_bytes())
+            .wrap_err_with(|| format!("Failed to write to log {}", self.path.display()))?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    /// Function to return the path of a rotated log, where 1 is the most recently rotated
+    pub fn backup_path(&self, backup: u32) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", backup));
+        PathBuf::from(name)
+    }
+
+    /// Function to move the current log to the first backup, moving every backup along one and deleting the
+    /// oldest, then start a new empty log
+    fn rotate(&mut self) -> Result<()> {
+        self.file.flush()?;
+        let oldest: PathBuf = self.backup_path(LOG_BACKUPS);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for backup in (1..LOG_BACKUPS).rev() {
+            let from: PathBuf = self.backup_path(backup);
+            if from.exists() {
+                fs::rename(&from, self.backup_path(backup + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.backup_path(1))
+            .wrap_err_with(|| format!("Failed to rotate log {}", self.path.display()))?;
+
+        let file: File = File::create(&self.path).wrap_err_with(|| format!("Failed to open log {}", self.path.display()))?;
+        self.file = LineWriter::new(file);
+        self.written = 0;
+        self.write_header()
+    }
+}
@@ -0,0 +1,139 @@
+//! This module lets library users compare several operator configurations on one country in a single call.
+//! [`run_matrix`] runs every [`OperatorConfig`] the given number of times on the same parsed [`Country`],
+//! spreading the runs of each configuration across the CPUs, and returns the runs grouped by configuration
+//! as [`ConfigResults`].
+
+use std::{collections::VecDeque, sync::Arc, thread};
+
+use color_eyre::{eyre::WrapErr, Result};
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    country::Country,
+    interface::{CrossoverOperator, MutationOperator, RunParameters},
+    orchestrator::{spawn_runs, FinishedRun, QueuedRun, RunSettings, Scheduling},
+    random::derive_seed,
+    simulation::Simulation,
+};
+
+/// A named set of parameters to run, usually differing from the others compared only in their operators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorConfig {
+    /// The name the configuration is reported under
+    pub name: String,
+    /// The parameters every run of the configuration uses
+    pub parameters: RunParameters,
+}
+
+/// Implement methods on `OperatorConfig`
+impl OperatorConfig {
+    /// Function to name a set of parameters
+    pub fn new(name: impl Into<String>, parameters: RunParameters) -> Self {
+        Self { name: name.into(), parameters }
+    }
+
+    /// Function to use the given crossover and mutation operator with the rest of the parameters, named after
+    /// the two operators such as `Ordered + Inversion`
+    pub fn with_operators(
+        parameters: RunParameters,
+        crossover_operator: CrossoverOperator,
+        mutation_operator: MutationOperator,
+    ) -> Self {
+        Self {
+            name: format!("{:?} + {:?}", crossover_operator, mutation_operator),
+            parameters: RunParameters { crossover_operator, mutation_operator, ..parameters },
+        }
+    }
+
+    /// Function to check the parameters of the configuration, naming the configuration along with every problem
+    /// found with its parameters
+    pub fn validate(&self) -> Result<()> {
+        self.parameters
+            .validate()
+            .wrap_err_with(|| format!("The parameters of configuration {} are invalid", self.name))
+    }
+}
+
+/// Every run of a single configuration
+pub struct ConfigResults {
+    /// The configuration that was run
+    pub config: OperatorConfig,
+    /// Each run once it has finished, in run order
+    pub simulations: Vec<Simulation>,
+}
+
+/// Implement methods on `ConfigResults`
+impl ConfigResults {
+    /// Function to list the cost of the cheapest tour found by each run, in run order
+    pub fn best_costs(&self) -> Result<Vec<f64>> {
+        self.simulations
+            .iter()
+            .map(|simulation| Ok(simulation.report()?.best_cost()))
+            .collect()
+    }
+
+    /// Function to find the average of the cheapest tour found by each run, or None if there were no runs
+    pub fn mean_best_cost(&self) -> Result<Option<f64>> {
+        let costs: Vec<f64> = self.best_costs()?;
+        if costs.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(costs.iter().sum::<f64>() / costs.len() as f64))
+    }
+}
+
+/// Function to run every configuration the given number of times on the same country, returning the runs of each
+/// configuration in the order the configurations were given. Every configuration is checked before any run starts.
+/// The country is set up once for each configuration and shared read-only between its runs. The runs of each
+/// configuration are shared between one worker per CPU, and any run that fails stops the matrix with its error.
+/// With a seed, every run gets its own seed derived from it, so calling it again with the same seed gives the
+/// same results
+pub fn run_matrix(
+    instance: &Country,
+    configs: Vec<OperatorConfig>,
+    runs: u32,
+    seed: Option<u64>,
+) -> Result<Vec<ConfigResults>> {
+    for config in configs.iter() {
+        config.validate()?;
+    }
+    let workers: usize = thread::available_parallelism().map(|cpus| cpus.get()).unwrap_or(1);
+
+    let mut results: Vec<ConfigResults> = Vec::with_capacity(configs.len());
+    for (position, config) in configs.into_iter().enumerate() {
+        // Set up the graph for the configuration, so its runs can share it without copying it
+        let mut configured: Country = instance.clone();
+        configured.graph.configure(&config.parameters);
+        let country: Arc<Country> = Arc::new(configured);
+
+        // Queue every run of the configuration, numbered by its position in the queue
+        let config_seed: Option<u64> = seed.map(|seed| derive_seed(seed, position as u64));
+        let queue: VecDeque<QueuedRun> = (0..runs as usize)
+            .map(|index| QueuedRun {
+                index,
+                run: index as u32 + 1,
+                country: Arc::clone(&country),
+                placement: None,
+                seed: config_seed.map(|seed| derive_seed(seed, index as u64)),
+            })
+            .collect();
+        let settings = RunSettings { parameters: config.parameters, ..Default::default() };
+        let stream = spawn_runs(
+            queue,
+            &settings,
+            Scheduling::Batch { workers, cores: Vec::new() },
+            ProgressBar::hidden,
+        );
+
+        // The runs finish in any order, so put them back in run order
+        let mut finished: Vec<FinishedRun> = stream.collect()?;
+        finished.sort_by_key(|run| run.index);
+
+        results.push(ConfigResults {
+            config,
+            simulations: finished.into_iter().map(|run| run.simulation).collect(),
+        });
+    }
+    Ok(results)
+}
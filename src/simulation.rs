@@ -1,124 +1,1212 @@
 //! This module defines the structure [`Simulation`] and methods for the Simulation of the [`Population`].
 
-use color_eyre::{Result, eyre::ContextCompat};
+use color_eyre::{Result, eyre::{bail, ContextCompat}};
 use chrono::prelude::*;
 use indicatif::ProgressBar;
 use plotters::prelude::*;
+use std::{cmp::Ordering, collections::{BTreeSet, VecDeque}, fmt::Display, path::PathBuf, sync::Arc, thread, time::{Duration, Instant}};
 
 use super::{
-    chromosome::Chromosome, 
+    adaptive::OperatorStatistics,
+    baseline::RandomSearch,
+    build_info::BuildInfo,
+    cancellation::CancellationToken,
+    chromosome::{Chromosome, DEFAULT_COST_EPSILON, MINIMUM_CITIES},
+    control::{apply_setting, ControlFile, ParameterChange},
     country::Country, 
+    hillclimb::HillClimber,
     interface::*,
+    manifest::ExperimentNotes,
     population::Population,
-    NUMBER_OF_GENERATIONS
+    repair::ForbiddenEdgeRepair,
+    runlog::RunLog,
+    snapshot::PopulationSnapshot,
+    solver::{Solver, SolverReport, TrajectoryPoint},
+    warnings::{WarningKind, Warnings},
 };
 
+/// The diversity below which a population is taken to have converged
+pub const CONVERGED_DIVERSITY: f64 = 0.05;
+
+/// How far above the optimal cost the best tour of a converged population can be before it converged too early
+pub const OPTIMAL_GAP: f64 = 0.05;
+
+/// How far above the lower bound of a country the best tour of a converged population can be before it converged
+/// too early. The bound is often a third below the optimal cost, so this is much looser than [`OPTIMAL_GAP`]
+pub const BOUND_GAP: f64 = 0.5;
+
+/// How often the progress bar of a run is updated without `--progress-every`, the rate indicatif redraws at
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The number of generations the improvement rate of the best ever cost is measured over
+pub const IMPROVEMENT_WINDOW: u32 = 100;
+
+/// A labelled line of (generation, value) coordinates drawn on a plot
+pub type PlotSeries = (String, Vec<(f32, f32)>);
+
 /// The `Simulation` type, which contains all the information needed to run the simulation
 pub struct Simulation {
-    /// Data for the country
-    pub country_data: Country,
+    /// Data for the country, shared read-only with the other runs of the country set up the same way
+    pub country_data: Arc<Country>,
     /// The actual population of chromosomes for the simulation
     pub population: Population,
-    /// Crossover operator: 0 = crossover with fix, 1 = ordered crossover.
-    pub crossover_operator: CrossoverOperator,
-    /// Mutation operator: 0 = inversion, 1 = single swap mutation, 2 = multiple swap mutation
-    pub mutation_operator: MutationOperator,
-    /// Population size: Minimum 10, Default 50.
-    pub population_size: u64,
-    /// Tournament size: Minimum 2, Default 5.
-    pub tournament_size: u32,
-    /// Number of generations to run simulation for.
-    pub generations: u32,
+    /// The hill climber run instead of the genetic algorithm, starting from the best of the population, if it is chosen
+    pub hill_climber: Option<HillClimber>,
+    /// The operators and sizes chosen for this simulation
+    pub parameters: RunParameters,
     /// A vector containing the best Chromosome of a generation
     pub best_chromosome: Vec<Chromosome>,
     /// A vector containing the worse Chromosome of a generation
     pub worst_chromosome: Vec<Chromosome>,
     /// A vector containing the average cost of a generation
     pub average_cost: Vec<f64>,
+    /// A vector containing the generation each entry in the stat vectors was recorded at
+    pub recorded_generations: Vec<u32>,
+    /// A vector containing the number of fitness evaluations used by the time each entry in the stat vectors was recorded
+    pub recorded_evaluations: Vec<u64>,
+    /// A vector containing the standard deviation of the costs of a generation, or None if it wasn't recorded
+    pub cost_deviation: Vec<Option<f64>>,
+    /// A vector containing the entropy of the edge distribution of a generation, or None if it wasn't recorded
+    pub entropy: Vec<Option<f64>>,
+    /// A vector containing the average fraction of edges two tours of a generation don't share, or None if it wasn't recorded
+    pub diversity: Vec<Option<f64>>,
+    /// A vector containing the tournament size parents were selected with in a generation
+    pub tournament_sizes: Vec<u32>,
+    /// How spread out the costs of the initial population were, which an adaptive tournament size is measured against
+    pub initial_cost_spread: f64,
+    /// File checked between generations for changes to the parameters, if any
+    pub control_file: Option<ControlFile>,
+    /// File the events of the run are logged to, if any
+    pub log: Option<RunLog>,
+    /// Every change made to the parameters while running, in the order they were made
+    pub parameter_changes: Vec<ParameterChange>,
+    /// The generations to take a snapshot of the population at
+    pub snapshot_generations: BTreeSet<u32>,
+    /// Snapshots of the population taken so far, in generation order
+    pub snapshots: Vec<PopulationSnapshot>,
+    /// Random search given the same number of fitness evaluations as the population, if it is run
+    pub random_search: Option<RandomSearch>,
+    /// A vector containing the best cost found by the random search by each generation, or None if it isn't run
+    pub random_search_cost: Vec<Option<f64>>,
+    /// How long the simulation took to run
+    pub elapsed: Duration,
+    /// How much of the run was spent updating its progress bar
+    pub progress_overhead: Duration,
+    /// Problems found while running that didn't stop the run
+    pub warnings: Warnings,
+    /// Stops the run at the end of the generation it is cancelled in
+    pub cancellation: CancellationToken,
+    /// Whether the run was stopped by its cancellation token before its last generation
+    pub cancelled: bool,
+    /// How often each crossover operator was used and succeeded, empty unless operators are adaptive
+    pub crossover_statistics: Vec<OperatorStatistics>,
+    /// How often each mutation operator was used and succeeded, empty unless operators are adaptive
+    pub mutation_statistics: Vec<OperatorStatistics>,
+    /// The edges whose frequency in the population is tracked, each with its lower numbered city first
+    pub tracked_edges: Vec<(u32, u32)>,
+    /// A vector containing the fraction of the population holding each tracked edge in a generation, or None if
+    /// no edges are tracked or the algorithm has no population
+    pub edge_frequencies: Vec<Option<Vec<f64>>>,
+    /// A vector containing how much the best ever cost fell over the last [`IMPROVEMENT_WINDOW`] generations,
+    /// at each recorded generation
+    pub improvement_rate: Vec<f64>,
+    /// The best ever cost at each of the last [`IMPROVEMENT_WINDOW`] generations and the one before them, oldest
+    /// first, so the improvement rate of each generation is found without looking back through every generation
+    pub recent_best_costs: VecDeque<f64>,
 }
 
-/// Implement Methods on the [`Simulation`] type
-impl Simulation {
-    /// This function creates a new [`Simulation`] with a random [`Population`]
+/// The stats of a single generation, before they are added to the stat vectors of a [`Simulation`]
+#[derive(Clone, Debug)]
+pub struct GenerationStats {
+    /// The generation the stats were taken at
+    pub generation: u32,
+    /// The number of fitness evaluations used by this generation
+    pub evaluations: u64,
+    /// The best Chromosome of the generation
+    pub best: Chromosome,
+    /// The worst Chromosome of the generation
+    pub worst: Chromosome,
+    /// The average cost of the generation
+    pub average: f64,
+    /// The standard deviation of the costs of the generation, if the metrics level records it
+    pub cost_deviation: Option<f64>,
+    /// The entropy of the edge distribution of the generation, if the metrics level records it
+    pub entropy: Option<f64>,
+    /// The average fraction of edges two tours of the generation don't share, if the metrics level records it
+    pub diversity: Option<f64>,
+    /// The tournament size parents were selected with in the generation
+    pub tournament_size: u32,
+    /// The best cost found by the random search by this generation, if it is run
+    pub random_search_cost: Option<f64>,
+    /// The fraction of the population holding each tracked edge, if any edges are tracked
+    pub edge_frequencies: Option<Vec<f64>>,
+    /// How much the best ever cost fell over the last [`IMPROVEMENT_WINDOW`] generations, 0 until it is set
+    pub improvement_rate: f64,
+}
+
+/// Implement Methods on the [`GenerationStats`] type
+impl GenerationStats {
+    /// This function takes the stats of the population at the given generation, only calculating 
+    /// the statistics the [`MetricsLevel`] asks for, along with the tournament size it was selected with, the best
+    /// cost of the random search if it is run and the frequency of the tracked edges if there are any
     pub fn new(
-        country_data: Country,
-        crossover_operator: CrossoverOperator,
-        mutation_operator: MutationOperator,
-        population_size: u64,
+        generation: u32, 
+        solver: &dyn Solver, 
+        metrics: MetricsLevel, 
         tournament_size: u32,
-    ) -> Result<Self> {
-        let new_population = Population::new(population_size, &country_data.graph)?;
+        random_search: Option<&RandomSearch>,
+        tracked_edges: &[(u32, u32)],
+    ) -> Self {
+        Self {
+            generation,
+            evaluations: solver.evaluations(),
+            best: solver.best().clone(),
+            worst: solver.worst().clone(),
+            average: solver.average_cost(),
+            cost_deviation: (metrics >= MetricsLevel::Standard).then(|| solver.cost_deviation()).flatten(),
+            entropy: (metrics >= MetricsLevel::Full).then(|| solver.edge_entropy()).flatten(),
+            diversity: (metrics >= MetricsLevel::Full).then(|| solver.diversity()).flatten(),
+            tournament_size,
+            random_search_cost: random_search.map(|search| search.best.cost),
+            edge_frequencies: (!tracked_edges.is_empty()).then(|| solver.edge_frequencies(tracked_edges)).flatten(),
+            improvement_rate: 0.0,
+        }
+    }
+}
+
+/// The colours, fonts and line widths used to draw a plot for a [`PlotTheme`]
+#[derive(Debug, Copy, Clone)]
+pub struct ThemeStyle {
+    /// Colour the plot is filled with
+    pub background: RGBColor,
+    /// Colour of text, axes and legend borders
+    pub foreground: RGBColor,
+    /// Colour of the grid lines
+    pub grid: RGBColor,
+    /// Font family used for all text
+    pub font: &'static str,
+    /// Font size of the caption
+    pub caption_size: u32,
+    /// Font size of axis labels, descriptions and legends
+    pub label_size: u32,
+    /// Width of the plotted lines
+    pub line_width: u32,
+    /// Colour of a single line, or the worst line when several are drawn
+    pub primary: RGBColor,
+    /// Colour of the average line when several are drawn
+    pub secondary: RGBColor,
+    /// Colour of the best line when several are drawn
+    pub tertiary: RGBColor,
+}
 
-        // Allocate these vectors now with the correct capacity so they don't keep reallocating as they grow.
+/// Implement Methods on the [`ThemeStyle`] type
+impl ThemeStyle {
+    /// This function returns the style for the given [`PlotTheme`]
+    pub fn new(plot_theme: PlotTheme) -> Self {
+        match plot_theme {
+            PlotTheme::Light => Self {
+                background: WHITE,
+                foreground: BLACK,
+                grid: RGBColor(204, 204, 204),
+                font: "sans-serif",
+                caption_size: 30,
+                label_size: 20,
+                line_width: 2,
+                primary: RED,
+                secondary: BLUE,
+                tertiary: GREEN,
+            },
+            PlotTheme::Dark => Self {
+                background: RGBColor(32, 32, 32),
+                foreground: RGBColor(230, 230, 230),
+                grid: RGBColor(80, 80, 80),
+                font: "sans-serif",
+                caption_size: 30,
+                label_size: 20,
+                line_width: 3,
+                primary: RGBColor(255, 99, 71),
+                secondary: RGBColor(100, 181, 246),
+                tertiary: RGBColor(129, 199, 132),
+            },
+            // Uses the Okabe-Ito colour-blind friendly palette
+            PlotTheme::Report => Self {
+                background: WHITE,
+                foreground: BLACK,
+                grid: RGBColor(200, 200, 200),
+                font: "serif",
+                caption_size: 36,
+                label_size: 28,
+                line_width: 4,
+                primary: RGBColor(213, 94, 0),
+                secondary: RGBColor(0, 114, 178),
+                tertiary: RGBColor(0, 158, 115),
+            },
+        }
+    }
+}
+
+/// Implement Methods on the [`Simulation`] type
+impl Simulation {
+    /// This function creates a new [`Simulation`] with a random [`Population`]. The country can be shared with
+    /// other runs, and is only copied if its graph isn't already set up for the parameters
+    pub fn new(country_data: impl Into<Arc<Country>>, parameters: RunParameters) -> Result<Self> {
+        // Set the graph up for the parameters before any route is costed, so the candidate lists and summation
+        // are those recorded with the parameters rather than whatever the command line says when resuming
+        let mut country_data: Arc<Country> = country_data.into();
+        if !country_data.graph.is_configured(&parameters) {
+            Arc::make_mut(&mut country_data).graph.configure(&parameters);
+        }
+
+        // A country of fewer than 3 cities is too small for the operators to pick genes to change
+        let cities: usize = country_data.graph.vertex.len();
+        if cities < MINIMUM_CITIES {
+            bail!(
+                "{} has {} cities, but at least {} are needed to run the genetic operators on it",
+                country_data.name, cities, MINIMUM_CITIES
+            );
+        }
+
+        // Costs are compared exactly unless the dataset has costs that aren't whole numbers, or a tolerance was given
+        let cost_epsilon: f64 = parameters.cost_epsilon.unwrap_or(
+            if country_data.graph.has_integral_costs() { 0.0 } else { DEFAULT_COST_EPSILON }
+        );
+        let new_population = Population::new(parameters.population_size, &country_data.graph)?
+            .with_elitism(parameters.elitism as usize)
+            .with_cost_epsilon(cost_epsilon)
+            .with_max_evaluations(parameters.max_evaluations)
+            .with_adaptive_operators(parameters.adaptive_operators);
+
+        // The random search starts from the same random tours as the population, before any are replaced by
+        // nearest neighbour tours
+        let random_search: Option<RandomSearch> = parameters.random_baseline.then(|| RandomSearch::new(&new_population));
+        let mut new_population = new_population
+            .with_nearest_neighbour_tours(parameters.nearest_neighbour_fraction, &country_data.graph)?;
+
+        // Children are kept to the edges of datasets where some cities have no edge between them, where possible
+        if country_data.graph.forbidden_edges() > 0 {
+            new_population = new_population.with_repair(Arc::new(ForbiddenEdgeRepair), &country_data.graph)?;
+        }
+
+        // Allocate these vectors now with the capacity needed for every stride so they don't keep reallocating as they grow.
         // They are + 1 because the population starts with these all having one value in them already
-        let mut best_chromosome: Vec<Chromosome> =
-            Vec::with_capacity(NUMBER_OF_GENERATIONS + 1);
-        let mut worst_chromosome: Vec<Chromosome> =
-            Vec::with_capacity(NUMBER_OF_GENERATIONS + 1);
-        let mut average_cost: Vec<f64> = Vec::with_capacity(NUMBER_OF_GENERATIONS + 1);
+        let capacity: usize = parameters.generations as usize / parameters.record_stride.max(1) as usize + 1;
+        let initial_stats = GenerationStats::new(
+            0,
+            &new_population,
+            parameters.metrics,
+            parameters.tournament_size,
+            random_search.as_ref(),
+            &[]
+        );
+        let initial_cost_spread: f64 = new_population.cost_spread();
+        let mut recent_best_costs: VecDeque<f64> = VecDeque::with_capacity(IMPROVEMENT_WINDOW as usize + 1);
+        recent_best_costs.push_back(initial_stats.best.cost);
 
-        best_chromosome.push(new_population.best_chromosome.clone());
-        worst_chromosome.push(new_population.worst_chromosome.clone());
-        average_cost.push(new_population.average_population_cost);
+        // The hill climber starts from the best of the initial tours of the population
+        let hill_climber: Option<HillClimber> = (parameters.algorithm == Algorithm::Hillclimb)
+            .then(|| HillClimber::new(&new_population));
 
-        Ok(Simulation {
+        let mut simulation = Simulation {
             country_data,
             population: new_population,
-            crossover_operator,
-            mutation_operator,
-            population_size,
-            tournament_size,
-            generations: NUMBER_OF_GENERATIONS as u32,
-            best_chromosome,
-            worst_chromosome,
-            average_cost,
-        })
+            hill_climber,
+            parameters,
+            best_chromosome: Vec::with_capacity(capacity),
+            worst_chromosome: Vec::with_capacity(capacity),
+            average_cost: Vec::with_capacity(capacity),
+            recorded_generations: Vec::with_capacity(capacity),
+            recorded_evaluations: Vec::with_capacity(capacity),
+            cost_deviation: Vec::with_capacity(capacity),
+            entropy: Vec::with_capacity(capacity),
+            diversity: Vec::with_capacity(capacity),
+            tournament_sizes: Vec::with_capacity(capacity),
+            initial_cost_spread,
+            control_file: None,
+            log: None,
+            parameter_changes: Vec::new(),
+            snapshot_generations: BTreeSet::new(),
+            snapshots: Vec::new(),
+            random_search,
+            random_search_cost: Vec::with_capacity(capacity),
+            elapsed: Duration::ZERO,
+            progress_overhead: Duration::ZERO,
+            warnings: Warnings::default(),
+            cancellation: CancellationToken::new(),
+            cancelled: false,
+            crossover_statistics: Vec::new(),
+            mutation_statistics: Vec::new(),
+            tracked_edges: Vec::new(),
+            edge_frequencies: Vec::with_capacity(capacity),
+            improvement_rate: Vec::with_capacity(capacity),
+            recent_best_costs,
+        };
+        simulation.record(initial_stats);
+
+        // 2-opt assumes a reversed section of the route costs the same, so it is skipped on asymmetric graphs
+        if parameters.local_search == Some(LocalSearch::TwoOpt) && !simulation.country_data.graph.symmetric {
+            simulation.warnings.push(
+                WarningKind::Configuration,
+                &simulation.country_data.name,
+                "the costs differ by direction, so the 2-opt local search is skipped, consider --local-search or-opt",
+            );
+        }
+
+        Ok(simulation)
+    }
+
+    /// This function returns the algorithm being run, the hill climber if it was chosen, otherwise the population
+    pub fn solver(&self) -> &dyn Solver {
+        match &self.hill_climber {
+            Some(hill_climber) => hill_climber,
+            None => &self.population,
+        }
+    }
+
+    /// This function sets the file checked between generations for changes to the parameters
+    pub fn with_control_file(mut self, path: Option<PathBuf>) -> Self {
+        self.control_file = path.map(ControlFile::new);
+        self
+    }
+
+    /// This function sets the log the events of the run are written to, if any
+    pub fn with_log(mut self, log: Option<RunLog>) -> Self {
+        self.log = log;
+        self
+    }
+
+    /// This function writes a line to the log of the run, if it has one. A log that can't be written to is
+    /// closed and added to the warnings of the run rather than stopping it
+    fn log(&mut self, message: impl Display) {
+        let Some(log) = self.log.as_mut() else {
+            return;
+        };
+        if let Err(report) = log.write(message) {
+            self.warnings.push(WarningKind::Log, &self.country_data.name, format!("{:#}", report));
+            self.log = None;
+        }
+    }
+
+    /// This function sets the token that stops the run early, which is also checked by the local search of children
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.population.cancellation = cancellation.clone();
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// This function sets the generations to take a snapshot of the population at, taking one straight away
+    /// if the initial population is wanted
+    pub fn with_snapshots(mut self, generations: &[u32]) -> Self {
+        self.snapshot_generations = generations.iter().copied().collect();
+        if self.snapshot_generations.contains(&0) {
+            self.snapshots.push(PopulationSnapshot::new(&self.country_data.name, 0, &self.population, self.parameters));
+        }
+        self
+    }
+
+    /// This function checks every edge joins two different cities of the country, returning the edges without repeats.
+    /// Edges are undirected, so each is returned with its lower numbered city first
+    pub fn normalise_edges(edges: &[(u32, u32)], country_data: &Country) -> Result<Vec<(u32, u32)>> {
+        let cities: usize = country_data.graph.vertex.len();
+        let mut normalised: Vec<(u32, u32)> = Vec::with_capacity(edges.len());
+        for &(from, to) in edges {
+            if from == to || from as usize >= cities || to as usize >= cities {
+                bail!("Edge {}-{} isn't an edge between two cities of {}", from, to, country_data.name);
+            }
+            let edge: (u32, u32) = (from.min(to), from.max(to));
+            if !normalised.contains(&edge) {
+                normalised.push(edge);
+            }
+        }
+        Ok(normalised)
+    }
+
+    /// This function records the parents of every child that joins the population, if asked to. The hill climber
+    /// has no population, so has no genealogy
+    pub fn with_genealogy(mut self, genealogy: bool) -> Self {
+        self.population = self.population.with_genealogy(genealogy && self.hill_climber.is_none());
+        if let Some(recorded) = self.population.genealogy.as_mut() {
+            recorded.instance = self.country_data.name.clone();
+        }
+        self
+    }
+
+    /// This function sets the edges whose frequency in the population is recorded each generation, replacing the
+    /// frequencies of the initial population
+    pub fn with_tracked_edges(mut self, edges: &[(u32, u32)]) -> Result<Self> {
+        self.tracked_edges = Simulation::normalise_edges(edges, &self.country_data)?;
+        let frequencies = (!self.tracked_edges.is_empty())
+            .then(|| self.solver().edge_frequencies(&self.tracked_edges))
+            .flatten();
+        if let Some(initial) = self.edge_frequencies.first_mut() {
+            *initial = frequencies;
+        }
+        Ok(self)
+    }
+
+    /// This function applies any changes made to the control file since it was last read, recording each
+    /// change made. Problems with the file are added to the warnings of the run rather than stopping it
+    fn apply_control_file(&mut self, generation: u32, progress_bar: &ProgressBar) {
+        let Some(control_file) = self.control_file.as_mut() else {
+            return;
+        };
+        let settings: Vec<(String, String)> = match control_file.read_if_changed() {
+            Ok(Some(settings)) => settings,
+            Ok(None) => return,
+            Err(report) => {
+                self.warnings.push(
+                    WarningKind::ControlFile,
+                    &self.country_data.name,
+                    format!("generation {}: {}", generation, report)
+                );
+                return;
+            }
+        };
+
+        let population_size: u64 = self.population.population_data.len() as u64;
+        for (setting, value) in settings {
+            match apply_setting(&mut self.parameters, &setting, &value, population_size) {
+                Ok(true) => {
+                    // Changes go to the log of the run if it has one, to keep the console clear
+                    let change: String = format!(
+                        "{}: {} set to {} at generation {}",
+                        self.country_data.name, setting, value, generation
+                    );
+                    if self.log.is_some() {
+                        self.log(change);
+                    } else {
+                        progress_bar.println(change);
+                    }
+                    self.parameter_changes.push(ParameterChange { generation, setting, value });
+                },
+                Ok(false) => {},
+                Err(problem) => self.warnings.push(
+                    WarningKind::ControlFile,
+                    &self.country_data.name,
+                    format!("generation {}: {}", generation, problem)
+                ),
+            }
+        }
+    }
+
+    /// This function returns the tournament size at the given generation, which changes linearly from the tournament
+    /// size to the final tournament size over the whole run if there is one. With a max tournament size instead, it
+    /// grows from the tournament size towards the maximum as the costs of the population become less spread out than
+    /// those of the initial population, so selection pressure is kept up as the population converges, and shrinks
+    /// again if they spread back out. It never exceeds the current population
+    pub fn scheduled_tournament_size(&self, generation: u32) -> u32 {
+        let start: u32 = self.parameters.tournament_size;
+        let size: u32 = match (self.parameters.final_tournament_size, self.parameters.max_tournament_size) {
+            (Some(end), _) => {
+                let last_generation: i64 = (self.parameters.generations.max(2) - 1) as i64;
+                let position: i64 = (generation as i64).min(last_generation);
+                (start as i64 + (end as i64 - start as i64) * position / last_generation) as u32
+            },
+            (None, Some(maximum)) => {
+                // The fraction of the initial spread the population has lost, all of it if it started with none
+                let converged: f64 = if self.initial_cost_spread > 0.0 {
+                    (1.0 - self.population.cost_spread() / self.initial_cost_spread).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                };
+                start + (maximum.saturating_sub(start) as f64 * converged).round() as u32
+            },
+            (None, None) => start,
+        };
+        size.min(self.population.population_data.len() as u32)
+    }
+
+    /// This function checks if the progress bar should be updated at the given generation, given how long ago it
+    /// was last updated. Updates are chunked every `--progress-every` generations if it is given, otherwise the bar
+    /// is updated once [`PROGRESS_INTERVAL`] has passed, so fast generations don't each pay for an update
+    pub fn progress_due(&self, generation: u32, since_update: Duration) -> bool {
+        match self.parameters.progress_every {
+            Some(every) => generation.is_multiple_of(every),
+            None => since_update >= PROGRESS_INTERVAL,
+        }
+    }
+
+    /// This function returns how long to wait before the given generation starts so the run keeps to
+    /// `--throttle`, given how long the run has taken so far. Generations are paced from the start of the run
+    /// rather than from each other, so a slow generation is made up for by not waiting before the next
+    pub fn throttle_delay(&self, generation: u32, elapsed: Duration) -> Option<Duration> {
+        let rate: f64 = self.parameters.throttle?;
+        let due: Duration = Duration::from_secs_f64(generation.saturating_sub(1) as f64 / rate);
+        due.checked_sub(elapsed).filter(|delay| !delay.is_zero())
+    }
+
+    /// This function returns the size the population should be at the given generation
+    /// according to the [`PopulationSchedule`] of the simulation
+    pub fn scheduled_population_size(&self, generation: u32) -> u64 {
+        let maximum: u64 = self.parameters.population_size;
+        let minimum: u64 = self.parameters.minimum_population.min(maximum);
+
+        match self.parameters.population_schedule {
+            // Size never changes
+            PopulationSchedule::Constant => maximum,
+            // Size falls linearly from the maximum to the minimum within each period,
+            // jumping back up to the maximum at the start of the next period
+            PopulationSchedule::SawTooth => {
+                let period: u64 = self.parameters.schedule_period.max(2) as u64;
+                let position: u64 = generation as u64 % period;
+                maximum - (maximum - minimum) * position / (period - 1)
+            },
+            // Size falls linearly from the maximum to the minimum over the whole run
+            PopulationSchedule::Shrinking => {
+                let last_generation: u64 = (self.parameters.generations.max(2) - 1) as u64;
+                let position: u64 = (generation as u64).min(last_generation);
+                maximum - (maximum - minimum) * position / last_generation
+            },
+        }
     }
 
     /// This function will run the simulation
     pub fn run(&mut self, progress_bar: ProgressBar) -> Result<()> {
+        // Time the whole run
+        let start: Instant = Instant::now();
+
         // Create counter variable
         let mut i: u32 = 1;
 
+        // Every stride'th generation is recorded, along with a window of generations either side
+        // of each improvement to the best ever cost
+        let stride: u32 = self.parameters.record_stride.max(1);
+        let window: u32 = self.parameters.record_window;
+        let mut best_ever_cost: f64 = self.solver().best().cost;
+        let mut record_until: u32 = 0;
+        // The most recent generations that were not recorded, oldest first
+        let mut recent: VecDeque<GenerationStats> = VecDeque::with_capacity(window as usize);
+
+        // The mutation rate used this generation, and how many generations the best ever cost hasn't improved for
+        let mut mutation_rate: f64 = self.parameters.mutation_rate;
+        let mut stagnant: u32 = 0;
+
+        // When the progress bar was last updated
+        let mut last_update: Instant = start;
+
+        let started: String = format!(
+            "Started {} with best cost {}, parameters {:?}",
+            self.country_data.name, self.country_data.format_cost(best_ever_cost), self.parameters
+        );
+        self.log(started);
+
         // Loop through this for as many generations as required
-        while i < self.generations {
-            // Update the population with new children generated from crossover
-            self.population.selection_and_replacement(
-                self.tournament_size,
-                self.crossover_operator,
-                self.mutation_operator,
-                &self.country_data.graph,
-            )?;
-
-            // Update all the stats
-            self.best_chromosome
-                .push(self.population.best_chromosome.clone());
-            self.worst_chromosome
-                .push(self.population.worst_chromosome.clone());
-            self.average_cost
-                .push(self.population.average_population_cost);
+        while i < self.parameters.generations {
+            // Pick up any changes to the parameters made while running
+            self.apply_control_file(i, &progress_bar);
+
+            // Children that join the population from now on are from this generation
+            if let Some(genealogy) = self.population.genealogy.as_mut() {
+                genealogy.generation = i;
+            }
+
+            // Grow or shrink the population if the schedule requires it, the hill climber doesn't use it
+            let scheduled_size: u64 = self.scheduled_population_size(i);
+            if self.hill_climber.is_none() && scheduled_size != self.population.population_data.len() as u64 {
+                self.population.resize(scheduled_size, &self.country_data.graph)?;
+            }
+
+            // Replace the most expensive Chromosomes with random immigrants at the end of every period
+            if self.hill_climber.is_none() && i > 0 && i.is_multiple_of(self.parameters.immigrant_period) {
+                self.population.add_immigrants(self.parameters.immigrant_rate, &self.country_data.graph)?;
+            }
+
+            // Update the population with new children generated from crossover, mutated at the current rate and
+            // selected with the tournament size of this generation. The rate can't fall below the chosen rate,
+            // which may have been changed by the control file
+            mutation_rate = mutation_rate.max(self.parameters.mutation_rate);
+            let parameters = RunParameters {
+                mutation_rate,
+                tournament_size: self.scheduled_tournament_size(i),
+                ..self.parameters
+            };
+            match self.hill_climber.as_mut() {
+                Some(hill_climber) => hill_climber.step(&parameters, &self.country_data.graph)?,
+                None => self.population.step(&parameters, &self.country_data.graph)?,
+            }
+
+            // Take a snapshot of the population if this generation is wanted
+            if self.hill_climber.is_none() && self.snapshot_generations.contains(&i) {
+                self.snapshots.push(PopulationSnapshot::new(&self.country_data.name, i, &self.population, self.parameters));
+                self.log(format!("Generation {}: took a snapshot of the population", i));
+            }
+
+            // Give the random search the evaluations the algorithm has used
+            let evaluations: u64 = self.solver().evaluations();
+            if let Some(random_search) = self.random_search.as_mut() {
+                random_search.sample_until(evaluations, &self.country_data.graph)?;
+            }
+
+            // Check if this generation found a new best ever Chromosome
+            let improved: bool = Chromosome::compare_costs(
+                self.solver().best().cost,
+                best_ever_cost,
+                self.population.cost_epsilon
+            ) == Ordering::Less;
+
+            // With adaptive mutation, raise the rate each time the population stagnates and lower it after an improvement
+            if let Some(limit) = self.parameters.stagnation_limit {
+                (mutation_rate, stagnant) = Simulation::adapt_mutation_rate(
+                    mutation_rate, 
+                    self.parameters.mutation_rate, 
+                    stagnant, 
+                    limit, 
+                    improved
+                );
+            }
+
+            if improved {
+                best_ever_cost = self.solver().best().cost;
+                let improvement: String = format!(
+                    "Generation {}: best cost improved to {} after {} evaluations",
+                    i, self.country_data.format_cost(best_ever_cost), evaluations
+                );
+                self.log(improvement);
+
+                // Record the generations held back before the improvement, and keep recording after it
+                for stats in recent.drain(..) {
+                    self.record(stats);
+                }
+                record_until = i.saturating_add(window);
+            }
+
+            // The run ends on the last generation, once the evaluation budget has been used up, or once it is cancelled
+            self.cancelled = self.cancellation.is_cancelled() && i + 1 < self.parameters.generations;
+            let finished: bool = i + 1 == self.parameters.generations || self.evaluation_budget_spent() || self.cancelled;
+
+            // Record the stats on every stride, inside an improvement window and on the final generation. The
+            // improvement rate is updated every generation, whether it is recorded or not, but the stats are only
+            // worked out for generations that are recorded or may be held back
+            let improvement_rate: f64 = self.update_improvement_rate(best_ever_cost);
+            let recorded: bool = i.is_multiple_of(stride) || i <= record_until || finished;
+            if recorded || window > 0 {
+                let current = GenerationStats {
+                    improvement_rate,
+                    ..GenerationStats::new(
+                        i,
+                        self.solver(),
+                        self.parameters.metrics,
+                        parameters.tournament_size,
+                        self.random_search.as_ref(),
+                        &self.tracked_edges
+                    )
+                };
+                if recorded {
+                    // Anything held back is older than this generation so can no longer be recorded in order
+                    recent.clear();
+                    self.record(current);
+                } else {
+                    // Hold back this generation in case an improvement happens soon
+                    if recent.len() == window as usize {
+                        recent.pop_front();
+                    }
+                    recent.push_back(current);
+                }
+            }
 
             // Increment the counter variable
             i += 1;
 
-            // Change the message displayed to show the current generation
-            progress_bar.set_message(format!("Generation {}", i));
-            // Set the position of the progress bar to the current generation
-            progress_bar.set_position(i as u64);
+            if finished {
+                break;
+            }
+
+            // Update the progress bar if it is due, timing the update so its overhead can be reported
+            if self.progress_due(i, last_update.elapsed()) {
+                let update_start: Instant = Instant::now();
+                // Change the message displayed to show the current generation
+                progress_bar.set_message(format!("Generation {}", i));
+                // Set the position of the progress bar to the current generation
+                progress_bar.set_position(i as u64);
+                last_update = Instant::now();
+                self.progress_overhead += last_update - update_start;
+            }
+
+            // Wait for the next generation to be due when throttled, a little at a time so cancelling isn't held up
+            while let Some(delay) = self.throttle_delay(i, start.elapsed()) {
+                if self.cancellation.is_cancelled() {
+                    break;
+                }
+                thread::sleep(delay.min(PROGRESS_INTERVAL));
+            }
+        }
+        // Change message displayed to show that the countries simulation is finished, or was stopped early
+        if self.cancelled {
+            progress_bar.finish_with_message(format!("{} Cancelled at generation {}", self.country_data.name, i));
+            self.warnings.push(
+                WarningKind::Cancelled,
+                &self.country_data.name,
+                format!(
+                    "cancelled at generation {} of {}, its results only cover the generations before it stopped",
+                    i, self.parameters.generations
+                ),
+            );
+        } else {
+            progress_bar.finish_with_message(format!("{} Done", self.country_data.name));
+        }
+        self.elapsed = start.elapsed();
+        self.check_population();
+        self.check_feasibility();
+
+        // Log how the run ended, then every warning of the run
+        let ended: String = format!(
+            "{} at generation {} after {:.3} seconds and {} evaluations, best cost {}",
+            if self.cancelled { "Cancelled" } else { "Finished" },
+            i,
+            self.elapsed.as_secs_f64(),
+            self.solver().evaluations(),
+            self.country_data.format_cost(best_ever_cost)
+        );
+        self.log(ended);
+        let warnings: Vec<String> = self.warnings.warnings
+            .iter()
+            .map(|warning| format!("Warning ({}): {}", warning.kind, warning.message))
+            .collect();
+        for warning in warnings {
+            self.log(warning);
+        }
+        if let Some(adaptive) = &self.population.adaptive_operators {
+            self.crossover_statistics = adaptive.crossover.statistics();
+            self.mutation_statistics = adaptive.mutation.statistics();
         }
-        // Change message displayed to show that the countries simulation is finished
-        progress_bar.finish_with_message(format!("{} Done", self.country_data.name));
         Ok(())
     }
 
-    /// Define function to plot a graph of the best chromosome each generation
+    /// This function adds a warning if the population behaved in a way that suggests the options don't suit
+    /// the country: if most pairs of parents were the same Chromosome twice, or most children were thrown
+    /// away as duplicates. The hill climber has no population so is never checked
+    pub fn check_population(&mut self) {
+        if self.hill_climber.is_some() {
+            return;
+        }
+        let population: &Population = &self.population;
+
+        if population.duplicate_parents * 2 > population.bred_pairs {
+            self.warnings.push(
+                WarningKind::Selection,
+                &self.country_data.name,
+                format!(
+                    "the same chromosome was picked as both parents for {} of {} pairs, consider --distinct-parents",
+                    population.duplicate_parents, population.bred_pairs
+                ),
+            );
+        }
+        if population.rejected_children > population.bred_pairs {
+            self.warnings.push(
+                WarningKind::Replacement,
+                &self.country_data.name,
+                format!(
+                    "{} of {} children were thrown away as duplicates, the population may have converged",
+                    population.rejected_children, population.bred_pairs * 2
+                ),
+            );
+        }
+    }
+
+    /// This function adds a warning if the best tour found travels between cities the dataset has no edge between,
+    /// so no tour along the edges of the dataset was found
+    pub fn check_feasibility(&mut self) {
+        let best: &Chromosome = self.solver().best();
+        let forbidden: usize = best.forbidden_edges(&self.country_data.graph);
+        if forbidden > 0 {
+            self.warnings.push(
+                WarningKind::Feasibility,
+                &self.country_data.name,
+                format!(
+                    "the best tour travels between {} pairs of cities with no edge between them, no tour along the \
+                    edges of the dataset was found",
+                    forbidden
+                ),
+            );
+        }
+    }
+
+    /// This function checks if the population converged too early, returning the first recorded generation its
+    /// diversity fell below [`CONVERGED_DIVERSITY`] and how far its best tour is above the reference cost, as a
+    /// fraction of the reference cost, if that is more than the gap allowed. Runs that didn't record their diversity
+    /// are never found to have converged
+    pub fn premature_convergence(&self, reference_cost: f64, allowed_gap: f64) -> Option<(u32, f64)> {
+        let generation: u32 = self.recorded_generations
+            .iter()
+            .zip(self.diversity.iter())
+            .find(|(_, diversity)| diversity.is_some_and(|diversity| diversity < CONVERGED_DIVERSITY))
+            .map(|(generation, _)| *generation)?;
+
+        let best_cost: f64 = self.best_chromosome.iter().map(|best| best.cost).fold(f64::INFINITY, f64::min);
+        let gap: f64 = (best_cost - reference_cost) / reference_cost;
+        (reference_cost > 0.0 && gap > allowed_gap).then_some((generation, gap))
+    }
+
+    /// This function suggests changes to the parameters that keep a population diverse for longer
+    pub fn convergence_advice(&self) -> String {
+        let mut advice: Vec<String> = Vec::new();
+        if self.parameters.mutation_rate < 1.0 {
+            advice.push(format!("a higher --mutation-rate (now {})", self.parameters.mutation_rate));
+        }
+        advice.push(format!("a larger --population-size (now {})", self.parameters.population_size));
+        if self.parameters.tournament_size > 2 {
+            advice.push(format!("a smaller --tournament-size (now {})", self.parameters.tournament_size));
+        }
+
+        match advice.split_last() {
+            Some((last, rest)) if !rest.is_empty() => format!("try {} or {}", rest.join(", "), last),
+            Some((last, _)) => format!("try {}", last),
+            None => String::new(),
+        }
+    }
+
+    /// This function reports the results of the run in the same form whichever algorithm was run
+    pub fn report(&self) -> Result<SolverReport> {
+        // The best cost of each generation can rise if the population is resized, so keep the cheapest so far
+        let mut best_cost: f64 = f64::INFINITY;
+        let trajectory: Vec<TrajectoryPoint> = self.recorded_generations
+            .iter()
+            .zip(self.recorded_evaluations.iter())
+            .zip(self.best_chromosome.iter())
+            .map(|((generation, evaluations), best)| {
+                best_cost = best_cost.min(best.cost);
+                TrajectoryPoint { generation: *generation, evaluations: *evaluations, best_cost }
+            })
+            .collect();
+
+        // The cheapest tour of any recorded generation
+        let best_tour: Chromosome = self.best_chromosome
+            .iter()
+            .min_by(|x, y| x.cost.total_cmp(&y.cost))
+            .wrap_err("Cannot access Chromosome data in Simulation")?
+            .clone();
+
+        Ok(SolverReport {
+            algorithm: self.parameters.algorithm,
+            trajectory,
+            evaluations: self.solver().evaluations(),
+            elapsed: self.elapsed,
+            best_tour,
+        })
+    }
+
+    /// This function returns the mutation rate and number of stagnant generations for the next generation of
+    /// adaptive mutation. After an improvement the rate falls half way back to the base rate, and each time the best
+    /// cost hasn't improved for the stagnation limit the rate rises half way to 1
+    pub fn adapt_mutation_rate(rate: f64, base: f64, stagnant: u32, limit: u32, improved: bool) -> (f64, u32) {
+        if improved {
+            (base + (rate - base) / 2.0, 0)
+        } else if stagnant + 1 >= limit {
+            (rate + (1.0 - rate) / 2.0, 0)
+        } else {
+            (rate, stagnant + 1)
+        }
+    }
+
+    /// This function returns true if the simulation has an evaluation budget and the algorithm has used all of it
+    pub fn evaluation_budget_spent(&self) -> bool {
+        self.parameters
+            .max_evaluations
+            .is_some_and(|budget| self.solver().evaluations() >= budget)
+    }
+
+    /// This function adds the stats of a generation to the stat vectors
+    fn record(&mut self, stats: GenerationStats) {
+        self.best_chromosome.push(stats.best);
+        self.worst_chromosome.push(stats.worst);
+        self.average_cost.push(stats.average);
+        self.recorded_generations.push(stats.generation);
+        self.recorded_evaluations.push(stats.evaluations);
+        self.cost_deviation.push(stats.cost_deviation);
+        self.entropy.push(stats.entropy);
+        self.diversity.push(stats.diversity);
+        self.tournament_sizes.push(stats.tournament_size);
+        self.random_search_cost.push(stats.random_search_cost);
+        self.edge_frequencies.push(stats.edge_frequencies);
+        self.improvement_rate.push(stats.improvement_rate);
+    }
+
+    /// This function adds the best ever cost of the latest generation to the recent best costs, returning how much
+    /// the best ever cost fell over the last [`IMPROVEMENT_WINDOW`] generations. Until that many generations have
+    /// run, the fall since the first generation is scaled up to the length of the window
+    pub fn update_improvement_rate(&mut self, best_ever_cost: f64) -> f64 {
+        if self.recent_best_costs.len() > IMPROVEMENT_WINDOW as usize {
+            self.recent_best_costs.pop_front();
+        }
+        self.recent_best_costs.push_back(best_ever_cost);
+
+        let generations: usize = self.recent_best_costs.len() - 1;
+        match self.recent_best_costs.front() {
+            Some(oldest) if generations > 0 => (oldest - best_ever_cost) * IMPROVEMENT_WINDOW as f64 / generations as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// This function averages several lines of (generation, value) coordinates that may have been 
+    /// recorded at different generations. Between its recorded generations, each line is taken to 
+    /// keep its last recorded value
+    pub fn average_coordinates(lines: &[Vec<(f32, f32)>]) -> Vec<(f32, f32)> {
+        Simulation::aligned_values(lines)
+            .into_iter()
+            .map(|(generation, values)| (generation, values.iter().sum::<f32>() / values.len() as f32))
+            .collect()
+    }
+
+    /// This function gives the value of every line at each generation recorded by any line, in generation order.
+    /// Between its recorded generations, each line is taken to keep its last recorded value
+    pub fn aligned_values(lines: &[Vec<(f32, f32)>]) -> Vec<(f32, Vec<f32>)> {
+        // Every generation recorded by any line, in order and without repeats
+        let generations: BTreeSet<u32> = lines
+            .iter()
+            .flatten()
+            .map(|(x, _)| *x as u32)
+            .collect();
+
+        // The position in each line of its last recorded value
+        let mut positions: Vec<usize> = vec![0; lines.len()];
+        let mut output: Vec<(f32, Vec<f32>)> = Vec::with_capacity(generations.len());
+
+        for generation in generations {
+            let mut values: Vec<f32> = Vec::with_capacity(lines.len());
+            for (line, position) in lines.iter().zip(positions.iter_mut()) {
+                // Move forward to the last coordinate at or before this generation
+                while *position + 1 < line.len() && line[*position + 1].0 as u32 <= generation {
+                    *position += 1;
+                }
+                values.push(line[*position].1);
+            }
+            output.push((generation as f32, values));
+        }
+        output
+    }
+
+    /// This function gives a band one standard deviation either side of the average of several lines, as
+    /// (generation, lower, upper) coordinates at each generation recorded by any line. The band is kept within the
+    /// lowest and highest value of the lines, so it never shows values no line reached
+    pub fn deviation_band(lines: &[Vec<(f32, f32)>]) -> Vec<(f32, f32, f32)> {
+        Simulation::aligned_values(lines)
+            .into_iter()
+            .map(|(generation, values)| {
+                let count: f32 = values.len() as f32;
+                let mean: f32 = values.iter().sum::<f32>() / count;
+                let deviation: f32 = (values.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / count).sqrt();
+                let lowest: f32 = values.iter().copied().fold(f32::INFINITY, f32::min);
+                let highest: f32 = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                (generation, (mean - deviation).max(lowest), (mean + deviation).min(highest))
+            })
+            .collect()
+    }
+
+    /// Function to pick the chosen statistic out of each Simulation as a line of (generation, value) coordinates
+    pub fn statistic_lines(data: &[Simulation], statistic_plotted: PlotStatistic) -> Vec<Vec<(f32, f32)>> {
+        // Each line is a vector of (generation, value) coordinates, as plotters requires coordinates 
+        // to be in the form (f32, f32). Generations are taken from recorded_generations as not every
+        // generation has to be recorded
+        let mut data_simplified: Vec<Vec<(f32, f32)>> = Vec::with_capacity(data.len());
+
+        match statistic_plotted {
+            PlotStatistic::Average => {
+                // Iterate over data
+                data.iter()
+                    // For each Simulation in data, push its average_cost field paired with its generations to data_simplified
+                    .for_each(|sim| data_simplified.push({sim
+                        .recorded_generations
+                        .iter()
+                        .zip(sim.average_cost.iter())
+                        .map(|(x, y)| (*x as f32, *y as f32))
+                        .collect::<Vec<(f32, f32)>>()
+                    }))
+
+            },
+            PlotStatistic::Best => {
+                // Iterate over data
+                data.iter().for_each(|sim| {
+                    data_simplified
+                        // Iterate over the best chromosome field in the Simulation, pair its costs with their 
+                        // generations into a vector and push this vector to data_simplified
+                        .push({sim
+                            .recorded_generations
+                            .iter()
+                            .zip(sim.best_chromosome.iter())
+                            .map(|(x, chromo)| (*x as f32, chromo.cost as f32))
+                            .collect::<Vec<(f32, f32)>>()
+                        })
+                })
+            },
+            PlotStatistic::Worst => {
+                // Iterate over data
+                data.iter().for_each(|sim| {
+                    data_simplified
+                        // Iterate over the worst chromosome field in the Simulation, pair its costs with their 
+                        // generations into a vector and push this vector to data_simplified
+                        .push({sim
+                            .recorded_generations
+                            .iter()
+                            .zip(sim.worst_chromosome.iter())
+                            .map(|(x, chromo)| (*x as f32, chromo.cost as f32))
+                            .collect::<Vec<(f32, f32)>>()
+                        })
+                })
+            },
+            PlotStatistic::Deviation => {
+                // Iterate over data
+                data.iter()
+                    // For each Simulation in data, push the generations that recorded a cost_deviation paired with it to data_simplified
+                    .for_each(|sim| data_simplified.push({sim
+                        .recorded_generations
+                        .iter()
+                        .zip(sim.cost_deviation.iter())
+                        .filter_map(|(x, y)| y.map(|y| (*x as f32, y as f32)))
+                        .collect::<Vec<(f32, f32)>>()
+                    }))
+            },
+            PlotStatistic::Entropy => {
+                // Iterate over data
+                data.iter()
+                    // For each Simulation in data, push the generations that recorded an entropy paired with it to data_simplified
+                    .for_each(|sim| data_simplified.push({sim
+                        .recorded_generations
+                        .iter()
+                        .zip(sim.entropy.iter())
+                        .filter_map(|(x, y)| y.map(|y| (*x as f32, y as f32)))
+                        .collect::<Vec<(f32, f32)>>()
+                    }))
+            },
+            PlotStatistic::Diversity => {
+                // Iterate over data
+                data.iter()
+                    // For each Simulation in data, push the generations that recorded a diversity paired with it to data_simplified
+                    .for_each(|sim| data_simplified.push({sim
+                        .recorded_generations
+                        .iter()
+                        .zip(sim.diversity.iter())
+                        .filter_map(|(x, y)| y.map(|y| (*x as f32, y as f32)))
+                        .collect::<Vec<(f32, f32)>>()
+                    }))
+            },
+            PlotStatistic::Improvement => {
+                // Iterate over data
+                data.iter()
+                    // For each Simulation in data, push its improvement_rate field paired with its generations to data_simplified
+                    .for_each(|sim| data_simplified.push({sim
+                        .recorded_generations
+                        .iter()
+                        .zip(sim.improvement_rate.iter())
+                        .map(|(x, y)| (*x as f32, *y as f32))
+                        .collect::<Vec<(f32, f32)>>()
+                    }))
+            },
+        };
+        data_simplified
+    }
+
+    /// Function to pick the labelled lines a plot operator draws out of the lines of each Simulation
+    pub fn plot_series(lines: &[Vec<(f32, f32)>], plot_operator: PlotOperator) -> Result<Vec<PlotSeries>> {
+        // The lines whose last value is lowest and highest
+        let last = |line: &Vec<(f32, f32)>| line.last().map_or(f32::NAN, |point| point.1);
+        let best = || -> Result<Vec<(f32, f32)>> {
+            Ok(lines.iter().min_by(|x, y| last(x).total_cmp(&last(y))).wrap_err("No Simulation data to plot")?.clone())
+        };
+        let worst = || -> Result<Vec<(f32, f32)>> {
+            Ok(lines.iter().max_by(|x, y| last(x).total_cmp(&last(y))).wrap_err("No Simulation data to plot")?.clone())
+        };
+
+        Ok(match plot_operator {
+            PlotOperator::Average => vec![(String::from("Average Simulation"), Simulation::average_coordinates(lines))],
+            PlotOperator::Best => vec![(String::from("Best Simulation"), best()?)],
+            PlotOperator::Worst => vec![(String::from("Worst Simulation"), worst()?)],
+            PlotOperator::Range => vec![
+                (String::from("Worst Simulation"), worst()?),
+                (String::from("Average Simulation"), Simulation::average_coordinates(lines)),
+                (String::from("Best Simulation"), best()?),
+            ],
+            PlotOperator::DisplayAll => lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| (format!("Simulation {}", index + 1), line.clone()))
+                .collect(),
+        })
+    }
+
+    /// Function to average the best cost found by the random search of each Simulation into a reference line, 
+    /// returning None unless costs are plotted and every Simulation ran a random search
+    pub fn random_search_series(data: &[Simulation], statistic_plotted: PlotStatistic) -> Option<PlotSeries> {
+        if matches!(
+            statistic_plotted,
+            PlotStatistic::Deviation | PlotStatistic::Entropy | PlotStatistic::Diversity | PlotStatistic::Improvement
+        ) || data.is_empty() {
+            return None;
+        }
+
+        let lines: Vec<Vec<(f32, f32)>> = data
+            .iter()
+            .map(|sim| sim
+                .recorded_generations
+                .iter()
+                .zip(sim.random_search_cost.iter())
+                .map(|(x, y)| y.map(|y| (*x as f32, y as f32)))
+                .collect::<Option<Vec<(f32, f32)>>>())
+            .collect::<Option<Vec<Vec<(f32, f32)>>>>()?;
+
+        Some((String::from("Random Search"), Simulation::average_coordinates(&lines)))
+    }
+
+    /// Function to write the caption of a plot of this Simulation's country, naming the options used, the build and
+    /// the tags and notes of the experiment
+    pub fn caption(&self, id: &str, number_runs: u32, tags: &[String], notes: &ExperimentNotes) -> String {
+        let options: String = match self.parameters.algorithm {
+            // Adaptive operators use every operator, so name neither
+            Algorithm::Genetic if self.parameters.adaptive_operators => format!(
+                "TSP of dataset {}, Ran {} times, Population size: {}, Tournament size: {}, Adaptive operators",
+                id,
+                number_runs,
+                self.parameters.population_size,
+                self.parameters.tournament_size,
+            ),
+            Algorithm::Genetic => format!(
+                "TSP of dataset {}, Ran {} times, Population size: {}, Tournament size: {}, Mutation: {:?}, Crossover: {:?}",
+                id, 
+                number_runs,
+                self.parameters.population_size, 
+                self.parameters.tournament_size,
+                self.parameters.mutation_operator,
+                self.parameters.crossover_operator,
+            ),
+            // The hill climber only uses the operators to match its evaluations to the genetic algorithm
+            Algorithm::Hillclimb => format!(
+                "TSP of dataset {}, Ran {} times, 2-opt hill climber with restarts, {} evaluations per generation",
+                id, 
+                number_runs,
+                self.parameters.evaluations_per_generation(),
+            ),
+        };
+        format!("{}, Build: {}{}", options, BuildInfo::current().id(), notes.tagged_caption(tags))
+    }
+
+    /// Function to name the values of a statistic and describe them for the y axis, costs use the units of the country
+    pub fn value_names(statistic_plotted: PlotStatistic, country_data: &Country) -> (&'static str, String) {
+        match statistic_plotted {
+            PlotStatistic::Deviation => ("deviation", format!("Standard deviation of {}", country_data.cost_label())),
+            PlotStatistic::Entropy => ("entropy", String::from("Edge entropy")),
+            PlotStatistic::Diversity => ("diversity", String::from("Fraction of edges not shared")),
+            PlotStatistic::Improvement => (
+                "improvement",
+                format!("Fall in best {} over the last {} generations", country_data.cost_label(), IMPROVEMENT_WINDOW),
+            ),
+            _ => ("cost", format!("{:?} {}", statistic_plotted, country_data.cost_label())),
+        }
+    }
+
+    /// Define function to plot a graph of the best chromosome each generation,
+    /// returning the path the plot was saved to
+    #[allow(clippy::too_many_arguments)]
     pub fn plot(
-        data: &Vec<Simulation>, 
+        data: &[Simulation], 
         plot_operator: PlotOperator, 
         statistic_plotted: PlotStatistic,
+        plot_theme: PlotTheme,
         number_runs: u32, 
-        id: String
-    ) -> Result<()> {
+        id: String,
+        tags: &[String],
+        notes: &ExperimentNotes,
+    ) -> Result<PathBuf> {
+        // Get the colours, fonts and line widths for the chosen theme
+        let style = ThemeStyle::new(plot_theme);
+
         // Check if a results directory exists
         match std::fs::metadata("results") {
             Ok(_) => (),
@@ -137,9 +1225,9 @@ impl Simulation {
         );
 
         // Create root structure for charts with a specified size, coordinate 
-        // range and path and give it a white background
+        // range and path and give it the themes background
         let root = BitMapBackend::new(name.as_str(), (1920, 1080)).into_drawing_area();
-        root.fill(&WHITE)?;
+        root.fill(&style.background)?;
 
         // Set maximum height for y axis
         let mut y_max: f32 = 0.0;
@@ -147,316 +1235,233 @@ impl Simulation {
         // Loop through simulations in data
         for i in data {
 
-            // Define the worst cost as the worst chromosome from the 
-            // first generation of the Simulations Population
-            let worst = i.worst_chromosome
-                .first()
-                .wrap_err("Cannot access Chromosome data in Simulation")?;
-
-            // If this worst cost is higher than current one, replace it
-            if worst.cost as f32 > y_max {
-                y_max = worst.cost as f32
+            // Deviation, entropy, diversity and improvement aren't costs so use their highest value in any generation, otherwise define
+            // the highest value as the cost of the worst chromosome from the first generation of the Simulations Population
+            let highest: f32 = match statistic_plotted {
+                PlotStatistic::Deviation => i.cost_deviation.iter().flatten().copied().fold(0.0, f64::max) as f32,
+                PlotStatistic::Entropy => i.entropy.iter().flatten().copied().fold(0.0, f64::max) as f32,
+                PlotStatistic::Diversity => i.diversity.iter().flatten().copied().fold(0.0, f64::max) as f32,
+                PlotStatistic::Improvement => i.improvement_rate.iter().copied().fold(0.0, f64::max) as f32,
+                _ => i.worst_chromosome
+                    .first()
+                    .wrap_err("Cannot access Chromosome data in Simulation")?
+                    .cost as f32,
+            };
+
+            // If this value is higher than current one, replace it
+            if highest > y_max {
+                y_max = highest
             }
         }
 
         // Adds 10% to the height of the Y axis
         y_max *= 1.1;
 
+        // All simulations in data share the same country, so use the first for its cost metadata
+        let country_data: &Country = &data.first().wrap_err("No Simulation data to plot")?.country_data;
+
+        // Name and format the values being plotted, costs use the units of the country
+        let (value_name, y_desc): (&str, String) = Simulation::value_names(statistic_plotted, country_data);
+        let format_value = |value: f32| -> String {
+            match statistic_plotted {
+                PlotStatistic::Deviation => country_data.format_cost(value as f64),
+                PlotStatistic::Entropy | PlotStatistic::Diversity => format!("{:.3}", value),
+                _ => country_data.format_cost(value as f64),
+            }
+        };
+
+        // The x axis covers the longest simulation
+        let x_max: u32 = data.iter().map(|sim| sim.parameters.generations).max().unwrap_or(1);
+
         // Write caption for plot
-        let caption: String = format!(
-            "TSP of dataset {}, Ran {} times, Population size: {}, Tournament size: {}, Mutation: {:?}, Crossover: {:?}",
-            id, 
-            number_runs,
-            data.first().unwrap().population_size, 
-            data.first().unwrap().tournament_size,
-            data.first().unwrap().mutation_operator,
-            data.first().unwrap().crossover_operator,
-        );
+        let caption: String = data.first().unwrap().caption(&id, number_runs, tags, notes);
 
         // Create a chart for the graph to be drawn on
         let mut chart = ChartBuilder::on(&root)
             .margin(10)
-            .caption(caption, ("sans-serif", 30).into_font())
+            .caption(caption, (style.font, style.caption_size).into_font().color(&style.foreground))
             .margin(10)
             .x_label_area_size(50)
             .y_label_area_size(50)
-            .build_cartesian_2d(0f32..NUMBER_OF_GENERATIONS as f32, 0f32..y_max)?;
+            .build_cartesian_2d(0f32..x_max as f32, 0f32..y_max)?;
 
         // Add a mesh object to chart
         chart.configure_mesh()
+            .bold_line_style(style.grid)
+            .light_line_style(style.grid.mix(0.3))
+            .axis_style(style.foreground)
+            .label_style((style.font, style.label_size).into_font().color(&style.foreground))
+            .axis_desc_style((style.font, style.label_size).into_font().color(&style.foreground))
             .x_labels(5)
             .x_desc("Generations Passed")
             .y_labels(5)
-            .y_desc("Average cost")
+            .y_desc(y_desc)
             .draw()?;
 
 
-        let mut data_simplified: Vec<Vec<f64>> = Vec::with_capacity(data.capacity());
+        // Each line is a vector of (generation, value) coordinates, one for each Simulation
+        let data_simplified: Vec<Vec<(f32, f32)>> = Simulation::statistic_lines(data, statistic_plotted);
 
-         match statistic_plotted {
-            PlotStatistic::Average => {
-                // Iterate over data
-                data.iter()
-                    // For each Simulation in data, push its average_cost field to data_simplified
-                    .for_each(|sim| data_simplified.push(sim.average_cost.clone()))
+        // Draw the random search first so the simulations are drawn over it
+        let random_search = Simulation::random_search_series(data, statistic_plotted);
+        let mut labelled: bool = random_search.is_some();
+        if let Some((label, line)) = &random_search {
+            let random_search_final = line.last().wrap_err("Random search data not found")?.1;
+            let colour = style.foreground.mix(0.5);
+            chart.draw_series(LineSeries::new(line.clone(), colour.stroke_width(style.line_width)))?
+                .label(label.as_str())
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], colour.filled()));
 
-            },
-            PlotStatistic::Best => {
-                // Iterate over data
-                data.iter().for_each(|sim| {
-                    data_simplified
-                        // Iterate over the best chromosome field in the Simulation, collect its costs into a vector
-                        // and push this vector to data_simplified
-                        .push({sim
-                            .best_chromosome
-                            .iter()
-                            .map(|chromo| chromo.cost)
-                            .collect::<Vec<f64>>()
-                        })
-                })
-            },
-            PlotStatistic::Worst => {
-                // Iterate over data
-                data.iter().for_each(|sim| {
-                    data_simplified
-                        // Iterate over the worst chromosome field in the Simulation, collect its costs into a vector
-                        // and push this vector to data_simplified
-                        .push({sim
-                            .worst_chromosome
-                            .iter()
-                            .map(|chromo| chromo.cost)
-                            .collect::<Vec<f64>>()
-                        })
-                })
-            },
-        };
+            println!("Last {} of {} random search: {}", value_name, id, format_value(random_search_final));
+        }
 
-        // Pattern match on specified plot type
-        match plot_operator {
-            
-            PlotOperator::Average => {
-                // Create vector for average co-ords with the length 
-                // equal to the length of the first Simulations average_cost
-                let mut average_coords: Vec<f32> = vec![0.0; data_simplified[0].len()];
-
-                // Loop over every array in data_simplified
-                data_simplified.iter().for_each(|array| {
-                    // Loop over every element in the array
-                    array.iter().enumerate().for_each(|(index, value)| {
-                        // Get value of array at index, divide it by 
-                        // number of arrays and add it to value at index in average_coords
-                        average_coords[index] += (*value as f32) / (data_simplified.len() as f32)
-                    })
-                });
-
-                // plotters requires coordinates to be in the form (f32, f32) 
-                let output: Vec<(f32, f32)> = average_coords
-                    // Iterate over average_coords
-                    .iter_mut()
-                    // Get index of co-ords, elements are now (usize, f32)
-                    .enumerate()
-                    // Convert index from usize to f32, elements are now (f32, f32)
-                    .map(|(i, x)| (i as f32, *x))
-                    // Collect elements into new 
-                    .collect::<Vec<(f32, f32)>>();
-
-                // Get final cost of average Simulation
-                let average_final = output.last().wrap_err("Chromosome data not found")?.1;
-    
-                // Draw country data as a line graph on chart
-                chart.draw_series(LineSeries::new(output, RED.mix(0.9).stroke_width(2)))?;
-
-                println!("Last cost of {} best simulation: {}", id, average_final);
-
-                // Take root and present all charts, then output final plot
-                root.present()?;
-            },
+        // Pick the lines to draw the same way as the Vega-Lite export, so both show the same simulations
+        let series: Vec<PlotSeries> = Simulation::plot_series(&data_simplified, plot_operator)?;
+
+        // Shade one standard deviation either side of the average to show how much the simulations differ,
+        // going along the top of the band and back along the bottom
+        if matches!(plot_operator, PlotOperator::Average) && data_simplified.len() > 1 {
+            let band: Vec<(f32, f32, f32)> = Simulation::deviation_band(&data_simplified);
+            let outline: Vec<(f32, f32)> = band
+                .iter()
+                .map(|(generation, _, upper)| (*generation, *upper))
+                .chain(band.iter().rev().map(|(generation, lower, _)| (*generation, *lower)))
+                .collect();
+            let colour = style.primary.mix(0.2);
+            chart.draw_series(std::iter::once(Polygon::new(outline, colour.filled())))?
+                .label("Standard deviation of Simulations")
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], colour.filled()));
+            labelled = true;
+        }
 
-            PlotOperator::Best => {
-                
-                let country_coords: Vec<(f32, f32)> = data_simplified
-                    .iter()
-                    .min_by(|x, y| { x.last()
-                        .unwrap()
-                        .partial_cmp(y
-                            .last().unwrap()
-                        ).unwrap()
-                    }).wrap_err("Could not find Chromosome data in Simulation")?
-                    .iter()
-                    .enumerate()
-                    .map(|(x, y)| (x as f32, *y as f32))
-                    .collect::<Vec<(f32, f32)>>();
+        // Draw each line, a range plot gives the worst, average and best their own colours and display all picks a
+        // colour for each simulation
+        for (index, (label, line)) in series.into_iter().enumerate() {
+            let colour = match plot_operator {
+                PlotOperator::Range => [style.primary, style.secondary, style.tertiary][index % 3].mix(0.9),
+                PlotOperator::DisplayAll => Palette99::pick(index).mix(0.9),
+                _ => style.primary.mix(0.9),
+            };
 
-                // Get final cost of best Simulation
-                let best_final = country_coords.last().wrap_err("Chromosome data not found")?.1;
+            // Get the final value of the line
+            let line_final = line.last().wrap_err("Chromosome data not found")?.1;
 
-                // Draw country data as a line graph on chart
-                chart.draw_series(LineSeries::new(country_coords, RED.mix(0.9).stroke_width(2)))?;
+            // Draw the line as a line graph on chart
+            chart.draw_series(LineSeries::new(line, colour.stroke_width(style.line_width)))?
+                .label(label.as_str())
+                .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], colour.filled()));
 
-                println!("Last cost of {} best simulation: {}", id, best_final);
+            println!("Last {} of {} {}: {}", value_name, id, label.to_lowercase(), format_value(line_final));
+        }
 
-                // Take root and present all charts, then output final plot
-                root.present()?;
+        // The range and display all plots always label their lines, the others only need a legend for the random
+        // search or the band around the average
+        if labelled || matches!(plot_operator, PlotOperator::Range | PlotOperator::DisplayAll) {
+            chart.configure_series_labels()
+                .background_style(style.background.mix(0.8))
+                .border_style(style.foreground)
+                .label_font((style.font, style.label_size).into_font().color(&style.foreground))
+                .draw()?;
+        }
 
-            },
+        // Take root and present all charts, then output final plot
+        root.present()?;
 
-            PlotOperator::Worst => {
-                
-                let country_coords: Vec<(f32, f32)> = data_simplified
-                    .iter()
-                    .max_by(|x, y| { x.last()
-                        .unwrap()
-                        .partial_cmp(y
-                            .last().unwrap()
-                        ).unwrap()
-                    }).wrap_err("Could not find Chromosome data in Simulation")?
-                    .iter()
-                    .enumerate()
-                    .map(|(x, y)| (x as f32, *y as f32))
-                    .collect::<Vec<(f32, f32)>>();
-
-                // Get final cost of worst Simulation
-                let worst_final = country_coords.last().wrap_err("Chromosome data not found")?.1;
-
-                // Draw country data as a line graph on chart
-                chart.draw_series(LineSeries::new(country_coords, RED.mix(0.9).stroke_width(2)))?;
-
-                println!("Last cost of {} worst simulation: {}",id , worst_final);
-
-                // Take root and present all charts, then output final plot
-                root.present()?;
-            },
+        // Return the path of the plot if Function runs without error
+        Ok(PathBuf::from(&name))
+    }
 
-            PlotOperator::Range => {
-
-                let worst_coords: Vec<(f32, f32)> = data_simplified
-                    .iter()
-                    .max_by(|x, y| { x.last()
-                        .unwrap()
-                        .partial_cmp(y
-                            .last().unwrap()
-                        ).unwrap()
-                    }).wrap_err("Could not find Chromosome data in Simulation")?
-                    .iter()
-                    .enumerate()
-                    .map(|(x, y)| (x as f32, *y as f32))
-                    .collect::<Vec<(f32, f32)>>();
-
-                // Get final cost of worst Simulation
-                let worst_final = worst_coords.last().wrap_err("Chromosome data not found")?.1;
-
-
-                let best_coords: Vec<(f32, f32)> = data_simplified
-                    .iter()
-                    .min_by(|x, y| { x.last()
-                        .unwrap()
-                        .partial_cmp(y
-                            .last().unwrap()
-                        ).unwrap()
-                    }).wrap_err("Could not find Chromosome data in Simulation")?
-                    .iter()
-                    .enumerate()
-                    .map(|(x, y)| (x as f32, *y as f32))
-                    .collect::<Vec<(f32, f32)>>();
-
-                // Get final cost of best Simulation
-                let best_final = best_coords.last().wrap_err("Chromosome data not found")?.1;
-
-                // Create vector for average co-ords with the length 
-                // equal to the length of the first Simulations average_cost
-                let mut average_coords: Vec<f32> = vec![0.0; data_simplified[0].len()];
-
-                // Loop over every array in data_simplified
-                data_simplified.iter().for_each(|array| {
-                    // Loop over every element in the array
-                    array.iter().enumerate().for_each(|(index, value)| {
-                        // Get value of array at index, divide it by 
-                        // number of arrays and add it to value at index in average_coords
-                        average_coords[index] += (*value as f32) / (data_simplified.len() as f32)
-                    })
-                });
-
-                // plotters requires coordinates to be in the form (f32, f32) 
-                let output: Vec<(f32, f32)> = average_coords
-                    // Iterate over average_coords
-                    .iter_mut()
-                    // Get index of co-ords, elements are now (usize, f32)
-                    .enumerate()
-                    // Convert index from usize to f32, elements are now (f32, f32)
-                    .map(|(i, x)| (i as f32, *x))
-                    // Collect elements into new vector
-                    .collect::<Vec<(f32, f32)>>();
-
-                // Get final cost of average Simulation
-                let average_final = output.last().wrap_err("Chromosome data not found")?.1;
-
-                // Draw Worst Chromosome data as a line graph on chart
-                chart.draw_series(LineSeries::new(worst_coords, RED.mix(0.9).stroke_width(2)))?
-                    .label("Worst Simulation")
-                    .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], RED.mix(0.9).filled()));
-
-                // Draw Average Chromosome data as a line graph on chart
-                chart.draw_series(LineSeries::new(output, BLUE.mix(0.9).stroke_width(2)))?
-                    .label("Average Simulation")
-                    .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], BLUE.mix(0.9).filled()));
-
-                // Draw Best Chromosome data as a line graph on chart
-                chart.draw_series(LineSeries::new(best_coords, GREEN.mix(0.9).stroke_width(2)))?
-                    .label("Best Simulation")
-                    .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], GREEN.mix(0.9).filled()));
-
-                // Draw legend on graph
-                chart.configure_series_labels()
-                    .background_style(&WHITE.mix(0.8))
-                    .border_style(&BLACK)
-                    .draw()?;
-
-                println!("Last cost of {} worst simulation: {}",id , worst_final);
-                println!("Last cost of {} best simulation: {}", id, best_final);
-                println!("Last cost of {} average simulation: {}", id, average_final);
-
-                // Take root and present all charts, then output final plot
-                root.present()?;
-            },
+    /// Function to give the fraction of the population holding a tracked edge as a line of (generation, fraction)
+    /// coordinates for each Simulation, skipping generations the frequency wasn't recorded at
+    pub fn edge_frequency_lines(data: &[Simulation], edge: usize) -> Vec<Vec<(f32, f32)>> {
+        data.iter()
+            .map(|sim| sim
+                .recorded_generations
+                .iter()
+                .zip(sim.edge_frequencies.iter())
+                .filter_map(|(generation, frequencies)| frequencies
+                    .as_ref()
+                    .and_then(|frequencies| frequencies.get(edge))
+                    .map(|frequency| (*generation as f32, *frequency as f32)))
+                .collect::<Vec<(f32, f32)>>())
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
 
-            PlotOperator::DisplayAll => {
-                // Loop over every Simulation in data
-                for (index, array) in data_simplified.iter().enumerate() {
+    /// Define function to plot the average fraction of the population holding each tracked edge over the
+    /// generations, as a grid of small charts with one for each edge, returning the path the plot was saved to
+    pub fn plot_edge_frequencies(
+        data: &[Simulation],
+        plot_theme: PlotTheme,
+        number_runs: u32,
+        id: String,
+        tags: &[String],
+        notes: &ExperimentNotes,
+    ) -> Result<PathBuf> {
+        // Get the colours, fonts and line widths for the chosen theme
+        let style = ThemeStyle::new(plot_theme);
+
+        // Every Simulation of a country tracks the same edges, so use the first for them
+        let first: &Simulation = data.first().wrap_err("No Simulation data to plot")?;
+        let edges: &[(u32, u32)] = &first.tracked_edges;
+        if edges.is_empty() {
+            bail!("No edges were tracked for {}", id);
+        }
 
-                    // Create vector for x & y coordinates from country data
-                    let country_coords: Vec<(f32, f32)> = array
-                        .iter()
-                        .enumerate()
-                        .map(|(x, y)| (x as f32, *y as f32))
-                        .collect::<Vec<(f32, f32)>>();
-        
-                    // Randomly select colour for the line
-                    let colour =  Palette99::pick(index).mix(0.9);
-
-                    // Get final cost of Simulation
-                    let country_final = country_coords.last().wrap_err("Chromosome data not found")?.1;
-
-                    // Draw country data as a line graph on chart
-                    chart.draw_series(LineSeries::new(country_coords, colour.stroke_width(2)))?
-                        .label(format!("Simulation {}", index + 1))
-                        .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], colour.filled()));
-
-                    // Output final cost
-                    println!("Last cost of {} simulation {}: {}", id, index + 1, country_final);
-                }
+        // Create the results directory if it doesn't exist
+        std::fs::create_dir_all("results")?;
 
-                // Draw legend on graph
-                chart.configure_series_labels()
-                    .background_style(&WHITE.mix(0.8))
-                    .border_style(&BLACK)
-                    .draw()?;
+        // Generate unique path for plot to be saved to using date, time and id
+        let time: DateTime<Utc> = Utc::now();
+        let name: String = format!(
+            "results/edge-frequencies-{}-({}).png",
+            time.format("%Y-%m-%d-%H-%M-%S"),
+            id
+        );
 
-                // Take root and present all charts, then output final plot
-                root.present()?;
-            },
-        };
+        let root = BitMapBackend::new(name.as_str(), (1920, 1080)).into_drawing_area();
+        root.fill(&style.background)?;
+        let root = root.titled(
+            &first.caption(&id, number_runs, tags, notes),
+            (style.font, style.caption_size).into_font().color(&style.foreground)
+        )?;
+
+        // Lay the charts out in the smallest square grid that fits every edge
+        let columns: usize = (edges.len() as f64).sqrt().ceil() as usize;
+        let rows: usize = edges.len().div_ceil(columns);
+        let areas = root.split_evenly((rows, columns));
+
+        // The x axis covers the longest simulation
+        let x_max: u32 = data.iter().map(|sim| sim.parameters.generations).max().unwrap_or(1);
+
+        // Smaller charts need smaller labels to stay readable
+        let label_size: u32 = (style.label_size / columns.max(1) as u32).max(12);
+
+        for (index, (area, (from, to))) in areas.iter().zip(edges.iter()).enumerate() {
+            let mut chart = ChartBuilder::on(area)
+                .margin(10)
+                .caption(format!("Edge {}-{}", from, to), (style.font, label_size).into_font().color(&style.foreground))
+                .x_label_area_size(30)
+                .y_label_area_size(40)
+                .build_cartesian_2d(0f32..x_max as f32, 0f32..1f32)?;
+
+            chart.configure_mesh()
+                .bold_line_style(style.grid)
+                .light_line_style(style.grid.mix(0.3))
+                .axis_style(style.foreground)
+                .label_style((style.font, label_size).into_font().color(&style.foreground))
+                .x_labels(3)
+                .y_labels(3)
+                .draw()?;
+
+            // Average the fraction over every run of the country
+            let average: Vec<(f32, f32)> = Simulation::average_coordinates(&Simulation::edge_frequency_lines(data, index));
+            chart.draw_series(LineSeries::new(average, style.primary.mix(0.9).stroke_width(style.line_width)))?;
+        }
 
-        // Return OK if Function runs without error
-        Ok(())
+        // Take root and present all charts, then output final plot
+        root.present()?;
+
+        Ok(PathBuf::from(&name))
     }
 }
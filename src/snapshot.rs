@@ -0,0 +1,229 @@
+//! This module defines [`PopulationSnapshot`], a copy of every [`Chromosome`] in a [`Population`] at a
+//! chosen generation saved to a JSON file, and [`PopulationDiff`], which compares two snapshots
+//! to show how a population changed between them.
+//! 
+//! [`Population`]: crate::population::Population
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::prelude::*;
+use color_eyre::{eyre::{ContextCompat, WrapErr}, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{build_info::BuildInfo, chromosome::Chromosome, interface::RunParameters, population::Population};
+
+/// Every Chromosome of a population at a single generation
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PopulationSnapshot {
+    /// Name of the country the population was run on
+    pub instance: String,
+    /// Which run of the country the population is from, starting from 1. 0 until the run is known
+    pub run: u32,
+    /// The generation the snapshot was taken at
+    pub generation: u32,
+    /// Every Chromosome in the population
+    pub chromosomes: Vec<Chromosome>,
+    /// The build that took the snapshot, None for snapshots saved before builds were recorded
+    #[serde(default)]
+    pub build: Option<BuildInfo>,
+    /// The parameters the run was using when the snapshot was taken, None for snapshots saved before they
+    /// were recorded
+    #[serde(default)]
+    pub parameters: Option<RunParameters>,
+}
+
+/// Implement methods on `PopulationSnapshot`
+impl PopulationSnapshot {
+    /// Function to copy every Chromosome of a population, along with the parameters it is being run with
+    pub fn new(instance: &str, generation: u32, population: &Population, parameters: RunParameters) -> Self {
+        Self {
+            instance: instance.to_string(),
+            run: 0,
+            generation,
+            chromosomes: population.population_data.clone(),
+            build: Some(BuildInfo::current()),
+            parameters: Some(parameters),
+        }
+    }
+
+    /// Function to write the snapshot to a new file in the results directory, returning its path
+    pub fn save(&self) -> Result<PathBuf> {
+        // Create the results directory if it doesn't exist
+        fs::create_dir_all("results")?;
+
+        // Generate unique path for the snapshot using the run, generation, date and time
+        let time: DateTime<Utc> = Utc::now();
+        let path = PathBuf::from(format!(
+            "results/snapshot-{}-run{}-generation{}-{}.json",
+            self.instance,
+            self.run,
+            self.generation,
+            time.format("%Y-%m-%d-%H-%M-%S"),
+        ));
+        fs::write(&path, serde_json::to_string(self)?)?;
+        Ok(path)
+    }
+
+    /// Function to read a snapshot written by an earlier invocation
+    pub fn load(path: &Path) -> Result<Self> {
+        let src: String = fs::read_to_string(path).wrap_err("Failed to read population snapshot")?;
+        serde_json::from_str(&src).wrap_err("Failed to deserialize population snapshot")
+    }
+}
+
+/// The spread of costs in a population
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostDistribution {
+    /// The cheapest cost
+    pub best: f64,
+    /// The most expensive cost
+    pub worst: f64,
+    /// The average cost
+    pub average: f64,
+    /// The standard deviation of the costs
+    pub deviation: f64,
+}
+
+/// Implement methods on `CostDistribution`
+impl CostDistribution {
+    /// Function to find the spread of the costs of some Chromosomes, None if there aren't any
+    pub fn new(chromosomes: &[Chromosome]) -> Option<Self> {
+        let costs: Vec<f64> = chromosomes.iter().map(|chromosome| chromosome.cost).collect();
+        let best: f64 = costs.iter().copied().reduce(f64::min)?;
+        let worst: f64 = costs.iter().copied().reduce(f64::max)?;
+        let average: f64 = costs.iter().sum::<f64>() / costs.len() as f64;
+        let variance: f64 = costs.iter().map(|cost| (cost - average).powi(2)).sum::<f64>() / costs.len() as f64;
+        Some(Self { best, worst, average, deviation: variance.sqrt() })
+    }
+}
+
+/// A comparison of two population snapshots
+#[derive(Clone, Debug, PartialEq)]
+pub struct PopulationDiff {
+    /// Country and generation of the first snapshot
+    pub first_label: String,
+    /// Country and generation of the second snapshot
+    pub second_label: String,
+    /// Spread of the costs of the first snapshot
+    pub first_costs: CostDistribution,
+    /// Spread of the costs of the second snapshot
+    pub second_costs: CostDistribution,
+    /// Number of different tours found in both snapshots, ignoring where a tour starts and its direction
+    pub shared_tours: usize,
+    /// Whether the best Chromosomes of both snapshots are the same tour
+    pub same_best_tour: bool,
+    /// Number of edges the best Chromosomes of both snapshots share, out of the number of cities
+    pub best_shared_edges: usize,
+    /// Number of cities in each route
+    pub route_length: usize,
+    /// Jensen-Shannon divergence between how often each edge is used in the two snapshots. It is 0 when every edge
+    /// is used equally often in both and ln 2 when they have no edges in common
+    pub edge_divergence: f64,
+}
+
+/// Implement methods on `PopulationDiff`
+impl PopulationDiff {
+    /// Function to compare two snapshots
+    pub fn new(first: &PopulationSnapshot, second: &PopulationSnapshot) -> Result<Self> {
+        let first_costs = CostDistribution::new(&first.chromosomes).wrap_err("First snapshot has no Chromosomes")?;
+        let second_costs = CostDistribution::new(&second.chromosomes).wrap_err("Second snapshot has no Chromosomes")?;
+
+        // Tours found in both snapshots
+        let first_tours: HashSet<Vec<(u32, u32)>> = first.chromosomes.iter().map(|chromosome| tour(&chromosome.route)).collect();
+        let second_tours: HashSet<Vec<(u32, u32)>> = second.chromosomes.iter().map(|chromosome| tour(&chromosome.route)).collect();
+        let shared_tours: usize = first_tours.intersection(&second_tours).count();
+
+        // Compare the best Chromosome of each snapshot
+        let first_best = best(&first.chromosomes).wrap_err("First snapshot has no Chromosomes")?;
+        let second_best = best(&second.chromosomes).wrap_err("Second snapshot has no Chromosomes")?;
+
+        Ok(Self {
+            first_label: format!("{} generation {}", first.instance, first.generation),
+            second_label: format!("{} generation {}", second.instance, second.generation),
+            first_costs,
+            second_costs,
+            shared_tours,
+            same_best_tour: tour(&first_best.route) == tour(&second_best.route),
+            best_shared_edges: first_best.shared_edges(second_best),
+            route_length: first_best.route.len(),
+            edge_divergence: edge_divergence(&first.chromosomes, &second.chromosomes),
+        })
+    }
+}
+
+/// Implements [`Display`] for PopulationDiff, writing a report of the comparison
+/// 
+/// [`Display`]: std::fmt::Display
+impl fmt::Display for PopulationDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<10}{:>30}{:>30}", "", self.first_label, self.second_label)?;
+        for (name, first, second) in [
+            ("Best", self.first_costs.best, self.second_costs.best),
+            ("Worst", self.first_costs.worst, self.second_costs.worst),
+            ("Average", self.first_costs.average, self.second_costs.average),
+            ("Deviation", self.first_costs.deviation, self.second_costs.deviation),
+        ] {
+            writeln!(f, "{:<10}{:>30.2}{:>30.2}", name, first, second)?;
+        }
+        writeln!(f, "Tours in both populations: {}", self.shared_tours)?;
+        writeln!(
+            f, 
+            "Best tours are {}, sharing {} of {} edges", 
+            if self.same_best_tour { "the same" } else { "different" },
+            self.best_shared_edges,
+            self.route_length
+        )?;
+        write!(f, "Edge frequency divergence: {:.4} (0 is identical, {:.4} is no edges in common)", self.edge_divergence, 2f64.ln())
+    }
+}
+
+/// Function to find the cheapest Chromosome
+fn best(chromosomes: &[Chromosome]) -> Option<&Chromosome> {
+    chromosomes.iter().min_by(|a, b| a.cost.total_cmp(&b.cost))
+}
+
+/// Function to give the same value for every way of writing a tour, whichever city it starts from and whichever
+/// direction it is travelled in, by sorting its edges
+fn tour(route: &[u32]) -> Vec<(u32, u32)> {
+    let mut edges: Vec<(u32, u32)> = Chromosome::edges(route);
+    edges.sort();
+    edges
+}
+
+/// Function to find the proportion of all the edges of some Chromosomes each edge makes up
+fn edge_frequency(chromosomes: &[Chromosome]) -> HashMap<(u32, u32), f64> {
+    let mut frequency: HashMap<(u32, u32), f64> = HashMap::new();
+    let mut total: f64 = 0.0;
+    for chromosome in chromosomes {
+        for edge in Chromosome::edges(&chromosome.route) {
+            *frequency.entry(edge).or_insert(0.0) += 1.0;
+            total += 1.0;
+        }
+    }
+    frequency.values_mut().for_each(|count| *count /= total);
+    frequency
+}
+
+/// Function to find the Jensen-Shannon divergence between the edge frequencies of two groups of Chromosomes
+pub fn edge_divergence(first: &[Chromosome], second: &[Chromosome]) -> f64 {
+    let first: HashMap<(u32, u32), f64> = edge_frequency(first);
+    let second: HashMap<(u32, u32), f64> = edge_frequency(second);
+
+    // Each edge adds to the divergence of both groups from their average, p ln(p / m)
+    let edges: HashSet<&(u32, u32)> = first.keys().chain(second.keys()).collect();
+    edges
+        .into_iter()
+        .map(|edge| {
+            let p: f64 = first.get(edge).copied().unwrap_or(0.0);
+            let q: f64 = second.get(edge).copied().unwrap_or(0.0);
+            let m: f64 = (p + q) / 2.0;
+            let term = |x: f64| if x > 0.0 { x * (x / m).ln() } else { 0.0 };
+            (term(p) + term(q)) / 2.0
+        })
+        .sum()
+}
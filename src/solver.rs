@@ -0,0 +1,88 @@
+//! This module defines the [`Solver`] trait, the steps a [`Simulation`] needs from an algorithm so the
+//! genetic algorithm and the baselines it is compared against can be run, recorded and plotted the same way,
+//! and [`SolverReport`], the results of a run of any algorithm.
+//! 
+//! [`Simulation`]: crate::simulation::Simulation
+
+use std::time::Duration;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{chromosome::Chromosome, country::Graph, interface::{Algorithm, RunParameters}};
+
+/// An algorithm that improves tours one generation at a time
+pub trait Solver {
+    /// Function to run a single generation of the algorithm
+    fn step(&mut self, parameters: &RunParameters, graph: &Graph) -> Result<()>;
+
+    /// Function to return the cheapest tour the algorithm has now
+    fn best(&self) -> &Chromosome;
+
+    /// Function to return the most expensive tour the algorithm has now
+    fn worst(&self) -> &Chromosome;
+
+    /// Function to return the average cost of the tours the algorithm has now
+    fn average_cost(&self) -> f64;
+
+    /// Function to return the number of fitness evaluations used so far
+    fn evaluations(&self) -> u64;
+
+    /// Function to return the standard deviation of the costs of the tours the algorithm has now,
+    /// by default there is no spread of tours to measure
+    fn cost_deviation(&self) -> Option<f64> {
+        None
+    }
+
+    /// Function to return the entropy of the edges of the tours the algorithm has now,
+    /// by default there is no spread of tours to measure
+    fn edge_entropy(&self) -> Option<f64> {
+        None
+    }
+
+    /// Function to return the average fraction of edges two of the tours the algorithm has now don't share,
+    /// by default there is no spread of tours to measure
+    fn diversity(&self) -> Option<f64> {
+        None
+    }
+
+    /// Function to return the fraction of the tours the algorithm has now that contain each of the given edges,
+    /// by default there is no spread of tours to measure
+    fn edge_frequencies(&self, _edges: &[(u32, u32)]) -> Option<Vec<f64>> {
+        None
+    }
+}
+
+/// The best cost found by a [`Solver`] by a recorded generation
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    /// The generation the cost was recorded at
+    pub generation: u32,
+    /// The number of fitness evaluations used by that generation
+    pub evaluations: u64,
+    /// The cheapest cost found by that generation
+    pub best_cost: f64,
+}
+
+/// The results of a run of any [`Solver`], so runs of different algorithms can be compared without knowing which ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolverReport {
+    /// The algorithm that was run
+    pub algorithm: Algorithm,
+    /// The cheapest cost found by each recorded generation, in generation order
+    pub trajectory: Vec<TrajectoryPoint>,
+    /// The number of fitness evaluations used by the whole run
+    pub evaluations: u64,
+    /// How long the run took
+    pub elapsed: Duration,
+    /// The cheapest tour found by the run
+    pub best_tour: Chromosome,
+}
+
+/// Implement Methods on the [`SolverReport`] type
+impl SolverReport {
+    /// This function returns the cheapest cost found by the run
+    pub fn best_cost(&self) -> f64 {
+        self.best_tour.cost
+    }
+}
@@ -0,0 +1,367 @@
+//! This module defines the [`Tour`] trait, the order of the cities in a route as seen by a local search, and two
+//! ways of storing it. [`ArrayTour`] keeps the route in a vector, which is fastest on small countries, while
+//! [`TreeTour`] keeps it in a balanced binary tree so reversing a section of a route with thousands of cities
+//! doesn't move thousands of cities. Chromosomes keep their route as a vector, a tour is only built for the
+//! length of a local search.
+
+use rand::Rng;
+
+use super::random::rng;
+
+/// The number of cities from which local searches use a [`TreeTour`] rather than an [`ArrayTour`]. Finding a
+/// neighbour in the tree means walking it, which costs far more than looking in a vector, so the tree is only
+/// faster once reversals in a vector move many thousands of cities. Measured with the `local-search` benchmark,
+/// 2-opt from a random route with the tree takes around four times as long on 2,000 cities, 1.2 times as long
+/// on 10,000, the same on 12,500 and 0.7 to 0.9 times as long on 20,000
+pub const TREE_TOUR_CITIES: usize = 12_500;
+
+/// The order of the cities in a route, as a cycle that can be walked in either direction
+pub trait Tour {
+    /// The number of cities in the tour
+    fn len(&self) -> usize;
+
+    /// Whether the tour has no cities
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The city visited after the given city
+    fn next(&self, city: u32) -> u32;
+
+    /// The city visited before the given city
+    fn prev(&self, city: u32) -> u32;
+
+    /// Reverse the path of cities from `from` forwards to `to`, so the city before `from` is joined to `to`
+    /// and `from` is joined to the city after `to`. The tour may instead reverse the rest of the cities,
+    /// which joins the same cities together going the other way round
+    fn reverse(&mut self, from: u32, to: u32);
+
+    /// The cities of the tour in the order they're visited
+    fn route(&self) -> Vec<u32>;
+}
+
+/// The `ArrayTour` type, a route kept in a vector along with the position of every city in it
+#[derive(Debug, Clone)]
+pub struct ArrayTour {
+    /// The cities in the order they're visited
+    route: Vec<u32>,
+    /// The position of each city in the route
+    positions: Vec<usize>,
+}
+
+/// Implement Methods on the [`ArrayTour`] type
+impl ArrayTour {
+    /// This function builds the tour of a route
+    pub fn new(route: &[u32]) -> Self {
+        let mut positions: Vec<usize> = vec![0; route.len()];
+        for (position, city) in route.iter().enumerate() {
+            positions[*city as usize] = position;
+        }
+        Self { route: route.to_vec(), positions }
+    }
+}
+
+/// Implement [`Tour`] for the [`ArrayTour`] type, reversing whichever of the path and the rest of the
+/// cities is shorter so at most half of the cities move
+impl Tour for ArrayTour {
+    fn len(&self) -> usize {
+        self.route.len()
+    }
+
+    fn next(&self, city: u32) -> u32 {
+        self.route[(self.positions[city as usize] + 1) % self.route.len()]
+    }
+
+    fn prev(&self, city: u32) -> u32 {
+        let length: usize = self.route.len();
+        self.route[(self.positions[city as usize] + length - 1) % length]
+    }
+
+    fn reverse(&mut self, from: u32, to: u32) {
+        let length: usize = self.route.len();
+        let (first, last) = (self.positions[from as usize], self.positions[to as usize]);
+
+        // The number of cities from `from` to `to`, which can wrap around the end of the route
+        let inside: usize = (last + length - first) % length + 1;
+        let (mut first, mut last, count) = if inside * 2 <= length {
+            (first, last, inside)
+        } else {
+            ((last + 1) % length, (first + length - 1) % length, length - inside)
+        };
+
+        // Swap the cities from both ends inwards
+        for _ in 0..count / 2 {
+            self.route.swap(first, last);
+            self.positions[self.route[first] as usize] = first;
+            self.positions[self.route[last] as usize] = last;
+            first = (first + 1) % length;
+            last = (last + length - 1) % length;
+        }
+    }
+
+    fn route(&self) -> Vec<u32> {
+        self.route.clone()
+    }
+}
+
+/// Marks a missing child or parent in a [`TreeTour`]
+const NONE: u32 = u32::MAX;
+
+/// The `TreeTour` type, a route kept as a treap, a binary tree balanced by giving each city a random priority
+/// that is never lower than its children's. Reading the tree from left to right gives the route, and each city
+/// knows the size of the subtree below it, so its position can be found by walking up to the root. A section is
+/// reversed by splitting it off, marking it as reversed and joining the tree back together, and a marked subtree
+/// only swaps its children when something looks inside it. Every operation takes time proportional to the
+/// logarithm of the number of cities
+#[derive(Debug, Clone)]
+pub struct TreeTour {
+    /// The city at the root of the tree
+    root: u32,
+    /// The left child of each city
+    left: Vec<u32>,
+    /// The right child of each city
+    right: Vec<u32>,
+    /// The parent of each city
+    parent: Vec<u32>,
+    /// The priority of each city, higher priorities are nearer the root
+    priority: Vec<u32>,
+    /// The number of cities in the subtree below each city, including the city
+    size: Vec<usize>,
+    /// Whether the subtree below each city is yet to be reversed
+    flipped: Vec<bool>,
+}
+
+/// Implement Methods on the [`TreeTour`] type
+impl TreeTour {
+    /// This function builds the tour of a route
+    pub fn new(route: &[u32]) -> Self {
+        let length: usize = route.len();
+        let mut tour = Self {
+            root: NONE,
+            left: vec![NONE; length],
+            right: vec![NONE; length],
+            parent: vec![NONE; length],
+            priority: (0..length).map(|_| rng().gen()).collect(),
+            size: vec![1; length],
+            flipped: vec![false; length],
+        };
+
+        // Build the tree from left to right, keeping the cities on its right edge with the rightmost last
+        let mut right_edge: Vec<u32> = Vec::new();
+        for city in route.iter().copied() {
+            let mut last: u32 = NONE;
+            while let Some(top) = right_edge.last().copied() {
+                if tour.priority[top as usize] >= tour.priority[city as usize] {
+                    break;
+                }
+                last = right_edge.pop().unwrap_or(NONE);
+            }
+            tour.left[city as usize] = last;
+            if let Some(top) = right_edge.last().copied() {
+                tour.right[top as usize] = city;
+            }
+            right_edge.push(city);
+        }
+        tour.root = right_edge.first().copied().unwrap_or(NONE);
+
+        // Set the parents and sizes from the bottom of the tree up
+        for city in tour.post_order() {
+            tour.update(city);
+        }
+        if tour.root != NONE {
+            tour.parent[tour.root as usize] = NONE;
+        }
+        tour
+    }
+
+    /// This function lists the cities of the tree, children before their parents
+    fn post_order(&self) -> Vec<u32> {
+        let mut order: Vec<u32> = Vec::with_capacity(self.left.len());
+        let mut stack: Vec<u32> = if self.root == NONE { Vec::new() } else { vec![self.root] };
+        while let Some(city) = stack.pop() {
+            order.push(city);
+            for child in [self.left[city as usize], self.right[city as usize]] {
+                if child != NONE {
+                    stack.push(child);
+                }
+            }
+        }
+        order.reverse();
+        order
+    }
+
+    /// This function gives the size of a subtree, which is 0 if there is no subtree
+    fn subtree_size(&self, city: u32) -> usize {
+        if city == NONE { 0 } else { self.size[city as usize] }
+    }
+
+    /// This function recalculates the size of a city's subtree and makes its children point back to it
+    fn update(&mut self, city: u32) {
+        let (left, right) = (self.left[city as usize], self.right[city as usize]);
+        self.size[city as usize] = 1 + self.subtree_size(left) + self.subtree_size(right);
+        for child in [left, right] {
+            if child != NONE {
+                self.parent[child as usize] = city;
+            }
+        }
+    }
+
+    /// This function reverses a city's subtree if it is marked as reversed, passing the mark on to its children
+    fn push_down(&mut self, city: u32) {
+        if city == NONE || !self.flipped[city as usize] {
+            return;
+        }
+        let city: usize = city as usize;
+        self.flipped[city] = false;
+        std::mem::swap(&mut self.left[city], &mut self.right[city]);
+        for child in [self.left[city], self.right[city]] {
+            if child != NONE {
+                self.flipped[child as usize] ^= true;
+            }
+        }
+    }
+
+    /// This function splits a tree into the first `count` cities and the rest, returning both roots
+    fn split(&mut self, city: u32, count: usize) -> (u32, u32) {
+        if city == NONE {
+            return (NONE, NONE);
+        }
+        self.push_down(city);
+        let left: u32 = self.left[city as usize];
+        if self.subtree_size(left) >= count {
+            let (first, rest) = self.split(left, count);
+            self.left[city as usize] = rest;
+            self.update(city);
+            if first != NONE {
+                self.parent[first as usize] = NONE;
+            }
+            (first, city)
+        } else {
+            let right: u32 = self.right[city as usize];
+            let (first, rest) = self.split(right, count - self.subtree_size(left) - 1);
+            self.right[city as usize] = first;
+            self.update(city);
+            if rest != NONE {
+                self.parent[rest as usize] = NONE;
+            }
+            (city, rest)
+        }
+    }
+
+    /// This function joins two trees with every city of the first before every city of the second, returning the root
+    fn merge(&mut self, first: u32, second: u32) -> u32 {
+        if first == NONE {
+            return second;
+        }
+        if second == NONE {
+            return first;
+        }
+        if self.priority[first as usize] > self.priority[second as usize] {
+            self.push_down(first);
+            let joined: u32 = self.merge(self.right[first as usize], second);
+            self.right[first as usize] = joined;
+            self.update(first);
+            first
+        } else {
+            self.push_down(second);
+            let joined: u32 = self.merge(first, self.left[second as usize]);
+            self.left[second as usize] = joined;
+            self.update(second);
+            second
+        }
+    }
+
+    /// This function finds the position of a city in the route. The marks of the city's ancestors are read
+    /// from the root down rather than pushed down, so finding a position doesn't change the tree
+    pub fn position(&self, city: u32) -> usize {
+        let mut ancestors: Vec<u32> = vec![city];
+        while let Some(parent) = ancestors.last().map(|last| self.parent[*last as usize]).filter(|parent| *parent != NONE) {
+            ancestors.push(parent);
+        }
+
+        // Walk down from the root, counting the cities before the path, with a subtree's children swapped if
+        // an odd number of marks above it, or its own, say it is reversed
+        let mut position: usize = 0;
+        let mut reversed: bool = false;
+        for pair in ancestors.windows(2).rev() {
+            let (child, parent) = (pair[0], pair[1]);
+            reversed ^= self.flipped[parent as usize];
+            let (left, right) = if reversed {
+                (self.right[parent as usize], self.left[parent as usize])
+            } else {
+                (self.left[parent as usize], self.right[parent as usize])
+            };
+            if child == right {
+                position += self.subtree_size(left) + 1;
+            }
+        }
+        reversed ^= self.flipped[city as usize];
+        let left: u32 = if reversed { self.right[city as usize] } else { self.left[city as usize] };
+        position + self.subtree_size(left)
+    }
+
+    /// This function finds the city at a position in the route, without changing the tree
+    pub fn city_at(&self, mut position: usize) -> u32 {
+        let mut city: u32 = self.root;
+        let mut reversed: bool = false;
+        loop {
+            reversed ^= self.flipped[city as usize];
+            let (left, right) = if reversed {
+                (self.right[city as usize], self.left[city as usize])
+            } else {
+                (self.left[city as usize], self.right[city as usize])
+            };
+            let before: usize = self.subtree_size(left);
+            match position.cmp(&before) {
+                std::cmp::Ordering::Less => city = left,
+                std::cmp::Ordering::Equal => return city,
+                std::cmp::Ordering::Greater => {
+                    position -= before + 1;
+                    city = right;
+                },
+            }
+        }
+    }
+
+    /// This function reverses the cities from position `first` to position `last`, which must not wrap
+    fn reverse_positions(&mut self, first: usize, last: usize) {
+        let (before, rest) = self.split(self.root, first);
+        let (section, after) = self.split(rest, last - first + 1);
+        if section != NONE {
+            self.flipped[section as usize] ^= true;
+        }
+        let joined: u32 = self.merge(before, section);
+        self.root = self.merge(joined, after);
+        self.parent[self.root as usize] = NONE;
+    }
+}
+
+/// Implement [`Tour`] for the [`TreeTour`] type, reversing the rest of the cities when the path wraps around
+/// the end of the route so a section of the tree never wraps
+impl Tour for TreeTour {
+    fn len(&self) -> usize {
+        self.subtree_size(self.root)
+    }
+
+    fn next(&self, city: u32) -> u32 {
+        self.city_at((self.position(city) + 1) % self.len())
+    }
+
+    fn prev(&self, city: u32) -> u32 {
+        let length: usize = self.len();
+        self.city_at((self.position(city) + length - 1) % length)
+    }
+
+    fn reverse(&mut self, from: u32, to: u32) {
+        let (first, last) = (self.position(from), self.position(to));
+        if first <= last {
+            self.reverse_positions(first, last);
+        } else if last + 1 < first {
+            self.reverse_positions(last + 1, first - 1);
+        }
+    }
+
+    fn route(&self) -> Vec<u32> {
+        (0..self.len()).map(|position| self.city_at(position)).collect()
+    }
+}
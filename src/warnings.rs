@@ -0,0 +1,111 @@
+//! This module defines [`Warnings`], the problems found during an experiment that don't stop it. They are
+//! collected while the runs are going and printed together, grouped by [`WarningKind`], once the experiment
+//! ends, rather than being printed between the progress bars where they are easily missed.
+
+use std::fmt;
+
+/// Enumerate that represents what a [`Warning`] is about, warnings are grouped by kind when printed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WarningKind {
+    /// The options chosen work together, but probably not as intended
+    Configuration,
+    /// A setting in the control file couldn't be applied, or the file couldn't be read
+    ControlFile,
+    /// Parent selection kept picking the same Chromosome as both parents
+    Selection,
+    /// Most children were thrown away for being duplicates
+    Replacement,
+    /// The population lost its diversity while its best tour was still far from the best possible
+    Convergence,
+    /// The log of a run couldn't be written to, so the rest of the run wasn't logged
+    Log,
+    /// The best tour of a run travels between cities the dataset has no edge between
+    Feasibility,
+    /// The run was stopped part way through, so its results only cover the generations before it stopped
+    Cancelled,
+}
+
+/// Implement Display for WarningKind, naming the group its warnings are printed under
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: &str = match self {
+            WarningKind::Configuration => "Configuration",
+            WarningKind::ControlFile => "Control file",
+            WarningKind::Selection => "Parent selection",
+            WarningKind::Replacement => "Replacement",
+            WarningKind::Convergence => "Premature convergence",
+            WarningKind::Log => "Run log",
+            WarningKind::Feasibility => "Infeasible tours",
+            WarningKind::Cancelled => "Cancelled runs",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single problem found during the experiment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// What the warning is about
+    pub kind: WarningKind,
+    /// Where the warning came from, such as the country and run, or empty if it is about the whole experiment
+    pub source: String,
+    /// What went wrong
+    pub message: String,
+}
+
+/// Every warning found so far, in the order they were found
+#[derive(Debug, Clone, Default)]
+pub struct Warnings {
+    /// The warnings found so far
+    pub warnings: Vec<Warning>,
+}
+
+/// Implement methods on `Warnings`
+impl Warnings {
+    /// Function to add a warning
+    pub fn push(&mut self, kind: WarningKind, source: impl Into<String>, message: impl Into<String>) {
+        self.warnings.push(Warning { kind, source: source.into(), message: message.into() });
+    }
+
+    /// Function to add every warning of another collection
+    pub fn extend(&mut self, other: Warnings) {
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Function to count the warnings found so far
+    pub fn len(&self) -> usize {
+        self.warnings.len()
+    }
+
+    /// Function to check if no warnings have been found
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Function to list the warnings of a single kind, in the order they were found
+    pub fn of_kind(&self, kind: WarningKind) -> impl Iterator<Item = &Warning> {
+        self.warnings.iter().filter(move |warning| warning.kind == kind)
+    }
+}
+
+/// Implement Display for Warnings, listing every warning under a heading for its kind
+impl fmt::Display for Warnings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut kinds: Vec<WarningKind> = self.warnings.iter().map(|warning| warning.kind).collect();
+        kinds.sort();
+        kinds.dedup();
+
+        write!(f, "{} warning(s):", self.len())?;
+        for kind in kinds {
+            write!(f, "\n  {} ({}):", kind, self.of_kind(kind).count())?;
+            for warning in self.of_kind(kind) {
+                if warning.source.is_empty() {
+                    write!(f, "\n    {}", warning.message)?;
+                } else {
+                    write!(f, "\n    {}: {}", warning.source, warning.message)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use tsp_coursework::*;
+
+#[test]
+fn check_adaptive_pursuit() {
+    let mut pursuit = adaptive::AdaptivePursuit::new(vec!['a', 'b', 'c', 'd']);
+
+    // Every operator starts equally likely
+    assert_eq!(pursuit.probabilities, vec![0.25; 4]);
+    assert_eq!(pursuit.minimum_probability, 0.125);
+
+    // Operators start with the best estimate, so one that hasn't been tried yet is still favoured
+    pursuit.reward(0, false);
+    assert!(pursuit.probabilities[1] > pursuit.probabilities[0]);
+
+    // Rewarding one operator over and over pursues it towards the highest probability, leaving the rest at the lowest
+    for _ in 0..200 {
+        pursuit.reward(2, true);
+        for position in [0, 1, 3] {
+            pursuit.reward(position, false);
+        }
+    }
+    assert!((pursuit.probabilities[2] - 0.625).abs() < 1e-6);
+    for position in [0, 1, 3] {
+        assert!((pursuit.probabilities[position] - 0.125).abs() < 1e-6);
+    }
+    assert!((pursuit.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // Operators that are never picked again keep their chance of being tried
+    assert!((0..100).all(|_| pursuit.choose().0 < 4));
+
+    let statistics = pursuit.statistics();
+    assert_eq!(statistics[2].operator, "'c'");
+    assert_eq!((statistics[2].uses, statistics[2].successes), (200, 200));
+    assert_eq!((statistics[0].uses, statistics[0].successes), (201, 0));
+    assert_eq!(statistics[1].uses, 200);
+}
+
+#[test]
+fn check_adaptive_operators() {
+    // Every crossover and mutation operator can be picked
+    let operators = adaptive::AdaptiveOperators::new();
+    assert_eq!(operators.crossover.operators.len(), 5);
+    assert_eq!(operators.mutation.operators.len(), 8);
+    assert!(operators.mutation.operators.contains(&interface::MutationOperator::OrOpt));
+}
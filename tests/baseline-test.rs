@@ -0,0 +1,51 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_random_search() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let population = population::Population::new(10, &burma_small.graph).unwrap();
+
+    // The random tours of the population are the first ones sampled
+    let mut search = baseline::RandomSearch::new(&population);
+    assert_eq!(search.evaluations, 10);
+    assert_eq!(search.best.cost, population.best_chromosome.cost);
+
+    // Sampling uses exactly the evaluations given and never gets worse
+    search.sample_until(200, &burma_small.graph).unwrap();
+    assert_eq!(search.evaluations, 200);
+    assert!(search.best.cost <= population.best_chromosome.cost);
+    assert_eq!(search.best.cost, chromosome::Chromosome::fitness(&search.best.route, &burma_small.graph).unwrap());
+}
+
+#[test]
+fn check_random_baseline() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 50,
+        random_baseline: true,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // The random search is given the same evaluations as the population and recorded every generation
+    assert_eq!(sim.random_search.as_ref().unwrap().evaluations, sim.population.evaluations);
+    assert_eq!(sim.random_search_cost.len(), sim.recorded_generations.len());
+    assert!(sim.random_search_cost.windows(2).all(|pair| pair[1] <= pair[0]));
+
+    // It is drawn for costs only
+    let data = [sim];
+    let (label, line) = simulation::Simulation::random_search_series(&data, interface::PlotStatistic::Best).unwrap();
+    assert_eq!(label, "Random Search");
+    assert_eq!(line.len(), 50);
+    assert!(simulation::Simulation::random_search_series(&data, interface::PlotStatistic::Entropy).is_none());
+
+    // Without the baseline nothing is drawn
+    let sim = simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap();
+    assert_eq!(sim.random_search_cost, vec![None]);
+    assert!(simulation::Simulation::random_search_series(&[sim], interface::PlotStatistic::Best).is_none());
+}
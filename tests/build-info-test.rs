@@ -0,0 +1,17 @@
+use tsp_coursework::*;
+
+#[test]
+fn check_build_info() {
+    let build = build_info::BuildInfo::current();
+
+    // The build matches the crate and is described the same way everywhere
+    assert_eq!(build.version, env!("CARGO_PKG_VERSION"));
+    assert!(!build.git_hash.is_empty());
+    assert_eq!(build.id(), format!("{}+{}.{}", build.version, build.git_hash, build.profile));
+    assert!(build.to_string().starts_with(&format!("tsp-coursework {} (commit {}", build.version, build.git_hash)));
+    assert!(build_info::LONG_VERSION.starts_with(build_info::VERSION));
+
+    // Exports can read the build back
+    let json = serde_json::to_string(&build).unwrap();
+    assert_eq!(serde_json::from_str::<build_info::BuildInfo>(&json).unwrap(), build);
+}
@@ -1,34 +1,8 @@
 use tsp_coursework::*;
+use clap::ValueEnum;
 
-const SRC: &str = r#"<travellingSalesmanProblemInstance>
-<name>burma14</name>
-<source>TSPLIB</source>
-<description>14-Staedte in Burma (Zaw Win)</description>
-<doublePrecision>15</doublePrecision>
-<ignoredDigits>5</ignoredDigits>
-<graph>
-<vertex>
-    <edge cost="1.530000000000000e+02">1</edge>
-    <edge cost="5.100000000000000e+02">2</edge>
-    <edge cost="7.060000000000000e+02">3</edge>
-</vertex>
-<vertex>
-    <edge cost="1.530000000000000e+02">0</edge>
-    <edge cost="4.220000000000000e+02">2</edge>
-    <edge cost="6.640000000000000e+02">3</edge>
-</vertex>
-<vertex>
-    <edge cost="5.100000000000000e+02">0</edge>
-    <edge cost="4.220000000000000e+02">1</edge>
-    <edge cost="2.890000000000000e+02">3</edge>
-</vertex>
-<vertex>
-    <edge cost="7.060000000000000e+02">0</edge>
-    <edge cost="6.640000000000000e+02">1</edge>
-    <edge cost="2.890000000000000e+02">2</edge>
-</vertex>
-</graph>
-</travellingSalesmanProblemInstance>"#;
+mod common;
+use common::SRC;
 
 #[test]
 fn check_fitness(){
@@ -43,39 +17,587 @@ fn check_fitness(){
         cost, chromosome::Chromosome::fitness(&test_chromosome.route, &burma_small.graph).unwrap());
 }
 
+#[test]
+fn check_fixture_fitness() {
+    // Every tour of the fixed graphs costs what was worked out by hand
+    for fixture in harness::graphs() {
+        harness::check_fitness(&fixture).unwrap();
+    }
+
+    // The burma fixture is the same graph as the XML
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    assert_eq!(burma_small.graph.distances.costs, harness::burma4().graph.distances.costs);
+}
+
 #[test]
 fn check_crossover() {
+    let line = harness::line6();
+
+    // Every crossover operator makes the child worked out by hand from fixed crossover points
+    for case in harness::crossover_cases() {
+        harness::check_crossover(&case, &line.graph).unwrap();
+    }
+
+    // Each operator has a case
+    for operator in interface::CrossoverOperator::value_variants() {
+        assert!(harness::crossover_cases().iter().any(|case| case.operator == *operator), "{:?}", operator);
+    }
+
+    // The wrong number of crossover points is an error rather than a panic
+    let error = chromosome::Chromosome::crossover_child(
+        &[0, 1, 2, 3, 4, 5],
+        &[3, 5, 1, 0, 4, 2],
+        interface::CrossoverOperator::Ordered,
+        &[1, 2, 3],
+        &line.graph
+    );
+    assert!(error.is_err());
+}
+
+#[test]
+fn check_mutation() {
+    let line = harness::line6();
+
+    // Every mutation operator makes the route worked out by hand at fixed indices, keeping the cost up to date
+    for case in harness::mutation_cases() {
+        harness::check_mutation(&case, &line.graph).unwrap();
+    }
+
+    // Each operator has a case
+    for operator in interface::MutationOperator::value_variants() {
+        assert!(harness::mutation_cases().iter().any(|case| case.operator == *operator), "{:?}", operator);
+    }
+
+    // A case with the wrong route is caught
+    let mut wrong = harness::mutation_cases()[0].clone();
+    wrong.mutated.swap(0, 1);
+    assert!(harness::check_mutation(&wrong, &line.graph).is_err());
 
-    // crossover point 3
-    // p1 [2, 1, 0, 3]
-    // p2 [0, 2, 3, 1]
+    // The wrong number of indices, or an inversion between the same index, is an error rather than a panic
+    let mut chromo = chromosome::Chromosome::new(vec![0, 1, 2, 3, 4, 5], 10.0);
+    assert!(chromo.mutate_at(interface::MutationOperator::Multiple, &[0, 1], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Inversion, &[2, 2], &line.graph).is_err());
 
-    // c1 [2, 2, 0, 1]
-    // c2 [0, 2, 0, 3]
+    // Indices past the end of the route, a slice that ends before it starts, or a segment put back past the end are
+    // errors too, and leave the route as it was
+    assert!(chromo.mutate_at(interface::MutationOperator::Single, &[0, 99], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Single, &[0, 6], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Multiple, &[0, 1, 2, 6], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Inversion, &[1, 7], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Scramble, &[4, 2], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Scramble, &[2, 7], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Displacement, &[3, 1, 0], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Displacement, &[1, 3, 5], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::OrOpt, &[5, 7, 0], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Insertion, &[6, 0], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::Insertion, &[0, 6], &line.graph).is_err());
+    assert!(chromo.mutate_at(interface::MutationOperator::ExpensiveEdge, &[1, 6], &line.graph).is_err());
+    assert_eq!(chromo.route, vec![0, 1, 2, 3, 4, 5]);
 
+    // The bounds themselves are still accepted
+    assert!(chromo.mutate_at(interface::MutationOperator::Displacement, &[4, 6, 4], &line.graph).is_ok());
+    assert_eq!(chromo.route, vec![0, 1, 2, 3, 4, 5]);
+    assert!(chromo.mutate_at(interface::MutationOperator::Insertion, &[5, 0], &line.graph).is_ok());
+    assert_eq!(chromo.route, vec![5, 0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn check_ordered_crossover() {
+    let line = harness::line6();
+
+    // Both slices keep their positions and the rest keep the order they have in the second parent
+    let first_parent: &[u32] = &[0, 1, 2, 3, 4, 5];
+    let second_parent: &[u32] = &[3, 5, 1, 0, 4, 2];
+    let child = chromosome::Chromosome::ordered_crossover(&first_parent, &second_parent, &[0, 1, 3, 4]).unwrap();
+    assert_eq!(child, vec![0, 1, 5, 3, 4, 2]);
+
+    // Random crossover points always give valid routes
+    let parent_one = chromosome::Chromosome::generation(&line.graph).unwrap();
+    let parent_two = chromosome::Chromosome::generation(&line.graph).unwrap();
+    for _ in 0..50 {
+        let (child_one, child_two) = parent_one
+            .crossover(&parent_two, interface::CrossoverOperator::Ordered, &line.graph)
+            .unwrap();
+        harness::check_route(&child_one.route, &line.graph).unwrap();
+        harness::check_route(&child_two.route, &line.graph).unwrap();
+    }
+}
+
+#[test]
+fn check_small_routes() {
+    let triangle = harness::graph_from_costs(&[
+        vec![0.0, 3.0, 4.0],
+        vec![3.0, 0.0, 5.0],
+        vec![4.0, 5.0, 0.0],
+    ]);
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Every operator works on 3 and 4 city routes, operators needing 4 indices falling back to fewer on 3 cities
+    for graph in [&triangle, &burma_small.graph] {
+        for operator in interface::MutationOperator::value_variants() {
+            let mut chromo = chromosome::Chromosome::generation(graph).unwrap();
+            for _ in 0..50 {
+                chromo.mutation(*operator, graph).unwrap();
+                harness::check_route(&chromo.route, graph).unwrap();
+                assert_eq!(chromo.cost, chromosome::Chromosome::fitness(&chromo.route, graph).unwrap(), "{:?}", operator);
+            }
+        }
+        for operator in interface::CrossoverOperator::value_variants() {
+            for _ in 0..50 {
+                let parent_one = chromosome::Chromosome::generation(graph).unwrap();
+                let parent_two = chromosome::Chromosome::generation(graph).unwrap();
+                let (child_one, child_two) = parent_one.crossover(&parent_two, *operator, graph).unwrap();
+                harness::check_route(&child_one.route, graph).unwrap();
+                harness::check_route(&child_two.route, graph).unwrap();
+            }
+        }
+    }
+
+    // Routes of fewer than 3 cities are an error rather than a panic or a hang
+    let pair = harness::graph_from_costs(&[vec![0.0, 1.0], vec![1.0, 0.0]]);
+    let mut chromo = chromosome::Chromosome::new(vec![0, 1], 2.0);
+    assert!(chromo.mutation(interface::MutationOperator::Multiple, &pair).is_err());
+    assert!(chromo.mutation(interface::MutationOperator::Single, &pair).is_err());
+    assert!(chromo.try_crossover(&chromosome::Chromosome::new(vec![1, 0], 2.0), interface::CrossoverOperator::Ordered, &pair).is_err());
+}
+
+#[test]
+fn check_delta_fitness() {
     let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // After many mutations of each type the updated cost still matches the cost of the whole route
+    for operator in [
+        interface::MutationOperator::Inversion,
+        interface::MutationOperator::Single,
+        interface::MutationOperator::Multiple,
+        interface::MutationOperator::Scramble,
+        interface::MutationOperator::Displacement,
+        interface::MutationOperator::Insertion,
+        interface::MutationOperator::OrOpt,
+    ] {
+        let mut chromo = chromosome::Chromosome::generation(&burma_small.graph).unwrap();
+        for _ in 0..100 {
+            chromo.mutation(operator, &burma_small.graph).unwrap();
+            let fitness = chromosome::Chromosome::fitness(&chromo.route, &burma_small.graph).unwrap();
+            assert_eq!(chromo.cost, fitness, "{:?} gave route {:?}", operator, chromo.route);
+        }
+    }
+}
+
+#[test]
+fn check_inversion_delta() {
+    // Every pair of different costs, the same in both directions
+    let costs: Vec<Vec<f64>> = (0..7)
+        .map(|from| {
+            (0..7)
+                .map(|to| if from == to { 0.0 } else { ((from + 1) * (to + 1) % 11 + from + to) as f64 })
+                .collect()
+        })
+        .collect();
+    let graph = harness::graph_from_costs(&costs);
+    assert!(graph.symmetric);
+
+    // Whatever slice is kept, the cost found from the edges that change matches the cost of the whole new route
+    let route: Vec<u32> = vec![3, 0, 6, 2, 5, 1, 4];
+    let cost: f64 = chromosome::Chromosome::fitness(&route, &graph).unwrap();
+    for lower in 0..route.len() {
+        for upper in lower + 1..=route.len() {
+            let mut chromo = chromosome::Chromosome::new(route.clone(), cost);
+            chromo.mutate_at(interface::MutationOperator::Inversion, &[lower, upper], &graph).unwrap();
+            let fitness = chromosome::Chromosome::fitness(&chromo.route, &graph).unwrap();
+            assert_eq!(chromo.cost, fitness, "inversion of {}..{} gave route {:?}", lower, upper, chromo.route);
+        }
+    }
+}
+
+#[test]
+fn check_asymmetric_costs() {
+    // Going round clockwise costs 1 per edge but anticlockwise costs 10, with a mix of costs across the middle
+    let graph = harness::graph_from_costs(&[
+        vec![0.0, 1.0, 4.0, 7.0, 10.0],
+        vec![10.0, 0.0, 1.0, 5.0, 3.0],
+        vec![6.0, 10.0, 0.0, 1.0, 8.0],
+        vec![2.0, 9.0, 10.0, 0.0, 1.0],
+        vec![1.0, 4.0, 3.0, 10.0, 0.0],
+    ]);
+    assert!(!graph.symmetric);
+
+    // Routes are costed in the direction they are travelled
+    let clockwise = chromosome::Chromosome::fitness(&[0, 1, 2, 3, 4], &graph).unwrap();
+    let anticlockwise = chromosome::Chromosome::fitness(&[4, 3, 2, 1, 0], &graph).unwrap();
+    assert_eq!((clockwise, anticlockwise), (5.0, 50.0));
+
+    // The updated cost after each mutation still matches the cost of the whole route
+    for operator in interface::MutationOperator::value_variants() {
+        let mut chromo = chromosome::Chromosome::generation(&graph).unwrap();
+        for _ in 0..100 {
+            chromo.mutation(*operator, &graph).unwrap();
+            let fitness = chromosome::Chromosome::fitness(&chromo.route, &graph).unwrap();
+            assert_eq!(chromo.cost, fitness, "{:?} gave route {:?}", operator, chromo.route);
+        }
+    }
+
+    // 2-opt reverses part of the route so it is skipped, while Or-opt keeps its cost matching the route
+    let mut chromo = chromosome::Chromosome::new(vec![4, 3, 2, 1, 0], anticlockwise);
+    chromo.local_search(interface::LocalSearch::TwoOpt, &graph).unwrap();
+    assert_eq!(chromo.route, vec![4, 3, 2, 1, 0]);
+    chromo.local_search(interface::LocalSearch::OrOpt, &graph).unwrap();
+    assert!(chromo.cost < anticlockwise);
+    assert_eq!(chromo.cost, chromosome::Chromosome::fitness(&chromo.route, &graph).unwrap());
+}
+
+#[test]
+fn check_nearest_neighbour_crossover() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Slices [0] and [1] are kept and cities 2 and 3 fill the rest. The second parent has 3 first, 
+    // but city 2 is cheaper to reach from city 1 (422 against 664) so it comes first
+    let first_parent: &[u32] = &[0, 1, 2, 3];
+    let second_parent: &[u32] = &[3, 2, 1, 0];
+    let child = chromosome::Chromosome::nearest_neighbour_crossover(
+        &first_parent, 
+        &second_parent, 
+        &[0, 0, 1, 1], 
+        &burma_small.graph
+    ).unwrap();
+    assert_eq!(child, vec![0, 1, 2, 3]);
+
+    // Every child is a valid route
     let parent_one = chromosome::Chromosome::generation(&burma_small.graph).unwrap();
     let parent_two = chromosome::Chromosome::generation(&burma_small.graph).unwrap();
+    let (child_one, child_two) = parent_one.crossover(&parent_two, interface::CrossoverOperator::Nearest, &burma_small.graph).unwrap();
+    for child in [child_one, child_two] {
+        let mut route = child.route.clone();
+        route.sort();
+        assert_eq!(route, vec![0, 1, 2, 3]);
+    }
+}
 
-    let (child_one, child_two) = parent_one.crossover(&parent_two, interface::CrossoverOperator::Fix, &burma_small.graph).unwrap();
+#[test]
+fn check_cycle_crossover() {
+    // Cycles are positions {0, 3, 6, 7}, {1, 2, 4} and {5}, taken from the first, second and first parent
+    let first_parent: &[u32] = &[0, 1, 2, 3, 4, 5, 6, 7];
+    let second_parent: &[u32] = &[7, 4, 1, 0, 2, 5, 3, 6];
 
-    println!("first child: {:?} second child: {:?} first parent: {:?} second parent: {:?}", child_one, child_two, parent_one, parent_two)
+    let child = chromosome::Chromosome::cycle_crossover(&first_parent, &second_parent).unwrap();
+    assert_eq!(child, vec![0, 4, 1, 3, 2, 5, 6, 7]);
+
+    // Every gene keeps the position it had in one of the parents
+    let other_child = chromosome::Chromosome::cycle_crossover(&second_parent, &first_parent).unwrap();
+    for (index, gene) in other_child.iter().enumerate() {
+        assert!(first_parent[index] == *gene || second_parent[index] == *gene);
+    }
 }
 
 #[test]
-fn check_mutation() {
+fn check_sequential_constructive_crossover() {
     let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
-    let route = vec![0,1,2,3,4,5];
-    let fitness = chromosome::Chromosome::fitness(&route, &burma_small.graph).unwrap();
 
-    let mut chromo = chromosome::Chromosome::new(route, fitness);
+    // From 0 the candidates are 1 (153) and 3 (706). From 1 the first parent gives 3 (664), but the second
+    // parent has nothing after 1 so gives the lowest unvisited city 2 (422). That leaves 3 as the only city left
+    let first_parent: &[u32] = &[0, 1, 3, 2];
+    let second_parent: &[u32] = &[2, 0, 3, 1];
+    let child = chromosome::Chromosome::sequential_constructive_crossover(
+        &first_parent, 
+        &second_parent, 
+        &burma_small.graph
+    ).unwrap();
+    assert_eq!(child, vec![0, 1, 2, 3]);
+}
 
-    chromo.mutation(interface::MutationOperator::Single, &burma_small.graph).unwrap();
+#[test]
+fn check_segment_mutations() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
 
-    todo!()
+    // Scrambling and displacing segments or single cities keep every city in the route exactly once
+    for operator in [
+        interface::MutationOperator::Scramble, 
+        interface::MutationOperator::Displacement, 
+        interface::MutationOperator::Insertion,
+        interface::MutationOperator::OrOpt,
+    ] {
+        let mut chromo = chromosome::Chromosome::generation(&burma_small.graph).unwrap();
+        for _ in 0..100 {
+            chromo.mutation(operator, &burma_small.graph).unwrap();
+            let mut route = chromo.route.clone();
+            route.sort();
+            assert_eq!(route, vec![0, 1, 2, 3], "{:?}", operator);
+        }
+    }
 }
 
 #[test]
-fn check_ordered_crossover() {
-    todo!()
-}
\ No newline at end of file
+fn check_expensive_edge_inversion() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Every use changes the tour, keeping every city once and the cost up to date. With four cities the second edge
+    // is always the one opposite the first, so the two edges costing 422 and 706 are removed together whenever
+    // either is picked first, which is 1128 times in every 1570
+    let mut removed_expensive: usize = 0;
+    for _ in 0..2000 {
+        let mut chromo = chromosome::Chromosome::new(vec![0, 1, 2, 3], 1570.0);
+        chromo.mutation(interface::MutationOperator::ExpensiveEdge, &burma_small.graph).unwrap();
+
+        let mut route = chromo.route.clone();
+        route.sort();
+        assert_eq!(route, vec![0, 1, 2, 3]);
+        assert_eq!(chromo.cost, chromosome::Chromosome::fitness(&chromo.route, &burma_small.graph).unwrap());
+        assert_ne!(chromo.tour_hash(), chromosome::Chromosome::new(vec![0, 1, 2, 3], 0.0).tour_hash());
+
+        if !chromosome::Chromosome::edges(&chromo.route).contains(&(0, 3)) {
+            removed_expensive += 1;
+        }
+    }
+    assert!((1300..1570).contains(&removed_expensive), "{}", removed_expensive);
+
+    // A route with only one tour is left as it is
+    let mut chromo = chromosome::Chromosome::new(vec![0, 1, 2], 0.0);
+    chromo.expensive_edge_inversion(&burma_small.graph).unwrap();
+    assert_eq!(chromo.route, vec![0, 1, 2]);
+}
+
+#[test]
+fn check_local_search() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // With 4 cities there are only 3 different tours, and both 2-opt and Or-opt can reach each of them from the others
+    let optimal = [vec![0, 1, 2, 3], vec![0, 1, 3, 2], vec![0, 2, 1, 3]]
+        .iter()
+        .map(|route| chromosome::Chromosome::fitness(route, &burma_small.graph).unwrap())
+        .fold(f64::INFINITY, f64::min);
+
+    // Local search always finds the cheapest tour and keeps the cost matching the route
+    for local_search in [interface::LocalSearch::TwoOpt, interface::LocalSearch::OrOpt] {
+        for _ in 0..20 {
+            let mut chromo = chromosome::Chromosome::generation(&burma_small.graph).unwrap();
+            let before = chromo.cost;
+            chromo.local_search(local_search, &burma_small.graph).unwrap();
+            assert!(chromo.cost <= before);
+            assert_eq!(chromo.cost, optimal, "{:?} gave route {:?}", local_search, chromo.route);
+            assert_eq!(chromo.cost, chromosome::Chromosome::fitness(&chromo.route, &burma_small.graph).unwrap());
+        }
+    }
+}
+
+#[test]
+fn check_try_crossover() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parent = chromosome::Chromosome::new(vec![0, 1, 2, 3], 0.0);
+    let parent = chromosome::Chromosome::new(parent.route.clone(), chromosome::Chromosome::fitness(&parent.route, &burma_small.graph).unwrap());
+
+    // Identical parents make no new genetic material, unless the operator can reorder the cities it fills in
+    let clones = parent.try_crossover(&parent, interface::CrossoverOperator::Cycle, &burma_small.graph).unwrap();
+    assert!(clones.is_none());
+    let children = parent.try_crossover(&parent, interface::CrossoverOperator::Nearest, &burma_small.graph).unwrap();
+    assert!(children.is_some());
+
+    // The compatible wrapper gives copies of the parents back with their cost
+    let (first_child, second_child) = parent.crossover(&parent, interface::CrossoverOperator::Scx, &burma_small.graph).unwrap();
+    assert_eq!(first_child.route, parent.route);
+    assert_eq!(second_child.route, parent.route);
+    assert_eq!(first_child.cost, parent.cost);
+}
+
+#[test]
+fn check_candidate_local_search() {
+    // Cities spread around a grid, so there are many moves to limit
+    let points: Vec<(f64, f64)> = (0..40).map(|city| ((city * 7 % 13) as f64, (city * 11 % 17) as f64)).collect();
+    let vertex: Vec<country::Vertex> = points
+        .iter()
+        .map(|from| country::Vertex {
+            edges: points
+                .iter()
+                .enumerate()
+                .map(|(to, point)| country::Edge { 
+                    cost: ((from.0 - point.0).powi(2) + (from.1 - point.1).powi(2)).sqrt(), 
+                    destination_city: to as u32 
+                })
+                .collect(),
+        })
+        .collect();
+    let mut graph = country::Graph::new(vertex);
+    graph.build_candidate_lists(5);
+
+    // Limited local search still only ever improves the route, keeping every city exactly once
+    for local_search in [interface::LocalSearch::TwoOpt, interface::LocalSearch::OrOpt] {
+        for _ in 0..10 {
+            let mut chromo = chromosome::Chromosome::generation(&graph).unwrap();
+            let before = chromo.cost;
+            chromo.local_search(local_search, &graph).unwrap();
+            assert!(chromo.cost < before, "{:?} didn't improve a random route", local_search);
+
+            let mut route = chromo.route.clone();
+            route.sort();
+            assert_eq!(route, (0..40).collect::<Vec<u32>>());
+        }
+    }
+}
+
+#[test]
+fn check_local_search_around() {
+    let points: Vec<(f64, f64)> = (0..40).map(|city| ((city * 7 % 13) as f64, (city * 11 % 17) as f64)).collect();
+    let vertex: Vec<country::Vertex> = points
+        .iter()
+        .map(|from| country::Vertex {
+            edges: points
+                .iter()
+                .enumerate()
+                .map(|(to, point)| country::Edge { 
+                    cost: ((from.0 - point.0).powi(2) + (from.1 - point.1).powi(2)).sqrt(), 
+                    destination_city: to as u32 
+                })
+                .collect(),
+        })
+        .collect();
+    let graph = country::Graph::new(vertex);
+
+    for local_search in [interface::LocalSearch::TwoOpt, interface::LocalSearch::OrOpt] {
+        let mut chromo = chromosome::Chromosome::generation(&graph).unwrap();
+        chromo.local_search(local_search, &graph).unwrap();
+        let optimum = chromo.clone();
+
+        // Searching from no cities leaves the route alone, without evaluating any moves
+        let moves = chromo.local_search_around(local_search, &[], &graph, &cancellation::CancellationToken::new(), u64::MAX).unwrap();
+        assert_eq!(chromo.route, optimum.route);
+        assert_eq!(moves, 0);
+
+        // Searching a local optimum again from every city finds nothing to improve
+        chromo.local_search(local_search, &graph).unwrap();
+        assert_eq!(chromo.route, optimum.route);
+
+        // Swapping two cities and searching from them never makes the route worse than it was after the swap
+        chromo.route.swap(3, 20);
+        chromo.cost = chromosome::Chromosome::fitness(&chromo.route, &graph).unwrap();
+        let swapped = chromo.cost;
+        let cities = [chromo.route[2], chromo.route[3], chromo.route[4], chromo.route[19], chromo.route[20], chromo.route[21]];
+        let moves = chromo.local_search_around(local_search, &cities, &graph, &cancellation::CancellationToken::new(), u64::MAX).unwrap();
+        assert!(chromo.cost <= swapped);
+        assert!(moves >= cities.len() as u64);
+
+        let mut route = chromo.route.clone();
+        route.sort();
+        assert_eq!(route, (0..40).collect::<Vec<u32>>());
+
+        // A cancelled search stops before looking at any city
+        let cancelled = cancellation::CancellationToken::new();
+        cancelled.cancel();
+        let mut random = chromosome::Chromosome::generation(&graph).unwrap();
+        let cities = random.route.clone();
+        random.local_search_around(local_search, &cities, &graph, &cancelled, u64::MAX).unwrap();
+        assert_eq!(random.route, cities);
+
+        // A search that may only evaluate a few moves stops once it has
+        let mut limited = chromosome::Chromosome::generation(&graph).unwrap();
+        let cities = limited.route.clone();
+        let moves = limited.local_search_around(local_search, &cities, &graph, &cancellation::CancellationToken::new(), 5).unwrap();
+        assert_eq!(moves, 5);
+        assert!(limited.cost <= chromosome::Chromosome::fitness(&cities, &graph).unwrap());
+    }
+
+    // No chain of 1 to 3 cities, including those that wrap around the end of the route, can be moved anywhere
+    // cheaper once Or-opt has finished
+    let cost = |from: u32, to: u32| graph.distances.get(from, to).unwrap();
+    let mut chromo = chromosome::Chromosome::generation(&graph).unwrap();
+    chromo.local_search(interface::LocalSearch::OrOpt, &graph).unwrap();
+    let (route, length) = (&chromo.route, chromo.route.len());
+    for start in 0..length {
+        for chain_length in 1..=3 {
+            let (first, last) = (route[start], route[(start + chain_length - 1) % length]);
+            let (before, after) = (route[(start + length - 1) % length], route[(start + chain_length) % length]);
+            let removed = cost(before, first) + cost(last, after) - cost(before, after);
+            for i in (0..length).filter(|i| (i + 1 + length - start) % length > chain_length) {
+                let (a, b) = (route[i], route[(i + 1) % length]);
+                assert!(cost(a, first) + cost(last, b) - cost(a, b) - removed >= -1e-9);
+            }
+        }
+    }
+}
+
+#[test]
+fn check_tour_hash() {
+    let route = chromosome::Chromosome::new(vec![2, 0, 3, 1, 4], 0.0);
+
+    // The same tour from a different start or in the other direction has the same normal form and hash
+    assert_eq!(route.normalised_route(), vec![0, 2, 4, 1, 3]);
+    for same in [vec![0, 3, 1, 4, 2], vec![4, 1, 3, 0, 2], vec![1, 3, 0, 2, 4]] {
+        let same = chromosome::Chromosome::new(same, 0.0);
+        assert_eq!(same.normalised_route(), route.normalised_route());
+        assert_eq!(same.tour_hash(), route.tour_hash());
+    }
+
+    // A different tour doesn't
+    let different = chromosome::Chromosome::new(vec![0, 1, 3, 4, 2], 0.0);
+    assert_ne!(different.tour_hash(), route.tour_hash());
+}
+
+#[test]
+fn check_distance() {
+    let route = chromosome::Chromosome::new(vec![0, 1, 2, 3, 4], 0.0);
+
+    // The same tour started elsewhere or travelled backwards has every edge in common
+    assert_eq!(route.distance(&chromosome::Chromosome::new(vec![2, 1, 0, 4, 3], 0.0)), 0);
+
+    // Swapping two neighbouring cities changes two edges
+    assert_eq!(route.distance(&chromosome::Chromosome::new(vec![0, 2, 1, 3, 4], 0.0)), 2);
+
+    // A pentagram shares no edges with the pentagon
+    assert_eq!(route.distance(&chromosome::Chromosome::new(vec![0, 2, 4, 1, 3], 0.0)), 5);
+}
+
+#[test]
+fn check_compare_costs() {
+    use std::cmp::Ordering;
+
+    // Costs that only differ by rounding are equal with a tolerance, but not without one
+    assert_eq!(chromosome::Chromosome::compare_costs(0.1 + 0.2, 0.3, 0.0), Ordering::Greater);
+    assert_eq!(chromosome::Chromosome::compare_costs(0.1 + 0.2, 0.3, 1e-9), Ordering::Equal);
+
+    // The tolerance is relative to the size of the costs
+    assert_eq!(chromosome::Chromosome::compare_costs(1000.0, 1000.5, 1e-3), Ordering::Equal);
+    assert_eq!(chromosome::Chromosome::compare_costs(1.0, 1.5, 1e-3), Ordering::Less);
+
+    // Nothing is within the tolerance of an infinite cost
+    assert_eq!(chromosome::Chromosome::compare_costs(1.0, f64::INFINITY, 1e-3), Ordering::Less);
+}
+
+#[test]
+fn check_compensated_summation() {
+    // A ring of cities whose edges alternate between a huge cost and a tiny one, both exactly representable,
+    // so the exact cost of the route is known
+    let cities: u32 = 2000;
+    let tiny: f64 = 1.0 / 1_048_576.0;
+    let cost = |city: u32| if city.is_multiple_of(2) { 1e8 } else { tiny };
+    let vertex: Vec<country::Vertex> = (0..cities)
+        .map(|city| country::Vertex {
+            edges: vec![
+                country::Edge { cost: cost(city), destination_city: (city + 1) % cities },
+                country::Edge { cost: cost((city + cities - 1) % cities), destination_city: (city + cities - 1) % cities },
+            ],
+        })
+        .collect();
+    let mut graph = country::Graph::new(vertex);
+    let route: Vec<u32> = (0..cities).collect();
+    let exact: f64 = 1e8 * (cities / 2) as f64 + tiny * (cities / 2) as f64;
+
+    // Each tiny cost is lost to rounding once the total is big enough when added up in turn
+    let plain_error: f64 = (chromosome::Chromosome::fitness(&route, &graph).unwrap() - exact).abs();
+
+    // Compensated summation keeps the total within rounding of the exact cost
+    graph.summation = interface::Summation::Compensated;
+    let compensated_error: f64 = (chromosome::Chromosome::fitness(&route, &graph).unwrap() - exact).abs();
+    assert!(compensated_error <= exact * f64::EPSILON);
+    assert!(plain_error > 10.0 * compensated_error.max(exact * f64::EPSILON));
+}
+
+#[test]
+fn check_nearest_neighbour() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Each step goes to the nearest city not yet visited
+    let tour = chromosome::Chromosome::nearest_neighbour(0, &burma_small.graph).unwrap();
+    assert_eq!(tour.route, vec![0, 1, 2, 3]);
+    assert_eq!(tour.cost, 1570.0);
+
+    let tour = chromosome::Chromosome::nearest_neighbour(3, &burma_small.graph).unwrap();
+    assert_eq!(tour.route, vec![3, 2, 1, 0]);
+}
@@ -0,0 +1,48 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_apply_setting() {
+    let mut parameters = interface::RunParameters::default();
+
+    // Valid changes are applied, and setting a value it already has isn't a change
+    assert_eq!(control::apply_setting(&mut parameters, "tournament-size", "8", 50), Ok(true));
+    assert_eq!(parameters.tournament_size, 8);
+    assert_eq!(control::apply_setting(&mut parameters, "tournament-size", "8", 50), Ok(false));
+    assert_eq!(control::apply_setting(&mut parameters, "mutation-operator", "I", 50), Ok(true));
+    assert_eq!(parameters.mutation_operator, interface::MutationOperator::Inversion);
+
+    // Invalid values and settings that can't change while running are refused without changing anything
+    assert!(control::apply_setting(&mut parameters, "tournament-size", "80", 50).is_err());
+    assert!(control::apply_setting(&mut parameters, "mutation-operator", "sideways", 50).is_err());
+    assert!(control::apply_setting(&mut parameters, "population-size", "100", 50).is_err());
+    assert_eq!(parameters.tournament_size, 8);
+}
+
+#[test]
+fn check_control_file() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let path = std::env::temp_dir().join(format!("tsp-control-file-{}.txt", std::process::id()));
+    std::fs::write(&path, "# Changed for the demo\ntournament-size = 3\ndistinct-parents = true\n").unwrap();
+
+    // The file is read before the first generation and each change is recorded
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 20,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters)
+        .unwrap()
+        .with_control_file(Some(path.clone()));
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(sim.parameters.tournament_size, 3);
+    assert!(sim.parameters.distinct_parents);
+    assert_eq!(sim.parameter_changes, vec![
+        control::ParameterChange { generation: 1, setting: String::from("tournament-size"), value: String::from("3") },
+        control::ParameterChange { generation: 1, setting: String::from("distinct-parents"), value: String::from("true") },
+    ]);
+}
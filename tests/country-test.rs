@@ -0,0 +1,515 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+const TSP_SRC: &str = "NAME : square4
+COMMENT : Corners of a 3 by 4 rectangle: with a colon
+TYPE : TSP
+DIMENSION: 4
+EDGE_WEIGHT_TYPE : EUC_2D
+NODE_COORD_SECTION
+1 0.0 0.0
+3 3.0 4.0
+2 3 0
+4 0.0 4.0e0
+EOF
+";
+
+const BURMA_TSP_SRC: &str = "NAME: burma14
+TYPE: TSP
+COMMENT: 14-Staedte in Burma (Zaw Win)
+DIMENSION: 14
+EDGE_WEIGHT_TYPE: GEO
+EDGE_WEIGHT_FORMAT: FUNCTION
+DISPLAY_DATA_TYPE: COORD_DISPLAY
+NODE_COORD_SECTION
+   1  16.47       96.10
+   2  16.47       94.44
+   3  20.09       92.54
+   4  22.39       93.37
+   5  25.23       97.24
+   6  22.00       96.05
+   7  20.47       97.02
+   8  17.20       96.29
+   9  16.30       97.38
+  10  14.05       98.12
+  11  16.53       97.38
+  12  21.52       95.59
+  13  19.41       97.13
+  14  20.09       94.55
+EOF
+";
+
+#[test]
+fn check_cost_metadata() {
+    // Instances without cost metadata print costs as they are
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    assert_eq!(burma_small.cost_label(), "cost");
+    assert_eq!(burma_small.format_cost(153.0), "153");
+
+    // Instances with cost metadata carry their unit and rounding into outputs
+    let with_units = SRC.replace(
+        "<ignoredDigits>5</ignoredDigits>",
+        "<ignoredDigits>5</ignoredDigits><costUnit>km</costUnit><costDecimals>1</costDecimals>",
+    );
+    let burma_units: country::Country = serde_xml_rs::from_str(&with_units).unwrap();
+    assert_eq!(burma_units.cost_label(), "cost (km)");
+    assert_eq!(burma_units.format_cost(153.04), "153.0 km");
+}
+
+#[test]
+fn check_xml_streaming() {
+    // Streaming an instance gives the same Country as deserializing it
+    let with_units = SRC.replace(
+        "<ignoredDigits>5</ignoredDigits>",
+        "<ignoredDigits>5</ignoredDigits><costUnit>km</costUnit><costDecimals>1</costDecimals>",
+    );
+    let declared = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!-- comment -->\n{}", with_units);
+    for src in [SRC, with_units.as_str(), declared.as_str()] {
+        let deserialized: country::Country = serde_xml_rs::from_str(src).unwrap();
+        let streamed = country::Country::from_xml(src).unwrap();
+        assert_eq!(streamed.name, deserialized.name);
+        assert_eq!(streamed.source, deserialized.source);
+        assert_eq!(streamed.description, deserialized.description);
+        assert_eq!(streamed.double_precision, deserialized.double_precision);
+        assert_eq!(streamed.ignored_digits, deserialized.ignored_digits);
+        assert_eq!(streamed.cost_unit, deserialized.cost_unit);
+        assert_eq!(streamed.cost_decimals, deserialized.cost_decimals);
+        assert_eq!(streamed.graph.vertex.len(), deserialized.graph.vertex.len());
+        let edges = |country: &country::Country| -> Vec<(f64, u32)> {
+            country.graph.vertex
+                .iter()
+                .flat_map(|vertex| vertex.edges.iter().map(|edge| (edge.cost, edge.destination_city)))
+                .collect()
+        };
+        assert_eq!(edges(&streamed), edges(&deserialized));
+    }
+
+    // Escaped text is unescaped, and elements outside the format are ignored
+    let escaped = SRC
+        .replace("14-Staedte in Burma (Zaw Win)", "Burma &amp; Thailand")
+        .replace("<graph>", "<notes><note>ignored</note></notes><graph>");
+    let streamed = country::Country::from_xml(&escaped).unwrap();
+    assert_eq!(streamed.description, "Burma & Thailand");
+    assert_eq!(streamed.graph.vertex.len(), 4);
+
+    // Instances that are missing parts or have invalid values are errors saying what is wrong
+    let cases = [
+        (SRC.replace("travellingSalesmanProblemInstance", "instance"), "Expected a travellingSalesmanProblemInstance"),
+        (SRC.replace("<name>burma14</name>", ""), "Missing name"),
+        (SRC.replace("<ignoredDigits>5</ignoredDigits>", "<ignoredDigits>five</ignoredDigits>"), "Invalid ignoredDigits"),
+        (SRC.replacen("cost=\"1.530000000000000e+02\"", "", 1), "has no cost"),
+        (SRC.replacen("e+02\">1</edge>", "e+02\">one</edge>", 1), "Invalid destination city"),
+        (SRC.replace("</graph>", ""), "Invalid XML"),
+        (String::new(), "Missing travellingSalesmanProblemInstance"),
+    ];
+    for (src, message) in cases {
+        let error = country::Country::from_xml(&src).unwrap_err();
+        assert!(format!("{:#}", error).contains(message), "{:#}", error);
+    }
+}
+
+#[test]
+fn check_xml_writer() {
+    let edges = |country: &country::Country| -> Vec<(f64, u32)> {
+        country.graph.vertex
+            .iter()
+            .flat_map(|vertex| vertex.edges.iter().map(|edge| (edge.cost, edge.destination_city)))
+            .collect()
+    };
+
+    // Writing a Country and reading it back gives the same Country, with text escaped and any cost metadata kept
+    let mut burma_small = country::Country::from_xml(SRC).unwrap();
+    burma_small.description = String::from("Burma & <Thailand>");
+    burma_small.cost_unit = Some(String::from("km"));
+    burma_small.cost_decimals = Some(1);
+    burma_small.graph.vertex[0].edges[0].cost = 0.1 + 0.2;
+    let xml = burma_small.to_xml();
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n<travellingSalesmanProblemInstance>"));
+    assert!(xml.contains("<edge cost=\"5.100000000000000e+02\">2</edge>"));
+    let read_back = country::Country::from_xml(&xml).unwrap();
+    assert_eq!(read_back.name, burma_small.name);
+    assert_eq!(read_back.description, "Burma & <Thailand>");
+    assert_eq!(read_back.double_precision, burma_small.double_precision);
+    assert_eq!(read_back.ignored_digits, burma_small.ignored_digits);
+    assert_eq!(read_back.cost_unit, burma_small.cost_unit);
+    assert_eq!(read_back.cost_decimals, burma_small.cost_decimals);
+    assert_eq!(edges(&read_back), edges(&burma_small));
+
+    // Datasets loaded from other formats can be written out too
+    let square = country::Country::from_tsplib(TSP_SRC).unwrap();
+    let read_back = country::Country::from_xml(&square.to_xml()).unwrap();
+    assert_eq!(read_back.name, "square4");
+    assert_eq!(edges(&read_back), edges(&square));
+
+    // Costs are written like the coursework files, whatever their size
+    assert_eq!(country::Country::scientific_cost(153.0), "1.530000000000000e+02");
+    assert_eq!(country::Country::scientific_cost(0.0015), "1.500000000000000e-03");
+    assert_eq!(country::Country::scientific_cost(0.0), "0.000000000000000e+00");
+    assert_eq!(country::Country::scientific_cost(1e120), "1.000000000000000e+120");
+}
+
+#[test]
+fn check_graph_validation() {
+    let validate = |src: &str| {
+        let country = country::Country::from_xml(src).unwrap();
+        country.graph.validate().map_err(|error| format!("{}", error))
+    };
+
+    // Complete symmetric graphs are valid, whatever format they were loaded from
+    assert!(validate(SRC).is_ok());
+    assert!(country::Country::from_tsplib(TSP_SRC).unwrap().graph.validate().is_ok());
+
+    // Each problem names the cities and edge at fault
+    let to_one = "<edge cost=\"1.530000000000000e+02\">1</edge>";
+    let back = "<edge cost=\"1.530000000000000e+02\">0</edge>";
+    let cases = [
+        (SRC.replacen(to_one, "<edge cost=\"1.530000000000000e+02\">9</edge>", 1), "city 0 has an edge to city 9, but there are only 4 cities"),
+        (SRC.replacen(to_one, "<edge cost=\"1.530000000000000e+02\">0</edge>", 1), "city 0 has an edge to itself"),
+        (SRC.replacen(back, &format!("{}{}", back, back), 1), "city 1 has more than one edge to city 0"),
+        (SRC.replacen(back, "<edge cost=\"-1.530000000000000e+02\">0</edge>", 1), "the edge from city 1 to city 0 has a cost of -153"),
+    ];
+    for (src, message) in cases {
+        let error = validate(&src).unwrap_err();
+        assert!(error.contains(message), "{}", error);
+    }
+
+    // Costs that differ by direction, or an edge missing one way, make an asymmetric graph, which is valid
+    assert!(country::Country::from_xml(SRC).unwrap().graph.symmetric);
+    for src in [
+        SRC.replacen(back, "<edge cost=\"1.540000000000000e+02\">0</edge>", 1),
+        SRC.replacen(to_one, "", 1),
+    ] {
+        assert!(validate(&src).is_ok());
+        assert!(!country::Country::from_xml(&src).unwrap().graph.symmetric);
+    }
+
+    // Cities with no edge between them either way are forbidden from travelling between each other, which is valid
+    assert!(validate(&SRC.replacen(to_one, "", 1).replacen(back, "", 1)).is_ok());
+
+    // Only the first few problems are listed
+    let error = validate(&SRC.replace("cost=\"", "cost=\"-")).unwrap_err();
+    assert_eq!(error.matches("; ").count(), country::VALIDATION_PROBLEMS - 1, "{}", error);
+    assert!(error.ends_with(" and 7 more problems"), "{}", error);
+
+    // Invalid graphs can't be loaded
+    let invalid = SRC.replacen(back, "<edge cost=\"-1.530000000000000e+02\">0</edge>", 1);
+    let error = country::InstanceFormat::Xml.parse(&invalid).unwrap_err();
+    assert!(format!("{:#}", error).contains("Invalid graph in burma14: the edge from city 1 to city 0"), "{:#}", error);
+}
+
+#[test]
+fn check_forbidden_edges() {
+    // Remove the edge between cities 0 and 1 both ways
+    let src = SRC
+        .replacen("<edge cost=\"1.530000000000000e+02\">1</edge>", "", 1)
+        .replacen("<edge cost=\"1.530000000000000e+02\">0</edge>", "", 1);
+    let burma_small = country::Country::from_xml(&src).unwrap();
+    let graph = &burma_small.graph;
+    assert_eq!(graph.forbidden_edges(), 1);
+    assert!(graph.distances.is_forbidden(0, 1) && graph.distances.is_forbidden(1, 0));
+    assert!(!graph.distances.is_forbidden(0, 2) && !graph.distances.is_forbidden(1, 1));
+
+    // A forbidden edge costs more than any tour along the real edges, so the more forbidden edges a tour uses the
+    // more it costs, whatever its real edges cost
+    assert!(graph.distances.forbidden_cost > 4.0 * 706.0);
+    let feasible = chromosome::Chromosome::fitness(&[0, 2, 1, 3], graph).unwrap();
+    let infeasible = chromosome::Chromosome::fitness(&[0, 1, 2, 3], graph).unwrap();
+    assert!(feasible < graph.distances.forbidden_cost && infeasible > graph.distances.forbidden_cost);
+    assert_eq!(chromosome::Chromosome::new(vec![0, 1, 2, 3], infeasible).forbidden_edges(graph), 1);
+    assert_eq!(chromosome::Chromosome::new(vec![0, 2, 1, 3], feasible).forbidden_edges(graph), 0);
+
+    // Complete graphs have no forbidden edges
+    let complete: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    assert_eq!(complete.graph.forbidden_edges(), 0);
+}
+
+#[test]
+fn check_format_detection() {
+    use std::path::Path;
+
+    // Known extensions are trusted, otherwise the contents are checked
+    assert_eq!(country::InstanceFormat::detect(Path::new("burma14.XML"), ""), Some(country::InstanceFormat::Xml));
+    assert_eq!(country::InstanceFormat::detect(Path::new("burma14"), SRC), Some(country::InstanceFormat::Xml));
+    assert_eq!(country::InstanceFormat::detect(Path::new("notes.txt"), "burma14 notes"), None);
+    assert_eq!(country::InstanceFormat::detect(Path::new("square4.tsp"), ""), Some(country::InstanceFormat::Tsplib));
+    assert_eq!(country::InstanceFormat::detect(Path::new("square4"), TSP_SRC), Some(country::InstanceFormat::Tsplib));
+    let tour: &str = "NAME : square4.opt.tour\nTYPE : TOUR\nDIMENSION : 4\nTOUR_SECTION\n1\n2\n3\n4\n-1\nEOF\n";
+    assert_eq!(country::InstanceFormat::detect(Path::new("square4.opt"), tour), None);
+
+    // A directory with a mix of files loads the ones it recognises and reports the rest
+    let directory = std::env::temp_dir().join(format!("tsp-format-detection-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("burma14.xml"), SRC).unwrap();
+    std::fs::write(directory.join("burma14-copy"), SRC).unwrap();
+    std::fs::write(directory.join("notes.txt"), "burma14 notes").unwrap();
+
+    let (countries, formats) = country::Country::from_directory(&directory, false).unwrap();
+
+    assert_eq!(countries.len(), 2);
+    let detected: Vec<country::FileStatus> = formats.iter().map(|(_, status)| status.clone()).collect();
+    assert_eq!(detected, vec![
+        country::FileStatus::Loaded(country::InstanceFormat::Xml), 
+        country::FileStatus::Loaded(country::InstanceFormat::Xml), 
+        country::FileStatus::Skipped(String::from("format not recognised")),
+    ]);
+
+    // Stray files are skipped, unless strict where only hidden files are still skipped
+    std::fs::write(directory.join(".DS_Store"), [0u8, 159, 146, 150]).unwrap();
+    std::fs::write(directory.join("broken.xml"), "<travellingSalesmanProblemInstance>").unwrap();
+    let (countries, formats) = country::Country::from_directory(&directory, false).unwrap();
+    assert_eq!(countries.len(), 2);
+    assert_eq!(formats[0].1, country::FileStatus::Skipped(String::from("hidden file")));
+    assert!(matches!(&formats[1].1, country::FileStatus::Skipped(reason) if reason.starts_with("could not be read as Xml")));
+
+    std::fs::remove_file(directory.join("broken.xml")).unwrap();
+    std::fs::write(directory.join("binary"), [0u8, 159, 146, 150]).unwrap();
+    let (_, formats) = country::Country::from_directory(&directory, false).unwrap();
+    assert!(formats.contains(&(directory.join("binary"), country::FileStatus::Skipped(String::from("not a text file")))));
+    assert!(country::Country::from_directory(&directory, true).is_err());
+
+    std::fs::remove_file(directory.join("binary")).unwrap();
+    std::fs::remove_file(directory.join("notes.txt")).unwrap();
+    assert_eq!(country::Country::from_directory(&directory, true).unwrap().0.len(), 2);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn check_data_dir() {
+    // Datasets are loaded from whichever directory is given, wherever the program is run from
+    let directory = std::env::temp_dir().join(format!("tsp-data-dir-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("burma14.xml"), SRC).unwrap();
+    let countries = country::Country::new(&directory, true).unwrap();
+    assert_eq!(countries.len(), 1);
+    assert_eq!(countries[0].name, "burma14");
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    // A directory that doesn't exist is an error naming it
+    let error = country::Country::new(&directory, false).unwrap_err();
+    assert!(format!("{}", error).contains(&directory.display().to_string()), "{}", error);
+}
+
+#[test]
+fn check_instance_files() {
+    let directory = std::env::temp_dir().join(format!("tsp-instance-files-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(directory.join("burma14.xml"), SRC).unwrap();
+    std::fs::write(directory.join("square4.tsp"), TSP_SRC).unwrap();
+    std::fs::write(directory.join("notes.txt"), "burma14 notes").unwrap();
+
+    // Only the files given are loaded, in the order given, either by their path or their name in the data directory
+    let files = vec![std::path::PathBuf::from("square4.tsp"), directory.join("burma14.xml")];
+    let countries = country::Country::from_files(&files, &directory).unwrap();
+    let names: Vec<&str> = countries.iter().map(|country| country.name.as_str()).collect();
+    assert_eq!(names, vec!["square4", "burma14"]);
+
+    // A file that doesn't exist, can't be loaded or is given twice is an error rather than being skipped
+    let missing = country::Country::from_files(&[std::path::PathBuf::from("brazil58.xml")], &directory).unwrap_err();
+    assert!(format!("{}", missing).contains("brazil58.xml"), "{}", missing);
+    let unknown = country::Country::from_files(&[std::path::PathBuf::from("notes.txt")], &directory).unwrap_err();
+    assert!(format!("{}", unknown).contains("format not recognised"), "{}", unknown);
+    let twice = vec![std::path::PathBuf::from("burma14.xml"), directory.join("burma14.xml")];
+    assert!(country::Country::from_files(&twice, &directory).is_err());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn check_gzipped_files() {
+    use std::io::Write;
+
+    let directory = std::env::temp_dir().join(format!("tsp-gzipped-files-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    for (name, src) in [("burma14.xml.gz", SRC), ("square4.TSP.GZ", TSP_SRC)] {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(src.as_bytes()).unwrap();
+        std::fs::write(directory.join(name), encoder.finish().unwrap()).unwrap();
+    }
+    std::fs::write(directory.join("broken.xml.gz"), SRC).unwrap();
+
+    // Gzipped files are decompressed and detected by the extension under .gz, whatever its case
+    let (countries, formats) = country::Country::from_directory(&directory, false).unwrap();
+    let names: Vec<&str> = countries.iter().map(|country| country.name.as_str()).collect();
+    assert_eq!(names, vec!["burma14", "square4"]);
+    assert_eq!(formats[1].1, country::FileStatus::Loaded(country::InstanceFormat::Xml));
+    assert_eq!(formats[2].1, country::FileStatus::Loaded(country::InstanceFormat::Tsplib));
+
+    // A file that isn't really gzipped is skipped, or is an error if strict
+    assert!(matches!(&formats[0].1, country::FileStatus::Skipped(reason) if reason.contains("decompressed")));
+    assert!(country::Country::new(&directory, true).is_err());
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn check_filter() {
+    // * matches any run of characters and ? any single character
+    assert!(country::matches_pattern("burma14", "burma*"));
+    assert!(country::matches_pattern("burma14", "*14"));
+    assert!(country::matches_pattern("burma14", "b*a*4"));
+    assert!(country::matches_pattern("burma14", "burma1?"));
+    assert!(country::matches_pattern("burma14", "*"));
+    assert!(!country::matches_pattern("burma14", "burma"));
+    assert!(!country::matches_pattern("burma14", "Burma*"));
+    assert!(!country::matches_pattern("burma14", "burma1??"));
+    assert!(!country::matches_pattern("brazil58", "*14"));
+
+    // Only countries matching one of the patterns are kept, in the order they were loaded
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let mut brazil = burma_small.clone();
+    brazil.name = String::from("brazil58");
+    let mut ulysses = burma_small.clone();
+    ulysses.name = String::from("ulysses16");
+    let countries = vec![brazil, burma_small, ulysses];
+
+    let kept = country::Country::filter_by_name(countries.clone(), &[String::from("b*")]).unwrap();
+    let names: Vec<&str> = kept.iter().map(|country| country.name.as_str()).collect();
+    assert_eq!(names, vec!["brazil58", "burma14"]);
+    let kept = country::Country::filter_by_name(countries.clone(), &[String::from("*14"), String::from("ulysses*")]).unwrap();
+    assert_eq!(kept.len(), 2);
+
+    // Without patterns everything is kept, and patterns that match nothing are an error
+    assert_eq!(country::Country::filter_by_name(countries.clone(), &[]).unwrap().len(), 3);
+    assert!(country::Country::filter_by_name(countries, &[String::from("att*")]).is_err());
+}
+
+#[test]
+fn check_distance_matrix() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let distances = &burma_small.graph.distances;
+
+    // The matrix is built when the graph is deserialized
+    assert_eq!(distances.size, 4);
+    assert_eq!(distances.get(0, 1), Some(153.0));
+    assert_eq!(distances.get(3, 2), Some(289.0));
+
+    // Cities without an edge to themselves cost nothing, cities that don't exist have no cost
+    assert_eq!(distances.get(2, 2), Some(0.0));
+    assert_eq!(distances.get(0, 4), None);
+}
+
+#[test]
+fn check_integral_costs() {
+    let mut burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    assert!(burma_small.graph.has_integral_costs());
+
+    // A single cost that isn't a whole number is enough
+    burma_small.graph.vertex[0].edges[0].cost = 153.5;
+    let graph = country::Graph::new(burma_small.graph.vertex);
+    assert!(!graph.has_integral_costs());
+}
+
+#[test]
+fn check_lower_bound() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Half the two cheapest connections of each city: (153 + 510) + (153 + 422) + (289 + 422) + (289 + 664)
+    assert_eq!(burma_small.graph.lower_bound(), 1451.0);
+    assert!(burma_small.graph.lower_bound() <= 1570.0);
+}
+
+#[test]
+fn check_candidate_lists() {
+    let mut burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // No lists are built until asked for
+    assert!(burma_small.graph.candidates.is_empty());
+
+    // Each city lists its nearest other cities, nearest first
+    burma_small.graph.build_candidate_lists(2);
+    assert_eq!(burma_small.graph.candidates[0], vec![1, 2]);
+    assert_eq!(burma_small.graph.candidates[3], vec![2, 1]);
+
+    // Asking for more cities than there are lists every other city
+    burma_small.graph.build_candidate_lists(10);
+    assert_eq!(burma_small.graph.candidates[1].len(), 3);
+}
+
+#[test]
+fn check_tsplib() {
+    let square = country::Country::from_tsplib(TSP_SRC).unwrap();
+    assert_eq!(square.name, "square4");
+    assert_eq!(square.description, "Corners of a 3 by 4 rectangle: with a colon");
+
+    // Cities are numbered from 0 in the order of their numbers, with every edge the distance between them
+    let distances = &square.graph.distances;
+    assert_eq!(distances.size, 4);
+    assert_eq!(distances.get(0, 1), Some(3.0));
+    assert_eq!(distances.get(0, 2), Some(5.0));
+    assert_eq!(distances.get(2, 0), Some(5.0));
+    assert_eq!(distances.get(2, 3), Some(3.0));
+    assert_eq!(square.graph.vertex[0].edges.len(), 3);
+
+    // Distances are rounded to the nearest whole number like TSPLIB
+    assert_eq!(country::euclidean_2d((0.0, 0.0), (1.0, 1.0)), 1.0);
+    assert_eq!(country::euclidean_2d((0.0, 0.0), (1.5, 2.0)), 3.0);
+
+    // Files it can't read say why
+    let unsupported = TSP_SRC.replace("EUC_2D", "CEIL_2D");
+    let report = country::Country::from_tsplib(&unsupported).unwrap_err();
+    assert_eq!(report.to_string(), "Unsupported EDGE_WEIGHT_TYPE CEIL_2D");
+    let repeated = TSP_SRC.replace("3 3.0 4.0", "2 3.0 4.0");
+    assert_eq!(country::Country::from_tsplib(&repeated).unwrap_err().to_string(), "City 2 is listed more than once");
+    let missing = TSP_SRC.replace("4 0.0 4.0e0", "");
+    assert!(country::Country::from_tsplib(&missing).is_err());
+    let asymmetric = TSP_SRC.replace("TYPE : TSP", "TYPE : ATSP");
+    assert!(country::Country::from_tsplib(&asymmetric).is_err());
+}
+
+#[test]
+fn check_tsplib_distances() {
+    let burma_tsp = country::Country::from_tsplib(BURMA_TSP_SRC).unwrap();
+    let burma_xml: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // GEO distances match the costs of the same cities in the XML version of burma14
+    for from in 0..4 {
+        for to in 0..4 {
+            assert_eq!(burma_tsp.graph.distances.get(from, to), burma_xml.graph.distances.get(from, to));
+        }
+    }
+
+    // And the optimal tour costs the published 3323
+    let optimal: Vec<u32> = [1, 2, 14, 3, 4, 5, 6, 12, 7, 13, 8, 11, 9, 10].iter().map(|city| city - 1).collect();
+    let cost: f64 = optimal
+        .iter()
+        .zip(optimal.iter().cycle().skip(1))
+        .map(|(from, to)| burma_tsp.graph.distances.get(*from, *to).unwrap())
+        .sum();
+    assert_eq!(cost, 3323.0);
+
+    // ATT distances are scaled down by the square root of 10 and rounded up
+    assert_eq!(country::pseudo_euclidean((0.0, 0.0), (10.0, 0.0)), 4.0);
+    assert_eq!(country::pseudo_euclidean((0.0, 0.0), (30.0, 40.0)), 16.0);
+    assert_eq!(country::pseudo_euclidean((0.0, 0.0), (0.0, 0.0)), 0.0);
+    let att = TSP_SRC.replace("EUC_2D", "ATT");
+    assert_eq!(country::Country::from_tsplib(&att).unwrap().graph.distances.get(0, 2), Some(2.0));
+}
+
+#[test]
+fn check_tsplib_explicit() {
+    let burma_xml: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let explicit = |format: &str, weights: &str| format!(
+        "NAME : burma4\nTYPE : TSP\nDIMENSION : 4\nEDGE_WEIGHT_TYPE : EXPLICIT\nEDGE_WEIGHT_FORMAT : {}\n\
+        EDGE_WEIGHT_SECTION\n{}\nDISPLAY_DATA_SECTION\n1 0 0\nEOF\n",
+        format, weights
+    );
+
+    // Every layout of the same matrix gives the same costs as the XML version, wherever the lines break
+    let layouts = [
+        ("FULL_MATRIX", "0 153 510 706\n153 0 422 664\n510 422 0 289\n706 664 289 0"),
+        ("UPPER_ROW", "153 510 706\n422 664\n289"),
+        ("LOWER_DIAG_ROW", "0 153 0 510 422\n0 706 664 289 0"),
+    ];
+    for (format, weights) in layouts {
+        let burma_tsp = country::Country::from_tsplib(&explicit(format, weights)).unwrap();
+        assert_eq!(burma_tsp.graph.distances.costs, burma_xml.graph.distances.costs, "{}", format);
+        assert_eq!(burma_tsp.graph.vertex[3].edges.len(), 3);
+    }
+
+    // Files it can't read say why
+    let report = country::Country::from_tsplib(&explicit("UPPER_ROW", "153 510 706 422 664")).unwrap_err();
+    assert_eq!(report.to_string(), "EDGE_WEIGHT_SECTION has 5 values, expected 6 for 4 cities");
+    let report = country::Country::from_tsplib(&explicit("UPPER_COL", "153 510 706 422 664 289")).unwrap_err();
+    assert_eq!(report.to_string(), "Unsupported EDGE_WEIGHT_FORMAT UPPER_COL");
+    assert!(country::Country::from_tsplib(&explicit("UPPER_ROW", "153 510 706 422 664 far")).is_err());
+}
@@ -0,0 +1,140 @@
+use tsp_coursework::*;
+
+use std::io::Write;
+
+const TSP_SRC: &str = "NAME : square4
+COMMENT : Corners of a 3 by 4 rectangle
+TYPE : TSP
+DIMENSION: 4
+EDGE_WEIGHT_TYPE : EUC_2D
+NODE_COORD_SECTION
+1 0.0 0.0
+2 3.0 0.0
+3 3.0 4.0
+4 0.0 4.0
+EOF
+";
+
+/// Function to gzip some text like the files on a TSPLIB mirror
+fn gzip(src: &str) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(src.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn check_sha256() {
+    assert_eq!(fetch::sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    assert_eq!(fetch::sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+}
+
+#[test]
+fn check_instance_names() {
+    assert_eq!(
+        fetch::instance_url("http://example.com/tsp/", "berlin52"),
+        "http://example.com/tsp/berlin52.tsp.gz"
+    );
+    assert_eq!(fetch::instance_url("mirror", "pr1002"), "mirror/pr1002.tsp.gz");
+
+    // Names that could leave the mirror or the data directory aren't allowed
+    assert!(fetch::check_name("berlin52").is_ok());
+    assert!(fetch::check_name("ali535.v2").is_ok());
+    for name in ["", "../secrets", "a/b", ".hidden", "berlin52?x=1"] {
+        assert!(fetch::check_name(name).is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn check_checksums() {
+    let mut checksums = fetch::Checksums::default();
+
+    // A file without a checksum isn't trusted, one with a checksum has to match it
+    let error = checksums.verify("square4.tsp.gz", b"square").unwrap_err();
+    assert!(format!("{}", error).contains("No known checksum for square4.tsp.gz"), "{}", error);
+    checksums.entries.insert("square4.tsp.gz".to_string(), fetch::sha256_hex(b"square"));
+    assert!(checksums.verify("square4.tsp.gz", b"square").is_ok());
+    let error = checksums.verify("square4.tsp.gz", b"changed").unwrap_err();
+    assert!(format!("{}", error).contains("Checksum mismatch for square4.tsp.gz"), "{}", error);
+
+    // Checksums from a more trusted set are kept over those of another
+    let other = fetch::Checksums::parse(&format!(
+        "{}  square4.tsp.gz\n{}  other.tsp.gz\n",
+        fetch::sha256_hex(b"changed"),
+        fetch::sha256_hex(b"other")
+    )).unwrap();
+    let merged = checksums.clone().or(&other);
+    assert!(merged.verify("square4.tsp.gz", b"square").is_ok());
+    assert!(merged.verify("other.tsp.gz", b"other").is_ok());
+
+    // They are written and read in the format of sha256sum
+    let src = checksums.to_sha256sum();
+    assert_eq!(src, format!("{}  square4.tsp.gz\n", fetch::sha256_hex(b"square")));
+    assert_eq!(fetch::Checksums::parse(&src).unwrap(), checksums);
+    assert_eq!(fetch::Checksums::parse(&format!("{} *square4.tsp.gz\n\n", fetch::sha256_hex(b"square"))).unwrap(), checksums);
+    assert!(fetch::Checksums::parse("abc  square4.tsp.gz").is_err());
+    assert!(fetch::Checksums::parse(&fetch::sha256_hex(b"square")).is_err());
+
+    // Comments are ignored, so the pinned checksums shipped with the program can explain themselves
+    assert_eq!(fetch::Checksums::parse(&format!("# A comment\n{}", src)).unwrap(), checksums);
+    assert!(fetch::Checksums::pinned().is_ok());
+
+    // Checksums given on the command line name the instance rather than its file
+    let (name, checksum) = fetch::parse_checksum(&format!("square4={}", fetch::sha256_hex(b"square"))).unwrap();
+    assert_eq!((name.as_str(), checksum), ("square4.tsp.gz", fetch::sha256_hex(b"square")));
+    for src in ["square4", "square4=abc", "../square4=abc", &format!("={}", fetch::sha256_hex(b"square"))] {
+        assert!(fetch::parse_checksum(src).is_err(), "{}", src);
+    }
+}
+
+#[test]
+fn check_fetch_from_local_mirror() {
+    let root = std::env::temp_dir().join(format!("tsp-fetch-{}", std::process::id()));
+    let mirror = root.join("mirror");
+    let data = root.join("data");
+    std::fs::create_dir_all(&mirror).unwrap();
+    std::fs::write(mirror.join("square4.tsp.gz"), gzip(TSP_SRC)).unwrap();
+    std::fs::write(mirror.join("broken.tsp.gz"), gzip("NAME : broken\nTYPE : ATSP\n")).unwrap();
+    let mirror = mirror.display().to_string();
+    let trusted = fetch::Checksums::parse(&format!(
+        "{}  square4.tsp.gz\n{}  broken.tsp.gz\n",
+        fetch::sha256_hex(&gzip(TSP_SRC)),
+        fetch::sha256_hex(&gzip("NAME : broken\nTYPE : ATSP\n"))
+    )).unwrap();
+
+    // An instance without a known checksum isn't downloaded into the data directory
+    let error = fetch::fetch(&["square4".to_string()], &mirror, &data, &fetch::Checksums::default(), false).unwrap_err();
+    assert!(format!("{}", error).contains("No known checksum for square4.tsp.gz"), "{}", error);
+    assert!(!data.join("square4.xml").exists());
+
+    // The instance is converted to XML in the data directory, which is created, and loads like any other
+    let paths = fetch::fetch(&["square4".to_string()], &mirror, &data, &trusted, false).unwrap();
+    assert_eq!(paths, vec![data.join("square4.xml")]);
+    let (countries, _) = country::Country::from_directory(&data, true).unwrap();
+    assert_eq!(countries.len(), 1);
+    assert_eq!(countries[0].name, "square4");
+    assert_eq!(countries[0].graph.vertex[0].edges[0].cost, 3.0);
+    assert_eq!(countries[0].graph.vertex[0].edges[1].cost, 5.0);
+
+    // A dataset of the same name isn't overwritten unless forced, and nothing is downloaded if any would be
+    std::fs::write(data.join("square4.xml"), "kept").unwrap();
+    let error = fetch::fetch(&["berlin52".to_string(), "square4".to_string()], &mirror, &data, &trusted, false).unwrap_err();
+    assert!(format!("{}", error).contains("already exists, pass --force"), "{}", error);
+    assert_eq!(std::fs::read_to_string(data.join("square4.xml")).unwrap(), "kept");
+
+    // Its checksum is recorded in the data directory, so it is checked without being given when fetched again
+    let checksums = fetch::Checksums::load(&data).unwrap();
+    assert_eq!(checksums.entries["square4.tsp.gz"], fetch::sha256_hex(&gzip(TSP_SRC)));
+    assert!(fetch::fetch(&["square4".to_string()], &mirror, &data, &fetch::Checksums::default(), true).is_ok());
+    assert_ne!(std::fs::read_to_string(data.join("square4.xml")).unwrap(), "kept");
+    std::fs::write(root.join("mirror").join("square4.tsp.gz"), gzip(&TSP_SRC.replace("4.0", "5.0"))).unwrap();
+    let error = fetch::fetch(&["square4".to_string()], &mirror, &data, &fetch::Checksums::default(), true).unwrap_err();
+    assert!(format!("{}", error).contains("Checksum mismatch"), "{}", error);
+
+    // Instances that are missing or can't be converted are errors, and the checksum of a broken one isn't recorded
+    assert!(fetch::fetch(&["berlin52".to_string()], &mirror, &data, &trusted, false).is_err());
+    let error = fetch::fetch(&["broken".to_string()], &mirror, &data, &trusted, false).unwrap_err();
+    assert!(format!("{}", error).contains("Invalid instance broken"), "{}", error);
+    assert!(!fetch::Checksums::load(&data).unwrap().entries.contains_key("broken.tsp.gz"));
+    assert!(fetch::fetch(&["../square4".to_string()], &mirror, &data, &trusted, false).is_err());
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,88 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_genealogy() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 30,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap().with_genealogy(true);
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    let genealogy = sim.population.genealogy.as_ref().unwrap();
+    assert_eq!(genealogy.instance, "burma14");
+
+    // The initial population has no parents, and every later Chromosome was bred from Chromosomes before it
+    assert!(genealogy.chromosomes[..10].iter().all(|ancestry| ancestry.parents.is_empty()));
+    for (position, ancestry) in genealogy.chromosomes.iter().enumerate().skip(10) {
+        assert!((1..=2).contains(&ancestry.parents.len()));
+        assert!(ancestry.parents.iter().all(|parent| {
+            genealogy.chromosomes[..position].iter().any(|earlier| earlier.id == *parent)
+        }));
+    }
+
+    // Every Chromosome in the population is in the genealogy, and the lineage of the best goes back to the start
+    assert!(sim.population.population_data.iter().all(|chromosome| {
+        genealogy.chromosomes.iter().any(|ancestry| ancestry.id == chromosome.id)
+    }));
+    assert_eq!(genealogy.best, sim.population.best_chromosome.id);
+    let lineage = genealogy.lineage(genealogy.best);
+    assert!(lineage.contains(&genealogy.best));
+    assert!(lineage.iter().any(|id| *id <= 10));
+
+    // The DOT graph has a node for every Chromosome and an arrow for every parent
+    let dot = genealogy.to_dot();
+    assert!(dot.starts_with("digraph genealogy {"));
+    let arrows: usize = genealogy.chromosomes.iter().map(|ancestry| ancestry.parents.len()).sum();
+    assert_eq!(dot.matches(" -> ").count(), arrows);
+
+    // The build, tags and notes are kept in the JSON and label the DOT graph, with quotes escaped
+    let mut genealogy = genealogy.clone();
+    assert_eq!(genealogy.build, Some(build_info::BuildInfo::current()));
+    genealogy.tags = vec![String::from("baseline")];
+    genealogy.notes.description = Some(String::from("The \"first\" try"));
+    let json: serde_json::Value = serde_json::to_value(&genealogy).unwrap();
+    assert_eq!(json["tags"], serde_json::json!(["baseline"]));
+    assert_eq!(json["notes"]["description"], "The \"first\" try");
+    assert_eq!(json["build"]["version"], build_info::BuildInfo::current().version);
+    assert!(genealogy.to_dot().contains(&format!(
+        "label=\"burma14 run 0, Build: {}, Tags: baseline, Description: The \\\"first\\\" try\";",
+        build_info::BuildInfo::current().id()
+    )));
+
+    // The hill climber has no population, so has no genealogy
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters { algorithm: interface::Algorithm::Hillclimb, ..parameters };
+    let sim = simulation::Simulation::new(burma_small, parameters).unwrap().with_genealogy(true);
+    assert!(sim.population.genealogy.is_none());
+}
+
+#[test]
+fn check_settle() {
+    let chromosomes = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 10.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 12.0, id: 2 },
+    ];
+    let mut genealogy = genealogy::Genealogy::new(&chromosomes);
+    assert_eq!(genealogy.best, 1);
+
+    // Only proposed children that joined the population are kept, with their cost in the population
+    let kept = chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 8.0, id: 3 };
+    let rejected = chromosome::Chromosome { route: vec![0, 3, 2, 1], cost: 14.0, id: 4 };
+    genealogy.generation = 1;
+    genealogy.propose(&kept, &[1, 1]);
+    genealogy.propose(&rejected, &[1, 2]);
+    let population = vec![chromosomes[0].clone(), chromosome::Chromosome { cost: 7.0, ..kept }];
+    genealogy.settle(&population, 3);
+
+    assert!(genealogy.pending.is_empty());
+    assert_eq!(genealogy.best, 3);
+    assert_eq!(genealogy.chromosomes.len(), 3);
+    assert_eq!(genealogy.chromosomes[2], genealogy::Ancestry { id: 3, parents: vec![1], cost: 7.0, generation: 1 });
+    assert_eq!(genealogy.lineage(3).into_iter().collect::<Vec<u64>>(), vec![1, 3]);
+}
@@ -0,0 +1,69 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_hill_climber() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let population = population::Population::new(10, &burma_small.graph).unwrap();
+
+    // With 4 cities there are 2 moves, and the 3 different tours are all reachable from each other
+    assert_eq!(hillclimb::HillClimber::neighbourhood_size(4), 2);
+    let optimal = [vec![0, 1, 2, 3], vec![0, 1, 3, 2], vec![0, 2, 1, 3]]
+        .iter()
+        .map(|route| chromosome::Chromosome::fitness(route, &burma_small.graph).unwrap())
+        .fold(f64::INFINITY, f64::min);
+
+    // Climbing uses exactly the evaluations given, finds the cheapest tour and has to restart to keep going
+    let mut climber = hillclimb::HillClimber::new(&population);
+    assert_eq!(climber.evaluations, 10);
+    climber.climb(100, &burma_small.graph).unwrap();
+    assert_eq!(climber.evaluations, 100);
+    assert!(climber.restarts > 0);
+    assert!((climber.best.cost - optimal).abs() < 1e-9);
+    let fitness = chromosome::Chromosome::fitness(&climber.current.route, &burma_small.graph).unwrap();
+    assert!((climber.current.cost - fitness).abs() < 1e-9);
+}
+
+#[test]
+fn check_asymmetric_climb() {
+    // Going round clockwise costs 1 per edge but anticlockwise costs 10
+    let graph = harness::graph_from_costs(&[
+        vec![0.0, 1.0, 4.0, 7.0, 10.0],
+        vec![10.0, 0.0, 1.0, 5.0, 3.0],
+        vec![6.0, 10.0, 0.0, 1.0, 8.0],
+        vec![2.0, 9.0, 10.0, 0.0, 1.0],
+        vec![1.0, 4.0, 3.0, 10.0, 0.0],
+    ]);
+    let population = population::Population::new(10, &graph).unwrap();
+
+    // Reversing a segment changes the cost of the edges inside it, and the climber keeps track of that
+    let mut climber = hillclimb::HillClimber::new(&population);
+    climber.climb(500, &graph).unwrap();
+    let fitness = chromosome::Chromosome::fitness(&climber.current.route, &graph).unwrap();
+    assert!((climber.current.cost - fitness).abs() < 1e-9);
+    assert_eq!(climber.best.cost, 5.0);
+}
+
+#[test]
+fn check_hill_climb_simulation() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters {
+        algorithm: interface::Algorithm::Hillclimb,
+        population_size: 10,
+        generations: 50,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // Every generation uses the most evaluations a generation of the genetic algorithm can, 2 for crossover and 1 for each mutation
+    assert_eq!(parameters.evaluations_per_generation(), 4);
+    assert_eq!(sim.solver().evaluations(), 10 + 49 * 4);
+    assert_eq!(sim.recorded_evaluations.last(), Some(&(10 + 49 * 4)));
+
+    // The population is left as it started
+    assert_eq!(sim.population.evaluations, 10);
+    assert_eq!(sim.best_chromosome.last().unwrap().cost, sim.hill_climber.as_ref().unwrap().best.cost);
+}
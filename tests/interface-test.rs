@@ -0,0 +1,178 @@
+use tsp_coursework::*;
+use clap::Parser;
+
+#[test]
+fn check_config_violations() {
+    // Every problem is reported at once
+    let cli = interface::Cli::try_parse_from([
+        "tsp-coursework", 
+        "-p", "10", 
+        "-t", "20", 
+        "--population-schedule", "T", 
+        "--minimum-population", "15",
+        "-s", "E",
+    ]).unwrap();
+    let error = cli.into_config().unwrap_err();
+    assert_eq!(error.violations.len(), 4, "{}", error);
+
+    // Valid options are clamped to the number of generations
+    let cli = interface::Cli::try_parse_from([
+        "tsp-coursework", 
+        "-g", "100", 
+        "--record-every", "500",
+    ]).unwrap();
+    let parameters = cli.into_config().unwrap();
+    assert_eq!(parameters.record_stride, 100);
+}
+
+#[test]
+fn check_exit_codes() {
+    // Each outcome has its own exit code, with invalid options matching clap's code for bad arguments
+    let outcomes = [
+        interface::RunOutcome::Success,
+        interface::RunOutcome::InvalidConfig,
+        interface::RunOutcome::DataLoad,
+        interface::RunOutcome::Runtime,
+        interface::RunOutcome::Interrupted,
+    ];
+    let codes: Vec<u8> = outcomes.iter().map(|outcome| outcome.code()).collect();
+    assert_eq!(codes, vec![0, 2, 3, 4, 5]);
+
+    let error = interface::Cli::try_parse_from(["tsp-coursework", "-p", "1"]).unwrap_err();
+    assert_eq!(error.exit_code(), interface::RunOutcome::InvalidConfig.code() as i32);
+}
+
+#[test]
+fn check_crossover_rate() {
+    // The crossover rate is a probability so must be between 0 and 1
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--crossover-rate", "0.7"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().crossover_rate, 0.7);
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--crossover-rate", "1.5"]).is_err());
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--crossover-rate", "most"]).is_err());
+}
+
+#[test]
+fn check_throttle_rate() {
+    // The throttle is a rate so must be a number greater than 0
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--throttle", "2.5"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().throttle, Some(2.5));
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--throttle", "0"]).is_err());
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--throttle", "inf"]).is_err());
+}
+
+#[test]
+fn check_data_dir() {
+    // The datasets are loaded from data in the working directory unless another directory is given
+    let cli = interface::Cli::try_parse_from(["tsp-coursework"]).unwrap();
+    assert_eq!(cli.data_dir, std::path::PathBuf::from("data"));
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--data-dir", "/srv/tsp"]).unwrap();
+    assert_eq!(cli.data_dir, std::path::PathBuf::from("/srv/tsp"));
+
+    // Datasets can be picked one at a time, otherwise every dataset is run
+    assert!(cli.instances.is_empty());
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--instance", "brazil58.xml", "--instance", "burma14.tsp"]).unwrap();
+    assert_eq!(cli.instances, vec![std::path::PathBuf::from("brazil58.xml"), std::path::PathBuf::from("burma14.tsp")]);
+}
+
+#[test]
+fn check_elitism() {
+    // Elitism has to leave room in the population for children
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--elitism", "10"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--elitism", "3"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().elitism, 3);
+
+    // --protected-elites is another name for the same flag
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--protected-elites", "2"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().elitism, 2);
+}
+
+#[test]
+fn check_window_size() {
+    // The window of restricted tournament replacement has to fit in the population
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--replacement-strategy", "R", "--window-size", "11"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+
+    // It is only checked when it is used
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--window-size", "11"]).unwrap();
+    assert!(cli.into_config().is_ok());
+}
+
+#[test]
+fn check_final_tournament_size() {
+    // The tournaments at the end of the schedule have to fit in the population
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--final-tournament-size", "11"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+
+    // Including once a population schedule has shrunk it
+    let cli = interface::Cli::try_parse_from([
+        "tsp-coursework", "-p", "20", "--population-schedule", "S", "--minimum-population", "10", "--final-tournament-size", "12"
+    ]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "-t", "2", "--final-tournament-size", "10"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().final_tournament_size, Some(10));
+}
+
+#[test]
+fn check_max_tournament_size() {
+    // An adaptive tournament grows from the tournament size up to a size that fits in the population
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-p", "10", "--max-tournament-size", "11"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-t", "6", "--max-tournament-size", "4"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+
+    // It can't be used with a tournament size schedule
+    assert!(interface::Cli::try_parse_from([
+        "tsp-coursework", "--final-tournament-size", "10", "--max-tournament-size", "10"
+    ]).is_err());
+
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "-t", "2", "--max-tournament-size", "10"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().max_tournament_size, Some(10));
+}
+
+#[test]
+fn check_cost_epsilon() {
+    // The tolerance has to be a non-negative number
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--cost-epsilon=-1"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--cost-epsilon", "NaN"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--cost-epsilon", "1e-6"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().cost_epsilon, Some(1e-6));
+}
+
+#[test]
+fn check_tracked_edges() {
+    // Edges are two city numbers joined by a dash, and can be given more than once
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--track-edge", "0-1", "--track-edge", "5-2"]).unwrap();
+    assert_eq!(cli.tracked_edges, vec![(0, 1), (5, 2)]);
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--track-edge", "3-3"]).is_err());
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--track-edge", "0,1"]).is_err());
+
+    // Tracking the edges of the optimal tours needs somewhere to find them
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--track-optimal-edges"]).is_err());
+}
+
+#[test]
+fn check_local_search_evaluations() {
+    // Each move evaluated counts as the fraction of the route it changes, on top of recalculating the improved route
+    assert_eq!(interface::LocalSearch::TwoOpt.evaluations(0, 40), 1);
+    assert_eq!(interface::LocalSearch::TwoOpt.evaluations(20, 40), 2);
+    assert_eq!(interface::LocalSearch::TwoOpt.evaluations(21, 40), 3);
+    assert_eq!(interface::LocalSearch::OrOpt.evaluations(40, 40), 4);
+}
+
+#[test]
+fn check_children_per_generation() {
+    // Children are bred in pairs
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--children-per-generation", "5"]).unwrap();
+    assert_eq!(cli.into_config().unwrap_err().violations.len(), 1);
+    let cli = interface::Cli::try_parse_from(["tsp-coursework", "--children-per-generation", "8"]).unwrap();
+    assert_eq!(cli.into_config().unwrap().children_per_generation, 8);
+
+    // A (μ+λ) generation already sets how many children are bred
+    assert!(interface::Cli::try_parse_from(["tsp-coursework", "--children-per-generation", "4", "--offspring", "4"]).is_err());
+}
@@ -0,0 +1,47 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn test_resume() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Plan two seeded runs of burma14, then finish the second
+    let mut manifest = manifest::ExperimentManifest::new(
+        std::slice::from_ref(&burma_small),
+        interface::RunParameters { seed: Some(7), ..Default::default() },
+        2,
+        vec![String::from("v2")],
+        manifest::ExperimentNotes { description: Some(String::from("Baseline for v2")), author: None },
+    ).unwrap();
+    assert_eq!(manifest.missing_jobs(), vec![0, 1]);
+
+    let sim = simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap();
+    manifest.complete(1, results::RunSummary::new(2, &sim).unwrap()).unwrap();
+
+    // Reading the manifest back only leaves the first run to do
+    let resumed = manifest::ExperimentManifest::load(&manifest.path).unwrap();
+    std::fs::remove_file(&manifest.path).unwrap();
+
+    assert_eq!(resumed.missing_jobs(), vec![0]);
+    assert_eq!(resumed.tags, vec![String::from("v2")]);
+    assert_eq!(resumed.notes.description.as_deref(), Some("Baseline for v2"));
+    assert_eq!(resumed.notes.caption(), ", Description: Baseline for v2");
+    assert_eq!(resumed.notes.tagged_caption(&[]), ", Description: Baseline for v2");
+    assert_eq!(resumed.notes.tagged_caption(&resumed.tags), ", Tags: v2, Description: Baseline for v2");
+    assert_eq!(resumed.parameters.population_size, 50);
+
+    // Each run has its own seed, kept when resuming so the remaining runs are the same as they would have been
+    assert_eq!(resumed.parameters.seed, Some(7));
+    assert_eq!(resumed.jobs[0].seed, Some(random::derive_seed(7, 0)));
+    assert_eq!(resumed.jobs[1].seed, Some(random::derive_seed(7, 1)));
+
+    // The build that planned the experiment and ran each job is recorded
+    assert_eq!(resumed.build, Some(build_info::BuildInfo::current()));
+
+    let previous = resumed.completed_summaries(&[]);
+    assert_eq!(previous.len(), 1);
+    assert_eq!(previous[0].0, "burma14");
+    assert_eq!(previous[0].1.run, 2);
+}
@@ -0,0 +1,52 @@
+use tsp_coursework::*;
+
+const TOUR: &str = "NAME : square.opt.tour
+TYPE : TOUR
+DIMENSION : 4
+TOUR_SECTION
+1
+2
+3
+4
+-1
+EOF";
+
+#[test]
+fn check_tour_parsing() {
+    let optimal = optimal::OptimalTour::from_tsplib("square", TOUR).unwrap();
+
+    // Cities are renumbered from 0
+    assert_eq!(optimal.route, vec![0, 1, 2, 3]);
+
+    // A file without a tour section is rejected
+    assert!(optimal::OptimalTour::from_tsplib("square", "NAME : square").is_err());
+
+    // The tour has to visit every city of its country exactly once
+    assert!(optimal.check_cities(4).is_ok());
+    let error = optimal.check_cities(5).unwrap_err().to_string();
+    assert_eq!(error, "The optimal tour of square visits 4 cities, but it has 5");
+    let outside = optimal::OptimalTour { name: String::from("square"), route: vec![0, 1, 2, 4] };
+    let error = outside.check_cities(4).unwrap_err().to_string();
+    assert_eq!(error, "The optimal tour of square visits city 5, but it only has 4 cities");
+    let repeated = optimal::OptimalTour { name: String::from("square"), route: vec![0, 1, 1, 3] };
+    let error = repeated.check_cities(4).unwrap_err().to_string();
+    assert_eq!(error, "The optimal tour of square visits city 2 more than once");
+}
+
+#[test]
+fn check_edge_comparison() {
+    let optimal = optimal::OptimalTour::from_tsplib("square", TOUR).unwrap();
+
+    // The same tour travelled backwards from a different start shares every edge
+    let reversed = optimal::EdgeComparison::new(&[2, 1, 0, 3], &optimal.route);
+    assert_eq!(reversed.shared_edges, 4);
+    assert!(reversed.extra_edges.is_empty());
+
+    // Crossing the square keeps only two of the optimal edges
+    let crossed = optimal::EdgeComparison::new(&[0, 2, 1, 3], &optimal.route);
+    assert_eq!(crossed.shared_edges, 2);
+    assert_eq!(crossed.total_edges, 4);
+    assert_eq!(crossed.extra_edges, vec![(0, 2), (1, 3)]);
+    assert_eq!(crossed.missing_edges, vec![(0, 1), (2, 3)]);
+    assert_eq!(crossed.shared_fraction(), 0.5);
+}
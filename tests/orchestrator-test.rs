@@ -0,0 +1,142 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+/// Function to plan a job for a run of the given country
+fn job(instance: &str, run: u32) -> manifest::Job {
+    manifest::Job { instance: instance.to_string(), run, seed: None, completed: false, summary: None, placement: None }
+}
+
+/// Function to find the settings of quick runs
+fn settings() -> orchestrator::RunSettings {
+    orchestrator::RunSettings {
+        parameters: interface::RunParameters { generations: 5, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn check_queue_runs() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let input_data = vec![std::sync::Arc::new(burma_small)];
+    let jobs = vec![job("burma14", 1), job("burma14", 2), job("missing", 1)];
+
+    // Only the jobs given are queued, in the order given, each with its planned core
+    let core = placement::CorePlacement { socket: 0, core: 3 };
+    let queue = orchestrator::queue_runs(&jobs, &[1, 0], &input_data, vec![Some(core), None]).unwrap();
+    assert_eq!(queue.iter().map(|queued| queued.index).collect::<Vec<usize>>(), vec![1, 0]);
+    assert_eq!(queue[0].placement, Some(core));
+    assert_eq!(queue[1].placement, None);
+    assert_eq!(queue[0].country.name, "burma14");
+
+    // A job for a country with no data, or that isn't in the manifest, can't be queued
+    assert!(orchestrator::queue_runs(&jobs, &[2], &input_data, Vec::new()).is_err());
+    assert!(orchestrator::queue_runs(&jobs, &[3], &input_data, Vec::new()).is_err());
+}
+
+#[test]
+fn check_spawn_runs() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let input_data = vec![std::sync::Arc::new(burma_small)];
+    let jobs: Vec<manifest::Job> = (1..=5).map(|run| job("burma14", run)).collect();
+    let indices: Vec<usize> = (0..jobs.len()).collect();
+
+    // Every queued run arrives exactly once, whether each has a thread or workers share them
+    for scheduling in [
+        orchestrator::Scheduling::PerRun,
+        orchestrator::Scheduling::Batch { workers: 2, cores: Vec::new() },
+        orchestrator::Scheduling::Batch { workers: 10, cores: Vec::new() },
+    ] {
+        let queue = orchestrator::queue_runs(&jobs, &indices, &input_data, Vec::new()).unwrap();
+        let stream = orchestrator::spawn_runs(queue, &settings(), scheduling, indicatif::ProgressBar::hidden);
+        assert_eq!(stream.expected(), 5);
+
+        let mut finished: Vec<usize> = stream.collect().unwrap().into_iter().map(|run| run.index).collect();
+        finished.sort();
+        assert_eq!(finished, indices);
+    }
+
+    // Nothing queued finishes straight away
+    let stream = orchestrator::spawn_runs(
+        Default::default(),
+        &settings(),
+        orchestrator::Scheduling::Batch { workers: 4, cores: Vec::new() },
+        indicatif::ProgressBar::hidden,
+    );
+    assert!(stream.is_complete());
+    assert!(stream.collect().unwrap().is_empty());
+}
+
+#[test]
+fn check_spawn_runs_cancelled() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let input_data = vec![std::sync::Arc::new(burma_small)];
+    let jobs: Vec<manifest::Job> = (1..=3).map(|run| job("burma14", run)).collect();
+
+    // Runs stopped by their cancellation token aren't finished, so the missing runs are reported
+    let settings = settings();
+    settings.cancellation.cancel();
+    let queue = orchestrator::queue_runs(&jobs, &[0, 1, 2], &input_data, Vec::new()).unwrap();
+    let stream = orchestrator::spawn_runs(queue, &settings, orchestrator::Scheduling::PerRun, indicatif::ProgressBar::hidden);
+    let error = stream.collect().err().unwrap();
+    assert!(error.to_string().contains("Only 0 of 3 runs finished"));
+
+    // The cancelled runs still arrive with the stats up to where they stopped, but aren't counted as finished
+    let queue = orchestrator::queue_runs(&jobs, &[0, 1, 2], &input_data, Vec::new()).unwrap();
+    let mut stream = orchestrator::spawn_runs(queue, &settings, orchestrator::Scheduling::PerRun, indicatif::ProgressBar::hidden);
+    let mut arrived: usize = 0;
+    while !stream.is_complete() {
+        match stream.recv_timeout(std::time::Duration::from_millis(100)) {
+            Ok(run) => {
+                assert!(run.simulation.cancelled);
+                assert!(!run.simulation.best_chromosome.is_empty());
+                assert_eq!(run.simulation.warnings.of_kind(warnings::WarningKind::Cancelled).count(), 1);
+                assert!(results::RunSummary::new(1, &run.simulation).unwrap().cancelled);
+                arrived += 1;
+            },
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => continue,
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    assert_eq!((arrived, stream.received(), stream.cancelled()), (3, 0, 3));
+}
+
+#[test]
+fn check_instance_groups() {
+    let mut groups: orchestrator::InstanceGroups<u32> = orchestrator::InstanceGroups::new(["a", "b", "a"]);
+    assert!(groups.contains("a"));
+    assert!(!groups.contains("c"));
+    assert_eq!(groups.remaining("a"), 2);
+
+    // A country is only handed back once all of its runs have arrived, in the order they arrived
+    assert_eq!(groups.push("a", 2).unwrap(), None);
+    assert_eq!(groups.push("b", 1).unwrap(), Some(vec![1]));
+    assert_eq!(groups.push("a", 1).unwrap(), Some(vec![2, 1]));
+    assert_eq!(groups.remaining("a"), 0);
+
+    // Runs that weren't expected are errors rather than being lost or plotted twice
+    assert!(groups.push("a", 3).is_err());
+    assert!(groups.push("c", 1).is_err());
+
+    // Runs of countries still waiting on others can be taken when the experiment stops early
+    let mut groups: orchestrator::InstanceGroups<u32> = orchestrator::InstanceGroups::new(["b", "a", "a", "b", "c"]);
+    assert_eq!(groups.push("b", 1).unwrap(), None);
+    assert_eq!(groups.push("a", 2).unwrap(), None);
+    assert_eq!(groups.push("c", 3).unwrap(), Some(vec![3]));
+    assert_eq!(groups.take_arrived(), vec![2, 1]);
+    assert!(groups.take_arrived().is_empty());
+}
+
+#[test]
+fn check_group_by_instance() {
+    let grouped = orchestrator::group_by_instance(vec![
+        ("a".to_string(), 1),
+        ("b".to_string(), 2),
+        ("a".to_string(), 3),
+    ]);
+    assert_eq!(grouped.len(), 2);
+    assert_eq!(grouped["a"], vec![1, 3]);
+    assert_eq!(grouped["b"], vec![2]);
+    assert!(orchestrator::group_by_instance(Vec::<(String, u32)>::new()).is_empty());
+}
@@ -0,0 +1,28 @@
+use tsp_coursework::*;
+use placement::CorePlacement;
+
+#[test]
+fn check_plan_placements() {
+    // Two sockets with two cores each
+    let cores = [
+        CorePlacement { socket: 0, core: 0 },
+        CorePlacement { socket: 0, core: 1 },
+        CorePlacement { socket: 1, core: 2 },
+        CorePlacement { socket: 1, core: 3 },
+    ];
+
+    // Each country keeps to one socket, using its cores in turn and going back round once they are all used
+    let placements = placement::plan_placements(&["burma14", "burma14", "burma14", "brazil58", "brazil58"], &cores);
+    let placed: Vec<(usize, usize)> = placements.iter().map(|placement| {
+        let placement = placement.unwrap();
+        (placement.socket, placement.core)
+    }).collect();
+    assert_eq!(placed, vec![(0, 0), (0, 1), (0, 0), (1, 2), (1, 3)]);
+
+    // A third country shares the first socket, carrying on from its next core
+    let placements = placement::plan_placements(&["burma14", "brazil58", "att48"], &cores);
+    assert_eq!(placements[2], Some(CorePlacement { socket: 0, core: 1 }));
+
+    // Without any cores nothing is pinned
+    assert_eq!(placement::plan_placements(&["burma14"], &[]), vec![None]);
+}
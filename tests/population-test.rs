@@ -1,34 +1,7 @@
 use tsp_coursework::*;
 
-const SRC: &str = r#"<travellingSalesmanProblemInstance>
-<name>burma14</name>
-<source>TSPLIB</source>
-<description>14-Staedte in Burma (Zaw Win)</description>
-<doublePrecision>15</doublePrecision>
-<ignoredDigits>5</ignoredDigits>
-<graph>
-    <vertex>
-    <edge cost="1.530000000000000e+02">1</edge>
-    <edge cost="5.100000000000000e+02">2</edge>
-    <edge cost="7.060000000000000e+02">3</edge>
-    </vertex>
-    <vertex>
-    <edge cost="1.530000000000000e+02">0</edge>
-    <edge cost="4.220000000000000e+02">2</edge>
-    <edge cost="6.640000000000000e+02">3</edge>
-    </vertex>
-    <vertex>
-    <edge cost="5.100000000000000e+02">0</edge>
-    <edge cost="4.220000000000000e+02">1</edge>
-    <edge cost="2.890000000000000e+02">3</edge>
-    </vertex>
-    <vertex>
-    <edge cost="7.060000000000000e+02">0</edge>
-    <edge cost="6.640000000000000e+02">1</edge>
-    <edge cost="2.890000000000000e+02">2</edge>
-    </vertex>
-</graph>
-</travellingSalesmanProblemInstance>"#;
+mod common;
+use common::SRC;
 
 #[test]
 fn test_manual() {
@@ -74,11 +47,16 @@ fn test_auto() {
         test_pop.average_population_cost
     );
 
+    let parameters = interface::RunParameters {
+        tournament_size: 5,
+        crossover_operator: interface::CrossoverOperator::Fix,
+        mutation_operator: interface::MutationOperator::Single,
+        ..Default::default()
+    };
+
     test_pop
         .selection_and_replacement(
-            5, 
-            interface::CrossoverOperator::Fix, 
-            interface::MutationOperator::Single, 
+            &parameters, 
             &burma_small.graph,
     ).unwrap();
 
@@ -87,3 +65,842 @@ fn test_auto() {
         test_pop.average_population_cost
     );
 }
+
+#[test]
+fn test_min_parent_distance() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(2, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 0 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 900.0, id: 1 },
+    ];
+
+    // Tournaments of one pick either Chromosome, so about half the pairs are the same Chromosome twice
+    let mut parameters = interface::RunParameters { tournament_size: 1, ..Default::default() };
+    let identical_pairs = |parameters: &interface::RunParameters| {
+        (0..200)
+            .filter(|_| {
+                let ((_, first_parent), (_, second_parent)) = test_pop.select_parents(parameters);
+                first_parent.distance(&second_parent) == 0
+            })
+            .count()
+    };
+    assert!(identical_pairs(&parameters) > 50);
+
+    // Requiring the parents to differ reruns the second tournament until the other Chromosome is picked
+    parameters.min_parent_distance = 2;
+    assert!(identical_pairs(&parameters) < 10);
+
+    // Parents that can't be far enough apart are still picked rather than selecting forever
+    parameters.min_parent_distance = 4;
+    let ((_, first_parent), (_, second_parent)) = test_pop.select_parents(&parameters);
+    assert!(first_parent.distance(&second_parent) < 4);
+}
+
+#[test]
+fn test_resize() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let best_cost = test_pop.best_chromosome.cost;
+
+    // Shrinking keeps the cheapest chromosomes
+    test_pop.resize(4, &burma_small.graph).unwrap();
+    assert_eq!(test_pop.population_data.len(), 4);
+    assert_eq!(test_pop.population_size, 4);
+    assert_eq!(test_pop.best_chromosome.cost, best_cost);
+
+    // Growing refills the population with new chromosomes
+    test_pop.resize(12, &burma_small.graph).unwrap();
+    assert_eq!(test_pop.population_data.len(), 12);
+    assert!(test_pop.best_chromosome.cost <= best_cost);
+
+    // An empty population is not allowed
+    assert!(test_pop.resize(0, &burma_small.graph).is_err());
+}
+
+
+#[test]
+fn test_tournament_excluding() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+
+    // A tournament as large as the population always picks the best Chromosome that isn't excluded
+    let (best_index, best) = test_pop.run_tournament_excluding(10, &[], interface::TiePolicy::First, 1.0);
+    assert_eq!(best.cost, test_pop.best_chromosome.cost);
+
+    let (second_index, _) = test_pop.run_tournament_excluding(10, &[best_index], interface::TiePolicy::First, 1.0);
+    assert_ne!(best_index, second_index);
+}
+
+#[test]
+fn test_soft_tournament() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 300.0, id: 0 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 100.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 200.0, id: 2 },
+    ];
+    let winner = |test_pop: &population::Population, p: f64| {
+        test_pop.run_tournament_excluding(3, &[], interface::TiePolicy::First, p).0
+    };
+
+    // The cheapest always wins a hard tournament, and the most expensive always wins when nothing else can
+    assert!((0..20).all(|_| winner(&test_pop, 1.0) == 1));
+    assert!((0..20).all(|_| winner(&test_pop, 0.0) == 0));
+
+    // With a probability of a half, the cheapest wins half the time and the other two a quarter each
+    let mut counts = [0; 3];
+    for _ in 0..4000 {
+        counts[winner(&test_pop, 0.5)] += 1;
+    }
+    assert!((1800..2200).contains(&counts[1]));
+    assert!((800..1200).contains(&counts[2]));
+    assert!((800..1200).contains(&counts[0]));
+}
+
+#[test]
+fn test_tie_policy() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+
+    // Make every Chromosome tie on cost, the oldest being last
+    let route: Vec<u32> = vec![0, 1, 2, 3];
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: route.clone(), cost: 100.0, id: 7 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 100.0, id: 5 },
+        chromosome::Chromosome { route: route.clone(), cost: 100.0, id: 2 },
+    ];
+    test_pop.best_chromosome = chromosome::Chromosome::new(route, 100.0);
+
+    // Older picks the lowest id
+    assert_eq!(test_pop.break_tie(&[0, 1, 2], interface::TiePolicy::Older), Some(2));
+    // Diverse picks the route sharing the fewest edges with the best Chromosome
+    assert_eq!(test_pop.break_tie(&[0, 1, 2], interface::TiePolicy::Diverse), Some(1));
+    // First keeps the order the tournament picked them in
+    assert_eq!(test_pop.break_tie(&[2, 0], interface::TiePolicy::First), Some(2));
+
+    // A full tournament with the older policy always returns the oldest
+    let (winner, _) = test_pop.run_tournament_excluding(3, &[], interface::TiePolicy::Older, 1.0);
+    assert_eq!(winner, 2);
+
+    // Costs less than 1 apart don't tie, so the cheapest wins whatever the policy
+    test_pop.population_data[0].cost = 100.1;
+    test_pop.population_data[1].cost = 100.9;
+    test_pop.population_data[2].cost = 100.5;
+    for tie_policy in [interface::TiePolicy::Random, interface::TiePolicy::Older, interface::TiePolicy::Diverse] {
+        for _ in 0..20 {
+            assert_eq!(test_pop.run_tournament_excluding(3, &[], tie_policy, 1.0).0, 0);
+        }
+    }
+
+    // Within the cost epsilon they tie again
+    let test_pop = test_pop.with_cost_epsilon(0.01);
+    assert_eq!(test_pop.run_tournament_excluding(3, &[], interface::TiePolicy::Older, 1.0).0, 2);
+}
+
+#[test]
+fn test_roulette() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 0 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 900.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 900.0, id: 2 },
+    ];
+
+    // The cheap Chromosome has 9 times the weight of each of the others, so wins 9 spins in 11 on average
+    let wins = (0..1_000)
+        .filter(|_| test_pop.run_roulette_excluding(&[]).0 == 0)
+        .count();
+    assert!(wins > 700, "cheapest won {} of 1000", wins);
+
+    // Excluded Chromosomes are never picked
+    for _ in 0..100 {
+        assert_ne!(test_pop.run_roulette_excluding(&[0]).0, 0);
+    }
+
+    // The selection operator picks which scheme selects the parents
+    let parameters = interface::RunParameters {
+        selection_operator: interface::SelectionOperator::Roulette,
+        ..Default::default()
+    };
+    let (index, parent) = test_pop.select_parent(&parameters, &[0, 1]);
+    assert_eq!(index, 2);
+    assert_eq!(parent.cost, 900.0);
+}
+
+#[test]
+fn test_universal_sampling() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 0 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 900.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 900.0, id: 2 },
+    ];
+
+    // With 11 evenly spaced pointers each Chromosome is picked exactly as often as its weight says
+    for _ in 0..20 {
+        let mut counts = [0; 3];
+        for (index, _) in test_pop.run_universal_sampling(11) {
+            counts[index] += 1;
+        }
+        assert_eq!(counts, [9, 1, 1]);
+    }
+
+    // A free route is the only one on the wheel so both pointers land on it, and with distinct parents the second
+    // is picked again without it
+    test_pop.population_data[0].cost = 0.0;
+    let parameters = interface::RunParameters {
+        selection_operator: interface::SelectionOperator::Sus,
+        distinct_parents: true,
+        ..Default::default()
+    };
+    for _ in 0..20 {
+        let ((_, first_parent), (_, second_parent)) = test_pop.select_parents(&parameters);
+        assert_eq!(first_parent.id, 0);
+        assert_ne!(second_parent.id, 0);
+    }
+}
+
+#[test]
+fn test_elitism() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Every Chromosome ties on cost, with the newest first
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap().with_elitism(2);
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 3 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 100.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 100.0, id: 2 },
+    ];
+    test_pop.update_statistics().unwrap();
+
+    // The two oldest of the cheapest Chromosomes are kept, so an equally good child can only replace the newest
+    assert_eq!(test_pop.elite, vec![1, 2]);
+    test_pop.replacement(chromosome::Chromosome { route: vec![0, 3, 2, 1], cost: 100.0, id: 4 });
+    let mut ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 2, 4]);
+
+    // The elite can never take up the whole population
+    let test_pop = population::Population::new(3, &burma_small.graph).unwrap().with_elitism(5);
+    assert_eq!(test_pop.elite.len(), 2);
+
+    // Without elitism nothing is kept, however cheap
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap().with_elitism(0);
+    test_pop.update_elite();
+    assert!(test_pop.elite.is_empty());
+}
+
+#[test]
+fn test_immigrants() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(4, &burma_small.graph).unwrap().with_elitism(1);
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 9000.0, id: 2 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 300.0, id: 3 },
+        chromosome::Chromosome { route: vec![0, 3, 2, 1], cost: 8000.0, id: 4 },
+    ];
+    test_pop.update_statistics().unwrap();
+    let evaluations = test_pop.evaluations;
+
+    // Half the population is replaced, the most expensive first, and each immigrant is evaluated once
+    assert_eq!(test_pop.add_immigrants(0.5, &burma_small.graph).unwrap(), 2);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 5, 3, 6]);
+    assert_eq!(test_pop.evaluations, evaluations + 2);
+
+    // The elite is never replaced, even when every other Chromosome is
+    assert_eq!(test_pop.add_immigrants(1.0, &burma_small.graph).unwrap(), 3);
+    assert!(test_pop.population_data.iter().any(|chromosome| chromosome.id == 1));
+
+    // A rate too small for a whole Chromosome adds none
+    assert_eq!(test_pop.add_immigrants(0.1, &burma_small.graph).unwrap(), 0);
+}
+
+#[test]
+fn test_nearest_neighbour_tours() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Half of 10 is more tours than there are cities, so one is built from each city
+    let test_pop = population::Population::new(10, &burma_small.graph)
+        .unwrap()
+        .with_nearest_neighbour_tours(0.5, &burma_small.graph)
+        .unwrap();
+    assert_eq!(test_pop.evaluations, 14);
+    let mut starts: Vec<u32> = test_pop.population_data[..4].iter().map(|chromosome| chromosome.route[0]).collect();
+    starts.sort();
+    assert_eq!(starts, vec![0, 1, 2, 3]);
+    for chromosome in &test_pop.population_data[..4] {
+        assert_eq!(chromosome.route, chromosome::Chromosome::nearest_neighbour(chromosome.route[0], &burma_small.graph).unwrap().route);
+    }
+
+    // The ids of the random tours replaced are kept
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, (1..=10).collect::<Vec<u64>>());
+}
+
+#[test]
+fn test_edge_frequencies() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(4, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 200.0, id: 2 },
+        chromosome::Chromosome { route: vec![3, 2, 1, 0], cost: 100.0, id: 3 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 900.0, id: 4 },
+    ];
+
+    // Edges are counted whichever direction they are given or travelled in
+    assert_eq!(test_pop.edge_frequencies(&[(0, 1), (2, 1), (0, 2), (1, 3)]), vec![0.75, 0.75, 0.5, 0.5]);
+    assert!(test_pop.edge_frequencies(&[]).is_empty());
+}
+
+#[test]
+fn test_replace_parent() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 100.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 200.0, id: 2 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 900.0, id: 3 },
+    ];
+
+    // A child replaces the worse of its parents, even when a Chromosome outside its family is worse
+    test_pop.replace_parents(vec![chromosome::Chromosome { route: vec![0, 3, 2, 1], cost: 150.0, id: 4 }], [0, 1]);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 3]);
+
+    // A child worse than both parents doesn't join the population
+    test_pop.replace_parents(vec![chromosome::Chromosome { route: vec![0, 3, 1, 2], cost: 500.0, id: 5 }], [0, 1]);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 3]);
+
+    // When both children beat both parents, each child takes the place of a different parent rather than the
+    // second child replacing its sibling, whichever order they are given in
+    test_pop.replace_parents(vec![
+        chromosome::Chromosome { route: vec![2, 1, 0, 3], cost: 90.0, id: 6 },
+        chromosome::Chromosome { route: vec![1, 0, 2, 3], cost: 50.0, id: 7 },
+    ], [0, 1]);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![6, 7, 3]);
+
+    // When only one child beats either parent it replaces the worse one, and the other child is thrown away
+    test_pop.replace_parents(vec![
+        chromosome::Chromosome { route: vec![3, 1, 2, 0], cost: 600.0, id: 8 },
+        chromosome::Chromosome { route: vec![3, 0, 1, 2], cost: 70.0, id: 9 },
+    ], [0, 1]);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![9, 7, 3]);
+
+    // A whole generation keeps the population the same size and never loses the best Chromosome
+    let parameters = interface::RunParameters {
+        replacement_strategy: interface::ReplacementStrategy::WorstParent,
+        tournament_size: 2,
+        ..Default::default()
+    };
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let best_cost = test_pop.best_chromosome.cost;
+    for _ in 0..50 {
+        test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    }
+    assert_eq!(test_pop.population_data.len(), 10);
+    assert!(test_pop.best_chromosome.cost <= best_cost);
+}
+
+#[test]
+fn test_crowding() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 500.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 500.0, id: 2 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 900.0, id: 3 },
+    ];
+
+    // The first child has the route of the second parent, and the second child the route of the first,
+    // so each replaces the parent it is like. Only the cheaper child replaces its parent
+    test_pop.crowding_replacement(vec![
+        chromosome::Chromosome { route: vec![2, 0, 3, 1], cost: 400.0, id: 4 },
+        chromosome::Chromosome { route: vec![3, 2, 1, 0], cost: 600.0, id: 5 },
+    ], [0, 1]);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 3]);
+
+    // The worst Chromosome outside the family is never replaced
+    let parameters = interface::RunParameters {
+        replacement_strategy: interface::ReplacementStrategy::Crowding,
+        ..Default::default()
+    };
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let best_cost = test_pop.best_chromosome.cost;
+    for _ in 0..50 {
+        test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    }
+    assert_eq!(test_pop.population_data.len(), 10);
+    assert!(test_pop.best_chromosome.cost <= best_cost);
+}
+
+#[test]
+fn test_restricted_replacement() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 900.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 500.0, id: 2 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 800.0, id: 3 },
+    ];
+
+    // With the whole population in the window the child competes against the route it is most like,
+    // rather than the most expensive Chromosome
+    test_pop.restricted_replacement(chromosome::Chromosome { route: vec![1, 2, 0, 3], cost: 400.0, id: 4 }, 3);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 3]);
+
+    // A more expensive child doesn't replace it
+    test_pop.restricted_replacement(chromosome::Chromosome { route: vec![1, 2, 0, 3], cost: 450.0, id: 5 }, 3);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 3]);
+
+    // A window bigger than the population is the whole population
+    test_pop.restricted_replacement(chromosome::Chromosome { route: vec![3, 1, 0, 2], cost: 100.0, id: 6 }, 50);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 6]);
+}
+
+#[test]
+fn test_cost_epsilon() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let population = || {
+        let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+        test_pop.population_data = vec![
+            chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 0.3, id: 1 },
+            chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 0.5, id: 2 },
+            chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 0.9, id: 3 },
+        ];
+        test_pop
+    };
+    // The same tour as the first Chromosome, with its cost added up in a different order
+    let child = || chromosome::Chromosome { route: vec![1, 2, 3, 0], cost: 0.1 + 0.2, id: 4 };
+
+    // Compared exactly the child is dearer, so it doesn't replace the Chromosome with the same tour
+    let mut test_pop = population();
+    test_pop.restricted_replacement(child(), 3);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    // With a tolerance the costs are the same, and a child has to be cheaper to replace it
+    let mut test_pop = population().with_cost_epsilon(1e-9);
+    test_pop.restricted_replacement(child(), 3);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    test_pop.restricted_replacement(chromosome::Chromosome { route: vec![1, 2, 3, 0], cost: 0.2, id: 5 }, 3);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![5, 2, 3]);
+
+    // Costs less than 1 apart aren't the same without a tolerance, whatever order they are in
+    let mut test_pop = population();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 1_000.9, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 1_000.1, id: 2 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 1_000.5, id: 3 },
+    ];
+    test_pop.update_statistics().unwrap();
+    assert_eq!(test_pop.best_chromosome.id, 2);
+    assert_eq!(test_pop.worst_chromosome.id, 1);
+    test_pop.replacement(chromosome::Chromosome { route: vec![1, 2, 3, 0], cost: 1_000.2, id: 4 });
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![4, 2, 3]);
+}
+
+#[test]
+fn test_unique_children() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 1_000.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 2_000.0, id: 2 },
+    ];
+    // The first child is the first tour backwards, the second is new
+    let children = || [
+        chromosome::Chromosome { route: vec![3, 2, 1, 0], cost: 1_000.0, id: 3 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 1_500.0, id: 4 },
+    ];
+
+    // By default every child is kept
+    let parameters = interface::RunParameters::default();
+    let kept = test_pop.unique_children(children(), &parameters, &burma_small.graph).unwrap();
+    assert_eq!(kept.len(), 2);
+
+    // Rejecting duplicates leaves out the child whose tour is already in the population
+    let parameters = interface::RunParameters {
+        duplicate_policy: interface::DuplicatePolicy::Reject,
+        ..Default::default()
+    };
+    let kept = test_pop.unique_children(children(), &parameters, &burma_small.graph).unwrap();
+    assert_eq!(kept.iter().map(|child| child.id).collect::<Vec<u64>>(), vec![4]);
+
+    // Identical children are duplicates of each other
+    let twins = [children()[1].clone(), children()[1].clone()];
+    assert_eq!(test_pop.unique_children(twins, &parameters, &burma_small.graph).unwrap().len(), 1);
+
+    // Perturbing mutates the duplicate until its tour is new, using evaluations, so every child kept has a new tour
+    let parameters = interface::RunParameters {
+        duplicate_policy: interface::DuplicatePolicy::Perturb,
+        mutation_operator: interface::MutationOperator::Single,
+        ..Default::default()
+    };
+    let evaluations = test_pop.evaluations;
+    let kept = test_pop.unique_children(children(), &parameters, &burma_small.graph).unwrap();
+    let tours: std::collections::HashSet<u64> = test_pop.population_data.iter().map(|x| x.tour_hash()).collect();
+    assert!(kept.iter().all(|child| !tours.contains(&child.tour_hash())));
+    assert!(test_pop.evaluations > evaluations);
+}
+
+#[test]
+fn test_plus_selection() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // The cheapest of the population and the children survive, whichever they came from
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    test_pop.population_data = vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 500.0, id: 1 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 900.0, id: 2 },
+        chromosome::Chromosome { route: vec![0, 1, 3, 2], cost: 700.0, id: 3 },
+    ];
+    test_pop.plus_replacement(vec![
+        chromosome::Chromosome { route: vec![0, 1, 2, 3], cost: 600.0, id: 4 },
+        chromosome::Chromosome { route: vec![0, 2, 1, 3], cost: 800.0, id: 5 },
+    ]);
+    let ids: Vec<u64> = test_pop.population_data.iter().map(|chromosome| chromosome.id).collect();
+    assert_eq!(ids, vec![1, 4, 3]);
+
+    // A generation breeds a pair of children for every two offspring, keeping the population size
+    let parameters = interface::RunParameters {
+        offspring: Some(6),
+        // The nearest neighbour crossover never skips its evaluations, so every pair costs the same
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        ..Default::default()
+    };
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let (evaluations, best_cost) = (test_pop.evaluations, test_pop.best_chromosome.cost);
+    test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    assert_eq!(test_pop.population_data.len(), 10);
+    assert_eq!(test_pop.evaluations - evaluations, parameters.evaluations_per_generation());
+    assert!(test_pop.best_chromosome.cost <= best_cost);
+    assert_eq!(test_pop.next_id, 17);
+}
+
+#[test]
+fn test_children_per_generation() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Each round of breeding gives a pair of children, so 6 children take 3 rounds
+    let parameters = interface::RunParameters {
+        children_per_generation: 6,
+        // The nearest neighbour crossover never skips its evaluations, so every pair costs the same
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        ..Default::default()
+    };
+    assert_eq!(parameters.evaluations_per_generation(), 3 * interface::RunParameters::default().evaluations_per_generation());
+
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let (evaluations, best_cost) = (test_pop.evaluations, test_pop.best_chromosome.cost);
+    test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    assert_eq!(test_pop.population_data.len(), 10);
+    assert_eq!(test_pop.evaluations - evaluations, parameters.evaluations_per_generation());
+    assert!(test_pop.best_chromosome.cost <= best_cost);
+    assert_eq!(test_pop.next_id, 17);
+    assert_eq!(test_pop.bred_pairs, 3);
+
+    // A local search counts the moves it evaluates as well, so improving a child uses more than one evaluation
+    let parameters = interface::RunParameters { local_search: Some(interface::LocalSearch::TwoOpt), ..parameters };
+    let evaluations = test_pop.evaluations;
+    test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    assert!(test_pop.evaluations - evaluations > parameters.evaluations_per_generation());
+}
+
+#[test]
+fn test_distinct_parents_per_generation() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // With distinct parents no Chromosome is a parent twice in a generation, across every pair it breeds
+    for selection_operator in [
+        interface::SelectionOperator::Tournament,
+        interface::SelectionOperator::Roulette,
+        interface::SelectionOperator::Sus,
+    ] {
+        for offspring in [None, Some(10)] {
+            let parameters = interface::RunParameters {
+                selection_operator,
+                distinct_parents: true,
+                children_per_generation: 10,
+                offspring,
+                ..Default::default()
+            };
+            let mut test_pop = population::Population::new(12, &burma_small.graph).unwrap();
+            for _ in 0..5 {
+                test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+                let mut parents = test_pop.generation_parents.clone();
+                assert_eq!(parents.len(), 10);
+                parents.sort();
+                parents.dedup();
+                assert_eq!(parents.len(), 10, "{:?} {:?}", selection_operator, offspring);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_brood() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let first_parent = chromosome::Chromosome::new(vec![0, 1, 2, 3], 1570.0);
+    let second_parent = chromosome::Chromosome::new(vec![0, 2, 1, 3], 1920.0);
+
+    // A brood of 6 crosses the parents over 3 times, keeping the cheapest two children
+    let evaluations = test_pop.evaluations;
+    let (first_child, second_child) = test_pop
+        .brood(&first_parent, &second_parent, interface::CrossoverOperator::Ordered, 6, &burma_small.graph)
+        .unwrap()
+        .unwrap();
+    assert_eq!(test_pop.evaluations - evaluations, 6);
+    assert!(first_child.cost <= second_child.cost);
+
+    // Identical parents make no new genetic material, so use no evaluations
+    let evaluations = test_pop.evaluations;
+    let children = test_pop
+        .brood(&first_parent, &first_parent, interface::CrossoverOperator::Ordered, 6, &burma_small.graph)
+        .unwrap();
+    assert!(children.is_none());
+    assert_eq!(test_pop.evaluations, evaluations);
+
+    // A generation counts every crossover of the brood
+    let parameters = interface::RunParameters {
+        brood_size: 5,
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        ..Default::default()
+    };
+    assert_eq!(parameters.evaluations_per_generation(), 3 * 2 + 2);
+    let evaluations = test_pop.evaluations;
+    test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    assert_eq!(test_pop.evaluations - evaluations, parameters.evaluations_per_generation());
+}
+
+#[test]
+fn test_adaptive_operators() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Every pair is crossed over and both children mutated, each use being rewarded
+    let parameters = interface::RunParameters { children_per_generation: 10, ..Default::default() };
+    let mut test_pop = population::Population::new(10, &burma_small.graph)
+        .unwrap()
+        .with_adaptive_operators(true);
+    test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    let adaptive = test_pop.adaptive_operators.as_ref().unwrap();
+    assert_eq!(adaptive.crossover.uses.iter().sum::<u64>(), 5);
+    assert_eq!(adaptive.mutation.uses.iter().sum::<u64>(), 10);
+    assert!((adaptive.mutation.probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+    // Without adaptive operators nothing is recorded
+    let test_pop = population::Population::new(10, &burma_small.graph).unwrap().with_adaptive_operators(false);
+    assert!(test_pop.adaptive_operators.is_none());
+}
+
+#[test]
+fn test_edge_entropy() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(2, &burma_small.graph).unwrap();
+
+    // A population of identical routes uses each of its 4 edges equally often, so the entropy is ln 4
+    test_pop.population_data = vec![
+        chromosome::Chromosome::new(vec![0, 1, 2, 3], 100.0),
+        chromosome::Chromosome::new(vec![3, 2, 1, 0], 100.0),
+    ];
+    assert!((test_pop.edge_entropy() - 4f64.ln()).abs() < 1e-9);
+
+    // These routes share edges 1-2 and 0-3 and use 4 other edges once each, out of 8 edges in total,
+    // so the entropy is 4 * (1/8 ln 8) + 2 * (2/8 ln 4) = 2.5 ln 2
+    test_pop.population_data[1] = chromosome::Chromosome::new(vec![0, 2, 1, 3], 100.0);
+    assert!((test_pop.edge_entropy() - 2.5 * 2f64.ln()).abs() < 1e-9);
+}
+
+#[test]
+fn test_diversity() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(3, &burma_small.graph).unwrap();
+
+    // Routes with the same edges, whichever way round they go, aren't diverse at all
+    test_pop.population_data = vec![
+        chromosome::Chromosome::new(vec![0, 1, 2, 3], 100.0),
+        chromosome::Chromosome::new(vec![3, 2, 1, 0], 100.0),
+        chromosome::Chromosome::new(vec![1, 2, 3, 0], 100.0),
+    ];
+    assert_eq!(test_pop.diversity(), 0.0);
+
+    // The last route only shares edges 1-2 and 0-3 with the others, so two of the three pairs share
+    // half their edges and the average fraction not shared is (0 + 0.5 + 0.5) / 3
+    test_pop.population_data[2] = chromosome::Chromosome::new(vec![0, 2, 1, 3], 100.0);
+    assert!((test_pop.diversity() - 1.0 / 3.0).abs() < 1e-9);
+
+    // A single route has nothing to differ from
+    test_pop.population_data.truncate(1);
+    assert_eq!(test_pop.diversity(), 0.0);
+}
+
+/// A repair that always puts the cities back in order
+struct SortRepair;
+
+impl repair::Repair for SortRepair {
+    fn repair(&self, child: &mut chromosome::Chromosome, _graph: &country::Graph) -> bool {
+        child.route.sort();
+        true
+    }
+}
+
+#[test]
+fn test_repair() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    test_pop.repair = std::sync::Arc::new(SortRepair);
+    let evaluations = test_pop.evaluations;
+
+    // The nearest neighbour crossover always makes new children, so always uses its evaluations
+    let parameters = interface::RunParameters {
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        ..Default::default()
+    };
+    test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+
+    // Both repaired children cost one more evaluation each, on top of crossover and mutation
+    assert_eq!(test_pop.evaluations, evaluations + 6);
+
+    // Any child that joined the population was repaired and has the cost of its repaired route
+    let sorted_cost = chromosome::Chromosome::fitness(&[0, 1, 2, 3], &burma_small.graph).unwrap();
+    for chromo in test_pop.population_data.iter().filter(|chromo| chromo.id > 10) {
+        assert_eq!(chromo.route, vec![0, 1, 2, 3]);
+        assert_eq!(chromo.cost, sorted_cost);
+    }
+}
+
+/// A ring of six cities, each with an edge of cost 1 to the cities either side of it only, so the ring is the only
+/// feasible tour
+fn ring_graph() -> country::Graph {
+    let vertex: Vec<country::Vertex> = (0..6u32)
+        .map(|from| country::Vertex {
+            edges: [(from + 1) % 6, (from + 5) % 6]
+                .into_iter()
+                .map(|to| country::Edge { cost: 1.0, destination_city: to })
+                .collect(),
+        })
+        .collect();
+    country::Graph::new(vertex)
+}
+
+#[test]
+fn test_forbidden_edge_repair() {
+    use repair::Repair;
+    let graph = ring_graph();
+    let repair = repair::ForbiddenEdgeRepair;
+
+    // A route with forbidden edges is changed to one with fewer, visiting every city once
+    let mut child = chromosome::Chromosome::new(vec![0, 1, 2, 4, 3, 5], 0.0);
+    assert_eq!(child.forbidden_edges(&graph), 2);
+    assert!(repair.repair(&mut child, &graph));
+    assert_eq!(child.forbidden_edges(&graph), 0);
+    let mut cities = child.route.clone();
+    cities.sort();
+    assert_eq!(cities, vec![0, 1, 2, 3, 4, 5]);
+
+    // A feasible route is left as it is
+    let mut feasible = chromosome::Chromosome::new(vec![0, 1, 2, 3, 4, 5], 6.0);
+    assert!(!repair.repair(&mut feasible, &graph));
+    assert_eq!(feasible.route, vec![0, 1, 2, 3, 4, 5]);
+
+    // The initial population is repaired too, with the costs of the repaired routes
+    let population = population::Population::new(10, &graph).unwrap();
+    let before: usize = population.population_data.iter().map(|chromo| chromo.forbidden_edges(&graph)).sum();
+    let population = population.with_repair(std::sync::Arc::new(repair::ForbiddenEdgeRepair), &graph).unwrap();
+    let after: usize = population.population_data.iter().map(|chromo| chromo.forbidden_edges(&graph)).sum();
+    assert!(after <= before);
+    for chromo in population.population_data.iter() {
+        assert_eq!(chromo.cost, chromosome::Chromosome::fitness(&chromo.route, &graph).unwrap());
+    }
+}
+
+#[test]
+fn test_clone_crossover() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Every parent has the same route, so crossover only gives copies of them
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let route = test_pop.population_data[0].route.clone();
+    let cost = test_pop.population_data[0].cost;
+    for chromo in test_pop.population_data.iter_mut() {
+        chromo.route = route.clone();
+        chromo.cost = cost;
+    }
+    let evaluations = test_pop.evaluations;
+
+    test_pop.selection_and_replacement(&interface::RunParameters::default(), &burma_small.graph).unwrap();
+
+    // Only mutating the two children uses any evaluations
+    assert_eq!(test_pop.evaluations, evaluations + 2);
+}
+
+#[test]
+fn test_crossover_rate() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Without any crossover the parents are passed through, so only mutating the two children uses any evaluations
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let evaluations = test_pop.evaluations;
+    let parameters = interface::RunParameters {
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        crossover_rate: 0.0,
+        ..Default::default()
+    };
+    for _ in 0..10 {
+        test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    }
+    assert_eq!(test_pop.evaluations, evaluations + 20);
+}
+
+#[test]
+fn test_mutation_rate() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Without any mutation only crossover uses any evaluations
+    let mut test_pop = population::Population::new(10, &burma_small.graph).unwrap();
+    let evaluations = test_pop.evaluations;
+    let parameters = interface::RunParameters {
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        mutation_rate: 0.0,
+        ..Default::default()
+    };
+    for _ in 0..10 {
+        test_pop.selection_and_replacement(&parameters, &burma_small.graph).unwrap();
+    }
+    assert_eq!(test_pop.evaluations, evaluations + 20);
+}
@@ -0,0 +1,52 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_seed_current_thread() {
+    use rand::Rng;
+
+    // Seeding the thread again repeats every number drawn since it was last seeded
+    random::seed_current_thread(Some(42));
+    let first: Vec<u32> = (0..10).map(|_| random::rng().gen_range(0..1000)).collect();
+    random::seed_current_thread(Some(42));
+    let second: Vec<u32> = (0..10).map(|_| random::rng().gen_range(0..1000)).collect();
+    assert_eq!(first, second);
+
+    // Other seeds give other numbers
+    random::seed_current_thread(Some(43));
+    let other: Vec<u32> = (0..10).map(|_| random::rng().gen_range(0..1000)).collect();
+    assert_ne!(first, other);
+}
+
+#[test]
+fn check_derive_seed() {
+    // Every stream gets its own seed, and the same seed and stream always give the same seed
+    let seeds: std::collections::HashSet<u64> = (0..1000).map(|stream| random::derive_seed(1, stream)).collect();
+    assert_eq!(seeds.len(), 1000);
+    assert_eq!(random::derive_seed(1, 5), random::derive_seed(1, 5));
+    assert_ne!(random::derive_seed(1, 5), random::derive_seed(2, 5));
+}
+
+#[test]
+fn check_seeded_simulation() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 50,
+        mutation_operator: interface::MutationOperator::Scramble,
+        tournament_p: 0.8,
+        ..Default::default()
+    };
+
+    // A run seeded the same way before its population is made finds the same tours every generation
+    let run = |seed: u64| {
+        random::seed_current_thread(Some(seed));
+        let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+        sim.run(indicatif::ProgressBar::hidden()).unwrap();
+        let population: Vec<Vec<u32>> = sim.population.population_data.iter().map(|chromosome| chromosome.route.clone()).collect();
+        (sim.average_cost, population)
+    };
+    assert_eq!(run(3), run(3));
+}
@@ -0,0 +1,177 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn test_grouping() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let mut other_country = burma_small.clone();
+    other_country.name = String::from("another");
+
+    // Two runs of burma14 and one run of another
+    let simulations = vec![
+        simulation::Simulation::new(burma_small.clone(), interface::RunParameters::default()).unwrap(),
+        simulation::Simulation::new(other_country, interface::RunParameters::default()).unwrap(),
+        simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap(),
+    ];
+
+    let results = results::ExperimentResults::new(simulations, 2).unwrap();
+
+    // Instances are sorted by name and keep every run
+    assert_eq!(results.instances.len(), 2);
+    assert_eq!(results.instances[0].name, "another");
+    assert_eq!(results.instances[1].summaries.len(), 2);
+
+    let burma_results = results.instance("burma14").unwrap();
+    assert_eq!(burma_results.summaries[1].run, 2);
+    assert_eq!(
+        burma_results.summaries[0].best_cost, 
+        burma_results.simulations[0].population.best_chromosome.cost
+    );
+}
+
+#[test]
+fn test_append() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let mut other_country = burma_small.clone();
+    other_country.name = String::from("another");
+
+    // Results built as each country finishes are merged into one set
+    let mut results = results::ExperimentResults::new(vec![
+        simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap(),
+    ], 1).unwrap();
+    results.append(results::ExperimentResults::new(vec![
+        simulation::Simulation::new(other_country, interface::RunParameters::default()).unwrap(),
+    ], 1).unwrap());
+
+    // Instances stay sorted by name whichever finished first
+    let names: Vec<&str> = results.instances.iter().map(|instance| instance.name.as_str()).collect();
+    assert_eq!(names, vec!["another", "burma14"]);
+}
+
+#[test]
+fn test_tags() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let simulations = vec![
+        simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap(),
+    ];
+
+    let results = results::ExperimentResults::new(simulations, 1)
+        .unwrap()
+        .with_tags(vec![String::from("lr-experiment"), String::from("v2")]);
+
+    // Filtering matches only when every requested tag is present
+    assert!(results.has_tags(&[String::from("v2")]));
+    assert!(results.has_tags(&[]));
+    assert!(!results.has_tags(&[String::from("v2"), String::from("v3")]));
+}
+
+#[test]
+fn test_summary_columns() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters { population_size: 10, generations: 5, ..Default::default() };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    let mut results = results::ExperimentResults::new(vec![sim], 1)
+        .unwrap()
+        .with_tags(vec![String::from("v2")]);
+
+    // The original columns come first, with every column added since after them
+    let path = results.export_summary().unwrap();
+    let contents: String = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(
+        lines[0],
+        "instance,run,best_cost,worst_cost,average_cost,best_route,evaluations,tags,seconds,build,description,author,\
+        progress_seconds,cancelled"
+    );
+    let row: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!(row.len(), 14);
+    assert_eq!((row[0], row[1], row[7], row[13]), ("burma14", "1", "v2", "false"));
+    assert_eq!(row[5].split(' ').count(), 4);
+}
+
+#[test]
+fn test_vega_lite() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 5,
+        ..Default::default()
+    };
+    let mut simulations = vec![
+        simulation::Simulation::new(burma_small.clone(), parameters).unwrap(),
+        simulation::Simulation::new(burma_small, parameters).unwrap(),
+    ];
+    for sim in simulations.iter_mut() {
+        sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    }
+    let notes = manifest::ExperimentNotes {
+        description: Some(String::from("Range of burma14")),
+        author: Some(String::from("A. Student")),
+    };
+    let mut results = results::ExperimentResults::new(simulations, 2)
+        .unwrap()
+        .with_tags(vec![String::from("range")])
+        .with_notes(notes);
+
+    // The range chart has a worst, average and best line, each with a point for all 5 generations
+    let paths = results.export_vega_lite(interface::PlotOperator::Range, interface::PlotStatistic::Best).unwrap();
+    let spec: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&paths[0]).unwrap()).unwrap();
+    std::fs::remove_file(&paths[0]).unwrap();
+
+    let values = spec["data"]["values"].as_array().unwrap();
+    assert_eq!(values.len(), 15);
+    assert_eq!(values[0]["series"], "Worst Simulation");
+    assert_eq!(values[0]["generation"], 0.0);
+    assert_eq!(spec["encoding"]["y"]["field"], "value");
+
+    // The tags and notes of the experiment are recorded in the spec and its title
+    assert_eq!(spec["usermeta"]["author"], "A. Student");
+    assert_eq!(spec["usermeta"]["tags"], serde_json::json!(["range"]));
+    assert!(spec["title"].as_str().unwrap().ends_with("Tags: range, Description: Range of burma14, Author: A. Student"));
+
+    // So are the parameters the runs used, in the same form as everywhere else they are recorded
+    let recorded: interface::RunParameters = serde_json::from_value(spec["usermeta"]["parameters"].clone()).unwrap();
+    assert_eq!(recorded, parameters);
+    assert_eq!(results.instances[0].summaries[0].parameters, Some(parameters));
+}
+
+#[test]
+fn test_edge_breakdown() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Every edge is listed in the order travelled, ending with the edge back to the start
+    let breakdown = results::route_breakdown(&[0, 1, 2, 3], &burma_small.graph).unwrap();
+    let edges: Vec<(u32, u32, f64, f64)> = breakdown
+        .iter()
+        .map(|edge| (edge.from, edge.to, edge.cost, edge.cumulative))
+        .collect();
+    assert_eq!(edges, vec![(0, 1, 153.0, 153.0), (1, 2, 422.0, 575.0), (2, 3, 289.0, 864.0), (3, 0, 706.0, 1570.0)]);
+    assert!(results::route_breakdown(&[0, 1, 4], &burma_small.graph).is_err());
+
+    // The most expensive edges are ranked from the most expensive down
+    assert_eq!(results::most_expensive_edges(&breakdown, 2), vec![3, 1]);
+    assert_eq!(results::most_expensive_edges(&breakdown, 10).len(), 4);
+
+    // The file has a line for every edge of the best tour, with only the asked for number of edges ranked
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 5,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    let mut results = results::ExperimentResults::new(vec![sim], 1).unwrap();
+    let paths = results.export_edge_breakdown(1).unwrap();
+    let csv = std::fs::read_to_string(&paths[0]).unwrap();
+    std::fs::remove_file(&paths[0]).unwrap();
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "position,from,to,cost,cumulative,expensive_rank");
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines.iter().filter(|line| line.ends_with(",1")).count(), 1);
+    assert_eq!(results.instances[0].artifacts, paths);
+}
@@ -0,0 +1,63 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_run_log() {
+    let directory = std::env::temp_dir().join(format!("tsp-run-log-{}", std::process::id()));
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters { generations: 50, ..Default::default() };
+
+    // A run logs the build and its tags, then when it starts and ends, along with every new best cost in between
+    let tags = vec![String::from("baseline"), String::from("v2")];
+    let log = runlog::RunLog::open(&directory, &burma_small.name, 2, &tags).unwrap();
+    assert_eq!(log.path, directory.join("burma14-run2.log"));
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap().with_log(Some(log));
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    let contents: String = std::fs::read_to_string(directory.join("burma14-run2.log")).unwrap();
+    let lines: Vec<&str> = contents.lines().collect();
+    assert!(lines[0].ends_with(&format!("Build: {}, Tags: baseline;v2", build_info::BuildInfo::current())));
+    assert!(lines[1].contains("Started burma14 with best cost"));
+    assert!(lines.last().unwrap().contains("Finished at generation 50"));
+    let improvements: usize = lines.iter().filter(|line| line.contains("best cost improved to")).count();
+    assert_eq!(improvements + 3, lines.len());
+    assert!(sim.warnings.is_empty());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn check_log_rotation() {
+    let directory = std::env::temp_dir().join(format!("tsp-log-rotation-{}", std::process::id()));
+    let mut log = runlog::RunLog::open(&directory, "burma14", 1, &[]).unwrap();
+
+    // Writing a little over the limit rotates the log once
+    let line: String = "x".repeat(1000);
+    let lines_per_log: u64 = runlog::MAX_LOG_BYTES / 1000;
+    for _ in 0..=lines_per_log {
+        log.write(&line).unwrap();
+    }
+    assert!(log.backup_path(1).exists());
+    assert!(!log.backup_path(2).exists());
+    assert!(std::fs::metadata(&log.path).unwrap().len() < runlog::MAX_LOG_BYTES / 10);
+    assert!(std::fs::metadata(log.backup_path(1)).unwrap().len() <= runlog::MAX_LOG_BYTES);
+    assert!(std::fs::read_to_string(&log.path).unwrap().contains("Build: "));
+
+    // Only so many rotated logs are kept
+    for _ in 0..(runlog::LOG_BACKUPS as u64 + 1) * lines_per_log {
+        log.write(&line).unwrap();
+    }
+    assert!(log.backup_path(runlog::LOG_BACKUPS).exists());
+    assert!(!log.backup_path(runlog::LOG_BACKUPS + 1).exists());
+
+    // Opening the log again carries on from where it was
+    let size: u64 = std::fs::metadata(&log.path).unwrap().len();
+    drop(log);
+    let mut log = runlog::RunLog::open(&directory, "burma14", 1, &[]).unwrap();
+    log.write("reopened").unwrap();
+    assert!(std::fs::metadata(&log.path).unwrap().len() > size);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,88 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_operator_config() {
+    let parameters = interface::RunParameters { generations: 7, ..Default::default() };
+    let config = runner::OperatorConfig::with_operators(
+        parameters,
+        interface::CrossoverOperator::Ordered,
+        interface::MutationOperator::Inversion,
+    );
+
+    // The operators are swapped in and named, keeping every other parameter
+    assert_eq!(config.name, "Ordered + Inversion");
+    assert_eq!(config.parameters.crossover_operator, interface::CrossoverOperator::Ordered);
+    assert_eq!(config.parameters.mutation_operator, interface::MutationOperator::Inversion);
+    assert_eq!(config.parameters.generations, 7);
+
+    // A configuration can be saved and read back, like the parameters recorded by every other output
+    let saved: String = serde_json::to_string(&config).unwrap();
+    assert_eq!(serde_json::from_str::<runner::OperatorConfig>(&saved).unwrap(), config);
+}
+
+#[test]
+fn check_run_matrix() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters { population_size: 10, generations: 5, ..Default::default() };
+    let configs = vec![
+        runner::OperatorConfig::with_operators(
+            parameters,
+            interface::CrossoverOperator::Ordered,
+            interface::MutationOperator::Inversion,
+        ),
+        runner::OperatorConfig::new("Longer", interface::RunParameters { generations: 8, ..parameters }),
+    ];
+
+    // Every configuration is run the given number of times, grouped in the order the configurations were given
+    let results = runner::run_matrix(&burma_small, configs, 3, None).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].config.name, "Ordered + Inversion");
+    assert_eq!(results[1].config.name, "Longer");
+    for result in &results {
+        assert_eq!(result.simulations.len(), 3);
+        assert_eq!(result.best_costs().unwrap().len(), 3);
+        assert!(result.mean_best_cost().unwrap().unwrap() >= 1570.0);
+    }
+
+    // The runs of a configuration share one copy of the country
+    let shared = &results[0].simulations[0].country_data;
+    assert!(results[0].simulations.iter().all(|sim| std::sync::Arc::ptr_eq(&sim.country_data, shared)));
+
+    // Each run used the parameters of its own configuration
+    assert!(results[0].simulations.iter().all(|sim| sim.parameters.generations == 5));
+    assert!(results[1].simulations.iter().all(|sim| sim.parameters.generations == 8));
+    assert!(results[0].simulations.iter().all(|sim| sim.parameters.mutation_operator == interface::MutationOperator::Inversion));
+
+    // The same seed gives the same runs, each run with its own seed
+    let seeded = |seed: u64| {
+        let configs = vec![runner::OperatorConfig::new("Seeded", interface::RunParameters { generations: 20, ..parameters })];
+        runner::run_matrix(&burma_small, configs, 3, Some(seed)).unwrap().remove(0)
+    };
+    let routes = |results: &runner::ConfigResults| -> Vec<Vec<Vec<u32>>> {
+        results.simulations
+            .iter()
+            .map(|sim| sim.population.population_data.iter().map(|chromosome| chromosome.route.clone()).collect())
+            .collect()
+    };
+    let first = seeded(11);
+    assert_eq!(routes(&first), routes(&seeded(11)));
+    assert_ne!(routes(&first)[0], routes(&first)[1]);
+
+    // Every configuration is checked before any runs, naming the configuration at fault
+    let configs = vec![
+        runner::OperatorConfig::new("Valid", parameters),
+        runner::OperatorConfig::new("Small", interface::RunParameters { population_size: 4, tournament_size: 5, ..parameters }),
+    ];
+    let error = format!("{:#}", runner::run_matrix(&burma_small, configs, 1, None).err().unwrap());
+    assert!(error.contains("configuration Small"), "{}", error);
+    assert!(error.contains("population size (4) must be at least 10"), "{}", error);
+    assert!(error.contains("tournament size (5) is greater than the population size (4)"), "{}", error);
+
+    // No runs leave nothing to average
+    let results = runner::run_matrix(&burma_small, vec![runner::OperatorConfig::new("None", parameters)], 0, None).unwrap();
+    assert!(results[0].simulations.is_empty());
+    assert_eq!(results[0].mean_best_cost().unwrap(), None);
+}
@@ -0,0 +1,580 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_record_stride() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let parameters = interface::RunParameters {
+        record_stride: 100,
+        record_window: 5,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // Every stat vector has an entry for each recorded generation
+    assert_eq!(sim.best_chromosome.len(), sim.recorded_generations.len());
+    assert_eq!(sim.average_cost.len(), sim.recorded_generations.len());
+
+    // The first, every 100th and the final generation are recorded, in order
+    assert_eq!(sim.recorded_generations.first(), Some(&0));
+    assert_eq!(sim.recorded_generations.last(), Some(&(sim.parameters.generations - 1)));
+    assert!(sim.recorded_generations.contains(&500));
+    assert!(sim.recorded_generations.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Every improvement in best cost is recorded at the generation it happened
+    for pair in sim.best_chromosome.windows(2).zip(sim.recorded_generations.windows(2)) {
+        if pair.0[1].cost < pair.0[0].cost {
+            assert!(pair.1[1] - pair.1[0] == 1 || pair.1[1] % 100 == 0);
+        }
+    }
+}
+
+#[test]
+fn check_improvement_rate() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let parameters = interface::RunParameters {
+        generations: 300,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // There is a rate for each recorded generation, starting at 0 and never negative as the best ever cost can't rise
+    assert_eq!(sim.improvement_rate.len(), sim.recorded_generations.len());
+    assert_eq!(sim.improvement_rate.first(), Some(&0.0));
+    assert!(sim.improvement_rate.iter().all(|rate| *rate >= 0.0));
+
+    // Only the last window of best ever costs is remembered
+    assert_eq!(sim.recent_best_costs.len(), simulation::IMPROVEMENT_WINDOW as usize + 1);
+
+    // The rate is a drop in the best ever cost over a window, so no rate is over the drop across the whole run
+    let drop = sim.best_chromosome.first().unwrap().cost - sim.best_chromosome.last().unwrap().cost;
+    assert!(sim.improvement_rate[sim.improvement_rate.len() - 1] <= drop + 1e-9);
+
+    let lines = simulation::Simulation::statistic_lines(&[sim], interface::PlotStatistic::Improvement);
+    assert_eq!(lines[0].len(), 300);
+}
+
+#[test]
+fn check_average_coordinates() {
+    // Lines recorded at different generations hold their last value in between
+    let lines = vec![
+        vec![(0.0, 10.0), (2.0, 6.0)],
+        vec![(0.0, 20.0), (1.0, 10.0), (2.0, 8.0)],
+    ];
+
+    let average = simulation::Simulation::average_coordinates(&lines);
+
+    assert_eq!(average, vec![(0.0, 15.0), (1.0, 10.0), (2.0, 7.0)]);
+}
+
+#[test]
+fn check_deviation_band() {
+    // With two lines the band reaches from one line to the other
+    let lines = vec![
+        vec![(0.0, 10.0), (2.0, 6.0)],
+        vec![(0.0, 20.0), (1.0, 10.0), (2.0, 8.0)],
+    ];
+    let band = simulation::Simulation::deviation_band(&lines);
+    assert_eq!(band, vec![(0.0, 10.0, 20.0), (1.0, 10.0, 10.0), (2.0, 6.0, 8.0)]);
+
+    // The band doesn't go past the highest line, even when the average is a standard deviation from it
+    let lines = vec![vec![(0.0, 0.0)], vec![(0.0, 1.0)], vec![(0.0, 1.0)]];
+    let (_, lower, upper) = simulation::Simulation::deviation_band(&lines)[0];
+    assert!((lower - (2.0 / 3.0 - (2.0f32 / 9.0).sqrt())).abs() < 1e-6);
+    assert_eq!(upper, 1.0);
+}
+
+#[test]
+fn check_max_evaluations() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        max_evaluations: Some(1_000),
+        // The nearest neighbour crossover never skips its evaluations, so every generation costs the same
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // The population costs 10 evaluations and each generation costs 4, so generation 248 only has 2 left to make
+    // a child with, and the run stops on the budget exactly
+    assert_eq!(sim.population.evaluations, 1_000);
+    assert_eq!(sim.recorded_generations.last(), Some(&248));
+    assert_eq!(sim.recorded_evaluations.last(), Some(&1_000));
+    assert_eq!(sim.recorded_evaluations.len(), sim.recorded_generations.len());
+
+    // Children improved by a local search use a varying number of evaluations, but still stop on the budget, as does
+    // the hill climber
+    for algorithm in [interface::Algorithm::Genetic, interface::Algorithm::Hillclimb] {
+        let parameters = interface::RunParameters {
+            algorithm,
+            population_size: 10,
+            max_evaluations: Some(1_001),
+            local_search: Some(interface::LocalSearch::TwoOpt),
+            children_per_generation: 6,
+            ..Default::default()
+        };
+        let mut sim = simulation::Simulation::new(serde_xml_rs::from_str::<country::Country>(SRC).unwrap(), parameters).unwrap();
+        sim.run(indicatif::ProgressBar::hidden()).unwrap();
+        assert_eq!(sim.recorded_evaluations.last(), Some(&1_001));
+    }
+}
+
+#[test]
+fn check_generations() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let parameters = interface::RunParameters {
+        generations: 50,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // Every generation up to the supplied number is recorded
+    assert_eq!(sim.parameters.generations, 50);
+    assert_eq!(sim.recorded_generations, (0..50).collect::<Vec<u32>>());
+}
+
+#[test]
+fn check_progress_every() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // With --progress-every the bar is only updated on every Nth generation
+    let parameters = interface::RunParameters {
+        generations: 50,
+        progress_every: Some(10),
+        ..Default::default()
+    };
+    let sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    assert!(sim.progress_due(20, std::time::Duration::ZERO));
+    assert!(!sim.progress_due(21, simulation::PROGRESS_INTERVAL));
+
+    // Otherwise it is updated once it hasn't been for as long as the bar takes to redraw
+    let sim = simulation::Simulation::new(burma_small.clone(), interface::RunParameters::default()).unwrap();
+    assert!(!sim.progress_due(21, std::time::Duration::ZERO));
+    assert!(sim.progress_due(21, simulation::PROGRESS_INTERVAL));
+
+    // A run that never updates its bar spends no time doing so
+    let parameters = interface::RunParameters {
+        generations: 50,
+        progress_every: Some(100),
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert_eq!(sim.progress_overhead, std::time::Duration::ZERO);
+    assert!(sim.progress_overhead <= sim.elapsed);
+}
+
+#[test]
+fn check_small_countries() {
+    // A country of fewer than 3 cities can't be run, and the error names it
+    let mut pair: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    pair.name = String::from("pair");
+    pair.graph = harness::graph_from_costs(&[vec![0.0, 1.0], vec![1.0, 0.0]]);
+    let error = simulation::Simulation::new(pair, interface::RunParameters::default()).err().unwrap();
+    assert!(format!("{}", error).starts_with("pair has 2 cities"), "{}", error);
+}
+
+#[test]
+fn check_throttle() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // At 10 generations a second the 5th generation is due 0.4 seconds into the run
+    let parameters = interface::RunParameters {
+        generations: 50,
+        throttle: Some(10.0),
+        ..Default::default()
+    };
+    let sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    let delay = sim.throttle_delay(5, std::time::Duration::from_millis(100)).unwrap();
+    assert!((delay.as_secs_f64() - 0.3).abs() < 1e-9);
+
+    // A run behind its pace doesn't wait
+    assert_eq!(sim.throttle_delay(5, std::time::Duration::from_secs(1)), None);
+
+    // Neither does a run that isn't throttled
+    let sim = simulation::Simulation::new(burma_small.clone(), interface::RunParameters::default()).unwrap();
+    assert_eq!(sim.throttle_delay(5, std::time::Duration::ZERO), None);
+
+    // A throttled run takes at least as long as its generations are paced over
+    let parameters = interface::RunParameters {
+        generations: 4,
+        throttle: Some(20.0),
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert!(sim.elapsed >= std::time::Duration::from_millis(100));
+}
+
+#[test]
+fn check_tournament_schedule() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // The tournament size grows linearly from the start to the final size over the run
+    let parameters = interface::RunParameters {
+        generations: 11,
+        population_size: 20,
+        tournament_size: 2,
+        final_tournament_size: Some(10),
+        ..Default::default()
+    };
+    let sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    let sizes: Vec<u32> = [0, 5, 10, 20].iter().map(|generation| sim.scheduled_tournament_size(*generation)).collect();
+    assert_eq!(sizes, vec![2, 6, 10, 10]);
+
+    // It can shrink too, but never past the population
+    let parameters = interface::RunParameters {
+        generations: 11,
+        population_size: 20,
+        tournament_size: 30,
+        final_tournament_size: Some(4),
+        ..Default::default()
+    };
+    let sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    let sizes: Vec<u32> = [0, 5, 10].iter().map(|generation| sim.scheduled_tournament_size(*generation)).collect();
+    assert_eq!(sizes, vec![20, 17, 4]);
+
+    // Without a final size it never changes
+    let sim = simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap();
+    assert_eq!(sim.scheduled_tournament_size(9_000), 5);
+}
+
+#[test]
+fn check_adaptive_tournament_size() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters {
+        generations: 100,
+        population_size: 20,
+        tournament_size: 2,
+        max_tournament_size: Some(10),
+        ..Default::default()
+    };
+
+    // The initial population is as spread out as it started, so uses the tournament size
+    let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    assert_eq!(sim.scheduled_tournament_size(0), 2);
+
+    // Once every tour costs the same, the tournament is as big as it can get
+    let best = sim.population.population_data[0].clone();
+    sim.population.population_data.fill(best);
+    sim.population.average_population_cost = population::Population::find_average_cost(&sim.population.population_data);
+    assert!(sim.population.cost_spread() < 1e-9);
+    assert_eq!(sim.scheduled_tournament_size(0), 10);
+
+    // The size used is recorded every generation, starting from the tournament size and staying within the maximum
+    let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert_eq!(sim.tournament_sizes.len(), sim.recorded_generations.len());
+    assert_eq!(sim.tournament_sizes[0], 2);
+    assert!(sim.tournament_sizes.iter().all(|size| (2..=10).contains(size)));
+
+    // Without it the tournament size is recorded as it is
+    let mut sim = simulation::Simulation::new(burma_small, interface::RunParameters { generations: 20, ..Default::default() }).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert!(sim.tournament_sizes.iter().all(|size| *size == 5));
+}
+
+#[test]
+fn check_protected_elites() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Replacing the whole population with immigrants every generation only spares the elite
+    let parameters = interface::RunParameters {
+        generations: 50,
+        elitism: 1,
+        immigrant_rate: 1.0,
+        immigrant_period: 1,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // So the best cost never gets worse from one generation to the next
+    assert!(sim.best_chromosome.windows(2).all(|pair| pair[1].cost <= pair[0].cost));
+}
+
+#[test]
+fn check_metrics_level() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Basic only records costs
+    let basic = interface::RunParameters {
+        metrics: interface::MetricsLevel::Basic,
+        ..Default::default()
+    };
+    let sim = simulation::Simulation::new(burma_small.clone(), basic).unwrap();
+    assert_eq!(sim.cost_deviation, vec![None]);
+    assert_eq!(sim.entropy, vec![None]);
+    assert_eq!(sim.diversity, vec![None]);
+
+    // Full records everything
+    let full = interface::RunParameters {
+        metrics: interface::MetricsLevel::Full,
+        ..Default::default()
+    };
+    let sim = simulation::Simulation::new(burma_small, full).unwrap();
+    assert!(sim.cost_deviation[0].is_some());
+    assert!(sim.entropy[0].is_some());
+    assert!(sim.diversity[0].is_some());
+}
+
+#[test]
+fn check_graph_parameters() {
+    let mut burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // The candidate lists and summation come from the parameters, so they are recorded along with them
+    let parameters = interface::RunParameters {
+        candidate_list_size: Some(2),
+        summation: interface::Summation::Compensated,
+        generations: 5,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    assert!(sim.country_data.graph.candidates.iter().all(|nearest| nearest.len() == 2));
+    assert_eq!(sim.country_data.graph.summation, interface::Summation::Compensated);
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    let summary = results::RunSummary::new(1, &sim).unwrap();
+    assert_eq!(summary.parameters.unwrap().candidate_list_size, Some(2));
+    assert_eq!(summary.parameters.unwrap().summation, interface::Summation::Compensated);
+
+    // Whatever the country was set up with before is replaced by the parameters
+    burma_small.graph.build_candidate_lists(3);
+    burma_small.graph.summation = interface::Summation::Compensated;
+    let sim = simulation::Simulation::new(burma_small.clone(), interface::RunParameters::default()).unwrap();
+    assert!(sim.country_data.graph.candidates.is_empty());
+    assert_eq!(sim.country_data.graph.summation, interface::Summation::Plain);
+
+    // A shared country that is already set up for the parameters is used as it is, otherwise it is copied
+    burma_small.graph.configure(&parameters);
+    let shared = std::sync::Arc::new(burma_small);
+    let sim = simulation::Simulation::new(std::sync::Arc::clone(&shared), parameters).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&sim.country_data, &shared));
+    let sim = simulation::Simulation::new(std::sync::Arc::clone(&shared), interface::RunParameters::default()).unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&sim.country_data, &shared));
+    assert!(shared.graph.is_configured(&parameters));
+}
+
+#[test]
+fn check_adaptive_mutation() {
+    // The rate stays the same until the population has stagnated for the limit, then rises half way to 1
+    let (rate, stagnant) = simulation::Simulation::adapt_mutation_rate(0.2, 0.2, 0, 3, false);
+    assert_eq!((rate, stagnant), (0.2, 1));
+    let (rate, stagnant) = simulation::Simulation::adapt_mutation_rate(0.2, 0.2, 2, 3, false);
+    assert!((rate - 0.6).abs() < 1e-9);
+    assert_eq!(stagnant, 0);
+
+    // An improvement brings the rate half way back to the base rate
+    let (rate, stagnant) = simulation::Simulation::adapt_mutation_rate(0.6, 0.2, 1, 3, true);
+    assert!((rate - 0.4).abs() < 1e-9);
+    assert_eq!(stagnant, 0);
+
+    // Adaptive mutation needs room to raise the rate
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 200,
+        mutation_rate: 0.0,
+        stagnation_limit: Some(5),
+        // The nearest neighbour crossover never skips its evaluations, so any others are from mutation
+        crossover_operator: interface::CrossoverOperator::Nearest,
+        ..Default::default()
+    };
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // Once the population stagnates mutation starts using evaluations, which it never would at a fixed rate of 0
+    assert!(sim.population.evaluations > 10 + 2 * 199);
+}
+
+#[test]
+fn check_solver_report() {
+    // Every algorithm reports its run the same way
+    for algorithm in [interface::Algorithm::Genetic, interface::Algorithm::Hillclimb] {
+        let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+        let parameters = interface::RunParameters {
+            algorithm,
+            population_size: 10,
+            generations: 50,
+            ..Default::default()
+        };
+        let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+        sim.run(indicatif::ProgressBar::hidden()).unwrap();
+        let report = sim.report().unwrap();
+
+        // The trajectory has a point for every recorded generation and never gets worse
+        assert_eq!(report.algorithm, algorithm);
+        assert_eq!(report.trajectory.len(), sim.recorded_generations.len());
+        assert!(report.trajectory.windows(2).all(|pair| pair[1].best_cost <= pair[0].best_cost));
+        assert_eq!(report.trajectory.last().unwrap().best_cost, report.best_cost());
+        assert_eq!(report.evaluations, sim.solver().evaluations());
+
+        let fitness = chromosome::Chromosome::fitness(&report.best_tour.route, &burma_small.graph).unwrap();
+        assert!((report.best_cost() - fitness).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn check_population_warnings() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut sim = simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap();
+
+    // A population that behaved as expected gives no warnings
+    sim.population.bred_pairs = 100;
+    sim.population.duplicate_parents = 10;
+    sim.population.rejected_children = 20;
+    sim.check_population();
+    assert!(sim.warnings.is_empty());
+
+    // Mostly picking the same parent twice and mostly rejecting children are both warned about
+    sim.population.duplicate_parents = 60;
+    sim.population.rejected_children = 150;
+    sim.check_population();
+    assert_eq!(sim.warnings.of_kind(warnings::WarningKind::Selection).count(), 1);
+    assert_eq!(sim.warnings.of_kind(warnings::WarningKind::Replacement).count(), 1);
+    assert!(sim.warnings.warnings.iter().all(|warning| warning.source == "burma14"));
+}
+
+#[test]
+fn check_feasibility_warning() {
+    // City 0 is joined to every city, but no other cities are joined, so every tour uses forbidden edges
+    let vertex: Vec<country::Vertex> = (0..5u32)
+        .map(|from| country::Vertex {
+            edges: (0..5u32)
+                .filter(|to| *to != from && (from == 0 || *to == 0))
+                .map(|to| country::Edge { cost: 10.0, destination_city: to })
+                .collect(),
+        })
+        .collect();
+    let star = country::Country {
+        name: String::from("star5"),
+        source: String::new(),
+        description: String::new(),
+        double_precision: 15.0,
+        ignored_digits: 0,
+        graph: country::Graph::new(vertex),
+        cost_unit: None,
+        cost_decimals: None,
+    };
+    assert!(star.graph.validate().is_ok());
+
+    let parameters = interface::RunParameters { population_size: 10, generations: 20, ..Default::default() };
+    let mut sim = simulation::Simulation::new(star, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+
+    // The best tour still uses as few forbidden edges as possible, and is warned about
+    assert_eq!(sim.best_chromosome.last().unwrap().forbidden_edges(&sim.country_data.graph), 3);
+    assert_eq!(sim.warnings.of_kind(warnings::WarningKind::Feasibility).count(), 1);
+
+    // Runs on datasets with every edge have no forbidden edges to warn about
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert_eq!(sim.warnings.of_kind(warnings::WarningKind::Feasibility).count(), 0);
+}
+
+#[test]
+fn check_cancellation() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // A run cancelled before it starts stops after its first generation, keeping the stats so far
+    let token = cancellation::CancellationToken::new();
+    let mut sim = simulation::Simulation::new(burma_small.clone(), interface::RunParameters::default())
+        .unwrap()
+        .with_cancellation(token.clone());
+    assert!(!token.cancel());
+    assert!(token.cancel());
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert!(sim.cancelled);
+    assert_eq!(sim.recorded_generations, vec![0, 1]);
+    assert_eq!(sim.best_chromosome.len(), 2);
+
+    // A run that isn't cancelled runs every generation
+    let parameters = interface::RunParameters { generations: 20, ..Default::default() };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert!(!sim.cancelled);
+    assert_eq!(sim.recorded_generations.len(), 20);
+}
+
+#[test]
+fn check_tracked_edges() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+    let parameters = interface::RunParameters { generations: 20, ..Default::default() };
+
+    // Without tracked edges no frequencies are recorded
+    let mut sim = simulation::Simulation::new(burma_small.clone(), parameters).unwrap();
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert!(sim.edge_frequencies.iter().all(Option::is_none));
+
+    // Edges are stored lower city first without repeats, with a fraction for each recorded generation
+    let mut sim = simulation::Simulation::new(burma_small.clone(), parameters)
+        .unwrap()
+        .with_tracked_edges(&[(1, 0), (0, 1), (2, 3)])
+        .unwrap();
+    assert_eq!(sim.tracked_edges, vec![(0, 1), (2, 3)]);
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    assert_eq!(sim.edge_frequencies.len(), sim.recorded_generations.len());
+    for frequencies in &sim.edge_frequencies {
+        let frequencies = frequencies.as_ref().unwrap();
+        assert_eq!(frequencies.len(), 2);
+        assert!(frequencies.iter().all(|frequency| (0.0..=1.0).contains(frequency)));
+    }
+    let lines = simulation::Simulation::edge_frequency_lines(std::slice::from_ref(&sim), 1);
+    assert_eq!(lines[0].len(), sim.recorded_generations.len());
+
+    // Edges must join two different cities of the country
+    assert!(simulation::Simulation::new(burma_small.clone(), parameters).unwrap().with_tracked_edges(&[(0, 14)]).is_err());
+    assert!(simulation::Simulation::new(burma_small, parameters).unwrap().with_tracked_edges(&[(2, 2)]).is_err());
+}
+
+#[test]
+fn check_premature_convergence() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    let mut sim = simulation::Simulation::new(burma_small, interface::RunParameters::default()).unwrap();
+    sim.recorded_generations = vec![0, 10, 20];
+    sim.diversity = vec![Some(0.5), Some(0.01), Some(0.0)];
+    sim.best_chromosome = vec![
+        chromosome::Chromosome::new(vec![0, 2, 1, 3], 1800.0),
+        chromosome::Chromosome::new(vec![0, 2, 1, 3], 1800.0),
+        chromosome::Chromosome::new(vec![0, 2, 1, 3], 1800.0),
+    ];
+
+    // The diversity collapsed at generation 10 with the best tour well above the reference
+    let (generation, gap) = sim.premature_convergence(1500.0, 0.05).unwrap();
+    assert_eq!(generation, 10);
+    assert!((gap - 0.2).abs() < 1e-9);
+
+    // Close enough to the reference isn't premature
+    assert!(sim.premature_convergence(1500.0, 0.25).is_none());
+
+    // Nor is a population that kept its diversity, or one that didn't record it
+    sim.diversity = vec![Some(0.5), Some(0.3), Some(0.2)];
+    assert!(sim.premature_convergence(1500.0, 0.05).is_none());
+    sim.diversity = vec![None, None, None];
+    assert!(sim.premature_convergence(1500.0, 0.05).is_none());
+
+    // Only the changes that are still possible are suggested
+    assert_eq!(
+        sim.convergence_advice(),
+        "try a larger --population-size (now 50) or a smaller --tournament-size (now 5)"
+    );
+    sim.parameters.mutation_rate = 0.1;
+    sim.parameters.tournament_size = 2;
+    assert_eq!(
+        sim.convergence_advice(),
+        "try a higher --mutation-rate (now 0.1) or a larger --population-size (now 50)"
+    );
+}
@@ -0,0 +1,49 @@
+use tsp_coursework::*;
+
+mod common;
+use common::SRC;
+
+#[test]
+fn check_population_diff() {
+    let burma_small: country::Country = serde_xml_rs::from_str(SRC).unwrap();
+
+    // Snapshots are taken of the initial population and of each generation asked for
+    let parameters = interface::RunParameters {
+        population_size: 10,
+        generations: 20,
+        ..Default::default()
+    };
+    let mut sim = simulation::Simulation::new(burma_small, parameters).unwrap().with_snapshots(&[0, 15]);
+    sim.run(indicatif::ProgressBar::hidden()).unwrap();
+    let generations: Vec<u32> = sim.snapshots.iter().map(|snapshot| snapshot.generation).collect();
+    assert_eq!(generations, vec![0, 15]);
+    assert_eq!(sim.snapshots[1].chromosomes.len(), 10);
+
+    // Each snapshot records the parameters it was taken with
+    assert_eq!(sim.snapshots[1].parameters, Some(parameters));
+
+    // A snapshot compared with itself uses every edge equally often and has the same best tour
+    let diff = snapshot::PopulationDiff::new(&sim.snapshots[0], &sim.snapshots[0]).unwrap();
+    assert!(diff.edge_divergence.abs() < 1e-9);
+    assert!(diff.same_best_tour);
+    assert_eq!(diff.best_shared_edges, 4);
+
+    // The same tour started from a different city and travelled backwards is still the same tour
+    let snapshot = |route: Vec<u32>| snapshot::PopulationSnapshot {
+        instance: String::from("burma14"),
+        run: 1,
+        generation: 0,
+        chromosomes: vec![chromosome::Chromosome::new(route, 100.0)],
+        build: None,
+        parameters: None,
+    };
+    let diff = snapshot::PopulationDiff::new(&snapshot(vec![0, 1, 2, 3]), &snapshot(vec![2, 1, 0, 3])).unwrap();
+    assert_eq!(diff.shared_tours, 1);
+    assert!(diff.same_best_tour);
+
+    // Tours with half their edges in common are half way to having no edges in common
+    let diff = snapshot::PopulationDiff::new(&snapshot(vec![0, 1, 2, 3]), &snapshot(vec![0, 2, 1, 3])).unwrap();
+    assert_eq!(diff.shared_tours, 0);
+    assert_eq!(diff.best_shared_edges, 2);
+    assert!((diff.edge_divergence - 2f64.ln() / 2.0).abs() < 1e-9);
+}
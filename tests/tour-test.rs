@@ -0,0 +1,103 @@
+use rand::{thread_rng, Rng};
+use tsp_coursework::*;
+use tour::Tour;
+
+/// The undirected edges of a route, sorted so routes giving the same tour have the same edges
+fn sorted_edges(route: &[u32]) -> Vec<(u32, u32)> {
+    let mut edges = chromosome::Chromosome::edges(route);
+    edges.sort();
+    edges
+}
+
+/// Reverse the path from `from` forwards to `to` in a plain route, wrapping around its end if needed
+fn reverse_path(route: &mut [u32], from: u32, to: u32) {
+    let length = route.len();
+    let first = route.iter().position(|city| *city == from).unwrap();
+    let last = route.iter().position(|city| *city == to).unwrap();
+    let count = (last + length - first) % length + 1;
+    for step in 0..count / 2 {
+        route.swap((first + step) % length, (last + length - step) % length);
+    }
+}
+
+#[test]
+fn check_tours() {
+    let route: Vec<u32> = vec![3, 0, 4, 1, 5, 2, 6];
+
+    // Both tours start with the route they were built from
+    let array = tour::ArrayTour::new(&route);
+    let tree = tour::TreeTour::new(&route);
+    assert_eq!(array.route(), route);
+    assert_eq!(tree.route(), route);
+    assert_eq!(tree.position(5), 4);
+    assert_eq!(tree.city_at(4), 5);
+    for tour in [&array as &dyn Tour, &tree as &dyn Tour] {
+        assert_eq!(tour.len(), 7);
+        assert_eq!(tour.next(6), 3);
+        assert_eq!(tour.prev(3), 6);
+        assert_eq!(tour.next(4), 1);
+    }
+}
+
+#[test]
+fn check_tour_reversals() {
+    // Each reversal gives the same tour as reversing the path in a plain route. A tour may reverse the rest of
+    // the cities instead, which changes which way round later paths go, so each is checked against its own route
+    for length in [5, 50, 300] {
+        let route: Vec<u32> = (0..length).collect();
+        let mut array = tour::ArrayTour::new(&route);
+        let mut tree = tour::TreeTour::new(&route);
+
+        for _ in 0..500 {
+            let from: u32 = thread_rng().gen_range(0..length);
+            let to: u32 = thread_rng().gen_range(0..length);
+            for tour in [&mut array as &mut dyn Tour, &mut tree as &mut dyn Tour] {
+                let mut expected = tour.route();
+                reverse_path(&mut expected, from, to);
+                tour.reverse(from, to);
+                assert_eq!(sorted_edges(&tour.route()), sorted_edges(&expected));
+            }
+        }
+
+        // Walking the tree in either direction visits every city once
+        for city in 0..length {
+            assert_eq!(tree.prev(tree.next(city)), city);
+            assert_eq!(tree.city_at(tree.position(city)), city);
+        }
+    }
+}
+
+#[test]
+fn check_tree_tour_two_opt() {
+    // Cities spread around a grid
+    let points: Vec<(f64, f64)> = (0..60).map(|city| ((city * 7 % 13) as f64, (city * 11 % 17) as f64)).collect();
+    let vertex: Vec<country::Vertex> = points
+        .iter()
+        .map(|from| country::Vertex {
+            edges: points
+                .iter()
+                .enumerate()
+                .map(|(to, point)| country::Edge {
+                    cost: ((from.0 - point.0).powi(2) + (from.1 - point.1).powi(2)).sqrt(),
+                    destination_city: to as u32
+                })
+                .collect(),
+        })
+        .collect();
+    let graph = country::Graph::new(vertex);
+
+    // 2-opt on a tree tour improves a random route, keeping every city exactly once
+    let chromo = chromosome::Chromosome::generation(&graph).unwrap();
+    let mut tree = tour::TreeTour::new(&chromo.route);
+    assert!(chromosome::Chromosome::two_opt_tour(&mut tree, chromo.route.clone(), &graph, &cancellation::CancellationToken::new(), u64::MAX).unwrap().0);
+    let improved = tree.route();
+    assert!(chromosome::Chromosome::fitness(&improved, &graph).unwrap() < chromo.cost);
+
+    let mut sorted = improved.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..60).collect::<Vec<u32>>());
+
+    // The improved route is a 2-opt local optimum however it is stored
+    let mut array = tour::ArrayTour::new(&improved);
+    assert!(!chromosome::Chromosome::two_opt_tour(&mut array, improved.clone(), &graph, &cancellation::CancellationToken::new(), u64::MAX).unwrap().0);
+}
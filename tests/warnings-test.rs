@@ -0,0 +1,25 @@
+use tsp_coursework::*;
+
+#[test]
+fn check_warnings() {
+    let mut warnings = warnings::Warnings::default();
+    assert!(warnings.is_empty());
+
+    warnings.push(warnings::WarningKind::Replacement, "burma14 run 1", "most children were duplicates");
+    warnings.push(warnings::WarningKind::Configuration, "", "tournament is the whole population");
+    warnings.push(warnings::WarningKind::Replacement, "burma14 run 2", "most children were duplicates");
+
+    // Warnings are printed grouped by kind, each with where it came from
+    assert_eq!(warnings.len(), 3);
+    assert_eq!(
+        warnings.to_string(),
+        "3 warning(s):\n  Configuration (1):\n    tournament is the whole population\n  Replacement (2):\n    \
+         burma14 run 1: most children were duplicates\n    burma14 run 2: most children were duplicates"
+    );
+
+    // Collections from different runs can be merged
+    let mut other = warnings::Warnings::default();
+    other.push(warnings::WarningKind::ControlFile, "burma14 run 3", "generation 5: unknown setting");
+    warnings.extend(other);
+    assert_eq!(warnings.of_kind(warnings::WarningKind::ControlFile).count(), 1);
+}